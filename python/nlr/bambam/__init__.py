@@ -1,6 +1,20 @@
 from nlr.bambam.bambam_app import BambamRunner
+from nlr.bambam import preprocess
+from nlr.bambam.results import (
+    isochrone_arrow_table,
+    isochrone_geodataframe,
+    opportunity_arrow_table,
+    opportunity_dataframe,
+)
 import logging
 
 logging.basicConfig(level=logging.INFO)
 
-__all__ = ("BambamRunner", )
+__all__ = (
+    "BambamRunner",
+    "preprocess",
+    "opportunity_dataframe",
+    "isochrone_geodataframe",
+    "opportunity_arrow_table",
+    "isochrone_arrow_table",
+)
@@ -1,5 +1,8 @@
+import json
+from typing import Any, Callable, Dict, Iterator, List, Optional
+
 from nrel.routee.compass.compass_app import CompassApp
-from nlr.bambam.bambam_py_api import BambamAppWrapper
+from nlr.bambam.bambam_py_api import BambamAppWrapper, grid_run
 
 class BambamRunner(CompassApp):
     """
@@ -8,4 +11,54 @@ class BambamRunner(CompassApp):
     @classmethod
     def get_constructor(cls) -> BambamAppWrapper:
         """Override to use bambam's wrapper with extended builders"""
-        return BambamAppWrapper
\ No newline at end of file
+        return BambamAppWrapper
+
+    def run_grid(
+        self,
+        extent: Any,
+        grid_resolution: int,
+        modes: Optional[List[str]] = None,
+        query: Optional[Dict[str, Any]] = None,
+        batch_size: int = 1000,
+        progress: Optional[Callable[[int, int], None]] = None,
+    ) -> Iterator[List[Dict[str, Any]]]:
+        """
+        Runs an H3 grid over an extent, yielding the results in batches so that
+        large runs can be written out as they complete. The grid is expanded and
+        each batch is searched in Rust with the GIL released.
+
+        Args:
+            extent: WKT string, GeoJSON geometry dict or shapely geometry
+            grid_resolution: H3 resolution of the grid
+            modes: if provided, each grid cell is run once per mode by setting the
+                "mode" key of the query
+            query: template of keys copied into each grid cell query
+            batch_size: number of queries searched per batch
+            progress: called after each batch with the number of queries
+                completed and the total number of queries
+
+        Returns:
+            iterator of lists of result dicts, one list per batch
+        """
+        if isinstance(extent, str):
+            extent_format = "wkt"
+        elif isinstance(extent, dict):
+            extent_format = "geojson"
+        elif hasattr(extent, "__geo_interface__"):
+            extent, extent_format = extent.__geo_interface__, "geojson"
+        else:
+            raise TypeError(
+                f"extent must be WKT, a GeoJSON dict or a shapely geometry, found {type(extent)}"
+            )
+        batches = grid_run(
+            self._app,
+            extent,
+            grid_resolution,
+            modes=modes,
+            query=query,
+            extent_format=extent_format,
+            batch_size=batch_size,
+            progress=progress,
+        )
+        for batch in batches:
+            yield [json.loads(result) for result in batch]
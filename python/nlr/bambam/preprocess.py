@@ -0,0 +1,62 @@
+"""
+Pre-processing tools for building BAMBAM datasets from Python.
+
+These run the same operations as the `bambam_util`, `bambam_gtfs preprocess-bundle`
+and `bambam_osm pbf` command line tools. Each function returns a dict that
+summarizes the outcome, such as the number of opportunities or GTFS stops dropped
+during map matching. Failures raise a subclass of `BambamError`, narrowed by the
+kind of failure where the tool reports one: `GtfsError` has the subclasses
+`GtfsReadError`, `MapMatchError` and `ScheduleError`, `OsmError` has
+`OsmConfigError`, `PbfReadError`, `OsmGraphError` and `OsmWriteError`, and
+`GtfsConfigError` has `GtfsConfigReadError`.
+"""
+
+from nlr.bambam.bambam_py_api import (
+    BambamError,
+    GridError,
+    GtfsConfigError,
+    GtfsConfigReadError,
+    GtfsError,
+    GtfsReadError,
+    MapMatchError,
+    OpportunityError,
+    OsmConfigError,
+    OsmError,
+    OsmGraphError,
+    OsmWriteError,
+    OverlayError,
+    PbfReadError,
+    ScheduleError,
+    gtfs_config,
+    gtfs_preprocess_bundle,
+    opportunities_long,
+    opportunities_wide,
+    osm_pbf,
+    overlay_shapefile,
+    preprocess_grid,
+)
+
+__all__ = (
+    "BambamError",
+    "GridError",
+    "GtfsConfigError",
+    "GtfsConfigReadError",
+    "GtfsError",
+    "GtfsReadError",
+    "MapMatchError",
+    "OpportunityError",
+    "OsmConfigError",
+    "OsmError",
+    "OsmGraphError",
+    "OsmWriteError",
+    "OverlayError",
+    "PbfReadError",
+    "ScheduleError",
+    "gtfs_config",
+    "gtfs_preprocess_bundle",
+    "opportunities_long",
+    "opportunities_wide",
+    "osm_pbf",
+    "overlay_shapefile",
+    "preprocess_grid",
+)
@@ -0,0 +1,124 @@
+"""
+Tabular views of BAMBAM results.
+
+Each row of aggregate BAMBAM output nests opportunity counts and isochrones by
+time bin. The helpers here flatten a list of results (as returned by
+`BambamRunner.run`) into tidy tables. The flattening happens in Rust, and the
+column names are taken from the bambam output field names exported by
+`bambam_py_api` as `OPPORTUNITY_TABLE_COLUMNS` and `ISOCHRONE_TABLE_COLUMNS`.
+
+The opportunity table has one row per (grid_id, mode, bin, activity_type) with
+its `count`. The isochrone table has one row per (grid_id, mode, bin) with
+`n_destinations` and the `isochrone` geometry. Geometries are decoded from WKB
+no matter which isochrone format was set in the output plugin.
+
+pandas and geopandas come from the `osm` extra, and pyarrow from the `arrow`
+extra.
+"""
+
+from __future__ import annotations
+
+import json
+from typing import TYPE_CHECKING, Any, Dict, List, Union
+
+from nlr.bambam.bambam_py_api import (
+    ISOCHRONE_TABLE_COLUMNS,
+    OPPORTUNITY_TABLE_COLUMNS,
+    isochrone_table,
+    opportunity_table,
+)
+
+if TYPE_CHECKING:
+    import geopandas
+    import pandas
+    import pyarrow
+
+BambamResults = Union[Dict[str, Any], List[Dict[str, Any]]]
+
+GRID_ID, MODE, BIN, ACTIVITY_TYPE, COUNT = OPPORTUNITY_TABLE_COLUMNS
+_, _, _, N_DESTINATIONS, ISOCHRONE = ISOCHRONE_TABLE_COLUMNS
+
+
+def _as_json_rows(results: BambamResults) -> List[str]:
+    if isinstance(results, dict):
+        results = [results]
+    return [json.dumps(r) for r in results]
+
+
+def opportunity_dataframe(results: BambamResults) -> "pandas.DataFrame":
+    """
+    Flatten the aggregate opportunity counts of BAMBAM results into a long table.
+
+    :param results: one result or a list of results from `BambamRunner.run`
+    :return: DataFrame with columns grid_id, mode, bin, activity_type, count
+    """
+    import pandas as pd
+
+    return pd.DataFrame(
+        opportunity_table(_as_json_rows(results)), columns=OPPORTUNITY_TABLE_COLUMNS
+    )
+
+
+def isochrone_geodataframe(
+    results: BambamResults, crs: str = "EPSG:4326"
+) -> "geopandas.GeoDataFrame":
+    """
+    Flatten the isochrones of BAMBAM results into a table with one row per bin.
+
+    :param results: one result or a list of results from `BambamRunner.run`
+    :param crs: coordinate reference system of the network used in the run
+    :return: GeoDataFrame with columns grid_id, mode, bin, n_destinations and
+             an isochrone geometry column
+    """
+    import geopandas as gpd
+    import shapely
+
+    table = isochrone_table(_as_json_rows(results))
+    geometry = shapely.from_wkb(table.pop(ISOCHRONE))
+    return gpd.GeoDataFrame(table, geometry=geometry, crs=crs).rename_geometry(
+        ISOCHRONE
+    )
+
+
+def opportunity_arrow_table(results: BambamResults) -> "pyarrow.Table":
+    """
+    Flatten the aggregate opportunity counts of BAMBAM results into an Arrow table.
+
+    :param results: one result or a list of results from `BambamRunner.run`
+    :return: Table with columns grid_id, mode, bin, activity_type, count
+    """
+    import pyarrow as pa
+
+    schema = pa.schema(
+        [
+            (GRID_ID, pa.string()),
+            (MODE, pa.string()),
+            (BIN, pa.uint64()),
+            (ACTIVITY_TYPE, pa.string()),
+            (COUNT, pa.float64()),
+        ]
+    )
+    return pa.table(opportunity_table(_as_json_rows(results)), schema=schema)
+
+
+def isochrone_arrow_table(results: BambamResults) -> "pyarrow.Table":
+    """
+    Flatten the isochrones of BAMBAM results into an Arrow table. Isochrones
+    are stored as WKB in a binary column.
+
+    :param results: one result or a list of results from `BambamRunner.run`
+    :return: Table with columns grid_id, mode, bin, n_destinations, isochrone
+    """
+    import pyarrow as pa
+
+    table = isochrone_table(_as_json_rows(results))
+    schema = pa.schema(
+        [
+            (GRID_ID, pa.string()),
+            (MODE, pa.string()),
+            (BIN, pa.uint64()),
+            (N_DESTINATIONS, pa.uint64()),
+            (ISOCHRONE, pa.binary()),
+        ]
+    )
+    return pa.table(table, schema=schema)
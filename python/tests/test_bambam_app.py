@@ -0,0 +1,112 @@
+"""
+Checks that `BambamRunner.run_grid` passes the extent to the Rust grid run and
+decodes each batch of results.
+"""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+import pytest
+
+from nlr.bambam import bambam_app
+from nlr.bambam.bambam_app import BambamRunner
+
+EXTENT_WKT = (
+    "POLYGON((-105.0 39.7, -104.98 39.7, -104.98 39.72, -105.0 39.72, -105.0 39.7))"
+)
+DENVER_CONFIG = Path(__file__).parents[2] / "configuration" / "test_denver.toml"
+DENVER_DATA = Path(__file__).parents[2] / "denver_co"
+
+
+class FakeGridRun:
+    """stands in for the Rust grid run, recording its arguments."""
+
+    def __init__(self) -> None:
+        self.calls: List[Dict[str, Any]] = []
+
+    def __call__(
+        self, app: Any, extent: Any, grid_resolution: int, **kwargs: Any
+    ) -> List[List[str]]:
+        self.calls.append(
+            {"app": app, "extent": extent, "grid_resolution": grid_resolution, **kwargs}
+        )
+        return [
+            [json.dumps({"request": {"grid_id": "a", "mode": "walk"}})],
+            [json.dumps({"request": {"grid_id": "a", "mode": "bike"}})],
+        ]
+
+
+def _runner() -> BambamRunner:
+    # the grid run is faked, so no network needs to be loaded
+    runner = BambamRunner.__new__(BambamRunner)
+    runner._app = "app"
+    return runner
+
+
+def _run(
+    monkeypatch: pytest.MonkeyPatch, extent: Any, modes: Optional[List[str]] = None
+) -> tuple[FakeGridRun, List[List[Dict[str, Any]]]]:
+    fake = FakeGridRun()
+    monkeypatch.setattr(bambam_app, "grid_run", fake)
+    batches = list(_runner().run_grid(extent, 8, modes=modes, batch_size=1))
+    return fake, batches
+
+
+def test_run_grid_wkt_extent(monkeypatch: pytest.MonkeyPatch) -> None:
+    fake, batches = _run(monkeypatch, EXTENT_WKT, modes=["walk", "bike"])
+    (call,) = fake.calls
+    assert call["app"] == "app"
+    assert call["extent"] == EXTENT_WKT
+    assert call["extent_format"] == "wkt"
+    assert call["modes"] == ["walk", "bike"]
+    assert call["batch_size"] == 1
+    assert [b[0]["request"]["mode"] for b in batches] == ["walk", "bike"]
+
+
+def test_run_grid_geojson_extent(monkeypatch: pytest.MonkeyPatch) -> None:
+    geojson = {
+        "type": "Polygon",
+        "coordinates": [
+            [[-105.0, 39.7], [-104.98, 39.7], [-104.98, 39.72], [-105.0, 39.7]]
+        ],
+    }
+    fake, _ = _run(monkeypatch, geojson)
+    assert fake.calls[0]["extent"] == geojson
+    assert fake.calls[0]["extent_format"] == "geojson"
+
+
+def test_run_grid_geo_interface_extent(monkeypatch: pytest.MonkeyPatch) -> None:
+    shapely = pytest.importorskip("shapely")
+    polygon = shapely.from_wkt(EXTENT_WKT)
+    fake, _ = _run(monkeypatch, polygon)
+    assert fake.calls[0]["extent"]["type"] == "Polygon"
+    assert fake.calls[0]["extent_format"] == "geojson"
+
+
+def test_run_grid_invalid_extent(monkeypatch: pytest.MonkeyPatch) -> None:
+    with pytest.raises(TypeError, match="extent must be WKT"):
+        _run(monkeypatch, 42)
+
+
+@pytest.mark.skipif(
+    not DENVER_DATA.exists(), reason="requires the denver_co test network"
+)
+def test_run_grid_denver() -> None:
+    app = BambamRunner.from_config_file(DENVER_CONFIG)
+    progress: List[tuple[int, int]] = []
+    batches = list(
+        app.run_grid(
+            EXTENT_WKT,
+            8,
+            modes=["walk"],
+            batch_size=2,
+            progress=lambda done, total: progress.append((done, total)),
+        )
+    )
+    results = [r for batch in batches for r in batch]
+    assert all(len(batch) <= 2 for batch in batches)
+    assert progress[-1] == (len(results), len(results))
+    assert {r["request"]["mode"] for r in results} == {"walk"}
@@ -0,0 +1,76 @@
+"""
+Checks that failures in the pre-processing tools surface as typed exceptions.
+"""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from nlr.bambam import preprocess
+from nlr.bambam.preprocess import (
+    BambamError,
+    GridError,
+    GtfsError,
+    MapMatchError,
+    OsmError,
+    PbfReadError,
+    ScheduleError,
+)
+
+
+def test_errors_subclass_bambam_error() -> None:
+    for name in preprocess.__all__:
+        if name.endswith("Error"):
+            assert issubclass(getattr(preprocess, name), BambamError)
+
+
+def test_error_subclasses_narrow_tool_errors() -> None:
+    assert issubclass(ScheduleError, GtfsError)
+    assert issubclass(MapMatchError, GtfsError)
+    assert issubclass(PbfReadError, OsmError)
+    assert not issubclass(ScheduleError, MapMatchError)
+
+
+def test_osm_pbf_missing_file(tmp_path: Path) -> None:
+    with pytest.raises(PbfReadError):
+        preprocess.osm_pbf(tmp_path / "missing.osm.pbf", tmp_path / "output")
+
+
+def test_gtfs_preprocess_bundle_missing_vertices(tmp_path: Path) -> None:
+    with pytest.raises(MapMatchError, match="vertex index"):
+        preprocess.gtfs_preprocess_bundle(
+            input=tmp_path / "missing.zip",
+            output_directory=tmp_path / "output",
+            vertices_compass_filename=tmp_path / "missing.csv.gz",
+            start_date="08-15-2025",
+            end_date="08-15-2025",
+            date_mapping_policy="exact-date",
+            starting_edge_list_id=1,
+        )
+
+
+def test_preprocess_grid_invalid_extent(tmp_path: Path) -> None:
+    with pytest.raises(GridError) as error:
+        preprocess.preprocess_grid("NOT VALID WKT", tmp_path / "grid.json", 8)
+    assert isinstance(error.value, BambamError)
+    assert not (tmp_path / "grid.json").exists()
+
+
+def test_invalid_option_raises_value_error(tmp_path: Path) -> None:
+    # invalid arguments are rejected before any tool runs
+    with pytest.raises(ValueError, match="invalid extent_format 'wkb'"):
+        preprocess.preprocess_grid(
+            "POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))",
+            tmp_path / "grid.json",
+            8,
+            extent_format="wkb",
+        )
+    with pytest.raises(ValueError, match="invalid population_source"):
+        preprocess.preprocess_grid(
+            "POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))",
+            tmp_path / "grid.json",
+            8,
+            population_source={"type": "unknown"},
+        )
@@ -0,0 +1,110 @@
+"""
+Checks the tabular views of BAMBAM results.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Dict, List
+
+import pytest
+
+from nlr.bambam import results
+from nlr.bambam.bambam_py_api import (
+    ISOCHRONE_TABLE_COLUMNS,
+    OPPORTUNITY_TABLE_COLUMNS,
+    isochrone_table,
+    opportunity_table,
+)
+
+
+def _results() -> List[Dict[str, Any]]:
+    return [
+        {
+            "request": {"mode": "walk", "grid_id": "8826a8a5a9fffff"},
+            "info": {"isochrone_format": "wkt"},
+            "aggregate_opportunities": {
+                "20": {
+                    "isochrone": "POLYGON((0 0,2 0,2 2,0 2,0 0))",
+                    "n_destinations": 12,
+                    "opportunities": {"retail": 4.0, "jobs": 10.0},
+                },
+                "10": {
+                    "isochrone": "POLYGON((0 0,1 0,1 1,0 1,0 0))",
+                    "n_destinations": 3,
+                    "opportunities": {"retail": 1.0, "jobs": 2.0},
+                },
+            },
+        },
+        {"request": {"mode": "walk"}, "error": "no path"},
+    ]
+
+
+def test_table_columns_match_output_fields() -> None:
+    assert OPPORTUNITY_TABLE_COLUMNS == [
+        "grid_id",
+        "mode",
+        "bin",
+        "activity_type",
+        "count",
+    ]
+    assert ISOCHRONE_TABLE_COLUMNS == [
+        "grid_id",
+        "mode",
+        "bin",
+        "n_destinations",
+        "isochrone",
+    ]
+
+
+def test_opportunity_table() -> None:
+    table = opportunity_table(results._as_json_rows(_results()))
+    assert list(table.keys()) == OPPORTUNITY_TABLE_COLUMNS
+    assert table[results.BIN] == [10, 10, 20, 20]
+    assert table[results.ACTIVITY_TYPE] == ["jobs", "retail", "jobs", "retail"]
+    assert table[results.COUNT] == [2.0, 1.0, 10.0, 4.0]
+    assert set(table[results.GRID_ID]) == {"8826a8a5a9fffff"}
+
+
+def test_isochrone_table() -> None:
+    table = isochrone_table(results._as_json_rows(_results()))
+    assert list(table.keys()) == ISOCHRONE_TABLE_COLUMNS
+    assert table[results.BIN] == [10, 20]
+    assert table[results.N_DESTINATIONS] == [3, 12]
+    assert all(isinstance(wkb, bytes) for wkb in table[results.ISOCHRONE])
+
+
+def test_isochrone_table_rejects_invalid_json() -> None:
+    with pytest.raises(ValueError, match="result 0 is not valid JSON"):
+        isochrone_table(["not json"])
+
+
+def test_opportunity_arrow_table() -> None:
+    pa = pytest.importorskip("pyarrow")
+    table = results.opportunity_arrow_table(_results())
+    assert table.column_names == OPPORTUNITY_TABLE_COLUMNS
+    assert table.schema.field(results.BIN).type == pa.uint64()
+    assert table.num_rows == 4
+
+
+def test_isochrone_arrow_table() -> None:
+    pa = pytest.importorskip("pyarrow")
+    table = results.isochrone_arrow_table(_results()[0])
+    assert table.column_names == ISOCHRONE_TABLE_COLUMNS
+    assert table.schema.field(results.ISOCHRONE).type == pa.binary()
+    assert table.column(results.N_DESTINATIONS).to_pylist() == [3, 12]
+
+
+def test_opportunity_dataframe() -> None:
+    pytest.importorskip("pandas")
+    df = results.opportunity_dataframe(_results())
+    assert list(df.columns) == OPPORTUNITY_TABLE_COLUMNS
+    assert df[results.COUNT].sum() == pytest.approx(17.0)
+
+
+def test_isochrone_geodataframe() -> None:
+    pytest.importorskip("geopandas")
+    pytest.importorskip("shapely")
+    gdf = results.isochrone_geodataframe(_results())
+    assert gdf.geometry.name == results.ISOCHRONE
+    assert list(gdf[results.BIN]) == [10, 20]
+    assert gdf.geometry.area.tolist() == pytest.approx([1.0, 4.0])
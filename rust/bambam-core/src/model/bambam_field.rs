@@ -11,9 +11,14 @@ pub const TIME_BINS: &str = "bin";
 pub const TIME_BIN: &str = "time_bin";
 pub const INFO: &str = "info";
 pub const MODE: &str = "mode";
+/// departure time of the search, read by time-dependent models. see
+/// [`crate::util::date_deserialization_ops::parse_naive_datetime`] for accepted formats.
+pub const START_TIME: &str = "start_time";
 pub const OUTPUT_CONFIG: &str = "output_config";
 pub const ISOCHRONE: &str = "isochrone";
 pub const ISOCHRONE_ALGORITHM: &str = "isochrone_algorithm";
+pub const BIN_ISOCHRONE_ALGORITHMS: &str = "bin_isochrone_algorithms";
+pub const ISOCHRONE_POST_PROCESSING: &str = "isochrone_post_processing";
 pub const ISOCHRONE_FORMAT: &str = "isochrone_format";
 pub const GEOMETRY_MODEL: &str = "geometry_model";
 pub const BIN_RANGE: &str = "bin_range";
@@ -33,6 +38,9 @@ pub const OPP_FMT_DISAGGREGATE: &str = "disaggregate";
 pub const OPPORTUNITY_PLUGIN_RUNTIME: &str = "opportunity_runtime";
 pub const OPPORTUNITY_BIN_RUNTIME: &str = "bin_runtime";
 pub const COST: &str = "cost";
+pub const GRID_ID: &str = "grid_id";
+pub const ACTIVITY_TYPE: &str = "activity_type";
+pub const COUNT: &str = "count";
 
 pub mod get {
     use itertools::Itertools;
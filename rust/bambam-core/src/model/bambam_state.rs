@@ -24,5 +24,9 @@ pub const DWELL_TIME: &str = "dwell_time";
 /// if it is < 1, it reduces cost, and > 1, increases cost.
 pub const COST_PENALTY_FACTOR: &str = "penalty_factor";
 
+/// the id of the most recently traversed edge, or -1 before the first edge.
+/// used to build turn-aware search labels for turn restrictions.
+pub const INCOMING_EDGE_ID: &str = "incoming_edge_id";
+
 pub use routee_compass_core::model::traversal::default::fieldname::*;
 // pub use routee_compass_powertrain::model::fieldname::*;
@@ -0,0 +1,237 @@
+//! Flat tables built from bambam output rows, for analysis outside of Rust.
+//!
+//! Aggregate output rows nest opportunity counts and isochrones by time bin. These
+//! functions flatten them into one record per (grid cell, mode, bin, activity type)
+//! and one record per (grid cell, mode, bin) isochrone, with geometries encoded as
+//! WKB regardless of the isochrone format used during the run. Columns are named by
+//! the [`bambam_field`] constants in [`OPPORTUNITY_TABLE_COLUMNS`] and
+//! [`ISOCHRONE_TABLE_COLUMNS`].
+//!
+//! Rows without an `aggregate_opportunities` section, such as failed searches or
+//! disaggregate outputs, contribute no records.
+
+use geo::{Geometry, TryConvert};
+use geozero::{CoordDimensions, ToWkb};
+use itertools::Itertools;
+use routee_compass::plugin::output::OutputPluginError;
+use serde_json::Value;
+
+use crate::model::{
+    bambam_field,
+    bambam_typed::{AggregateSection, BambamOutputRow},
+};
+
+/// column names of the opportunity table, in order.
+pub const OPPORTUNITY_TABLE_COLUMNS: [&str; 5] = [
+    bambam_field::GRID_ID,
+    bambam_field::MODE,
+    bambam_field::TIME_BINS,
+    bambam_field::ACTIVITY_TYPE,
+    bambam_field::COUNT,
+];
+
+/// column names of the isochrone table, in order.
+pub const ISOCHRONE_TABLE_COLUMNS: [&str; 5] = [
+    bambam_field::GRID_ID,
+    bambam_field::MODE,
+    bambam_field::TIME_BINS,
+    bambam_field::N_DESTINATIONS,
+    bambam_field::ISOCHRONE,
+];
+
+/// the count of opportunities of one activity type reachable within a bin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpportunityTableRow {
+    /// grid cell of the search origin, if the query came from a grid input
+    pub grid_id: Option<String>,
+    pub mode: String,
+    /// upper bound of the bin, as found in the bin key
+    pub bin: u64,
+    pub activity_type: String,
+    pub count: f64,
+}
+
+/// the isochrone of one bin, encoded as WKB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsochroneTableRow {
+    /// grid cell of the search origin, if the query came from a grid input
+    pub grid_id: Option<String>,
+    pub mode: String,
+    /// upper bound of the bin, as found in the bin key
+    pub bin: u64,
+    pub n_destinations: Option<usize>,
+    pub isochrone_wkb: Vec<u8>,
+}
+
+/// flattens the aggregate opportunity counts of an output row, ordered by bin and
+/// then by activity type.
+pub fn opportunity_rows(output: &mut Value) -> Result<Vec<OpportunityTableRow>, OutputPluginError> {
+    if !has_aggregate_section(output)? {
+        return Ok(vec![]);
+    }
+    let mut row = BambamOutputRow::new(output);
+    let (grid_id, mode) = grid_id_and_mode(&row)?;
+    let aggregate = row.aggregate()?;
+    let mut rows = vec![];
+    for (bin, bin_key) in sorted_bins(&aggregate)? {
+        let Some(counts) = aggregate.get_opportunities(bin_key)? else {
+            continue;
+        };
+        for (activity_type, count) in counts.into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+            rows.push(OpportunityTableRow {
+                grid_id: grid_id.clone(),
+                mode: mode.clone(),
+                bin,
+                activity_type,
+                count,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// flattens the isochrones of an output row into WKB, ordered by bin. the format of
+/// the stored isochrones is read from the info section of the row.
+pub fn isochrone_rows(output: &mut Value) -> Result<Vec<IsochroneTableRow>, OutputPluginError> {
+    if !has_aggregate_section(output)? {
+        return Ok(vec![]);
+    }
+    let mut row = BambamOutputRow::new(output);
+    let (grid_id, mode) = grid_id_and_mode(&row)?;
+    let format = row.info_ref()?.get_isochrone_format()?.ok_or_else(|| {
+        OutputPluginError::OutputPluginFailed(format!(
+            "cannot find '{}' in the '{}' section of output row",
+            bambam_field::ISOCHRONE_FORMAT,
+            bambam_field::INFO
+        ))
+    })?;
+    let aggregate = row.aggregate()?;
+
+    let mut rows = vec![];
+    for (bin, bin_key) in sorted_bins(&aggregate)? {
+        let Some(isochrone) = aggregate.get_isochrone(bin_key) else {
+            continue;
+        };
+        let geometry: Geometry<f64> = format
+            .deserialize_geometry(isochrone)?
+            .try_convert()
+            .map_err(|e| {
+                OutputPluginError::OutputPluginFailed(format!(
+                    "unable to convert isochrone of bin {bin} from f32 to f64: {e}"
+                ))
+            })?;
+        let isochrone_wkb = geometry.to_wkb(CoordDimensions::xy()).map_err(|e| {
+            OutputPluginError::OutputPluginFailed(format!(
+                "failed to write isochrone of bin {bin} as WKB: {e}"
+            ))
+        })?;
+        rows.push(IsochroneTableRow {
+            grid_id: grid_id.clone(),
+            mode: mode.clone(),
+            bin,
+            n_destinations: aggregate.get_n_destinations(bin_key)?,
+            isochrone_wkb,
+        });
+    }
+    Ok(rows)
+}
+
+/// true if the row has an aggregate section, failing if that section is not an object.
+fn has_aggregate_section(output: &Value) -> Result<bool, OutputPluginError> {
+    match output.get(bambam_field::AGGREGATE_OPPORTUNITIES) {
+        None => Ok(false),
+        Some(section) if section.is_object() => Ok(true),
+        Some(_) => Err(OutputPluginError::OutputPluginFailed(format!(
+            "{} section is not a JSON object",
+            bambam_field::AGGREGATE_OPPORTUNITIES
+        ))),
+    }
+}
+
+/// the bin keys of the aggregate section ordered by their upper bound.
+fn sorted_bins<'a>(
+    aggregate: &'a AggregateSection<'_>,
+) -> Result<Vec<(u64, &'a str)>, OutputPluginError> {
+    let bins = aggregate
+        .bin_keys()
+        .into_iter()
+        .map(|key| {
+            let bin = key.parse::<u64>().map_err(|e| {
+                OutputPluginError::OutputPluginFailed(format!(
+                    "bin key '{key}' is not an unsigned integer: {e}"
+                ))
+            })?;
+            Ok((bin, key))
+        })
+        .collect::<Result<Vec<_>, OutputPluginError>>()?
+        .into_iter()
+        .sorted_by_key(|(bin, _)| *bin)
+        .collect_vec();
+    Ok(bins)
+}
+
+fn grid_id_and_mode(
+    row: &BambamOutputRow<'_>,
+) -> Result<(Option<String>, String), OutputPluginError> {
+    let request = row.request()?;
+    Ok((request.get_grid_id(), request.get_mode()?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{isochrone_rows, opportunity_rows};
+    use geozero::{wkb::Wkb, ToGeo};
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_aggregate_row() {
+        let mut row = json!({
+            "request": { "mode": "walk", "grid_id": "8826a8a5a9fffff" },
+            "info": { "isochrone_format": "wkt" },
+            "aggregate_opportunities": {
+                "20": {
+                    "isochrone": "POLYGON((0 0,2 0,2 2,0 2,0 0))",
+                    "n_destinations": 12,
+                    "opportunities": { "retail": 4.0, "jobs": 10.0 }
+                },
+                "10": {
+                    "isochrone": "POLYGON((0 0,1 0,1 1,0 1,0 0))",
+                    "n_destinations": 3,
+                    "opportunities": { "retail": 1.0, "jobs": 2.0 }
+                }
+            }
+        });
+        let opportunities = opportunity_rows(&mut row).expect("failed flattening opportunities");
+        let keys = opportunities
+            .iter()
+            .map(|r| (r.bin, r.activity_type.as_str(), r.count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                (10, "jobs", 2.0),
+                (10, "retail", 1.0),
+                (20, "jobs", 10.0),
+                (20, "retail", 4.0)
+            ]
+        );
+        assert!(opportunities
+            .iter()
+            .all(|r| r.grid_id.as_deref() == Some("8826a8a5a9fffff") && r.mode == "walk"));
+
+        let isochrones = isochrone_rows(&mut row).expect("failed flattening isochrones");
+        assert_eq!(isochrones.len(), 2);
+        assert_eq!(isochrones[1].bin, 20);
+        assert_eq!(isochrones[1].n_destinations, Some(12));
+        let decoded = Wkb(isochrones[1].isochrone_wkb.clone())
+            .to_geo()
+            .expect("invalid WKB");
+        assert!(matches!(decoded, geo::Geometry::Polygon(_)));
+
+        let mut failed = json!({ "request": { "mode": "walk" }, "error": "no path" });
+        assert!(opportunity_rows(&mut failed).unwrap().is_empty());
+        assert!(isochrone_rows(&mut failed).unwrap().is_empty());
+        // flattening does not add an aggregate section to rows without one
+        assert!(failed.get("aggregate_opportunities").is_none());
+    }
+}
@@ -37,6 +37,7 @@
 
 use std::collections::HashMap;
 
+use chrono::NaiveDateTime;
 use itertools::Itertools;
 use routee_compass::plugin::output::OutputPluginError;
 use routee_compass_core::model::cost::TraversalCost;
@@ -46,9 +47,13 @@ use crate::model::{
     bambam_field,
     destination::{BinningConfig, DestinationFilter, DestinationPredicate},
     output_plugin::{
-        isochrone::{GeometryModelConfig, IsochroneAlgorithm, IsochroneOutputFormat},
+        isochrone::{
+            GeometryModelConfig, IsochroneAlgorithm, IsochroneOutputFormat,
+            IsochronePostProcessingConfig,
+        },
         opportunity::{OpportunityFormat, OpportunityOrientation},
     },
+    util::date_deserialization_ops::{parse_naive_datetime, APP_DATETIME_FORMAT},
 };
 
 // ─── top-level entry point ────────────────────────────────────────────────────
@@ -149,6 +154,30 @@ impl<'a> RequestSection<'a> {
     pub fn get_mode(&self) -> Result<String, OutputPluginError> {
         get_field(self.0, bambam_field::MODE)
     }
+
+    /// Returns the `grid_id` of the grid cell the search started from, if the query
+    /// came from a grid input. numeric ids are returned as their JSON text.
+    pub fn get_grid_id(&self) -> Option<String> {
+        self.0.get(bambam_field::GRID_ID).map(|g| match g {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    /// Returns the optional `start_time` of the search, in the
+    /// [`APP_DATETIME_FORMAT`] format.
+    pub fn get_start_time(&self) -> Result<Option<NaiveDateTime>, OutputPluginError> {
+        let value: Option<String> = get_field_opt(self.0, bambam_field::START_TIME)?;
+        value
+            .map(|s| {
+                parse_naive_datetime(&s).map_err(|e| {
+                    OutputPluginError::OutputPluginFailed(format!(
+                        "request start_time '{s}' must be in the format '{APP_DATETIME_FORMAT}': {e}"
+                    ))
+                })
+            })
+            .transpose()
+    }
 }
 
 // ─── info section ─────────────────────────────────────────────────────────────
@@ -188,6 +217,18 @@ impl<'a> InfoSectionRef<'a> {
         get_field_opt(self.0, bambam_field::ISOCHRONE_ALGORITHM)
     }
 
+    pub fn get_bin_isochrone_algorithms(
+        &self,
+    ) -> Result<Option<HashMap<String, IsochroneAlgorithm>>, OutputPluginError> {
+        get_field_opt(self.0, bambam_field::BIN_ISOCHRONE_ALGORITHMS)
+    }
+
+    pub fn get_isochrone_post_processing(
+        &self,
+    ) -> Result<Option<IsochronePostProcessingConfig>, OutputPluginError> {
+        get_field_opt(self.0, bambam_field::ISOCHRONE_POST_PROCESSING)
+    }
+
     pub fn get_isochrone_format(&self) -> Result<Option<IsochroneOutputFormat>, OutputPluginError> {
         get_field_opt(self.0, bambam_field::ISOCHRONE_FORMAT)
     }
@@ -264,6 +305,30 @@ impl<'a> InfoSectionMut<'a> {
         set_field(self.0, bambam_field::ISOCHRONE_ALGORITHM, v)
     }
 
+    pub fn get_bin_isochrone_algorithms(
+        &self,
+    ) -> Result<Option<HashMap<String, IsochroneAlgorithm>>, OutputPluginError> {
+        get_field_opt(self.0, bambam_field::BIN_ISOCHRONE_ALGORITHMS)
+    }
+    pub fn set_bin_isochrone_algorithms(
+        &mut self,
+        v: &HashMap<String, IsochroneAlgorithm>,
+    ) -> Result<(), OutputPluginError> {
+        set_field(self.0, bambam_field::BIN_ISOCHRONE_ALGORITHMS, v)
+    }
+
+    pub fn get_isochrone_post_processing(
+        &self,
+    ) -> Result<Option<IsochronePostProcessingConfig>, OutputPluginError> {
+        get_field_opt(self.0, bambam_field::ISOCHRONE_POST_PROCESSING)
+    }
+    pub fn set_isochrone_post_processing(
+        &mut self,
+        v: &IsochronePostProcessingConfig,
+    ) -> Result<(), OutputPluginError> {
+        set_field(self.0, bambam_field::ISOCHRONE_POST_PROCESSING, v)
+    }
+
     pub fn get_isochrone_format(&self) -> Result<Option<IsochroneOutputFormat>, OutputPluginError> {
         get_field_opt(self.0, bambam_field::ISOCHRONE_FORMAT)
     }
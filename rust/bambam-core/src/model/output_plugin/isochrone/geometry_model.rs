@@ -1,4 +1,6 @@
-use geo::{line_measures::Densifiable, Haversine, LineString, MultiPoint, Point};
+use geo::{
+    line_measures::Densifiable, Geometry, Haversine, LineString, MultiLineString, MultiPoint, Point,
+};
 use routee_compass::plugin::output::OutputPluginError;
 use routee_compass_core::{
     algorithm::search::SearchTreeNode,
@@ -8,7 +10,8 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uom::si::f64::Length;
 
-use crate::model::output_plugin::isochrone::GeometryModelConfig;
+use crate::model::output_plugin::isochrone::{GeometryModelConfig, IsochroneAlgorithm};
+use crate::util::buffer::Buffer;
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -86,6 +89,53 @@ impl TryFrom<&GeometryModelConfig> for GeometryModel {
 }
 
 impl GeometryModel {
+    /// draws the isochrone for a set of destinations. buffered models draw the union of
+    /// buffers around the network geometry reached, which follows the street network,
+    /// while other models run the isochrone algorithm over the destination points.
+    pub fn generate_isochrone(
+        &self,
+        destinations: &[(Label, &SearchTreeNode)],
+        map_model: Arc<MapModel>,
+        isochrone_algorithm: &IsochroneAlgorithm,
+    ) -> Result<Geometry<f32>, OutputPluginError> {
+        let buffered = match self {
+            GeometryModel::BufferedLinestring {
+                buffer_radius,
+                buffer_stride,
+            } => {
+                let linestrings = destinations
+                    .iter()
+                    .filter_map(|(_, branch)| branch.incoming_edge())
+                    .map(|e| {
+                        map_model
+                            .get_linestring(&e.edge_list_id, &e.edge_id)
+                            .cloned()
+                            .map_err(|e| {
+                                OutputPluginError::OutputPluginFailed(format!(
+                                    "failure generating buffered linestrings: {e}"
+                                ))
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                MultiLineString::new(linestrings).buffer_with_stride(*buffer_radius, *buffer_stride)
+            }
+            GeometryModel::BufferedDestinationPoint {
+                buffer_radius,
+                buffer_stride,
+            } => {
+                let points = self.generate_destination_points(destinations, map_model)?;
+                points.buffer_with_stride(*buffer_radius, *buffer_stride)
+            }
+            _ => {
+                let points = self.generate_destination_points(destinations, map_model)?;
+                return isochrone_algorithm.run(points);
+            }
+        };
+        buffered.map_err(|e| {
+            OutputPluginError::OutputPluginFailed(format!("failure buffering isochrone: {e}"))
+        })
+    }
+
     pub fn generate_destination_points(
         &self,
         destinations: &[(Label, &SearchTreeNode)],
@@ -116,7 +166,7 @@ impl GeometryModel {
         linestring: &LineString<f32>,
     ) -> Result<Vec<Point<f32>>, OutputPluginError> {
         match self {
-            GeometryModel::DestinationPoint => {
+            GeometryModel::DestinationPoint | GeometryModel::BufferedDestinationPoint { .. } => {
                 let last_point = linestring.points().next_back().ok_or_else(|| {
                     OutputPluginError::OutputPluginFailed(format!(
                         "geometry for edge_id {edge_id} has no points",
@@ -124,22 +174,14 @@ impl GeometryModel {
                 })?;
                 Ok(vec![last_point])
             }
-            GeometryModel::LinestringCoordinates => Ok(linestring.points().collect()),
+            GeometryModel::LinestringCoordinates | GeometryModel::BufferedLinestring { .. } => {
+                Ok(linestring.points().collect())
+            }
             GeometryModel::LinestringStride { stride } => {
                 let meters = stride.get::<uom::si::length::meter>() as f32;
                 let dense_linestring = linestring.densify(&Haversine, meters);
                 Ok(dense_linestring.into_points())
             }
-            GeometryModel::BufferedLinestring {
-                buffer_radius: _,
-                buffer_stride: _,
-            } => {
-                todo!("geo rust does not currently support geometry buffering")
-            }
-            GeometryModel::BufferedDestinationPoint {
-                buffer_radius: _,
-                buffer_stride: _,
-            } => todo!("geo rust does not currently support geometry buffering"),
         }
     }
 }
@@ -12,11 +12,15 @@ pub enum GeometryModelConfig {
         stride: f64,
         distance_unit: DistanceUnit,
     },
+    /// isochrone drawn as the union of buffers around the network edges reached,
+    /// with vertices placed every `buffer_stride` along the curved edges of the buffer.
     BufferedLinestring {
         buffer_radius: f64,
         buffer_stride: f64,
         distance_unit: DistanceUnit,
     },
+    /// isochrone drawn as the union of buffers around the destination of each edge
+    /// reached, with vertices placed every `buffer_stride` along each circle.
     BufferedDestinationPoint {
         buffer_radius: f64,
         buffer_stride: f64,
@@ -3,8 +3,15 @@ use geo::concave_hull::ConcaveHullOptions;
 use geo::Geometry;
 use geo::KNearestConcaveHull;
 use geo::MultiPoint;
+use geo::{Coord, MapCoords, MultiPolygon};
+use h3o::geom::SolventBuilder;
+use h3o::{CellIndex, LatLng};
 use routee_compass::plugin::output::OutputPluginError;
+use routee_compass_core::model::unit::DistanceUnit;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::util::alpha_shape::AlphaShape;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -25,6 +32,17 @@ pub enum IsochroneAlgorithm {
     /// destinations.
     /// guards against dynamically-computed k < 3.
     KNearestSqrtScaled,
+    /// uses an alpha shape, the union of the Delaunay triangles over the destinations
+    /// with a circumradius no greater than `alpha`. smaller values follow the
+    /// destinations more closely and leave gaps over water and unreached areas, which
+    /// may produce multiple polygons with holes.
+    AlphaShape {
+        alpha: f64,
+        distance_unit: DistanceUnit,
+    },
+    /// marks every H3 cell at some resolution that contains a destination and
+    /// dissolves them into polygons, which may also contain holes.
+    H3CellUnion { resolution: h3o::Resolution },
 }
 
 impl IsochroneAlgorithm {
@@ -82,6 +100,119 @@ impl IsochroneAlgorithm {
                 let k = if sqrt_n < 3.0 { 3 } else { sqrt_n as u32 };
                 IsochroneAlgorithm::KNearestConcaveHull { k }.run(mp)
             }
+            IsochroneAlgorithm::AlphaShape {
+                alpha,
+                distance_unit,
+            } => {
+                if mp.len() < 3 {
+                    return Ok(Geometry::Polygon(geo::polygon!()));
+                }
+                let shape = mp.alpha_shape(distance_unit.to_uom(*alpha)).map_err(|e| {
+                    OutputPluginError::OutputPluginFailed(format!(
+                        "failure drawing alpha shape: {e}"
+                    ))
+                })?;
+                Ok(multipolygon_or_empty(shape))
+            }
+            IsochroneAlgorithm::H3CellUnion { resolution } => {
+                let cells = mp
+                    .iter()
+                    .map(|p| {
+                        LatLng::new(p.y() as f64, p.x() as f64)
+                            .map(|ll| ll.to_cell(*resolution))
+                            .map_err(|e| {
+                                OutputPluginError::OutputPluginFailed(format!(
+                                    "invalid destination coordinate for H3 cell union: {e}"
+                                ))
+                            })
+                    })
+                    .collect::<Result<HashSet<CellIndex>, _>>()?;
+                if cells.is_empty() {
+                    return Ok(Geometry::Polygon(geo::polygon!()));
+                }
+                let dissolved = SolventBuilder::new().build().dissolve(cells).map_err(|e| {
+                    OutputPluginError::OutputPluginFailed(format!(
+                        "failure dissolving H3 cells: {e}"
+                    ))
+                })?;
+                let shape = dissolved.map_coords(|Coord { x, y }| Coord {
+                    x: x as f32,
+                    y: y as f32,
+                });
+                Ok(multipolygon_or_empty(shape))
+            }
         }
     }
 }
+
+/// the isochrone as a multipolygon, or an empty polygon if nothing was drawn,
+/// matching the other algorithms.
+fn multipolygon_or_empty(shape: MultiPolygon<f32>) -> Geometry<f32> {
+    if shape.0.is_empty() {
+        Geometry::Polygon(geo::polygon!())
+    } else {
+        Geometry::MultiPolygon(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::output_plugin::isochrone::IsochroneOutputFormat;
+    use geo::Point;
+
+    fn destinations() -> MultiPoint<f32> {
+        let points = (0..5)
+            .flat_map(|i| (0..5).map(move |j| (i, j)))
+            .map(|(i, j)| Point::new(-105.0 + i as f32 * 0.001, 40.0 + j as f32 * 0.001))
+            .collect::<Vec<_>>();
+        MultiPoint::new(points)
+    }
+
+    #[test]
+    fn test_new_algorithms_write_to_all_formats() {
+        let algorithms = [
+            IsochroneAlgorithm::AlphaShape {
+                alpha: 150.0,
+                distance_unit: DistanceUnit::Meters,
+            },
+            IsochroneAlgorithm::H3CellUnion {
+                resolution: h3o::Resolution::Nine,
+            },
+        ];
+        let formats = [
+            IsochroneOutputFormat::Wkt,
+            IsochroneOutputFormat::Wkb,
+            IsochroneOutputFormat::GeoJson,
+        ];
+        for algorithm in algorithms.iter() {
+            let geometry = algorithm
+                .run(destinations())
+                .expect("should draw isochrone");
+            assert!(matches!(geometry, Geometry::MultiPolygon(_)));
+            for format in formats.iter() {
+                let serialized = format
+                    .serialize_geometry(&geometry)
+                    .expect("should serialize isochrone");
+                let deserialized = format
+                    .deserialize_geometry(&serde_json::json!(serialized))
+                    .expect("should deserialize isochrone");
+                assert!(matches!(deserialized, Geometry::MultiPolygon(_)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_deserialize_new_algorithms() {
+        let alpha: IsochroneAlgorithm = serde_json::from_value(serde_json::json!({
+            "type": "alpha_shape", "alpha": 250.0, "distance_unit": "meters"
+        }))
+        .expect("should deserialize alpha shape");
+        assert!(matches!(alpha, IsochroneAlgorithm::AlphaShape { .. }));
+        let h3: IsochroneAlgorithm = serde_json::from_value(serde_json::json!({
+            "type": "h3_cell_union", "resolution": 9
+        }))
+        .expect("should deserialize h3 cell union");
+        assert!(matches!(h3, IsochroneAlgorithm::H3CellUnion { .. }));
+    }
+}
@@ -0,0 +1,535 @@
+use geo::{
+    unary_union, BooleanOps, ChaikinSmoothing, ChamberlainDuquetteArea, Coord, CoordsIter,
+    Geometry, MapCoords, MultiPolygon, Polygon, Simplify, SimplifyVwPreserve,
+};
+use geozero::{geojson::GeoJsonString, wkt::Wkt as WktReader, ToGeo};
+use routee_compass::plugin::output::OutputPluginError;
+use std::path::Path;
+use uom::si::f64::Length;
+
+use crate::model::output_plugin::isochrone::{IsochronePostProcessingConfig, SimplifyConfig};
+use crate::util::buffer::LocalProjection;
+
+/// post-processing steps for isochrones, built from an [`IsochronePostProcessingConfig`]
+/// with any clip geometries loaded into memory.
+pub struct IsochronePostProcessing {
+    pub min_hole_area: Option<f64>,
+    pub simplify: Option<Simplification>,
+    pub smoothing_iterations: Option<usize>,
+    pub nested: bool,
+    pub clip_boundary: Option<MultiPolygon<f64>>,
+    pub water_mask: Option<MultiPolygon<f64>>,
+}
+
+pub enum Simplification {
+    DouglasPeucker { tolerance: Length },
+    Visvalingam { tolerance: Length },
+}
+
+impl TryFrom<&IsochronePostProcessingConfig> for IsochronePostProcessing {
+    type Error = OutputPluginError;
+
+    fn try_from(value: &IsochronePostProcessingConfig) -> Result<Self, Self::Error> {
+        if let Some(area) = value.min_hole_area {
+            if area <= 0.0 {
+                return Err(OutputPluginError::BuildFailed(format!(
+                    "min hole area must be strictly positive, found {area}"
+                )));
+            }
+        }
+        let simplify = match &value.simplify {
+            None => None,
+            Some(SimplifyConfig::DouglasPeucker {
+                tolerance,
+                distance_unit,
+            })
+            | Some(SimplifyConfig::Visvalingam {
+                tolerance,
+                distance_unit,
+            }) if *tolerance <= 0.0 => {
+                return Err(OutputPluginError::BuildFailed(format!(
+                "simplify tolerance must be strictly positive, found {tolerance} {distance_unit}"
+            )))
+            }
+            Some(SimplifyConfig::DouglasPeucker {
+                tolerance,
+                distance_unit,
+            }) => Some(Simplification::DouglasPeucker {
+                tolerance: distance_unit.to_uom(*tolerance),
+            }),
+            Some(SimplifyConfig::Visvalingam {
+                tolerance,
+                distance_unit,
+            }) => Some(Simplification::Visvalingam {
+                tolerance: distance_unit.to_uom(*tolerance),
+            }),
+        };
+        let clip_boundary = value
+            .clip_boundary_file
+            .as_ref()
+            .map(|f| read_polygons(f))
+            .transpose()
+            .map_err(OutputPluginError::BuildFailed)?;
+        let water_mask = value
+            .water_mask_file
+            .as_ref()
+            .map(|f| read_polygons(f))
+            .transpose()
+            .map_err(OutputPluginError::BuildFailed)?;
+        Ok(Self {
+            min_hole_area: value.min_hole_area,
+            simplify,
+            smoothing_iterations: value.smoothing_iterations,
+            nested: value.nested,
+            clip_boundary,
+            water_mask,
+        })
+    }
+}
+
+impl IsochronePostProcessing {
+    /// post-processes the isochrone of one bin. bins are expected in ascending order,
+    /// with `smaller_bins` holding the union of the isochrones of the bins so far,
+    /// which is updated here when nesting.
+    pub fn apply(
+        &self,
+        isochrone: &Geometry<f32>,
+        smaller_bins: &mut Option<MultiPolygon<f64>>,
+    ) -> Result<Geometry<f32>, OutputPluginError> {
+        let mut mp = to_multipolygon(isochrone)?;
+        if let Some(min_area) = self.min_hole_area {
+            mp = remove_holes(mp, min_area);
+        }
+        if let Some(simplification) = &self.simplify {
+            mp = simplification.run(mp)?;
+        }
+        if let Some(iterations) = self.smoothing_iterations {
+            mp = mp.chaikin_smoothing(iterations);
+        }
+        if self.nested {
+            if let Some(smaller) = smaller_bins.as_ref() {
+                mp = mp.union(smaller);
+            }
+            *smaller_bins = Some(mp.clone());
+        }
+        if let Some(boundary) = &self.clip_boundary {
+            mp = mp.intersection(boundary);
+        }
+        if let Some(mask) = &self.water_mask {
+            mp = mp.difference(mask);
+        }
+        Ok(to_geometry(mp))
+    }
+}
+
+impl Simplification {
+    /// simplifies polygons in WGS84 degrees, with the tolerance applied in meters on
+    /// a local projection. polygons that collapse are removed.
+    fn run(&self, mp: MultiPolygon<f64>) -> Result<MultiPolygon<f64>, OutputPluginError> {
+        let coords = mp.coords_iter().collect::<Vec<_>>();
+        let projection = match LocalProjection::new(coords.iter()) {
+            Some(p) => p,
+            None => return Ok(mp),
+        };
+        let meters = projection.multipolygon_to_meters(&mp);
+        let simplified = match self {
+            Simplification::DouglasPeucker { tolerance } => {
+                meters.simplify(tolerance.get::<uom::si::length::meter>())
+            }
+            Simplification::Visvalingam { tolerance } => {
+                let tolerance = tolerance.get::<uom::si::length::meter>();
+                meters.simplify_vw_preserve(tolerance * tolerance)
+            }
+        };
+        let simplified = simplified
+            .into_iter()
+            .filter(|p| p.exterior().0.len() > 3)
+            .collect::<MultiPolygon<f64>>();
+        projection.multipolygon_to_degrees(simplified).map_err(|e| {
+            OutputPluginError::OutputPluginFailed(format!("failure simplifying isochrone: {e}"))
+        })
+    }
+}
+
+/// removes interior rings with an area below the minimum, in square meters.
+fn remove_holes(mp: MultiPolygon<f64>, min_area: f64) -> MultiPolygon<f64> {
+    mp.into_iter()
+        .map(|p| {
+            let (exterior, interiors) = p.into_inner();
+            let interiors = interiors
+                .into_iter()
+                .filter(|ring| {
+                    let hole = Polygon::new(ring.clone(), vec![]);
+                    hole.chamberlain_duquette_unsigned_area() >= min_area
+                })
+                .collect();
+            Polygon::new(exterior, interiors)
+        })
+        .collect()
+}
+
+fn to_multipolygon(isochrone: &Geometry<f32>) -> Result<MultiPolygon<f64>, OutputPluginError> {
+    let mp = match isochrone {
+        Geometry::Polygon(p) if p.exterior().0.is_empty() => MultiPolygon::new(vec![]),
+        Geometry::Polygon(p) => MultiPolygon::new(vec![p.clone()]),
+        Geometry::MultiPolygon(mp) => mp.clone(),
+        _ => {
+            return Err(OutputPluginError::OutputPluginFailed(String::from(
+                "isochrone post-processing expects a polygonal isochrone",
+            )))
+        }
+    };
+    Ok(mp.map_coords(|Coord { x, y }| Coord {
+        x: x as f64,
+        y: y as f64,
+    }))
+}
+
+/// the isochrone in 32-bit precision, or an empty polygon if nothing remains.
+fn to_geometry(mp: MultiPolygon<f64>) -> Geometry<f32> {
+    if mp.0.is_empty() {
+        return Geometry::Polygon(geo::polygon!());
+    }
+    Geometry::MultiPolygon(mp.map_coords(|Coord { x, y }| Coord {
+        x: x as f32,
+        y: y as f32,
+    }))
+}
+
+/// reads the polygons in a WKT or GeoJSON file into a single multipolygon.
+fn read_polygons(file: &str) -> Result<MultiPolygon<f64>, String> {
+    let contents = std::fs::read_to_string(file)
+        .map_err(|e| format!("failure reading polygons from '{file}': {e}"))?;
+    let is_geojson = Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_lowercase().as_str(), "json" | "geojson"));
+    let geometries = if is_geojson {
+        let json: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("failure reading '{file}' as GeoJSON: {e}"))?;
+        let geometries_json = match json["type"].as_str() {
+            Some("FeatureCollection") => json["features"]
+                .as_array()
+                .map(|fs| fs.iter().map(|f| f["geometry"].clone()).collect())
+                .unwrap_or_default(),
+            Some("Feature") => vec![json["geometry"].clone()],
+            _ => vec![json],
+        };
+        geometries_json
+            .into_iter()
+            .map(|g| {
+                GeoJsonString(g.to_string())
+                    .to_geo()
+                    .map_err(|e| format!("failure reading geometry in '{file}': {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        let geometry = WktReader(contents.as_str())
+            .to_geo()
+            .map_err(|e| format!("failure reading '{file}' as WKT: {e}"))?;
+        vec![geometry]
+    };
+    let polygons = geometries
+        .iter()
+        .flat_map(polygons_of)
+        .collect::<Vec<Polygon<f64>>>();
+    if polygons.is_empty() {
+        return Err(format!("no polygons found in '{file}'"));
+    }
+    Ok(unary_union(polygons.iter()))
+}
+
+fn polygons_of(geometry: &Geometry<f64>) -> Vec<Polygon<f64>> {
+    match geometry {
+        Geometry::Polygon(p) => vec![p.clone()],
+        Geometry::MultiPolygon(mp) => mp.0.clone(),
+        Geometry::GeometryCollection(gc) => gc.iter().flat_map(polygons_of).collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{polygon, Area};
+    use routee_compass_core::model::unit::DistanceUnit;
+
+    /// a square with sides of roughly `meters` centered on (-105, 40)
+    fn square(meters: f32) -> Geometry<f32> {
+        let half_x = meters / 2.0 / 85_000.0;
+        let half_y = meters / 2.0 / 111_000.0;
+        Geometry::Polygon(polygon![
+            (x: -105.0 - half_x, y: 40.0 - half_y),
+            (x: -105.0 + half_x, y: 40.0 - half_y),
+            (x: -105.0 + half_x, y: 40.0 + half_y),
+            (x: -105.0 - half_x, y: 40.0 + half_y),
+            (x: -105.0 - half_x, y: 40.0 - half_y),
+        ])
+    }
+
+    fn post_processing(nested: bool) -> IsochronePostProcessing {
+        IsochronePostProcessing {
+            min_hole_area: None,
+            simplify: None,
+            smoothing_iterations: None,
+            nested,
+            clip_boundary: None,
+            water_mask: None,
+        }
+    }
+
+    #[test]
+    fn test_nested_contains_smaller_bins() {
+        let pp = post_processing(true);
+        let mut smaller_bins = None;
+        let small = pp.apply(&square(1000.0), &mut smaller_bins).unwrap();
+        // a larger bin whose isochrone does not cover the smaller one
+        let shifted = square(500.0).map_coords(|Coord { x, y }| Coord { x: x + 0.01, y });
+        let large = pp.apply(&shifted, &mut smaller_bins).unwrap();
+        let uncovered = to_multipolygon(&small)
+            .unwrap()
+            .difference(&to_multipolygon(&large).unwrap());
+        let small_area = to_multipolygon(&small).unwrap().unsigned_area();
+        assert!(uncovered.unsigned_area() / small_area < 1e-6);
+    }
+
+    #[test]
+    fn test_min_hole_area_removes_small_holes() {
+        let Geometry::Polygon(outer) = square(1000.0) else {
+            panic!("expected polygon")
+        };
+        let Geometry::Polygon(hole) = square(10.0) else {
+            panic!("expected polygon")
+        };
+        let with_hole = Geometry::Polygon(Polygon::new(
+            outer.exterior().clone(),
+            vec![hole.exterior().clone()],
+        ));
+        let mut pp = post_processing(false);
+        pp.min_hole_area = Some(1000.0);
+        let result = pp.apply(&with_hole, &mut None).unwrap();
+        match result {
+            Geometry::MultiPolygon(mp) => assert!(mp.0[0].interiors().is_empty()),
+            other => panic!("expected MultiPolygon, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_empty_isochrone_stays_empty() {
+        let mut pp = post_processing(true);
+        pp.smoothing_iterations = Some(2);
+        let result = pp.apply(&Geometry::Polygon(polygon!()), &mut None).unwrap();
+        assert!(matches!(result, Geometry::Polygon(p) if p.exterior().0.is_empty()));
+    }
+
+    /// a circle with `n` vertices and a radius of roughly `meters` centered on (-105, 40)
+    fn circle(meters: f32, n: usize) -> Geometry<f32> {
+        let coords = (0..=n)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * (i % n) as f32 / n as f32;
+                Coord {
+                    x: -105.0 + meters / 85_000.0 * angle.cos(),
+                    y: 40.0 + meters / 111_000.0 * angle.sin(),
+                }
+            })
+            .collect::<Vec<_>>();
+        Geometry::Polygon(Polygon::new(geo::LineString::new(coords), vec![]))
+    }
+
+    fn area(geometry: &Geometry<f32>) -> f64 {
+        to_multipolygon(geometry).unwrap().unsigned_area()
+    }
+
+    fn write_file(dir: &tempfile::TempDir, name: &str, contents: &str) -> String {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).expect("test invariant failed");
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_read_polygons_wkt() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        let file = write_file(
+            &dir,
+            "study_area.txt",
+            "MULTIPOLYGON(((0 0,1 0,1 1,0 1,0 0)),((5 5,6 5,6 6,5 6,5 5)))",
+        );
+        let polygons = read_polygons(&file).expect("should read WKT polygons");
+        assert_eq!(polygons.0.len(), 2);
+        assert!((polygons.unsigned_area() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_read_polygons_geojson() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        let feature_collection = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {},
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0], [0.0, 0.0]]]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": {},
+                    "geometry": { "type": "Point", "coordinates": [9.0, 9.0] }
+                }
+            ]
+        });
+        let file = write_file(&dir, "water.geojson", &feature_collection.to_string());
+        let polygons = read_polygons(&file).expect("should read GeoJSON polygons");
+        assert_eq!(polygons.0.len(), 1);
+        assert!((polygons.unsigned_area() - 4.0).abs() < 1e-9);
+
+        // a bare geometry with a .json extension is read as GeoJSON too
+        let file = write_file(
+            &dir,
+            "water.json",
+            &feature_collection["features"][0]["geometry"].to_string(),
+        );
+        assert_eq!(read_polygons(&file).expect("should read").0.len(), 1);
+    }
+
+    #[test]
+    fn test_read_polygons_without_polygons() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        let file = write_file(&dir, "points.txt", "POINT(0 0)");
+        let error = read_polygons(&file).expect_err("points are not polygons");
+        assert!(error.starts_with("no polygons found"), "{error}");
+        let missing = dir.path().join("missing.txt").to_string_lossy().to_string();
+        assert!(read_polygons(&missing).is_err());
+    }
+
+    #[test]
+    fn test_clip_boundary_file() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        // the western half of the isochrone
+        let file = write_file(
+            &dir,
+            "study_area.txt",
+            "POLYGON((-106 39,-105 39,-105 41,-106 41,-106 39))",
+        );
+        let config = IsochronePostProcessingConfig {
+            clip_boundary_file: Some(file),
+            ..Default::default()
+        };
+        let pp = IsochronePostProcessing::try_from(&config).expect("should build");
+        let isochrone = square(1000.0);
+        let clipped = pp.apply(&isochrone, &mut None).unwrap();
+        let ratio = area(&clipped) / area(&isochrone);
+        assert!((ratio - 0.5).abs() < 1e-3, "ratio {ratio}");
+        let bounds = geo::BoundingRect::bounding_rect(&to_multipolygon(&clipped).unwrap())
+            .expect("clipped isochrone should not be empty");
+        assert!(bounds.max().x <= -105.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_water_mask_file() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        // water over the eastern half of the isochrone
+        let water = serde_json::json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[-105.0, 39.0], [-104.0, 39.0], [-104.0, 41.0], [-105.0, 41.0], [-105.0, 39.0]]]
+            }
+        });
+        let file = write_file(&dir, "water.geojson", &water.to_string());
+        let config = IsochronePostProcessingConfig {
+            water_mask_file: Some(file),
+            ..Default::default()
+        };
+        let pp = IsochronePostProcessing::try_from(&config).expect("should build");
+        let isochrone = square(1000.0);
+        let masked = pp.apply(&isochrone, &mut None).unwrap();
+        let ratio = area(&masked) / area(&isochrone);
+        assert!((ratio - 0.5).abs() < 1e-3, "ratio {ratio}");
+
+        // water covering the whole isochrone leaves an empty polygon
+        let result = pp
+            .apply(
+                &square(1000.0).map_coords(|Coord { x, y }| Coord { x: x + 0.5, y }),
+                &mut None,
+            )
+            .unwrap();
+        assert!(matches!(result, Geometry::Polygon(p) if p.exterior().0.is_empty()));
+    }
+
+    #[test]
+    fn test_missing_clip_file_fails_build() {
+        let config = IsochronePostProcessingConfig {
+            clip_boundary_file: Some(String::from("missing/study_area.txt")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            IsochronePostProcessing::try_from(&config),
+            Err(OutputPluginError::BuildFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_simplify_removes_vertices() {
+        let isochrone = circle(500.0, 200);
+        let n_coords = |g: &Geometry<f32>| g.coords_count();
+        for simplify in [
+            SimplifyConfig::DouglasPeucker {
+                tolerance: 20.0,
+                distance_unit: DistanceUnit::Meters,
+            },
+            SimplifyConfig::Visvalingam {
+                tolerance: 20.0,
+                distance_unit: DistanceUnit::Meters,
+            },
+        ] {
+            let config = IsochronePostProcessingConfig {
+                simplify: Some(simplify.clone()),
+                ..Default::default()
+            };
+            let pp = IsochronePostProcessing::try_from(&config).expect("should build");
+            let simplified = pp.apply(&isochrone, &mut None).unwrap();
+            assert!(
+                n_coords(&simplified) < n_coords(&isochrone) / 2,
+                "{simplify:?} kept {} of {} coordinates",
+                n_coords(&simplified),
+                n_coords(&isochrone)
+            );
+            let ratio = area(&simplified) / area(&isochrone);
+            assert!(
+                (ratio - 1.0).abs() < 0.05,
+                "{simplify:?} area ratio {ratio}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_simplify_tolerance_must_be_positive() {
+        let config = IsochronePostProcessingConfig {
+            simplify: Some(SimplifyConfig::DouglasPeucker {
+                tolerance: 0.0,
+                distance_unit: DistanceUnit::Meters,
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            IsochronePostProcessing::try_from(&config),
+            Err(OutputPluginError::BuildFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_smoothing_rounds_corners() {
+        let mut pp = post_processing(false);
+        pp.smoothing_iterations = Some(2);
+        let isochrone = square(1000.0);
+        let smoothed = pp.apply(&isochrone, &mut None).unwrap();
+        // each iteration replaces every vertex of the ring with two
+        assert_eq!(smoothed.coords_count(), 4 * 4 + 1);
+        let ratio = area(&smoothed) / area(&isochrone);
+        assert!(ratio < 1.0 && ratio > 0.7, "area ratio {ratio}");
+    }
+}
@@ -0,0 +1,48 @@
+use routee_compass_core::model::unit::DistanceUnit;
+use serde::{Deserialize, Serialize};
+
+/// optional steps applied to each isochrone after it is drawn. steps run in the
+/// order of the fields here: hole removal, simplification, smoothing, nesting and
+/// then clipping.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct IsochronePostProcessingConfig {
+    /// removes holes with an area below this value, in square meters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_hole_area: Option<f64>,
+    /// simplifies the isochrone boundary to some tolerance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simplify: Option<SimplifyConfig>,
+    /// number of iterations of Chaikin smoothing over the isochrone boundary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smoothing_iterations: Option<usize>,
+    /// if true, each isochrone is unioned with the isochrones of all smaller bins
+    /// so that the isochrone of each bin contains those of the bins before it.
+    #[serde(default)]
+    pub nested: bool,
+    /// file with polygons in WKT or GeoJSON (.json, .geojson) to clip isochrones to,
+    /// such as a study area boundary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clip_boundary_file: Option<String>,
+    /// file with polygons in WKT or GeoJSON (.json, .geojson) to remove from
+    /// isochrones, such as bodies of water.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water_mask_file: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum SimplifyConfig {
+    /// Douglas-Peucker simplification, which removes vertices closer than the
+    /// tolerance to the simplified boundary.
+    DouglasPeucker {
+        tolerance: f64,
+        distance_unit: DistanceUnit,
+    },
+    /// topology-preserving Visvalingam-Whyatt simplification, which removes vertices
+    /// whose triangle with their neighbors has an area below the tolerance squared.
+    Visvalingam {
+        tolerance: f64,
+        distance_unit: DistanceUnit,
+    },
+}
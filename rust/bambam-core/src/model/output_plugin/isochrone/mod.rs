@@ -2,8 +2,12 @@ mod geometry_model;
 mod geometry_model_config;
 mod isochrone_algorithm;
 mod isochrone_output_format;
+mod isochrone_post_processing;
+mod isochrone_post_processing_config;
 
 pub use geometry_model::GeometryModel;
 pub use geometry_model_config::GeometryModelConfig;
 pub use isochrone_algorithm::IsochroneAlgorithm;
 pub use isochrone_output_format::IsochroneOutputFormat;
+pub use isochrone_post_processing::{IsochronePostProcessing, Simplification};
+pub use isochrone_post_processing_config::{IsochronePostProcessingConfig, SimplifyConfig};
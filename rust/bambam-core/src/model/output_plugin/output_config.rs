@@ -6,6 +6,7 @@ use crate::model::{
     },
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// configures the granularity and algorithmic parameters for generating and
 /// recording opportunities.
@@ -23,6 +24,10 @@ pub enum BambamOutputConfig {
         geometry_model: GeometryModelConfig,
         /// algorithm used to draw isochrones from the destination points.
         isochrone_algorithm: IsochroneAlgorithm,
+        /// algorithms that replace `isochrone_algorithm` for some bins, keyed by the
+        /// bin's upper bound, such as `{ "10" = { type = "alpha_shape", ... } }`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bin_isochrone_algorithms: Option<HashMap<String, IsochroneAlgorithm>>,
         /// geometry format to use when writing isochrones.
         isochrone_format: IsochroneOutputFormat,
         /// location along a roadway where the opportunity is map matched. by default,
@@ -0,0 +1,125 @@
+use geo::{unary_union, Coord, CoordFloat, LineString, MultiPoint, MultiPolygon, Polygon};
+use num_traits::FromPrimitive;
+use spade::{DelaunayTriangulation, Point2, Triangulation};
+use uom::si::f64::Length;
+
+use super::buffer::{to_f64, LocalProjection};
+
+pub trait AlphaShape<F: CoordFloat + FromPrimitive> {
+    /// draws the alpha shape around a set of points. the points are triangulated
+    /// (Delaunay) and the triangles with a circumradius no greater than `alpha` are
+    /// unioned, which leaves gaps wherever the points are sparse. unlike a hull, the
+    /// result may be split into multiple polygons and may contain holes.
+    ///
+    /// points are expected in WGS84 lat/lon degrees and are triangulated in meters on
+    /// a local equirectangular projection, see [`super::buffer::Buffer`].
+    fn alpha_shape(&self, alpha: Length) -> Result<MultiPolygon<F>, String>;
+}
+
+impl<F: CoordFloat + FromPrimitive> AlphaShape<F> for MultiPoint<F> {
+    fn alpha_shape(&self, alpha: Length) -> Result<MultiPolygon<F>, String> {
+        let max_radius = alpha.get::<uom::si::length::meter>();
+        if max_radius <= 0.0 {
+            return Err(format!(
+                "alpha must be strictly positive, found {max_radius} meters"
+            ));
+        }
+        let coords = self
+            .iter()
+            .map(|p| to_f64(&p.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        let projection = match LocalProjection::new(coords.iter()) {
+            Some(p) => p,
+            None => return Ok(MultiPolygon::new(vec![])),
+        };
+        let vertices = coords
+            .iter()
+            .map(|c| {
+                let m = projection.to_meters(c);
+                Point2::new(m.x, m.y)
+            })
+            .collect::<Vec<_>>();
+        let triangulation = DelaunayTriangulation::<Point2<f64>>::bulk_load(vertices)
+            .map_err(|e| format!("failure triangulating points for alpha shape: {e}"))?;
+
+        let triangles = triangulation
+            .inner_faces()
+            .filter_map(|face| {
+                let [a, b, c] = face.positions().map(|p| Coord { x: p.x, y: p.y });
+                if circumradius(a, b, c) <= max_radius {
+                    Some(Polygon::new(LineString::new(vec![a, b, c, a]), vec![]))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let unioned = match triangles.len() {
+            0 | 1 => MultiPolygon::new(triangles),
+            _ => unary_union(triangles.iter()),
+        };
+
+        projection.multipolygon_to_degrees(unioned)
+    }
+}
+
+/// radius of the circle passing through all three corners of a triangle, which is
+/// infinite for a degenerate triangle.
+fn circumradius(a: Coord<f64>, b: Coord<f64>, c: Coord<f64>) -> f64 {
+    let ab = (b - a).x.hypot((b - a).y);
+    let bc = (c - b).x.hypot((c - b).y);
+    let ca = (a - c).x.hypot((a - c).y);
+    let double_area = ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs();
+    if double_area == 0.0 {
+        f64::INFINITY
+    } else {
+        (ab * bc * ca) / (2.0 * double_area)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AlphaShape;
+    use geo::{ChamberlainDuquetteArea, MultiPoint, Point};
+    use uom::si::{f64::Length, length::meter};
+
+    /// a 10x10 lattice of points 100 meters apart with the middle 4x4 removed
+    fn lattice_with_hole() -> MultiPoint<f64> {
+        let step = 100.0 / 111_195.0;
+        let points = (0..10)
+            .flat_map(|i| (0..10).map(move |j| (i, j)))
+            .filter(|(i, j)| !((3..7).contains(i) && (3..7).contains(j)))
+            .map(|(i, j)| Point::new(-105.0 + i as f64 * step, j as f64 * step))
+            .collect::<Vec<_>>();
+        MultiPoint::new(points)
+    }
+
+    #[test]
+    fn test_alpha_shape_has_hole() {
+        let points = lattice_with_hole();
+        let shape = points.alpha_shape(Length::new::<meter>(80.0)).unwrap();
+        assert_eq!(shape.0.len(), 1);
+        assert_eq!(shape.0[0].interiors().len(), 1);
+        // 900 x 900 meters, less the 500 x 500 meter hole
+        let area = shape.chamberlain_duquette_unsigned_area();
+        assert!((area / (900.0 * 900.0 - 500.0 * 500.0) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_alpha_shape_large_alpha_fills_hole() {
+        let points = lattice_with_hole();
+        let shape = points.alpha_shape(Length::new::<meter>(10_000.0)).unwrap();
+        assert_eq!(shape.0.len(), 1);
+        assert!(shape.0[0].interiors().is_empty());
+    }
+
+    #[test]
+    fn test_alpha_shape_collinear_is_empty() {
+        let points = MultiPoint::new(vec![
+            Point::new(-105.0, 40.0),
+            Point::new(-105.001, 40.0),
+            Point::new(-105.002, 40.0),
+        ]);
+        let shape = points.alpha_shape(Length::new::<meter>(100.0)).unwrap();
+        assert!(shape.0.is_empty());
+    }
+}
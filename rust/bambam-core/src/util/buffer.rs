@@ -0,0 +1,309 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use geo::{
+    unary_union, Coord, CoordFloat, Geometry, LineString, MapCoords, MultiLineString, MultiPoint,
+    MultiPolygon, Point, Polygon,
+};
+use num_traits::FromPrimitive;
+use uom::si::f64::Length;
+
+/// default distance between vertices along the curved edges of a buffer.
+pub const DEFAULT_BUFFER_STRIDE_METERS: f64 = 15.0;
+/// fewest vertices used to approximate a circle, which at least gives us a hexagon.
+pub const MIN_CIRCLE_RESOLUTION: usize = 6;
+/// most vertices used to approximate a circle, one every five degrees.
+pub const MAX_CIRCLE_RESOLUTION: usize = 72;
+
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+pub trait Buffer<F: CoordFloat + FromPrimitive> {
+    /// buffer a geometry up to some distance. GEOS uses a fancier method to determine
+    /// the resolution, but here, we simply assign points at 15 meter intervals along
+    /// the curve since that is our typical tolerance for spatial errors. or, if that
+    /// produces fewer than six points, we use six, since that at least gives us a hexagon.
+    ///
+    /// for GEOS' approach, see [this page](https://github.com/libgeos/geos/blob/main/src/operation/buffer/OffsetSegmentGenerator.cpp#L78)
+    /// where `filletAngleQuantum` is calculated and then used to set the `maxCurveSegmentError`
+    /// before building buffer segments.
+    fn buffer(&self, size: Length) -> Result<Geometry<F>, String> {
+        let stride = Length::new::<uom::si::length::meter>(DEFAULT_BUFFER_STRIDE_METERS);
+        self.buffer_with_stride(size, stride)
+    }
+
+    /// buffer a geometry up to some distance, placing vertices at `stride` intervals
+    /// along the curved edges of the buffer. linestrings are buffered with round caps
+    /// and joins, and overlapping buffers are unioned.
+    ///
+    /// geometries are expected in WGS84 lat/lon degrees. buffers are built in meters
+    /// on a local equirectangular projection centered on the geometry, which is accurate
+    /// at the scale of a city or region.
+    fn buffer_with_stride(&self, size: Length, stride: Length) -> Result<Geometry<F>, String>;
+}
+
+impl<F: CoordFloat + FromPrimitive> Buffer<F> for Point<F> {
+    fn buffer_with_stride(&self, size: Length, stride: Length) -> Result<Geometry<F>, String> {
+        let mp = buffer_paths(&[vec![self.0]], size, stride)?;
+        let polygon =
+            mp.0.into_iter()
+                .next()
+                .ok_or_else(|| String::from("buffering a point produced no polygon"))?;
+        Ok(Geometry::Polygon(polygon))
+    }
+}
+
+impl<F: CoordFloat + FromPrimitive> Buffer<F> for MultiPoint<F> {
+    fn buffer_with_stride(&self, size: Length, stride: Length) -> Result<Geometry<F>, String> {
+        let paths = self.iter().map(|p| vec![p.0]).collect::<Vec<_>>();
+        let mp = buffer_paths(&paths, size, stride)?;
+        Ok(Geometry::MultiPolygon(mp))
+    }
+}
+
+impl<F: CoordFloat + FromPrimitive> Buffer<F> for LineString<F> {
+    fn buffer_with_stride(&self, size: Length, stride: Length) -> Result<Geometry<F>, String> {
+        let mp = buffer_paths(&[self.0.clone()], size, stride)?;
+        Ok(Geometry::MultiPolygon(mp))
+    }
+}
+
+impl<F: CoordFloat + FromPrimitive> Buffer<F> for MultiLineString<F> {
+    fn buffer_with_stride(&self, size: Length, stride: Length) -> Result<Geometry<F>, String> {
+        let paths = self.iter().map(|l| l.0.clone()).collect::<Vec<_>>();
+        let mp = buffer_paths(&paths, size, stride)?;
+        Ok(Geometry::MultiPolygon(mp))
+    }
+}
+
+/// buffers a collection of paths, where a path with a single coordinate is a point,
+/// and unions the result.
+fn buffer_paths<F: CoordFloat + FromPrimitive>(
+    paths: &[Vec<Coord<F>>],
+    size: Length,
+    stride: Length,
+) -> Result<MultiPolygon<F>, String> {
+    let radius = size.get::<uom::si::length::meter>();
+    let stride_meters = stride.get::<uom::si::length::meter>();
+    if radius <= 0.0 {
+        return Err(format!(
+            "buffer size must be strictly positive, found {radius} meters"
+        ));
+    }
+    if stride_meters <= 0.0 {
+        return Err(format!(
+            "buffer stride must be strictly positive, found {stride_meters} meters"
+        ));
+    }
+    let resolution = circle_resolution(radius, stride_meters);
+
+    let paths_f64 = paths
+        .iter()
+        .map(|path| path.iter().map(to_f64).collect::<Result<Vec<_>, _>>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let projection = match LocalProjection::new(paths_f64.iter().flatten()) {
+        Some(p) => p,
+        None => return Ok(MultiPolygon::new(vec![])),
+    };
+
+    let mut polygons = vec![];
+    for path in paths_f64.iter() {
+        let meters = path
+            .iter()
+            .map(|c| projection.to_meters(c))
+            .collect::<Vec<_>>();
+        match meters[..] {
+            [] => {}
+            [c] => polygons.push(Polygon::new(circle(c, radius, resolution), vec![])),
+            _ => {
+                for pair in meters.windows(2) {
+                    let ring = capsule(pair[0], pair[1], radius, resolution);
+                    polygons.push(Polygon::new(ring, vec![]));
+                }
+            }
+        }
+    }
+    let unioned = match polygons.len() {
+        0 | 1 => MultiPolygon::new(polygons),
+        _ => unary_union(polygons.iter()),
+    };
+
+    projection.multipolygon_to_degrees(unioned)
+}
+
+/// number of vertices used to approximate a circle with vertices `stride` apart.
+/// rounded up to an even number so that round caps split evenly into two halves.
+fn circle_resolution(radius: f64, stride: f64) -> usize {
+    let circumference = 2.0 * PI * radius;
+    let n = (circumference / stride).ceil() as usize;
+    let n = n.clamp(MIN_CIRCLE_RESOLUTION, MAX_CIRCLE_RESOLUTION);
+    n + n % 2
+}
+
+/// circle around a coordinate, counter-clockwise.
+fn circle(center: Coord<f64>, radius: f64, resolution: usize) -> LineString<f64> {
+    let step = 2.0 * PI / resolution as f64;
+    let mut coords = (0..resolution)
+        .map(|i| offset(center, radius, i as f64 * step))
+        .collect::<Vec<_>>();
+    coords.push(coords[0]);
+    LineString::new(coords)
+}
+
+/// the buffer of a single line segment with round caps, counter-clockwise.
+fn capsule(a: Coord<f64>, b: Coord<f64>, radius: f64, resolution: usize) -> LineString<f64> {
+    if a == b {
+        return circle(a, radius, resolution);
+    }
+    let theta = (b.y - a.y).atan2(b.x - a.x);
+    let half = resolution / 2;
+    let step = PI / half as f64;
+    let mut coords = Vec::with_capacity(resolution + 3);
+    for (center, start) in [(b, theta - FRAC_PI_2), (a, theta + FRAC_PI_2)] {
+        for i in 0..=half {
+            coords.push(offset(center, radius, start + i as f64 * step));
+        }
+    }
+    coords.push(coords[0]);
+    LineString::new(coords)
+}
+
+fn offset(center: Coord<f64>, radius: f64, angle: f64) -> Coord<f64> {
+    Coord {
+        x: center.x + radius * angle.cos(),
+        y: center.y + radius * angle.sin(),
+    }
+}
+
+pub(crate) fn to_f64<F: CoordFloat>(c: &Coord<F>) -> Result<Coord<f64>, String> {
+    match (c.x.to_f64(), c.y.to_f64()) {
+        (Some(x), Some(y)) => Ok(Coord { x, y }),
+        _ => Err(String::from(
+            "failure converting coordinate to 64-bit float",
+        )),
+    }
+}
+
+/// an equirectangular projection from WGS84 degrees to meters around an origin.
+pub(crate) struct LocalProjection {
+    origin: Coord<f64>,
+    meters_per_degree_x: f64,
+    meters_per_degree_y: f64,
+}
+
+impl LocalProjection {
+    /// builds a projection centered on the bounding box of the coordinates, or None
+    /// if there are no coordinates.
+    pub(crate) fn new<'a>(coords: impl Iterator<Item = &'a Coord<f64>>) -> Option<Self> {
+        let (min, max) = coords.fold(None, |acc: Option<(Coord<f64>, Coord<f64>)>, c| {
+            Some(match acc {
+                None => (*c, *c),
+                Some((min, max)) => (
+                    Coord {
+                        x: min.x.min(c.x),
+                        y: min.y.min(c.y),
+                    },
+                    Coord {
+                        x: max.x.max(c.x),
+                        y: max.y.max(c.y),
+                    },
+                ),
+            })
+        })?;
+        let origin = Coord {
+            x: (min.x + max.x) / 2.0,
+            y: (min.y + max.y) / 2.0,
+        };
+        let meters_per_degree_y = EARTH_RADIUS_METERS * PI / 180.0;
+        let meters_per_degree_x = meters_per_degree_y * origin.y.to_radians().cos();
+        Some(Self {
+            origin,
+            meters_per_degree_x,
+            meters_per_degree_y,
+        })
+    }
+
+    pub(crate) fn to_meters(&self, c: &Coord<f64>) -> Coord<f64> {
+        Coord {
+            x: (c.x - self.origin.x) * self.meters_per_degree_x,
+            y: (c.y - self.origin.y) * self.meters_per_degree_y,
+        }
+    }
+
+    /// converts polygons in WGS84 degrees to meters.
+    pub(crate) fn multipolygon_to_meters(
+        &self,
+        multipolygon: &MultiPolygon<f64>,
+    ) -> MultiPolygon<f64> {
+        multipolygon.map_coords(|c| self.to_meters(&c))
+    }
+
+    /// converts polygons in meters back to WGS84 degrees.
+    pub(crate) fn multipolygon_to_degrees<F: CoordFloat + FromPrimitive>(
+        &self,
+        multipolygon: MultiPolygon<f64>,
+    ) -> Result<MultiPolygon<F>, String> {
+        multipolygon
+            .into_iter()
+            .map(|p| {
+                let (exterior, interiors) = p.into_inner();
+                let exterior = self.ring_to_degrees(&exterior)?;
+                let interiors = interiors
+                    .iter()
+                    .map(|r| self.ring_to_degrees(r))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Polygon::new(exterior, interiors))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map(MultiPolygon::new)
+    }
+
+    fn ring_to_degrees<F: CoordFloat + FromPrimitive>(
+        &self,
+        ring: &LineString<f64>,
+    ) -> Result<LineString<F>, String> {
+        ring.coords()
+            .map(|c| {
+                let x = self.origin.x + c.x / self.meters_per_degree_x;
+                let y = self.origin.y + c.y / self.meters_per_degree_y;
+                match (F::from_f64(x), F::from_f64(y)) {
+                    (Some(x), Some(y)) => Ok(Coord { x, y }),
+                    _ => Err(format!("failure converting buffer coordinate ({x}, {y})")),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(LineString::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Buffer;
+    use geo::{line_string, point, ChamberlainDuquetteArea, Geometry};
+    use std::f64::consts::PI;
+    use uom::si::{f64::Length, length::meter};
+
+    #[test]
+    fn test_buffer_point_and_linestring() {
+        let radius = Length::new::<meter>(100.0);
+        let stride = Length::new::<meter>(5.0);
+
+        let point = point!(x: -105.0, y: 40.0);
+        let circle = point.buffer_with_stride(radius, stride).unwrap();
+        let area = circle.chamberlain_duquette_unsigned_area();
+        assert!((area / (PI * 100.0 * 100.0) - 1.0).abs() < 0.01);
+
+        // two segments roughly 850 meters long that overlap at the joint
+        let linestring = line_string![
+            (x: -105.0, y: 40.0),
+            (x: -104.99, y: 40.0),
+            (x: -104.99, y: 40.0077)
+        ];
+        let buffered = linestring.buffer_with_stride(radius, stride).unwrap();
+        match &buffered {
+            Geometry::MultiPolygon(mp) => assert_eq!(mp.0.len(), 1),
+            other => panic!("expected MultiPolygon, found {other:?}"),
+        }
+        let area = buffered.chamberlain_duquette_unsigned_area();
+        let expected = 2.0 * 100.0 * (852.0 + 856.0) + PI * 100.0 * 100.0;
+        assert!((area / expected - 1.0).abs() < 0.02);
+    }
+}
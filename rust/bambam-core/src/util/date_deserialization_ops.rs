@@ -10,6 +10,13 @@ pub fn naive_date_to_str(date_str: &str) -> ParseResult<NaiveDate> {
     chrono::NaiveDate::parse_from_str(date_str, APP_DATE_FORMAT)
 }
 
+/// parses a datetime in the [`APP_DATETIME_FORMAT`], falling back to ISO 8601
+/// (`2025-09-01T09:00:00`), which is how a serialized [`NaiveDateTime`] is written.
+pub fn parse_naive_datetime(datetime_str: &str) -> ParseResult<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(datetime_str, APP_DATETIME_FORMAT)
+        .or_else(|e| datetime_str.parse::<NaiveDateTime>().map_err(|_| e))
+}
+
 pub fn deserialize_naive_datetime<'de, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
 where
     D: Deserializer<'de>,
@@ -27,3 +34,20 @@ where
     naive_date_to_str(&date_str)
         .map_err(|e| D::Error::custom(format!("Invalid datetime format: {e}")))
 }
+
+#[cfg(test)]
+mod test {
+    use super::parse_naive_datetime;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_parse_naive_datetime() {
+        let expected = NaiveDate::from_ymd_opt(2025, 9, 1)
+            .and_then(|d| d.and_hms_opt(9, 30, 0))
+            .expect("test invariant");
+        assert_eq!(parse_naive_datetime("2025-09-01 09:30:00"), Ok(expected));
+        assert_eq!(parse_naive_datetime("2025-09-01T09:30:00"), Ok(expected));
+        assert!(parse_naive_datetime("2025-09-01").is_err());
+        assert!(parse_naive_datetime("09:30:00").is_err());
+    }
+}
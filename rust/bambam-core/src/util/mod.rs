@@ -1,2 +1,4 @@
+pub mod alpha_shape;
+pub mod buffer;
 pub mod date_deserialization_ops;
 pub mod geo_utils;
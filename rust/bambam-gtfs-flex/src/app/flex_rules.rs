@@ -0,0 +1,294 @@
+use crate::model::GtfsFlexError;
+use crate::util::zone::{BookingType, FlexTripRules};
+use chrono::NaiveTime;
+use gtfs_structures::StopTime;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+pub const BOOKING_RULES_FILENAME: &str = "booking_rules.txt";
+pub const STOP_TIMES_FILENAME: &str = "stop_times.txt";
+
+/// booking and travel duration rules read directly from the text files of a
+/// GTFS-Flex archive, for the fields not exposed by the parsed [gtfs_structures::Gtfs].
+#[derive(Default, Debug)]
+pub struct FlexRulesLookup {
+    booking_rules: HashMap<String, BookingRuleRow>,
+    stop_times: HashMap<(String, u32), StopTimeRulesRow>,
+}
+
+/// row of `booking_rules.txt`
+#[derive(Deserialize, Debug)]
+struct BookingRuleRow {
+    booking_rule_id: String,
+    booking_type: i64,
+    prior_notice_duration_min: Option<i64>,
+    prior_notice_duration_max: Option<i64>,
+    prior_notice_last_day: Option<i64>,
+    prior_notice_last_time: Option<String>,
+}
+
+/// the GTFS-Flex booking and duration columns of `stop_times.txt`
+#[derive(Deserialize, Debug)]
+struct StopTimeRulesRow {
+    trip_id: String,
+    stop_sequence: u32,
+    pickup_booking_rule_id: Option<String>,
+    mean_duration_factor: Option<f64>,
+    mean_duration_offset: Option<f64>,
+    safe_duration_factor: Option<f64>,
+    safe_duration_offset: Option<f64>,
+}
+
+impl FlexRulesLookup {
+    /// reads `booking_rules.txt` and `stop_times.txt` from a GTFS-Flex archive.
+    /// archives without booking rules produce an empty lookup for those rules.
+    pub fn from_archive(path: &Path) -> Result<FlexRulesLookup, GtfsFlexError> {
+        let file = File::open(path).map_err(|error| GtfsFlexError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let mut archive = zip::ZipArchive::new(file).map_err(|error| GtfsFlexError::ZipRead {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        let booking_rules =
+            read_archive_file::<BookingRuleRow>(&mut archive, path, BOOKING_RULES_FILENAME)?
+                .into_iter()
+                .map(|row| (row.booking_rule_id.clone(), row))
+                .collect::<HashMap<_, _>>();
+        let stop_times =
+            read_archive_file::<StopTimeRulesRow>(&mut archive, path, STOP_TIMES_FILENAME)?
+                .into_iter()
+                .map(|row| ((row.trip_id.clone(), row.stop_sequence), row))
+                .collect::<HashMap<_, _>>();
+        log::info!(
+            "read {} booking rules and {} stop time rules from {:?}",
+            booking_rules.len(),
+            stop_times.len(),
+            path
+        );
+        Ok(FlexRulesLookup {
+            booking_rules,
+            stop_times,
+        })
+    }
+
+    /// collects the rules for a flex trip from its pickup (src) and drop-off (dst)
+    /// stop times. the booking rule is taken from the pickup. duration fields are
+    /// taken from the drop-off, falling back to the pickup.
+    pub fn get_trip_rules(
+        &self,
+        trip_id: &str,
+        src: &StopTime,
+        dst: &StopTime,
+    ) -> Result<FlexTripRules, GtfsFlexError> {
+        self.get_trip_rules_by_sequence(trip_id, src.stop_sequence, dst.stop_sequence)
+    }
+
+    /// collects the rules for a flex trip from the stop sequence numbers of its
+    /// pickup (src) and drop-off (dst) stop times.
+    fn get_trip_rules_by_sequence(
+        &self,
+        trip_id: &str,
+        src_stop_sequence: u32,
+        dst_stop_sequence: u32,
+    ) -> Result<FlexTripRules, GtfsFlexError> {
+        let src_row = self
+            .stop_times
+            .get(&(trip_id.to_string(), src_stop_sequence));
+        let dst_row = self
+            .stop_times
+            .get(&(trip_id.to_string(), dst_stop_sequence));
+
+        let booking_rule = src_row
+            .and_then(|r| r.pickup_booking_rule_id.as_ref())
+            .and_then(|id| {
+                let rule = self.booking_rules.get(id);
+                if rule.is_none() {
+                    log::warn!("trip {trip_id} references missing booking rule {id}");
+                }
+                rule
+            });
+
+        let mut rules = FlexTripRules::default();
+        if let Some(rule) = booking_rule {
+            rules.booking_type = BookingType::from_gtfs_value(rule.booking_type);
+            rules.prior_notice_duration_min = rule.prior_notice_duration_min;
+            rules.prior_notice_duration_max = rule.prior_notice_duration_max;
+            rules.prior_notice_last_day = rule.prior_notice_last_day;
+            rules.prior_notice_last_time = rule
+                .prior_notice_last_time
+                .as_deref()
+                .map(parse_gtfs_time)
+                .transpose()?;
+        }
+        let duration_field = |f: fn(&StopTimeRulesRow) -> Option<f64>| {
+            dst_row.and_then(f).or_else(|| src_row.and_then(f))
+        };
+        rules.mean_duration_factor = duration_field(|r| r.mean_duration_factor);
+        rules.mean_duration_offset = duration_field(|r| r.mean_duration_offset);
+        rules.safe_duration_factor = duration_field(|r| r.safe_duration_factor);
+        rules.safe_duration_offset = duration_field(|r| r.safe_duration_offset);
+        Ok(rules)
+    }
+}
+
+/// deserializes every row of a file in the archive, or returns no rows if the
+/// archive does not contain the file. feeds may nest files in a directory.
+fn read_archive_file<T: serde::de::DeserializeOwned>(
+    archive: &mut zip::ZipArchive<File>,
+    path: &Path,
+    filename: &str,
+) -> Result<Vec<T>, GtfsFlexError> {
+    let entry_name = archive
+        .file_names()
+        .find(|name| *name == filename || name.ends_with(&format!("/{filename}")))
+        .map(String::from);
+    let entry_name = match entry_name {
+        Some(name) => name,
+        None => {
+            log::debug!("archive {path:?} has no {filename}");
+            return Ok(vec![]);
+        }
+    };
+    let entry = archive
+        .by_name(&entry_name)
+        .map_err(|error| GtfsFlexError::ZipRead {
+            path: path.join(&entry_name),
+            error,
+        })?;
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(entry);
+    reader
+        .deserialize::<T>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| GtfsFlexError::CsvRead {
+            path: path.join(&entry_name),
+            error,
+        })
+}
+
+/// parses a GTFS time (HH:MM:SS) as a time of day. GTFS times are measured from
+/// noon minus 12h of the service day and may be 24:00:00 or later, in which case
+/// they wrap around to the time of day on the following day.
+fn parse_gtfs_time(value: &str) -> Result<NaiveTime, GtfsFlexError> {
+    let invalid = |reason: &str| {
+        GtfsFlexError::Chrono(format!("invalid GTFS time of day '{value}': {reason}"))
+    };
+    let fields = value
+        .trim()
+        .split(':')
+        .map(|f| f.parse::<u32>().map_err(|e| invalid(&e.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [hours, minutes, seconds] = fields[..] else {
+        return Err(invalid("expected HH:MM:SS"));
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return Err(invalid("minutes and seconds must be less than 60"));
+    }
+    NaiveTime::from_hms_opt(hours % 24, minutes, seconds)
+        .ok_or_else(|| invalid("time out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_gtfs_time, FlexRulesLookup};
+    use crate::util::zone::BookingType;
+    use chrono::NaiveTime;
+    use std::path::PathBuf;
+
+    fn test_archive(filename: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("test")
+            .join("assets")
+            .join("flex")
+            .join(filename)
+    }
+
+    fn time(h: u32, m: u32, s: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, s).expect("test invariant")
+    }
+
+    #[test]
+    fn test_parse_gtfs_time() {
+        assert_eq!(parse_gtfs_time("15:00:00").ok(), Some(time(15, 0, 0)));
+        assert_eq!(parse_gtfs_time("7:05:30").ok(), Some(time(7, 5, 30)));
+    }
+
+    #[test]
+    fn test_parse_gtfs_time_past_midnight() {
+        assert_eq!(parse_gtfs_time("24:00:00").ok(), Some(time(0, 0, 0)));
+        assert_eq!(parse_gtfs_time("25:30:15").ok(), Some(time(1, 30, 15)));
+        assert_eq!(parse_gtfs_time("47:59:59").ok(), Some(time(23, 59, 59)));
+    }
+
+    #[test]
+    fn test_parse_gtfs_time_invalid() {
+        for value in [
+            "",
+            "12:00",
+            "12:60:00",
+            "12:00:60",
+            "ab:00:00",
+            "12:00:00:00",
+        ] {
+            assert!(parse_gtfs_time(value).is_err(), "'{value}' should fail");
+        }
+    }
+
+    #[test]
+    fn test_from_archive_prior_day_booking() {
+        let path = test_archive("on-demand-services-within-a-single-zone.zip");
+        let lookup = FlexRulesLookup::from_archive(&path).expect("test invariant");
+        assert_eq!(lookup.booking_rules.len(), 1);
+        assert_eq!(lookup.stop_times.len(), 8);
+
+        let rules = lookup
+            .get_trip_rules_by_sequence("t_5374944_b_77497_tn_0", 1, 2)
+            .expect("test invariant");
+        assert_eq!(rules.booking_type, Some(BookingType::PriorDay));
+        assert_eq!(rules.prior_notice_last_day, Some(1));
+        assert_eq!(rules.prior_notice_last_time, Some(time(15, 0, 0)));
+        assert_eq!(rules.prior_notice_duration_min, None);
+        assert_eq!(rules.mean_duration_factor, Some(1.0));
+        assert_eq!(rules.mean_duration_offset, Some(30.0));
+        assert_eq!(rules.safe_duration_factor, Some(1.0));
+        assert_eq!(rules.safe_duration_offset, Some(60.0));
+    }
+
+    #[test]
+    fn test_from_archive_same_day_booking() {
+        let path = test_archive("on-demand-services-between-multiple-zones-river-valley.zip");
+        let lookup = FlexRulesLookup::from_archive(&path).expect("test invariant");
+        let rules = lookup
+            .get_trip_rules_by_sequence("t_5298036_b_77503_tn_0", 1, 2)
+            .expect("test invariant");
+        assert_eq!(rules.booking_type, Some(BookingType::SameDay));
+        assert_eq!(rules.prior_notice_duration_min, Some(60));
+        assert_eq!(rules.prior_notice_duration_max, Some(1440));
+        assert_eq!(rules.prior_notice_last_time, None);
+        assert_eq!(rules.mean_duration_factor, None);
+        assert_eq!(rules.safe_duration_offset, None);
+    }
+
+    #[test]
+    fn test_unknown_trip_has_empty_rules() {
+        let path = test_archive("on-demand-services-within-a-single-zone.zip");
+        let lookup = FlexRulesLookup::from_archive(&path).expect("test invariant");
+        let rules = lookup
+            .get_trip_rules_by_sequence("not_a_trip", 1, 2)
+            .expect("test invariant");
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn test_from_archive_missing_file_fails() {
+        let path = test_archive("not-an-archive.zip");
+        assert!(FlexRulesLookup::from_archive(&path).is_err());
+    }
+}
@@ -29,7 +29,8 @@ pub struct GtfsFLexCliArguments {
     /// location to write the resulting dataset.
     pub output_directory: String,
 
-    /// date for which to process GTFS-Flex feeds (format: YYYYMMDD)
+    /// service date(s) for which to process GTFS-Flex feeds. accepts a single date
+    /// (YYYYMMDD), an inclusive range (YYYYMMDD:YYYYMMDD), or a comma-separated list of either.
     pub date_requested: String,
 }
 
@@ -1,14 +1,17 @@
+use crate::app::flex_rules::FlexRulesLookup;
 use crate::model::consts;
 use crate::model::GtfsFlexError;
 use crate::util::zone::ZoneGeometry;
 use crate::util::zone::ZoneId;
 use crate::util::zone::ZoneRecord;
 use chrono::Datelike;
+use chrono::NaiveDate;
 use chrono::NaiveTime;
 use geozero::ToWkt;
 use gtfs_structures::Location;
-use gtfs_structures::{Calendar, Gtfs, PickupDropOffType, StopTime, Trip};
+use gtfs_structures::{Calendar, Exception, Gtfs, PickupDropOffType, StopTime, Trip};
 use kdam::tqdm;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
@@ -111,8 +114,17 @@ pub fn process_gtfs_flex_bundle(
     // discover gtfs-flex feeds
     discover_gtfs_flex_feeds(flex_directory_path)?;
 
+    // expand the requested dates, ranges and lists into service dates
+    let dates = parse_requested_dates(date_requested)?;
+    log::info!(
+        "processing {} service date(s) from {} to {}",
+        dates.len(),
+        dates.first().map(|d| d.to_string()).unwrap_or_default(),
+        dates.last().map(|d| d.to_string()).unwrap_or_default()
+    );
+
     // process files in each feed
-    let gtfs_flex_dataset = process_flex_files(flex_directory_path, date_requested)?;
+    let gtfs_flex_dataset = process_flex_files(flex_directory_path, &dates)?;
 
     gtfs_flex_dataset.write(out_directory_path)?;
 
@@ -156,10 +168,10 @@ pub fn discover_gtfs_flex_feeds(flex_directory_path: &Path) -> Result<(), GtfsFl
 }
 
 /// iterate over gtfs-flex feeds and process files from each feed
-/// return valid zones for the requested date
+/// return valid zones for each requested date
 pub fn process_flex_files(
     flex_directory_path: &Path,
-    date_requested: &str,
+    dates: &[NaiveDate],
 ) -> Result<GtfsFlexDataset, GtfsFlexError> {
     log::info!("Processing GTFS-Flex feeds in {:?}", flex_directory_path);
 
@@ -193,8 +205,11 @@ pub fn process_flex_files(
                 error,
             })?;
 
-            // process files for requested date and time and get valid zones
-            let archive_dataset = process_archive(&gtfs, date_requested, &feed_name, idx)?;
+            // GTFS-Flex booking rules and trip durations read from the archive text files
+            let rules = FlexRulesLookup::from_archive(&path)?;
+
+            // process files for each requested date and get valid zones
+            let archive_dataset = process_archive(&gtfs, &rules, dates, &feed_name, idx)?;
             log::info!(
                 "BAMBAM dataset created with {} records",
                 archive_dataset.records.len()
@@ -208,22 +223,42 @@ pub fn process_flex_files(
     Ok(dataset)
 }
 
-/// process calender, trips, routes, and stop_times files for the requested date and time
-pub fn process_archive(
-    gtfs: &Gtfs,
-    date_requested: &str,
-    feed_name: &str,
-    archive_idx: usize,
-) -> Result<GtfsFlexDataset, GtfsFlexError> {
-    // parse requested date
-    let date = chrono::NaiveDate::parse_from_str(date_requested, "%Y%m%d").map_err(|e| {
-        let msg = format!("user date request is invalid: {e}");
-        GtfsFlexError::Runtime(msg)
-    })?;
-
-    log::info!("requested date: {:?}", date);
+/// parses the dates requested by the user. accepts a single date (YYYYMMDD), an
+/// inclusive date range (YYYYMMDD:YYYYMMDD), or a comma-separated list of either.
+/// the result is sorted and free of duplicates.
+pub fn parse_requested_dates(date_requested: &str) -> Result<Vec<NaiveDate>, GtfsFlexError> {
+    let parse = |value: &str| {
+        NaiveDate::parse_from_str(value.trim(), consts::SERVICE_DATE_FORMAT).map_err(|e| {
+            let msg = format!("user date request '{value}' is invalid: {e}");
+            GtfsFlexError::Runtime(msg)
+        })
+    };
+    let mut dates = BTreeSet::new();
+    for entry in date_requested.split(',').filter(|e| !e.trim().is_empty()) {
+        match entry.split_once(':') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if end < start {
+                    let msg = format!("user date range '{entry}' ends before it starts");
+                    return Err(GtfsFlexError::Runtime(msg));
+                }
+                dates.extend(start.iter_days().take_while(|d| *d <= end));
+            }
+            None => {
+                dates.insert(parse(entry)?);
+            }
+        }
+    }
+    if dates.is_empty() {
+        let msg = format!("user date request '{date_requested}' contains no dates");
+        return Err(GtfsFlexError::Runtime(msg));
+    }
+    Ok(dates.into_iter().collect())
+}
 
-    // filter calendar for the requested date
+/// finds the service ids active on a date from the weekly schedules in calendar.txt,
+/// with the added and removed service exceptions in calendar_dates.txt applied.
+fn active_service_ids(gtfs: &Gtfs, date: &NaiveDate) -> HashSet<String> {
     let weekday = match date.weekday() {
         chrono::Weekday::Mon => |c: &Calendar| c.monday,
         chrono::Weekday::Tue => |c: &Calendar| c.tuesday,
@@ -233,20 +268,40 @@ pub fn process_archive(
         chrono::Weekday::Sat => |c: &Calendar| c.saturday,
         chrono::Weekday::Sun => |c: &Calendar| c.sunday,
     };
-    log::info!("requested day: {:?}", date.weekday());
 
-    let active_service_ids: Vec<&str> = gtfs
+    let mut active: HashSet<String> = gtfs
         .calendar
         .values()
-        .filter(|c| weekday(c) && c.start_date <= date && date <= c.end_date)
-        .map(|c| c.id.as_str())
+        .filter(|c| weekday(c) && c.start_date <= *date && *date <= c.end_date)
+        .map(|c| c.id.clone())
         .collect();
 
-    log::info!(
-        "ServiceIds active on date {date_requested}: [{}]",
-        active_service_ids.join(", ")
-    );
+    for (service_id, exceptions) in gtfs.calendar_dates.iter() {
+        for exception in exceptions.iter().filter(|e| e.date == *date) {
+            match exception.exception_type {
+                Exception::Added => {
+                    active.insert(service_id.clone());
+                }
+                Exception::Deleted => {
+                    active.remove(service_id);
+                }
+            }
+        }
+    }
+    active
+}
 
+/// process calender, calendar dates, trips, routes, and stop_times files for each requested date.
+/// each record carries the service date it was found on along with the booking rules and
+/// trip duration fields of its trip. zone ids and geometries shared across dates are
+/// only written once.
+pub fn process_archive(
+    gtfs: &Gtfs,
+    rules: &FlexRulesLookup,
+    dates: &[NaiveDate],
+    feed_name: &str,
+    archive_idx: usize,
+) -> Result<GtfsFlexDataset, GtfsFlexError> {
     // 1. Map route_id -> agency_id (fallback to archive_idx if missing)
     // at the end, route_to_agency contains ALL AgencyIds referenced by
     let default_agency_id = format!("archive{archive_idx}");
@@ -261,88 +316,129 @@ pub fn process_archive(
         }
     }
 
-    // filter trips by active service_ids and map trip_id -> agency_id
-    let mut trip_to_agency: HashMap<&str, String> = HashMap::new();
-    let active_trips: Vec<&Trip> = gtfs
-        .trips
-        .values()
-        .filter(|t| active_service_ids.contains(&t.service_id.as_str()))
-        .inspect(|t| {
-            if let Some(agency_id) = route_to_agency.get(t.route_id.as_str()) {
-                trip_to_agency.insert(&t.id, agency_id.to_string());
-            }
-        })
-        .collect();
-
     // create each output dataset using a fully-qualified ZoneId identifier for each record.
     let mut records = vec![];
     let mut geometries = vec![];
     let mut all_zone_ids = HashSet::new();
-    for trip in active_trips.into_iter() {
-        match trip.stop_times.as_slice() {
-            [src, dst] if valid_flex_trip_stops(src, dst) => {
-                let resolved_agency_id = trip_to_agency.get(&trip.id.as_str())
-                    .cloned()
-                    .ok_or_else(|| {
-                        let msg = format!("after ensuring bijection from routes to agencies, found trip {} had no agency", trip.id);
-                        GtfsFlexError::Internal(msg)
-                    })?;
-                let route_id = &trip.route_id;
-                let trip_id = &trip.id;
-                let (src_loc, dst_loc) = get_locations(src, dst, trip)?;
-                let src_loc_id = &src_loc.id;
-                let dst_loc_id = &dst_loc.id;
-                let src_zone_id =
-                    ZoneId::from_full_namespace(&resolved_agency_id, route_id, trip_id, src_loc_id);
-                let dst_zone_id =
-                    ZoneId::from_full_namespace(&resolved_agency_id, route_id, trip_id, dst_loc_id);
-                let start_pickup_drop_off_window = src
-                    .start_pickup_drop_off_window
-                    .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, 0));
-                let end_pickup_drop_off_window = dst
-                    .end_pickup_drop_off_window
-                    .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, 0));
-                let src_geometry = match &src_loc.geometry {
-                    gtfs_structures::LocationGeometry::Polygon(geometry) => geometry,
-                    gtfs_structures::LocationGeometry::MultiPolygon(geometry) => geometry,
-                };
-                let geom: geo_types::Geometry<f64> = src_geometry.try_into().map_err(|e| {
-                    let msg = format!("failed to parse GeoJSON geometry into Geo geometry: {e}");
-                    GtfsFlexError::Runtime(msg)
-                })?;
-
-                let wkt_str = geom
-                    .to_wkt()
-                    .map_err(|e| GtfsFlexError::Runtime(format!("WKT error: {e}")))?;
-
-                records.push(ZoneRecord {
-                    agency_id: resolved_agency_id,
-                    feed: feed_name.to_string(),
-                    requested_date: date_requested.to_string(),
-                    trip_id: trip.id.clone(),
-                    origin_zone: src_zone_id.clone(),
-                    start_pickup_drop_off_window,
-                    end_pickup_drop_off_window,
-                    destination_zone: dst_zone_id.clone(),
-                });
-
-                all_zone_ids.insert(src_zone_id.clone());
-                all_zone_ids.insert(dst_zone_id.clone());
-
-                geometries.push(ZoneGeometry {
-                    zone_id: src_zone_id.clone(),
-                    geometry: wkt_str,
-                });
-            }
-            [_src, _dst] => {
-                log::debug!("GTFS-Flex Trip {} has 2 StopTime entries but not valid_flex_trip_stops, skipping.", trip.id)
-            }
-            other => {
-                log::warn!(
-                    "GTFS-Flex Trip {} has {} StopTime entries, assumed should always be 2.",
-                    trip.id,
-                    other.len()
-                );
+    let mut geometry_zone_ids = HashSet::new();
+    for date in dates.iter() {
+        let requested_date = date.format(consts::SERVICE_DATE_FORMAT).to_string();
+        log::info!("requested date: {:?} ({:?})", date, date.weekday());
+
+        let active_service_ids = active_service_ids(gtfs, date);
+        let mut active_service_ids_sorted = active_service_ids.iter().collect::<Vec<_>>();
+        active_service_ids_sorted.sort();
+        log::info!(
+            "ServiceIds active on date {requested_date}: [{}]",
+            active_service_ids_sorted
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        // filter trips by active service_ids and map trip_id -> agency_id
+        let mut trip_to_agency: HashMap<&str, String> = HashMap::new();
+        let active_trips: Vec<&Trip> = gtfs
+            .trips
+            .values()
+            .filter(|t| active_service_ids.contains(&t.service_id))
+            .inspect(|t| {
+                if let Some(agency_id) = route_to_agency.get(t.route_id.as_str()) {
+                    trip_to_agency.insert(&t.id, agency_id.to_string());
+                }
+            })
+            .collect();
+
+        for trip in active_trips.into_iter() {
+            match trip.stop_times.as_slice() {
+                [src, dst] if valid_flex_trip_stops(src, dst) => {
+                    let resolved_agency_id = trip_to_agency.get(&trip.id.as_str())
+                        .cloned()
+                        .ok_or_else(|| {
+                            let msg = format!("after ensuring bijection from routes to agencies, found trip {} had no agency", trip.id);
+                            GtfsFlexError::Internal(msg)
+                        })?;
+                    let route_id = &trip.route_id;
+                    let trip_id = &trip.id;
+                    let (src_loc, dst_loc) = get_locations(src, dst, trip)?;
+                    let src_loc_id = &src_loc.id;
+                    let dst_loc_id = &dst_loc.id;
+                    let src_zone_id = ZoneId::from_full_namespace(
+                        &resolved_agency_id,
+                        route_id,
+                        trip_id,
+                        src_loc_id,
+                    );
+                    let dst_zone_id = ZoneId::from_full_namespace(
+                        &resolved_agency_id,
+                        route_id,
+                        trip_id,
+                        dst_loc_id,
+                    );
+                    let start_pickup_drop_off_window = src
+                        .start_pickup_drop_off_window
+                        .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, 0));
+                    let end_pickup_drop_off_window = dst
+                        .end_pickup_drop_off_window
+                        .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, 0));
+                    let trip_rules = rules.get_trip_rules(trip_id, src, dst)?;
+
+                    records.push(ZoneRecord {
+                        agency_id: resolved_agency_id,
+                        feed: feed_name.to_string(),
+                        requested_date: requested_date.clone(),
+                        trip_id: trip.id.clone(),
+                        origin_zone: src_zone_id.clone(),
+                        start_pickup_drop_off_window,
+                        end_pickup_drop_off_window,
+                        destination_zone: dst_zone_id.clone(),
+                        booking_type: trip_rules.booking_type,
+                        prior_notice_duration_min: trip_rules.prior_notice_duration_min,
+                        prior_notice_duration_max: trip_rules.prior_notice_duration_max,
+                        prior_notice_last_day: trip_rules.prior_notice_last_day,
+                        prior_notice_last_time: trip_rules.prior_notice_last_time,
+                        mean_duration_factor: trip_rules.mean_duration_factor,
+                        mean_duration_offset: trip_rules.mean_duration_offset,
+                        safe_duration_factor: trip_rules.safe_duration_factor,
+                        safe_duration_offset: trip_rules.safe_duration_offset,
+                    });
+
+                    all_zone_ids.insert(src_zone_id.clone());
+                    all_zone_ids.insert(dst_zone_id.clone());
+
+                    // the same trip may be active on many dates, but its zone is only written once
+                    if geometry_zone_ids.insert(src_zone_id.clone()) {
+                        let src_geometry = match &src_loc.geometry {
+                            gtfs_structures::LocationGeometry::Polygon(geometry) => geometry,
+                            gtfs_structures::LocationGeometry::MultiPolygon(geometry) => geometry,
+                        };
+                        let geom: geo_types::Geometry<f64> =
+                            src_geometry.try_into().map_err(|e| {
+                                let msg = format!(
+                                    "failed to parse GeoJSON geometry into Geo geometry: {e}"
+                                );
+                                GtfsFlexError::Runtime(msg)
+                            })?;
+                        let wkt_str = geom
+                            .to_wkt()
+                            .map_err(|e| GtfsFlexError::Runtime(format!("WKT error: {e}")))?;
+                        geometries.push(ZoneGeometry {
+                            zone_id: src_zone_id.clone(),
+                            geometry: wkt_str,
+                        });
+                    }
+                }
+                [_src, _dst] => {
+                    log::debug!("GTFS-Flex Trip {} has 2 StopTime entries but not valid_flex_trip_stops, skipping.", trip.id)
+                }
+                other => {
+                    log::warn!(
+                        "GTFS-Flex Trip {} has {} StopTime entries, assumed should always be 2.",
+                        trip.id,
+                        other.len()
+                    );
+                }
             }
         }
     }
@@ -386,3 +482,106 @@ fn get_locations(
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{active_service_ids, parse_requested_dates};
+    use chrono::NaiveDate;
+    use gtfs_structures::Gtfs;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).expect("test invariant")
+    }
+
+    fn river_valley() -> Gtfs {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("test")
+            .join("assets")
+            .join("flex")
+            .join("on-demand-services-between-multiple-zones-river-valley.zip");
+        Gtfs::from_path(&path).expect("test invariant")
+    }
+
+    #[test]
+    fn test_parse_single_date() {
+        let dates = parse_requested_dates("20240315").expect("test invariant");
+        assert_eq!(dates, vec![date(2024, 3, 15)]);
+    }
+
+    #[test]
+    fn test_parse_date_range() {
+        let dates = parse_requested_dates("20240228:20240302").expect("test invariant");
+        let expected = vec![
+            date(2024, 2, 28),
+            date(2024, 2, 29),
+            date(2024, 3, 1),
+            date(2024, 3, 2),
+        ];
+        assert_eq!(dates, expected);
+        let dates = parse_requested_dates("20240301:20240301").expect("test invariant");
+        assert_eq!(dates, vec![date(2024, 3, 1)]);
+    }
+
+    #[test]
+    fn test_parse_date_list() {
+        // entries are sorted, deduplicated and may mix dates and ranges
+        let dates =
+            parse_requested_dates("20240310, 20240301:20240302,20240301,").expect("test invariant");
+        let expected = vec![date(2024, 3, 1), date(2024, 3, 2), date(2024, 3, 10)];
+        assert_eq!(dates, expected);
+    }
+
+    #[test]
+    fn test_parse_reversed_range_fails() {
+        assert!(parse_requested_dates("20240302:20240301").is_err());
+    }
+
+    #[test]
+    fn test_parse_malformed_dates_fail() {
+        for value in [
+            "",
+            ",",
+            "2024-03-01",
+            "20240230",
+            "20240301:",
+            ":20240301",
+            "20240301:20240302:20240303",
+            "20240301,tomorrow",
+        ] {
+            assert!(
+                parse_requested_dates(value).is_err(),
+                "'{value}' should fail"
+            );
+        }
+    }
+
+    #[test]
+    fn test_active_service_ids_by_weekday() {
+        let gtfs = river_valley();
+        // tuesday
+        let active = active_service_ids(&gtfs, &date(2024, 12, 24));
+        assert_eq!(
+            active,
+            HashSet::from([String::from("c_67301_b_77503_d_31")])
+        );
+        // saturday
+        let active = active_service_ids(&gtfs, &date(2024, 12, 28));
+        assert_eq!(
+            active,
+            HashSet::from([String::from("c_67301_b_77503_d_32")])
+        );
+        // outside of the calendar date range
+        assert!(active_service_ids(&gtfs, &date(2025, 3, 4)).is_empty());
+    }
+
+    #[test]
+    fn test_active_service_ids_removal_overrides_calendar() {
+        let gtfs = river_valley();
+        // christmas day is a wednesday with weekday service removed in calendar_dates.txt
+        assert!(active_service_ids(&gtfs, &date(2024, 12, 25)).is_empty());
+        assert!(active_service_ids(&gtfs, &date(2024, 7, 4)).is_empty());
+    }
+}
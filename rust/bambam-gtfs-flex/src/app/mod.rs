@@ -1,3 +1,4 @@
+pub mod flex_rules;
 mod gtfs_flex_cli;
 
 pub use gtfs_flex_cli::{Cli, Commands};
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use bambam_core::model::state::multimodal_state_ops;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use routee_compass_core::model::{
     constraint::{ConstraintModel, ConstraintModelError},
     network::{Edge, Vertex},
@@ -18,11 +18,18 @@ use crate::{
 pub struct GtfsFlexDepartureConstraintModel {
     lookup: Arc<ZoneLookup>,
     params: GtfsFlexParams,
+    /// service date whose schedules apply to this query
+    service_date: Option<NaiveDate>,
 }
 
 impl GtfsFlexDepartureConstraintModel {
     pub fn new(lookup: Arc<ZoneLookup>, params: GtfsFlexParams) -> Self {
-        Self { lookup, params }
+        let service_date = lookup.pick_service_date(&params.start_time);
+        Self {
+            lookup,
+            params,
+            service_date,
+        }
     }
 }
 
@@ -48,7 +55,12 @@ impl ConstraintModel for GtfsFlexDepartureConstraintModel {
         let current_time = current_datetime(self.params.start_time, state, state_model)?;
         let lookup_result = current_zone(&self.lookup, ctx.dst)?;
         let is_valid = match &lookup_result {
-            Some(src_zone_id) => is_valid_departure(&self.lookup, src_zone_id, &current_time),
+            Some(src_zone_id) => is_valid_departure(
+                &self.lookup,
+                src_zone_id,
+                self.service_date.as_ref(),
+                &current_time,
+            ),
             None => Ok(false),
         }?;
         log::debug!(
@@ -106,10 +118,11 @@ fn current_zone(lookup: &ZoneLookup, dst: &Vertex) -> Result<Option<ZoneId>, Con
 fn is_valid_departure(
     lookup: &ZoneLookup,
     src_zone_id: &ZoneId,
+    service_date: Option<&NaiveDate>,
     current_time: &NaiveDateTime,
 ) -> Result<bool, ConstraintModelError> {
     lookup
-        .valid_departure(src_zone_id, current_time)
+        .valid_departure(src_zone_id, service_date, current_time)
         .map_err(|e| {
             let msg =
                 format!("while validating frontier for gtfs-flex trip via ZoneGraph lookup, {e}");
@@ -9,3 +9,6 @@ pub const GEOMETRIES_FILENAME: &str = "geometries.csv.gz";
 
 /// string used to name this travel mode in the label, constraint and traversal models.
 pub const MODE_NAME: &str = "gtfs-flex";
+
+/// format of the service dates requested at import and stored on each zone record.
+pub const SERVICE_DATE_FORMAT: &str = "%Y%m%d";
@@ -32,6 +32,13 @@ pub enum GtfsFlexError {
     Internal(String),
     #[error("error writing {path}: {error}")]
     CsvWrite { path: PathBuf, error: csv::Error },
+    #[error("error reading {path}: {error}")]
+    CsvRead { path: PathBuf, error: csv::Error },
+    #[error("error reading archive {path}: {error}")]
+    ZipRead {
+        path: PathBuf,
+        error: zip::result::ZipError,
+    },
     #[error("error writing {path}: {error}")]
     IoWrite { path: PathBuf, error: io::Error },
     #[error("failure while working with geometry for zone_id {zone_id}: {error}")]
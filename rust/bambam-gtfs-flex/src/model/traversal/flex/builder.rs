@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
-use super::GtfsFlexService;
-use crate::util::zone::{ZoneLookup, ZoneLookupConfig};
+use super::{GtfsFlexConfig, GtfsFlexService};
+use crate::util::zone::ZoneLookup;
 
 use routee_compass_core::model::traversal::{
     TraversalModelBuilder, TraversalModelError, TraversalModelService,
@@ -14,15 +14,22 @@ impl TraversalModelBuilder for GtfsFlexBuilder {
         &self,
         config: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
-        let config: ZoneLookupConfig = serde_json::from_value(config.clone()).map_err(|e| {
+        let config: GtfsFlexConfig = serde_json::from_value(config.clone()).map_err(|e| {
             let msg = format!("failure reading config for Flex builder: {e}");
             TraversalModelError::BuildError(msg)
         })?;
-        let lookup = ZoneLookup::try_from(&config).map_err(|e| {
+        if config.pooling_detour_factor < 1.0 {
+            let msg = format!(
+                "GtfsFlex pooling_detour_factor must be at least 1.0, found {}",
+                config.pooling_detour_factor
+            );
+            return Err(TraversalModelError::BuildError(msg));
+        }
+        let lookup = ZoneLookup::try_from(&config.lookup).map_err(|e| {
             let msg = format!("failure building engine from config for GtfsFlex builder: {e}");
             TraversalModelError::BuildError(msg)
         })?;
-        let service = GtfsFlexService::new(lookup)?;
+        let service = GtfsFlexService::new(lookup, config)?;
         Ok(Arc::new(service))
     }
 }
@@ -1,4 +1,28 @@
 use serde::{Deserialize, Serialize};
 
+use crate::util::zone::ZoneLookupConfig;
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct GtfsFlexConfig {}
+pub struct GtfsFlexConfig {
+    /// input files for the zonal data
+    #[serde(flatten)]
+    pub lookup: ZoneLookupConfig,
+    /// factor applied to the driving time of a flex trip to account for detours made to
+    /// serve other passengers sharing the vehicle. a value of 1.0 models a private taxi.
+    #[serde(default = "GtfsFlexConfig::default_pooling_detour_factor")]
+    pub pooling_detour_factor: f64,
+    /// if true, travelers are assumed to have booked ahead, so booking lead times are
+    /// ignored and prior-day services may be boarded.
+    #[serde(default)]
+    pub assume_prebooked: bool,
+    /// if true, the safe (upper bound) duration fields of a trip are used in place of
+    /// the mean duration fields when estimating trip time.
+    #[serde(default)]
+    pub use_safe_duration: bool,
+}
+
+impl GtfsFlexConfig {
+    pub fn default_pooling_detour_factor() -> f64 {
+        1.0
+    }
+}
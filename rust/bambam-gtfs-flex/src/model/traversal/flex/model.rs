@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
+use super::GtfsFlexConfig;
 use crate::{
     model::{feature, ops, GtfsFlexParams},
     util::zone::{ZoneId, ZoneLookup},
 };
 
-use bambam_core::model::state::CategoricalMapping;
+use bambam_core::model::state::{fieldname, CategoricalMapping};
+use chrono::NaiveDate;
 use routee_compass_core::{
     algorithm::search::SearchTree,
     model::{
@@ -14,23 +16,31 @@ use routee_compass_core::{
         traversal::{EdgeFrontierContext, TraversalModel, TraversalModelError},
     },
 };
+use uom::si::f64::Time;
 
 pub struct GtfsFlexModel {
     pub lookup: Arc<ZoneLookup>,
     pub mapping: Arc<CategoricalMapping<ZoneId, i64>>,
+    pub config: Arc<GtfsFlexConfig>,
     pub params: GtfsFlexParams,
+    /// service date whose schedules apply to this query
+    pub service_date: Option<NaiveDate>,
 }
 
 impl GtfsFlexModel {
     pub fn new(
         lookup: Arc<ZoneLookup>,
         mapping: Arc<CategoricalMapping<ZoneId, i64>>,
+        config: Arc<GtfsFlexConfig>,
         params: GtfsFlexParams,
     ) -> Self {
+        let service_date = lookup.pick_service_date(&params.start_time);
         Self {
             lookup,
             mapping,
+            config,
             params,
+            service_date,
         }
     }
 }
@@ -41,7 +51,10 @@ impl TraversalModel for GtfsFlexModel {
     }
 
     fn input_features(&self) -> Vec<InputFeature> {
-        vec![]
+        vec![InputFeature::Time {
+            name: fieldname::EDGE_TIME.to_string(),
+            unit: None,
+        }]
     }
 
     fn output_features(&self) -> Vec<(String, StateVariableConfig)> {
@@ -73,7 +86,15 @@ impl TraversalModel for GtfsFlexModel {
             set_src_zone_id(state, state_model, ctx.dst, self)?;
         }
 
-        // todo: pooling delay?
+        // apply the booking lead time and pooling detour to the trip time. trips that
+        // cannot be booked on the day of travel never reach a valid destination.
+        let bookable = add_pooling_delay(state, state_model, not_existing_gtfs_flex_trip, self)?;
+        if !bookable {
+            return ops::set_is_valid(false, state, state_model).map_err(|e| {
+                let msg = format!("while assigning unbookable trip in state vector, {e}");
+                TraversalModelError::TraversalModelFailure(msg)
+            });
+        }
 
         // for every edge, assign whether it is a valid GTFS-Flex destination
         validate_flex_destination(state, state_model, ctx.dst, self)
@@ -129,6 +150,69 @@ fn set_src_zone_id(
     }
 }
 
+/// adds the delay of riding a shared, booked vehicle instead of a private taxi to the
+/// edge and trip time. when boarding, this includes the booking lead time and the trip
+/// duration offset. on every edge, the driving time is scaled by the pooling detour
+/// factor and the trip duration factor. returns false if the trip cannot be booked.
+fn add_pooling_delay(
+    state: &mut [StateVariable],
+    state_model: &StateModel,
+    boarding: bool,
+    model: &GtfsFlexModel,
+) -> Result<bool, TraversalModelError> {
+    let src_zone_id = ops::get_src_zone_id(state, state_model, &model.mapping)
+        .map_err(|e| {
+            let msg = format!("while computing pooling delay, {e}");
+            TraversalModelError::TraversalModelFailure(msg)
+        })?
+        .ok_or_else(|| {
+            let msg = format!(
+                "field '{}' must be set before computing pooling delay",
+                feature::fieldname::LEG_SRC_ZONE_ID
+            );
+            TraversalModelError::InternalError(msg)
+        })?;
+    let rules = model
+        .lookup
+        .get_trip_rules(src_zone_id)
+        .cloned()
+        .unwrap_or_default();
+    let lead_time = match rules.booking_lead_time(model.config.assume_prebooked) {
+        Some(lead_time) => lead_time,
+        None => return Ok(false),
+    };
+
+    let (duration_factor, duration_offset_minutes) =
+        rules.duration_factor_and_offset(model.config.use_safe_duration);
+    let edge_time = state_model.get_time(state, fieldname::EDGE_TIME)?;
+    let delay = pooling_delay(
+        edge_time,
+        model.config.pooling_detour_factor * duration_factor,
+        boarding.then_some((lead_time, duration_offset_minutes)),
+    );
+
+    state_model.set_time(state, feature::fieldname::EDGE_POOLING_DELAY, &delay)?;
+    if delay > Time::ZERO {
+        state_model.add_time(state, fieldname::EDGE_TIME, &delay)?;
+        state_model.add_time(state, fieldname::TRIP_TIME, &delay)?;
+    }
+    Ok(true)
+}
+
+/// the delay added to an edge with the given driving time. the driving time is scaled
+/// by the combined detour and duration factor, and factors below 1 never produce a
+/// negative delay. when boarding, the booking lead time and duration offset (minutes)
+/// are added.
+fn pooling_delay(edge_time: Time, factor: f64, boarding: Option<(Time, f64)>) -> Time {
+    let scale = (factor - 1.0).max(0.0);
+    let mut delay = edge_time * scale;
+    if let Some((lead_time, duration_offset_minutes)) = boarding {
+        delay += lead_time;
+        delay += Time::new::<uom::si::time::minute>(duration_offset_minutes.max(0.0));
+    }
+    delay
+}
+
 /// Validates whether the destination vertex is a valid GTFS-Flex stop from the source zone
 /// at the current time. Updates the state with the validity result.
 ///
@@ -161,9 +245,12 @@ fn validate_flex_destination(
             },
         )?;
 
-    let is_valid = model
-        .lookup
-        .valid_destination(src_zone_id, dst, &current_datetime)?;
+    let is_valid = model.lookup.valid_destination(
+        src_zone_id,
+        dst,
+        model.service_date.as_ref(),
+        &current_datetime,
+    )?;
     log::debug!(
         "gtfs-flex traversal reaches vertex {} ({},{}) at time {}. is a valid destination? {is_valid}",
         dst.vertex_id,
@@ -177,3 +264,38 @@ fn validate_flex_destination(
         TraversalModelError::TraversalModelFailure(msg)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::pooling_delay;
+    use uom::si::{f64::Time, time::minute};
+
+    fn minutes(value: f64) -> Time {
+        Time::new::<minute>(value)
+    }
+
+    #[test]
+    fn test_pooling_delay_scales_edge_time() {
+        let delay = pooling_delay(minutes(10.0), 1.5, None);
+        assert!((delay.get::<minute>() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pooling_delay_never_negative() {
+        for factor in [0.0, 0.5, 0.99, 1.0] {
+            let delay = pooling_delay(minutes(10.0), factor, None);
+            assert_eq!(delay.get::<minute>(), 0.0, "factor {factor}");
+        }
+        let delay = pooling_delay(minutes(10.0), 0.5, Some((minutes(0.0), -30.0)));
+        assert_eq!(delay.get::<minute>(), 0.0);
+    }
+
+    #[test]
+    fn test_pooling_delay_when_boarding() {
+        // 20 minute edge with a 1.25 detour factor, 60 minute lead time and 30 minute offset
+        let delay = pooling_delay(minutes(20.0), 1.25, Some((minutes(60.0), 30.0)));
+        assert!((delay.get::<minute>() - 95.0).abs() < 1e-9);
+        let delay = pooling_delay(minutes(20.0), 1.25, None);
+        assert!((delay.get::<minute>() - 5.0).abs() < 1e-9);
+    }
+}
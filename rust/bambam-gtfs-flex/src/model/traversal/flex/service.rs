@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use super::GtfsFlexModel;
+use super::{GtfsFlexConfig, GtfsFlexModel};
 use crate::model::GtfsFlexParams;
 use crate::util::zone::{ZoneId, ZoneLookup};
 
@@ -12,16 +12,18 @@ use routee_compass_core::model::traversal::{
 pub struct GtfsFlexService {
     lookup: Arc<ZoneLookup>,
     mapping: Arc<CategoricalMapping<ZoneId, i64>>,
+    config: Arc<GtfsFlexConfig>,
 }
 
 impl GtfsFlexService {
-    pub fn new(lookup: ZoneLookup) -> Result<Self, TraversalModelError> {
+    pub fn new(lookup: ZoneLookup, config: GtfsFlexConfig) -> Result<Self, TraversalModelError> {
         let mut zone_ids: Vec<_> = lookup.graph.keys().cloned().collect();
         zone_ids.dedup();
         let mapping = CategoricalMapping::new(&zone_ids)?;
         Ok(Self {
             lookup: Arc::new(lookup),
             mapping: Arc::new(mapping),
+            config: Arc::new(config),
         })
     }
 }
@@ -35,7 +37,12 @@ impl TraversalModelService for GtfsFlexService {
             let msg = format!("failure reading params for GtfsFlex service: {e}");
             TraversalModelError::BuildError(msg)
         })?;
-        let model = GtfsFlexModel::new(self.lookup.clone(), self.mapping.clone(), params);
+        let model = GtfsFlexModel::new(
+            self.lookup.clone(),
+            self.mapping.clone(),
+            self.config.clone(),
+            params,
+        );
         Ok(Arc::new(model))
     }
 }
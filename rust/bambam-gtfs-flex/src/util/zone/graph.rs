@@ -1,19 +1,30 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+};
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use kdam::BarBuilder;
 use routee_compass_core::util::fs::read_utils;
 
 use crate::util::zone::ZoneError;
 
-use super::{ZonalRelation, ZonalRelationRecord, ZoneId};
+use super::{FlexTripRules, ZonalRelation, ZonalRelationRecord, ZoneId};
 
 /// a directed graph between GTFS-Flex zones. this metadata lookup
 /// supports GTFS-Flex traversals, which must first lookup their current
 /// location in a spatial index and their source zone in their state
 /// vector. if both values exist, the model can call ZoneGraph::valid_zonal_trip
 /// to determine whether the current edge is a destination.
-pub struct ZoneGraph(ZoneGraphImpl);
+///
+/// relations are stored by the service date they were imported for. relations from
+/// records without a service date are valid on every date.
+pub struct ZoneGraph {
+    relations: HashMap<Option<NaiveDate>, ZoneGraphImpl>,
+    /// booking and travel duration rules for trips departing from a source zone.
+    /// source zone ids are namespaced by trip, so each has at most one set of rules.
+    trip_rules: HashMap<ZoneId, FlexTripRules>,
+}
 
 /// represents all zone->zone relations where:
 ///   - the outer [ZoneId] key is a source zone
@@ -22,57 +33,85 @@ pub struct ZoneGraph(ZoneGraphImpl);
 type ZoneGraphImpl = HashMap<ZoneId, HashMap<ZoneId, ZonalRelation>>;
 
 impl ZoneGraph {
-    /// get the complete collection of [ZoneId]s that have relations in this graph.
-    /// the keys of the inner hashmap cover all zones that exist.
+    /// get the complete collection of [ZoneId]s that have relations in this graph
+    /// on any service date. the keys of the inner hashmap cover all zones that exist.
     pub fn keys<'a>(&'a self) -> Box<dyn Iterator<Item = &'a ZoneId> + 'a> {
-        Box::new(self.0.keys())
+        let unique = self
+            .relations
+            .values()
+            .flat_map(|g| g.keys())
+            .collect::<HashSet<_>>();
+        Box::new(unique.into_iter())
     }
 
-    /// get the relations from some source [ZoneId], if they exist.
-    pub fn get<'a>(&'a self, src_zone_id: &ZoneId) -> Option<&'a HashMap<ZoneId, ZonalRelation>> {
-        self.0.get(src_zone_id)
+    /// the service dates with imported relations, in order.
+    pub fn service_dates(&self) -> BTreeSet<NaiveDate> {
+        self.relations.keys().flatten().cloned().collect()
+    }
+
+    /// get the relations from some source [ZoneId] on some service date, if they exist.
+    pub fn get<'a>(
+        &'a self,
+        src_zone_id: &ZoneId,
+        service_date: Option<&NaiveDate>,
+    ) -> Vec<&'a HashMap<ZoneId, ZonalRelation>> {
+        self.relations_on(service_date)
+            .filter_map(|g| g.get(src_zone_id))
+            .collect()
+    }
+
+    /// get the booking and travel duration rules for trips departing from some
+    /// source [ZoneId], if they exist.
+    pub fn get_trip_rules(&self, src_zone_id: &ZoneId) -> Option<&FlexTripRules> {
+        self.trip_rules.get(src_zone_id)
     }
 
     pub fn valid_departure(
         &self,
         src_zone_id: &ZoneId,
+        service_date: Option<&NaiveDate>,
         current_datetime: &NaiveDateTime,
     ) -> Result<bool, ZoneError> {
-        // find all zone-to-zone relations starting from the src_zone_id.
-        let relations = match self.0.get(src_zone_id) {
-            None => return Ok(false), // cannot depart, not a source zone.
-            Some(r) => r,
-        };
+        // find all zone-to-zone relations starting from the src_zone_id. if there
+        // are none, we cannot depart, this is not a source zone on this date.
+        let relations = self.get(src_zone_id, service_date);
 
         // accept this is valid if ANY relation treats this as a valid time.
         let current_time = current_datetime.time();
-        let valid_time = relations.values().any(|r| r.valid_time(&current_time));
+        let valid_time = relations
+            .iter()
+            .flat_map(|r| r.values())
+            .any(|r| r.valid_time(&current_time));
 
         Ok(valid_time)
     }
 
-    /// confirms that this zone-to-zone trip exists in our zonal graph.
+    /// confirms that this zone-to-zone trip exists in our zonal graph on the service date.
     pub fn valid_zonal_trip(
         &self,
         src_zone_id: &ZoneId,
         dst_zone_id: &ZoneId,
+        service_date: Option<&NaiveDate>,
         _current_time: &NaiveDateTime,
     ) -> Result<bool, ZoneError> {
-        // find zone-to-zone trips starting from src_zone_id
-        let relations = match self.0.get(src_zone_id) {
-            Some(r) => r,
-            None => return Ok(false),
-        };
-
-        // check the destination exists and matches our current time
-        match relations.get(dst_zone_id) {
-            None => Ok(false),
-            Some(_relation) => {
-                // if there is no time validation to run, then we are done.
-                // todo: run time validation here
-                Ok(true)
-            }
-        }
+        // find zone-to-zone trips starting from src_zone_id and check the destination
+        // exists. if there is no time validation to run, then we are done.
+        // todo: run time validation here
+        let is_valid = self
+            .get(src_zone_id, service_date)
+            .iter()
+            .any(|relations| relations.contains_key(dst_zone_id));
+        Ok(is_valid)
+    }
+
+    /// the relations valid on a service date, which always includes undated relations.
+    fn relations_on<'a>(
+        &'a self,
+        service_date: Option<&NaiveDate>,
+    ) -> impl Iterator<Item = &'a ZoneGraphImpl> + 'a {
+        let undated = self.relations.get(&None);
+        let dated = service_date.and_then(|d| self.relations.get(&Some(*d)));
+        undated.into_iter().chain(dated)
     }
 }
 
@@ -94,11 +133,19 @@ impl TryFrom<&[ZonalRelationRecord]> for ZoneGraph {
     type Error = ZoneError;
 
     fn try_from(value: &[ZonalRelationRecord]) -> Result<Self, Self::Error> {
-        let mut graph: ZoneGraphImpl = HashMap::new();
+        let mut relations: HashMap<Option<NaiveDate>, ZoneGraphImpl> = HashMap::new();
+        let mut trip_rules: HashMap<ZoneId, FlexTripRules> = HashMap::new();
         for row in value.iter() {
-            insert_row(row, &mut graph)?;
+            let graph = relations.entry(row.get_service_date()?).or_default();
+            insert_row(row, graph)?;
+            if let Some(rules) = row.get_trip_rules() {
+                let _ = trip_rules.insert(row.src_zone_id.clone(), rules);
+            }
         }
-        Ok(Self(graph))
+        Ok(Self {
+            relations,
+            trip_rules,
+        })
     }
 }
 
@@ -137,3 +184,104 @@ fn insert_row(row: &ZonalRelationRecord, graph: &mut ZoneGraphImpl) -> Result<()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ZoneGraph;
+    use crate::util::zone::{ZonalRelationRecord, ZoneId};
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    fn zone_id(location_id: &str) -> ZoneId {
+        ZoneId::from_full_namespace("agency", "route", "trip", location_id)
+    }
+
+    fn record(src: &str, dst: &str, requested_date: Option<&str>) -> ZonalRelationRecord {
+        ZonalRelationRecord {
+            src_zone_id: zone_id(src),
+            dst_zone_id: Some(zone_id(dst)),
+            requested_date: requested_date.map(String::from),
+            start_time: None,
+            end_time: None,
+            booking_type: None,
+            prior_notice_duration_min: None,
+            prior_notice_duration_max: None,
+            prior_notice_last_day: None,
+            prior_notice_last_time: None,
+            mean_duration_factor: None,
+            mean_duration_offset: None,
+            safe_duration_factor: None,
+            safe_duration_offset: None,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).expect("test invariant")
+    }
+
+    fn noon(day: u32) -> NaiveDateTime {
+        date(day).and_hms_opt(12, 0, 0).expect("test invariant")
+    }
+
+    fn valid_trip(
+        graph: &ZoneGraph,
+        src: &str,
+        dst: &str,
+        service_date: Option<&NaiveDate>,
+    ) -> bool {
+        graph
+            .valid_zonal_trip(&zone_id(src), &zone_id(dst), service_date, &noon(1))
+            .expect("test invariant")
+    }
+
+    #[test]
+    fn test_undated_relations_apply_on_every_date() {
+        let records = [record("a", "b", None), record("c", "d", Some(""))];
+        let graph = ZoneGraph::try_from(&records[..]).expect("test invariant");
+        assert!(graph.service_dates().is_empty());
+        for service_date in [None, Some(date(3)), Some(date(4))] {
+            assert!(valid_trip(&graph, "a", "b", service_date.as_ref()));
+            assert!(valid_trip(&graph, "c", "d", service_date.as_ref()));
+            assert!(!valid_trip(&graph, "b", "a", service_date.as_ref()));
+        }
+    }
+
+    #[test]
+    fn test_dated_relations_apply_on_their_date() {
+        let records = [
+            record("a", "b", Some("20240603")),
+            record("a", "c", Some("20240604")),
+            record("x", "y", None),
+        ];
+        let graph = ZoneGraph::try_from(&records[..]).expect("test invariant");
+        let service_dates = graph.service_dates().into_iter().collect::<Vec<_>>();
+        assert_eq!(service_dates, vec![date(3), date(4)]);
+
+        assert!(valid_trip(&graph, "a", "b", Some(&date(3))));
+        assert!(!valid_trip(&graph, "a", "c", Some(&date(3))));
+        assert!(valid_trip(&graph, "a", "c", Some(&date(4))));
+        assert!(!valid_trip(&graph, "a", "b", Some(&date(5))));
+        // undated queries only see undated relations
+        assert!(!valid_trip(&graph, "a", "b", None));
+        assert!(valid_trip(&graph, "x", "y", None));
+        assert!(valid_trip(&graph, "x", "y", Some(&date(3))));
+    }
+
+    #[test]
+    fn test_departure_combines_undated_and_dated_relations() {
+        let records = [record("a", "b", Some("20240603")), record("a", "c", None)];
+        let graph = ZoneGraph::try_from(&records[..]).expect("test invariant");
+        assert_eq!(graph.get(&zone_id("a"), Some(&date(3))).len(), 2);
+        assert_eq!(graph.get(&zone_id("a"), Some(&date(4))).len(), 1);
+        assert_eq!(graph.get(&zone_id("a"), None).len(), 1);
+        let departs = graph
+            .valid_departure(&zone_id("a"), Some(&date(4)), &noon(4))
+            .expect("test invariant");
+        assert!(departs);
+    }
+
+    #[test]
+    fn test_invalid_requested_date_fails() {
+        let records = [record("a", "b", Some("2024-06-03"))];
+        assert!(ZoneGraph::try_from(&records[..]).is_err());
+    }
+}
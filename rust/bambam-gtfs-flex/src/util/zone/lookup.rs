@@ -1,17 +1,20 @@
 use std::path::PathBuf;
 
 use crate::util::zone::{
-    ZonalRelationRecord, ZoneError, ZoneGeometry, ZoneGraph, ZoneId, ZoneLookupConfig,
+    FlexTripRules, ZonalRelationRecord, ZoneError, ZoneGeometry, ZoneGraph, ZoneId,
+    ZoneLookupConfig,
 };
 
 use bambam_core::{model::state::CategoricalMapping, util::geo_utils::try_convert_f32};
-use chrono::NaiveDateTime;
+use bambam_gtfs::schedule::DateMappingPolicyType;
+use chrono::{NaiveDate, NaiveDateTime};
 use geozero::{wkt::Wkt, ToGeo};
 use kdam::BarBuilder;
 use routee_compass_core::{
     model::{constraint::ConstraintModelError, network::Vertex, traversal::TraversalModelError},
     util::{fs::read_utils, geo::PolygonalRTree},
 };
+use std::collections::BTreeSet;
 
 /// top-level API for working with GTFS-Flex zonal data.
 pub struct ZoneLookup {
@@ -22,6 +25,14 @@ pub struct ZoneLookup {
     pub graph: ZoneGraph,
     /// spatial lookup from the road network into the zone graph.
     pub rtree: PolygonalRTree<f32, ZoneId>,
+    /// service dates found in the zone graph
+    pub service_dates: BTreeSet<NaiveDate>,
+    /// maps the date of a query onto the service dates
+    pub date_mapping_policy: DateMappingPolicyType,
+    /// days from the query date to search for a service date with a nearest date policy
+    pub date_mapping_date_tolerance: Option<u64>,
+    /// whether a nearest date policy only picks service dates on the query's day of week
+    pub date_mapping_match_weekday: Option<bool>,
 }
 
 impl ZoneLookup {
@@ -39,14 +50,46 @@ impl ZoneLookup {
         Ok(result)
     }
 
+    /// picks the service date whose schedules apply to a query beginning at this time.
+    /// returns None for datasets imported without service dates, or if no service date
+    /// satisfies the date mapping policy, in which case only undated relations apply.
+    pub fn pick_service_date(&self, start_time: &NaiveDateTime) -> Option<NaiveDate> {
+        let picked = self
+            .date_mapping_policy
+            .pick_service_date(
+                &start_time.date(),
+                &self.service_dates,
+                self.date_mapping_date_tolerance,
+                self.date_mapping_match_weekday,
+            )
+            .unwrap_or_else(|e| {
+                log::error!("failure picking GTFS-Flex service date: {e}");
+                None
+            });
+        if picked.is_none() && !self.service_dates.is_empty() {
+            log::debug!(
+                "no GTFS-Flex service date found for query date {} using {:?}",
+                start_time.date(),
+                self.date_mapping_policy
+            );
+        }
+        picked
+    }
+
+    /// booking and travel duration rules for a trip departing from this zone, if any.
+    pub fn get_trip_rules(&self, src_zone_id: &ZoneId) -> Option<&FlexTripRules> {
+        self.graph.get_trip_rules(src_zone_id)
+    }
+
     /// is it valid to begin a trip in this zone at this time?
     pub fn valid_departure(
         &self,
         src_zone_id: &ZoneId,
+        service_date: Option<&NaiveDate>,
         current_time: &NaiveDateTime,
     ) -> Result<bool, ConstraintModelError> {
         self.graph
-            .valid_departure(src_zone_id, current_time)
+            .valid_departure(src_zone_id, service_date, current_time)
             .map_err(|e| ConstraintModelError::ConstraintModelError(e.to_string()))
     }
 
@@ -56,6 +99,7 @@ impl ZoneLookup {
         &self,
         src_zone_id: &ZoneId,
         current_vertex: &Vertex,
+        service_date: Option<&NaiveDate>,
         current_time: &NaiveDateTime,
     ) -> Result<bool, TraversalModelError> {
         let point = geo::Geometry::Point(geo::Point(current_vertex.coordinate.0));
@@ -69,7 +113,7 @@ impl ZoneLookup {
         for node in zone_iter {
             let is_valid = self
                 .graph
-                .valid_zonal_trip(src_zone_id, &node.data, current_time)
+                .valid_zonal_trip(src_zone_id, &node.data, service_date, current_time)
                 .map_err(|e| TraversalModelError::TraversalModelFailure(e.to_string()))?;
             if is_valid {
                 return Ok(true);
@@ -86,10 +130,22 @@ impl TryFrom<&ZoneLookupConfig> for ZoneLookup {
         let mapping = read_zone_ids(&config.zone_ids_input_file)?;
         let graph = read_records(&config.zone_record_input_file)?;
         let rtree = read_geometries(&config.zone_geometry_input_file)?;
+        let service_dates = graph.service_dates();
+        config
+            .date_mapping_policy
+            .nearest_date_args(
+                config.date_mapping_date_tolerance,
+                config.date_mapping_match_weekday,
+            )
+            .map_err(|e| ZoneError::Build(format!("invalid date mapping policy: {e}")))?;
         Ok(ZoneLookup {
             mapping,
             graph,
             rtree,
+            service_dates,
+            date_mapping_policy: config.date_mapping_policy.clone(),
+            date_mapping_date_tolerance: config.date_mapping_date_tolerance,
+            date_mapping_match_weekday: config.date_mapping_match_weekday,
         })
     }
 }
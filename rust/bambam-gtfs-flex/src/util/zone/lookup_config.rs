@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use bambam_gtfs::schedule::DateMappingPolicyType;
 use serde::{Deserialize, Serialize};
 
 use crate::model::consts;
@@ -16,6 +17,19 @@ pub struct ZoneLookupConfig {
     // pub zone_id_property: Option<String>,
     /// geometries for zones
     pub zone_geometry_input_file: String,
+    /// maps the date of a query onto the service dates of a multi-date import,
+    /// following the date mapping policies of `bambam-gtfs`. defaults to using the
+    /// exact query date.
+    #[serde(default)]
+    pub date_mapping_policy: DateMappingPolicyType,
+    /// for nearest date policies, the number of days +- from the query date to search
+    /// for a service date.
+    #[serde(default)]
+    pub date_mapping_date_tolerance: Option<u64>,
+    /// for nearest date policies, whether to only pick service dates that fall on the
+    /// same day of the week as the query date.
+    #[serde(default)]
+    pub date_mapping_match_weekday: Option<bool>,
 }
 
 impl From<&Path> for ZoneLookupConfig {
@@ -30,6 +44,9 @@ impl From<&Path> for ZoneLookupConfig {
             zone_ids_input_file,
             zone_record_input_file,
             zone_geometry_input_file,
+            date_mapping_policy: DateMappingPolicyType::default(),
+            date_mapping_date_tolerance: None,
+            date_mapping_match_weekday: None,
         }
     }
 }
@@ -6,6 +6,7 @@ mod lookup_config;
 mod record;
 mod relation;
 mod schedule;
+mod trip_rules;
 
 pub use error::ZoneError;
 pub use graph::ZoneGraph;
@@ -15,3 +16,4 @@ pub use lookup_config::ZoneLookupConfig;
 pub use record::{ZonalRelationRecord, ZoneGeometry, ZoneRecord};
 pub use relation::ZonalRelation;
 pub use schedule::ZoneSchedule;
+pub use trip_rules::{BookingType, FlexTripRules};
@@ -1,8 +1,8 @@
-use chrono::NaiveTime;
+use chrono::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
-use super::ZoneId;
-use crate::util::zone::ZoneSchedule;
+use super::{BookingType, FlexTripRules, ZoneError, ZoneId};
+use crate::{model::consts, util::zone::ZoneSchedule};
 
 /// a valid origin-destination zone pair for a trip
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,6 +15,16 @@ pub struct ZoneRecord {
     pub start_pickup_drop_off_window: Option<NaiveTime>,
     pub end_pickup_drop_off_window: Option<NaiveTime>,
     pub destination_zone: ZoneId,
+    // booking and travel duration rules, see [`FlexTripRules`]
+    pub booking_type: Option<BookingType>,
+    pub prior_notice_duration_min: Option<i64>,
+    pub prior_notice_duration_max: Option<i64>,
+    pub prior_notice_last_day: Option<i64>,
+    pub prior_notice_last_time: Option<NaiveTime>,
+    pub mean_duration_factor: Option<f64>,
+    pub mean_duration_offset: Option<f64>,
+    pub safe_duration_factor: Option<f64>,
+    pub safe_duration_offset: Option<f64>,
 }
 
 /// geometry WKT for the fully-qualified [ZoneId].
@@ -36,12 +46,36 @@ pub struct ZonalRelationRecord {
     /// fully-qualified id for the destination location associated with this trip.
     #[serde(rename = "destination_zone")]
     pub dst_zone_id: Option<ZoneId>,
+    /// service date (YYYYMMDD) this relation was imported for. relations without a
+    /// service date are valid on every date.
+    #[serde(default)]
+    pub requested_date: Option<String>,
     /// time that the pickup/drop-off window begins for this trip
     #[serde(rename = "start_pickup_drop_off_window")]
     pub start_time: Option<NaiveTime>,
     /// time that the pickup/drop-off window concludes for this trip
     #[serde(rename = "end_pickup_drop_off_window")]
     pub end_time: Option<NaiveTime>,
+    // booking and travel duration rules, see [`FlexTripRules`]. missing from
+    // datasets processed before these rules were imported.
+    #[serde(default)]
+    pub booking_type: Option<BookingType>,
+    #[serde(default)]
+    pub prior_notice_duration_min: Option<i64>,
+    #[serde(default)]
+    pub prior_notice_duration_max: Option<i64>,
+    #[serde(default)]
+    pub prior_notice_last_day: Option<i64>,
+    #[serde(default)]
+    pub prior_notice_last_time: Option<NaiveTime>,
+    #[serde(default)]
+    pub mean_duration_factor: Option<f64>,
+    #[serde(default)]
+    pub mean_duration_offset: Option<f64>,
+    #[serde(default)]
+    pub safe_duration_factor: Option<f64>,
+    #[serde(default)]
+    pub safe_duration_offset: Option<f64>,
 }
 
 impl ZonalRelationRecord {
@@ -59,4 +93,39 @@ impl ZonalRelationRecord {
         };
         schedule_internal.map(|(s, e)| ZoneSchedule::new(s, e))
     }
+
+    /// gets the service date of the row, if present.
+    pub fn get_service_date(&self) -> Result<Option<NaiveDate>, ZoneError> {
+        match self.requested_date.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(date) => NaiveDate::parse_from_str(date, consts::SERVICE_DATE_FORMAT)
+                .map(Some)
+                .map_err(|e| {
+                    ZoneError::Build(format!(
+                        "invalid requested_date '{date}' for zone {}: {e}",
+                        self.src_zone_id
+                    ))
+                }),
+        }
+    }
+
+    /// gets the booking and travel duration rules from the row, if any are present.
+    pub fn get_trip_rules(&self) -> Option<FlexTripRules> {
+        let rules = FlexTripRules {
+            booking_type: self.booking_type,
+            prior_notice_duration_min: self.prior_notice_duration_min,
+            prior_notice_duration_max: self.prior_notice_duration_max,
+            prior_notice_last_day: self.prior_notice_last_day,
+            prior_notice_last_time: self.prior_notice_last_time,
+            mean_duration_factor: self.mean_duration_factor,
+            mean_duration_offset: self.mean_duration_offset,
+            safe_duration_factor: self.safe_duration_factor,
+            safe_duration_offset: self.safe_duration_offset,
+        };
+        if rules.is_empty() {
+            None
+        } else {
+            Some(rules)
+        }
+    }
 }
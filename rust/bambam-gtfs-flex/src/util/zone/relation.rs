@@ -12,11 +12,13 @@ use super::{ZonalRelationRecord, ZoneError, ZoneId};
 /// # Date invariant
 ///
 /// All `ZonalRelation` values are constructed from records that have already been
-/// filtered to a single service date by the GTFS-Flex preprocessor
-/// (`flex_processor::process_gtfs_flex_bundle`). That step joins `calendar.txt`
-/// and `calendar_dates.txt` against the requested date and writes only the
-/// active trips to the `valid-zones.csv` output. By the time a `ZonalRelation`
-/// is built, the date-of-service question is fully resolved.
+/// filtered to a service date by the GTFS-Flex preprocessor
+/// (`import_dataset::process_gtfs_flex_bundle`). That step joins `calendar.txt`
+/// and `calendar_dates.txt` against each requested date and writes the active
+/// trips along with their service date. The [super::ZoneGraph] stores relations
+/// by service date and the date mapping policy of the [super::ZoneLookupConfig]
+/// picks the date for a query, so by the time a `ZonalRelation` is used, the
+/// date-of-service question is fully resolved.
 ///
 /// The only remaining time variability is the intra-day pickup/drop-off window
 /// stored in `ToZoneScheduled`. `valid_time` checks that window against the
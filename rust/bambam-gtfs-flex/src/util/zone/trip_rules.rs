@@ -0,0 +1,198 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use uom::si::f64::Time;
+
+/// how far in advance a GTFS-Flex trip must be booked, from the `booking_type`
+/// field of `booking_rules.txt`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingType {
+    /// booking_type=0: real-time booking
+    RealTime,
+    /// booking_type=1: up to same-day booking with advance notice
+    SameDay,
+    /// booking_type=2: up to prior day(s) booking
+    PriorDay,
+}
+
+impl BookingType {
+    /// decodes the integer `booking_type` value found in `booking_rules.txt`.
+    pub fn from_gtfs_value(value: i64) -> Option<BookingType> {
+        match value {
+            0 => Some(BookingType::RealTime),
+            1 => Some(BookingType::SameDay),
+            2 => Some(BookingType::PriorDay),
+            _ => None,
+        }
+    }
+}
+
+/// booking and travel duration rules for a GTFS-Flex trip, collected from
+/// `booking_rules.txt` and the duration fields of `stop_times.txt`.
+/// all durations from the source data are in minutes.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct FlexTripRules {
+    pub booking_type: Option<BookingType>,
+    /// minimum number of minutes before travel to make a same-day request
+    pub prior_notice_duration_min: Option<i64>,
+    /// maximum number of minutes before travel to make a same-day request
+    pub prior_notice_duration_max: Option<i64>,
+    /// last day before travel to make a prior-day booking request
+    pub prior_notice_last_day: Option<i64>,
+    /// last time on the last day before travel to make a prior-day booking request
+    pub prior_notice_last_time: Option<NaiveTime>,
+    /// factor applied to the driving duration to estimate the mean trip duration
+    pub mean_duration_factor: Option<f64>,
+    /// minutes added to the scaled driving duration to estimate the mean trip duration
+    pub mean_duration_offset: Option<f64>,
+    /// factor applied to the driving duration to estimate a safe (upper bound) trip duration
+    pub safe_duration_factor: Option<f64>,
+    /// minutes added to the scaled driving duration to estimate a safe trip duration
+    pub safe_duration_offset: Option<f64>,
+}
+
+impl FlexTripRules {
+    /// true if no booking or duration rule was found for this trip
+    pub fn is_empty(&self) -> bool {
+        self == &FlexTripRules::default()
+    }
+
+    /// the time between requesting a trip and the earliest pickup. returns None if
+    /// the trip cannot be booked on the day of travel, unless the traveler is assumed
+    /// to have booked ahead.
+    pub fn booking_lead_time(&self, assume_prebooked: bool) -> Option<Time> {
+        if assume_prebooked {
+            return Some(Time::new::<uom::si::time::second>(0.0));
+        }
+        match self.booking_type {
+            Some(BookingType::PriorDay) => None,
+            Some(BookingType::SameDay) => {
+                let minutes = self.prior_notice_duration_min.unwrap_or_default().max(0);
+                Some(Time::new::<uom::si::time::minute>(minutes as f64))
+            }
+            Some(BookingType::RealTime) | None => Some(Time::new::<uom::si::time::second>(0.0)),
+        }
+    }
+
+    /// factor and offset (minutes) used to turn a driving duration into a trip duration.
+    /// the safe duration is an upper bound and falls back to the mean duration if missing.
+    pub fn duration_factor_and_offset(&self, use_safe_duration: bool) -> (f64, f64) {
+        let mean = (
+            self.mean_duration_factor.unwrap_or(1.0),
+            self.mean_duration_offset.unwrap_or_default(),
+        );
+        if !use_safe_duration {
+            return mean;
+        }
+        match (self.safe_duration_factor, self.safe_duration_offset) {
+            (None, None) => mean,
+            (factor, offset) => (factor.unwrap_or(1.0), offset.unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BookingType, FlexTripRules};
+    use uom::si::time::minute;
+
+    fn lead_minutes(rules: &FlexTripRules, assume_prebooked: bool) -> Option<f64> {
+        rules
+            .booking_lead_time(assume_prebooked)
+            .map(|t| t.get::<minute>())
+    }
+
+    #[test]
+    fn test_booking_lead_time_real_time() {
+        let rules = FlexTripRules {
+            booking_type: Some(BookingType::RealTime),
+            prior_notice_duration_min: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(lead_minutes(&rules, false), Some(0.0));
+        assert_eq!(lead_minutes(&FlexTripRules::default(), false), Some(0.0));
+    }
+
+    #[test]
+    fn test_booking_lead_time_same_day() {
+        let rules = FlexTripRules {
+            booking_type: Some(BookingType::SameDay),
+            prior_notice_duration_min: Some(60),
+            prior_notice_duration_max: Some(1440),
+            ..Default::default()
+        };
+        assert_eq!(lead_minutes(&rules, false), Some(60.0));
+        assert_eq!(lead_minutes(&rules, true), Some(0.0));
+
+        let missing_notice = FlexTripRules {
+            booking_type: Some(BookingType::SameDay),
+            ..Default::default()
+        };
+        assert_eq!(lead_minutes(&missing_notice, false), Some(0.0));
+
+        let negative_notice = FlexTripRules {
+            booking_type: Some(BookingType::SameDay),
+            prior_notice_duration_min: Some(-15),
+            ..Default::default()
+        };
+        assert_eq!(lead_minutes(&negative_notice, false), Some(0.0));
+    }
+
+    #[test]
+    fn test_booking_lead_time_prior_day() {
+        let rules = FlexTripRules {
+            booking_type: Some(BookingType::PriorDay),
+            prior_notice_last_day: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(lead_minutes(&rules, false), None);
+        assert_eq!(lead_minutes(&rules, true), Some(0.0));
+    }
+
+    #[test]
+    fn test_duration_factor_and_offset_mean() {
+        let rules = FlexTripRules {
+            mean_duration_factor: Some(1.5),
+            mean_duration_offset: Some(10.0),
+            safe_duration_factor: Some(2.0),
+            safe_duration_offset: Some(20.0),
+            ..Default::default()
+        };
+        assert_eq!(rules.duration_factor_and_offset(false), (1.5, 10.0));
+        assert_eq!(rules.duration_factor_and_offset(true), (2.0, 20.0));
+    }
+
+    #[test]
+    fn test_duration_factor_and_offset_defaults() {
+        let empty = FlexTripRules::default();
+        assert_eq!(empty.duration_factor_and_offset(false), (1.0, 0.0));
+        assert_eq!(empty.duration_factor_and_offset(true), (1.0, 0.0));
+
+        // safe duration falls back to the mean duration when missing
+        let mean_only = FlexTripRules {
+            mean_duration_factor: Some(1.2),
+            mean_duration_offset: Some(5.0),
+            ..Default::default()
+        };
+        assert_eq!(mean_only.duration_factor_and_offset(true), (1.2, 5.0));
+
+        // a partial safe duration fills its missing field with the identity value
+        let safe_offset_only = FlexTripRules {
+            mean_duration_factor: Some(1.2),
+            safe_duration_offset: Some(45.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            safe_offset_only.duration_factor_and_offset(true),
+            (1.0, 45.0)
+        );
+    }
+
+    #[test]
+    fn test_booking_type_from_gtfs_value() {
+        assert_eq!(BookingType::from_gtfs_value(0), Some(BookingType::RealTime));
+        assert_eq!(BookingType::from_gtfs_value(1), Some(BookingType::SameDay));
+        assert_eq!(BookingType::from_gtfs_value(2), Some(BookingType::PriorDay));
+        assert_eq!(BookingType::from_gtfs_value(3), None);
+    }
+}
@@ -1,5 +1,7 @@
 mod gtfs_app;
 mod operation;
+mod preprocess_bundle;
 
 pub use gtfs_app::GtfsApp;
 pub use operation::GtfsOperation;
+pub use preprocess_bundle::PreprocessBundleArgs;
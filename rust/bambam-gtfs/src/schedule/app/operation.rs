@@ -1,28 +1,18 @@
 //! GTFS archive pre-processing scripts for bambam-gtfs transit modeling.
 //! see [https://github.com/MobilityData/mobility-database-catalogs] for
 //! information on the mobility database catalog listing.
-use crate::schedule::bundle_ops::ProcessBundlesConfig;
-use crate::schedule::distance_calculation_policy::DistanceCalculationPolicy;
+use crate::schedule::app::PreprocessBundleArgs;
 use crate::schedule::schedule_error::ScheduleError;
-use crate::schedule::{
-    bundle_ops, DateMappingPolicy, DateMappingPolicyConfig, DateMappingPolicyType, GtfsProvider,
-    GtfsSummary, MissingStopLocationPolicy,
-};
+use crate::schedule::{GtfsProvider, GtfsSummary};
 use clap::Subcommand;
-use geo::{Coord, Geometry, LineString};
-use geozero::{wkt::Wkt as WktReader, ToGeo, ToWkt};
+use geo::{Coord, LineString};
+use geozero::ToWkt;
 use gtfs_structures::Gtfs;
 use itertools::Itertools;
-use kdam::Bar;
 use rayon::prelude::*;
-use routee_compass_core::model::map::SpatialIndex;
-use routee_compass_core::model::network::Vertex;
-use routee_compass_core::util::fs::read_utils;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::Arc;
 use std::{collections::HashSet, fs::File, io::Write, path::Path, time::Duration};
-use uom::si::f64::Length;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Subcommand)]
 pub enum GtfsOperation {
@@ -63,63 +53,7 @@ pub enum GtfsOperation {
         manifest_file: String,
     },
     /// Process bundle into EdgeLists
-    PreprocessBundle {
-        /// a single GTFS archive or a directory of GTFS archives
-        #[arg(long)]
-        input: String,
-        /// in this case of a single input file, this sets the edge list id for that input.
-        /// for a directory input, sets the starting edge list id.
-        #[arg(long)]
-        starting_edge_list_id: usize,
-
-        #[arg(long, default_value_t = 1)]
-        parallelism: usize,
-
-        #[arg(long)]
-        output_directory: String,
-
-        #[arg(long)]
-        vertices_compass_filename: String,
-
-        #[arg(long)]
-        start_date: String,
-
-        #[arg(long)]
-        end_date: String,
-
-        #[arg(long)]
-        start_time: Option<String>,
-
-        #[arg(long)]
-        end_time: Option<String>,
-
-        #[arg(long, default_value_t = 325.)]
-        vertex_match_tolerance: f64,
-
-        #[arg(long, value_enum, default_value_t=MissingStopLocationPolicy::Fail)]
-        missing_stop_location_policy: MissingStopLocationPolicy,
-
-        #[arg(long, value_enum, default_value_t=DistanceCalculationPolicy::Haversine)]
-        distance_calculation_policy: DistanceCalculationPolicy,
-
-        #[arg(long, value_enum)]
-        date_mapping_policy: DateMappingPolicyType,
-
-        #[arg(long)]
-        date_mapping_date_tolerance: Option<u64>,
-
-        #[arg(long)]
-        date_mapping_match_weekday: Option<bool>,
-
-        #[arg(long)]
-        extent_file: Option<String>,
-
-        #[arg(long, default_value_t = true)]
-        overwrite: bool,
-
-        #[arg(long, default_value_t = true)]
-        ignore_bad_gtfs: bool,
-    },
+    PreprocessBundle(PreprocessBundleArgs),
 }
 
 // /// helper function for date deserialization in clap
@@ -161,109 +95,14 @@ impl GtfsOperation {
                     .expect("failed reading manifest");
                 download(&rows, *parallelism)
             }
-            GtfsOperation::PreprocessBundle {
-                input,
-                starting_edge_list_id,
-                vertices_compass_filename,
-                start_date,
-                end_date,
-                start_time,
-                end_time,
-                vertex_match_tolerance,
-                missing_stop_location_policy,
-                distance_calculation_policy,
-                extent_file,
-                output_directory,
-                overwrite,
-                parallelism,
-                ignore_bad_gtfs,
-                date_mapping_policy,
-                date_mapping_date_tolerance,
-                date_mapping_match_weekday,
-            } => {
-                let spatial_index = load_vertices_and_create_spatial_index(
-                    vertices_compass_filename,
-                    *vertex_match_tolerance,
-                )
-                .expect("failed reading vertices and building spatial index");
-
-                // build the date mapping policy based on the CLI arguments
-                let date_mapping_config = DateMappingPolicyConfig::new(
-                    start_date,
-                    end_date,
-                    start_time.as_ref(),
-                    end_time.as_ref(),
-                    date_mapping_policy,
-                    *date_mapping_date_tolerance,
-                    *date_mapping_match_weekday,
-                )
-                .unwrap_or_else(|e| panic!("invalid date mapping arguments caused error '{e}'"));
-                let date_mapping_policy: DateMappingPolicy =
-                    DateMappingPolicy::try_from(&date_mapping_config).unwrap_or_else(|e| {
-                        panic!(
-                            "invalid date mapping arguments caused error '{}': {}",
-                            e,
-                            serde_json::to_string_pretty(&date_mapping_config).unwrap_or_default(),
-                        )
-                    });
-                let extent = match read_extent_file(extent_file.as_ref()) {
-                    Ok(e) => e,
-                    Err(err) => panic!("{}", err),
-                };
-
-                let config = Arc::new(ProcessBundlesConfig {
-                    start_date: start_date.clone(),
-                    end_date: end_date.clone(),
-                    spatial_index,
-                    starting_edge_list_id: *starting_edge_list_id,
-                    missing_stop_location_policy: missing_stop_location_policy.clone(),
-                    distance_calculation_policy: distance_calculation_policy.clone(),
-                    date_mapping_policy: date_mapping_policy.clone(),
-                    extent,
-                    output_directory: output_directory.clone(),
-                    overwrite: *overwrite,
-                });
-
-                let input_path = Path::new(input);
-                if input_path.is_dir() {
-                    bundle_ops::batch_process(input_path, *parallelism, config, *ignore_bad_gtfs)
-                        .unwrap_or_else(|e| {
-                            log::error!("failure running preprocess-bundle: {e}");
-                        })
-                } else {
-                    let bundle_opt = bundle_ops::process_bundle(input, config.clone())
-                        .expect("failure processing GTFS bundle");
-                    let bundle = bundle_opt.expect(
-                        "GTFS archive import was skipped as the extent does not intersect any archives",
-                    );
-                    bundle_ops::write_bundle(&bundle, config.clone(), config.starting_edge_list_id)
-                        .expect("failure writing GTFS bundle");
-                }
-            }
+            GtfsOperation::PreprocessBundle(args) => match args.run() {
+                Ok(summary) => log::info!("{summary}"),
+                Err(e) => log::error!("failure running preprocess-bundle: {e}"),
+            },
         }
     }
 }
 
-/// helper function for loading a spatial index over the vertices of the graph.
-fn load_vertices_and_create_spatial_index(
-    vertices_compass_filename: &str,
-    tolerance_meters: f64,
-) -> Result<Arc<SpatialIndex>, ScheduleError> {
-    let bar_builder = Bar::builder().desc("read vertices file");
-    let vertices: Box<[Vertex]> = read_utils::from_csv(
-        &Path::new(vertices_compass_filename),
-        true,
-        Some(bar_builder),
-        None,
-    )
-    .map_err(|e| ScheduleError::FailedToCreateVertexIndex(format!("{e}")))?;
-    let tol: Length = uom::si::f64::Length::new::<uom::si::length::meter>(tolerance_meters);
-    Ok(Arc::new(SpatialIndex::new_vertex_oriented(
-        &vertices,
-        Some(tol),
-    )))
-}
-
 /// reads rows from a GTFS manifest in the format of Mobility Data Catalog
 /// see [https://github.com/MobilityData/mobility-database-catalogs].
 ///
@@ -460,39 +299,3 @@ fn download(rows: &[GtfsProvider], parallelism: usize) {
         }
     }
 }
-
-fn read_extent_file(extent_file: Option<&String>) -> Result<Option<Geometry>, ScheduleError> {
-    let extent_file = match extent_file {
-        Some(f) => f,
-        None => return Ok(None),
-    };
-    let extent_string = std::fs::read_to_string(extent_file).map_err(|e| {
-        ScheduleError::InvalidData(format!("failed to read WKT from '{extent_file}': {e}"))
-    })?;
-    let extent = WktReader(extent_string.as_str()).to_geo().map_err(|e| {
-        ScheduleError::InvalidData(format!("could not read file '{extent_file}' as WKT: {e}"))
-    })?;
-    match &extent {
-        Geometry::Polygon(_) => Ok(()),
-        Geometry::MultiPolygon(_) => Ok(()),
-        Geometry::GeometryCollection(gc) => {
-            // test features within geometry collection
-            for g in gc.iter() {
-                match g {
-                    Geometry::Polygon(_) => {}
-                    Geometry::MultiPolygon(_) => {}
-                    _ => {
-                        return Err(ScheduleError::InvalidData(format!(
-                        "WKT in '{extent_file}' is a GeometryCollection with non-polygonal features"
-                    )))
-                    }
-                }
-            }
-            Ok(())
-        }
-        _ => Err(ScheduleError::InvalidData(format!(
-            "WKT in '{extent_file}' is not polygonal"
-        ))),
-    }?;
-    Ok(Some(extent))
-}
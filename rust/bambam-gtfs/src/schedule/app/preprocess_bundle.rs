@@ -0,0 +1,413 @@
+use crate::schedule::bundle_ops::{self, ProcessBundlesConfig};
+use crate::schedule::distance_calculation_policy::DistanceCalculationPolicy;
+use crate::schedule::schedule_error::ScheduleError;
+use crate::schedule::{
+    DateMappingPolicy, DateMappingPolicyConfig, DateMappingPolicyType, MissingStopLocationPolicy,
+    PreprocessBundleSummary,
+};
+use clap::Args;
+use geo::Geometry;
+use geozero::{wkt::Wkt as WktReader, ToGeo};
+use kdam::Bar;
+use routee_compass_core::model::map::SpatialIndex;
+use routee_compass_core::model::network::Vertex;
+use routee_compass_core::util::fs::read_utils;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use uom::si::f64::Length;
+
+/// arguments for processing GTFS archives into Compass edge lists.
+#[derive(Debug, Clone, Serialize, Deserialize, Args)]
+pub struct PreprocessBundleArgs {
+    /// a single GTFS archive or a directory of GTFS archives
+    #[arg(long)]
+    pub input: String,
+    /// in this case of a single input file, this sets the edge list id for that input.
+    /// for a directory input, sets the starting edge list id.
+    #[arg(long)]
+    pub starting_edge_list_id: usize,
+
+    #[arg(long, default_value_t = 1)]
+    pub parallelism: usize,
+
+    #[arg(long)]
+    pub output_directory: String,
+
+    #[arg(long)]
+    pub vertices_compass_filename: String,
+
+    #[arg(long)]
+    pub start_date: String,
+
+    #[arg(long)]
+    pub end_date: String,
+
+    #[arg(long)]
+    pub start_time: Option<String>,
+
+    #[arg(long)]
+    pub end_time: Option<String>,
+
+    #[arg(long, default_value_t = 325.)]
+    pub vertex_match_tolerance: f64,
+
+    #[arg(long, value_enum, default_value_t=MissingStopLocationPolicy::Fail)]
+    pub missing_stop_location_policy: MissingStopLocationPolicy,
+
+    #[arg(long, value_enum, default_value_t=DistanceCalculationPolicy::Haversine)]
+    pub distance_calculation_policy: DistanceCalculationPolicy,
+
+    #[arg(long, value_enum)]
+    pub date_mapping_policy: DateMappingPolicyType,
+
+    #[arg(long)]
+    pub date_mapping_date_tolerance: Option<u64>,
+
+    #[arg(long)]
+    pub date_mapping_match_weekday: Option<bool>,
+
+    #[arg(long)]
+    pub extent_file: Option<String>,
+
+    #[arg(long, default_value_t = true)]
+    pub overwrite: bool,
+
+    #[arg(long, default_value_t = true)]
+    pub ignore_bad_gtfs: bool,
+}
+
+impl PreprocessBundleArgs {
+    /// processes the input archive(s) and writes an edge list for each archive with
+    /// edges to the output directory.
+    pub fn run(&self) -> Result<PreprocessBundleSummary, ScheduleError> {
+        let spatial_index = load_vertices_and_create_spatial_index(
+            &self.vertices_compass_filename,
+            self.vertex_match_tolerance,
+        )?;
+
+        // build the date mapping policy based on the arguments
+        let date_mapping_config = DateMappingPolicyConfig::new(
+            &self.start_date,
+            &self.end_date,
+            self.start_time.as_ref(),
+            self.end_time.as_ref(),
+            &self.date_mapping_policy,
+            self.date_mapping_date_tolerance,
+            self.date_mapping_match_weekday,
+        )?;
+        let date_mapping_policy =
+            DateMappingPolicy::try_from(&date_mapping_config).map_err(|e| {
+                ScheduleError::GtfsApp(format!(
+                    "invalid date mapping arguments caused error '{}': {}",
+                    e,
+                    serde_json::to_string_pretty(&date_mapping_config).unwrap_or_default(),
+                ))
+            })?;
+        let extent = read_extent_file(self.extent_file.as_ref())?;
+
+        let config = Arc::new(ProcessBundlesConfig {
+            start_date: self.start_date.clone(),
+            end_date: self.end_date.clone(),
+            spatial_index,
+            starting_edge_list_id: self.starting_edge_list_id,
+            missing_stop_location_policy: self.missing_stop_location_policy.clone(),
+            distance_calculation_policy: self.distance_calculation_policy.clone(),
+            date_mapping_policy,
+            extent,
+            output_directory: self.output_directory.clone(),
+            overwrite: self.overwrite,
+        });
+
+        let input_path = Path::new(&self.input);
+        if input_path.is_dir() {
+            return bundle_ops::batch_process(
+                input_path,
+                self.parallelism,
+                config,
+                self.ignore_bad_gtfs,
+            );
+        }
+
+        let mut summary = PreprocessBundleSummary {
+            archives: 1,
+            ..Default::default()
+        };
+        match bundle_ops::process_bundle(&self.input, config.clone())? {
+            None => {
+                log::warn!(
+                    "GTFS archive import was skipped as the extent does not intersect the archive"
+                );
+                summary.skipped_archives = 1;
+            }
+            Some(bundle) => {
+                log::info!("{}", bundle.distance_calculation);
+                bundle_ops::write_bundle(&bundle, config.clone(), config.starting_edge_list_id)?;
+                summary.edge_list_ids.push(config.starting_edge_list_id);
+                summary.edges = bundle.edges.len();
+                summary.map_matching = bundle.map_matching;
+                summary.distance_calculation = bundle.distance_calculation;
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// helper function for loading a spatial index over the vertices of the graph.
+fn load_vertices_and_create_spatial_index(
+    vertices_compass_filename: &str,
+    tolerance_meters: f64,
+) -> Result<Arc<SpatialIndex>, ScheduleError> {
+    let bar_builder = Bar::builder().desc("read vertices file");
+    let vertices: Box<[Vertex]> = read_utils::from_csv(
+        &Path::new(vertices_compass_filename),
+        true,
+        Some(bar_builder),
+        None,
+    )
+    .map_err(|e| ScheduleError::FailedToCreateVertexIndex(format!("{e}")))?;
+    let tol: Length = uom::si::f64::Length::new::<uom::si::length::meter>(tolerance_meters);
+    Ok(Arc::new(SpatialIndex::new_vertex_oriented(
+        &vertices,
+        Some(tol),
+    )))
+}
+
+fn read_extent_file(extent_file: Option<&String>) -> Result<Option<Geometry>, ScheduleError> {
+    let extent_file = match extent_file {
+        Some(f) => f,
+        None => return Ok(None),
+    };
+    let extent_string = std::fs::read_to_string(extent_file).map_err(|e| {
+        ScheduleError::InvalidData(format!("failed to read WKT from '{extent_file}': {e}"))
+    })?;
+    let extent = WktReader(extent_string.as_str()).to_geo().map_err(|e| {
+        ScheduleError::InvalidData(format!("could not read file '{extent_file}' as WKT: {e}"))
+    })?;
+    match &extent {
+        Geometry::Polygon(_) => Ok(()),
+        Geometry::MultiPolygon(_) => Ok(()),
+        Geometry::GeometryCollection(gc) => {
+            // test features within geometry collection
+            for g in gc.iter() {
+                match g {
+                    Geometry::Polygon(_) => {}
+                    Geometry::MultiPolygon(_) => {}
+                    _ => {
+                        return Err(ScheduleError::InvalidData(format!(
+                        "WKT in '{extent_file}' is a GeometryCollection with non-polygonal features"
+                    )))
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Err(ScheduleError::InvalidData(format!(
+            "WKT in '{extent_file}' is not polygonal"
+        ))),
+    }?;
+    Ok(Some(extent))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_extent_file, PreprocessBundleArgs};
+    use crate::schedule::distance_calculation_policy::DistanceCalculationPolicy;
+    use crate::schedule::schedule_error::ScheduleError;
+    use crate::schedule::{DateMappingPolicyType, MissingStopLocationPolicy};
+    use flate2::read::GzDecoder;
+    use std::io::{BufRead, BufReader};
+    use std::path::{Path, PathBuf};
+
+    /// test assets downloaded by script/setup_test_bambam.sh
+    fn boulder_file(filename: &str) -> String {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("boulder_co")
+            .join(filename)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// arguments matching configuration/bambam-gtfs/local-match-nearest-date.sh
+    fn boulder_args(input: String, output_directory: &Path) -> PreprocessBundleArgs {
+        PreprocessBundleArgs {
+            input,
+            starting_edge_list_id: 3,
+            parallelism: 1,
+            output_directory: output_directory.to_string_lossy().to_string(),
+            vertices_compass_filename: boulder_file("vertices-complete.csv.gz"),
+            start_date: String::from("08-15-2025"),
+            end_date: String::from("08-15-2025"),
+            start_time: None,
+            end_time: None,
+            vertex_match_tolerance: 325.0,
+            missing_stop_location_policy: MissingStopLocationPolicy::Fail,
+            distance_calculation_policy: DistanceCalculationPolicy::Haversine,
+            date_mapping_policy: DateMappingPolicyType::NearestDate,
+            date_mapping_date_tolerance: Some(7),
+            date_mapping_match_weekday: Some(true),
+            extent_file: None,
+            overwrite: true,
+            ignore_bad_gtfs: true,
+        }
+    }
+
+    /// number of rows in a gzipped CSV file with a header.
+    fn count_rows(file: &Path) -> usize {
+        let f = std::fs::File::open(file).expect("test invariant failed");
+        BufReader::new(GzDecoder::new(f)).lines().count() - 1
+    }
+
+    fn write_file(dir: &Path, filename: &str, contents: &str) -> String {
+        let path = dir.join(filename);
+        std::fs::write(&path, contents).expect("test invariant failed");
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_e2e_preprocess_bundle_summary() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let args = boulder_args(boulder_file("ucb-gtfs.zip"), tmp.path());
+        let summary = args.run().expect("test invariant failed");
+        assert_eq!(summary.archives, 1);
+        assert_eq!(summary.skipped_archives, 0);
+        assert!(summary.failed_archives.is_empty());
+        assert_eq!(summary.edge_list_ids, vec![3]);
+        assert!(summary.edges > 0);
+        assert_eq!(summary.map_matching.dropped_stops, 0);
+        assert_eq!(
+            count_rows(&tmp.path().join("edges-compass-3.csv.gz")),
+            summary.edges
+        );
+        assert!(tmp.path().join("edges-gtfs-metadata-3.json").is_file());
+    }
+
+    #[test]
+    fn test_e2e_preprocess_bundle_directory() {
+        // a directory with one valid archive and one that is not a GTFS archive
+        let input = tempfile::tempdir().expect("test invariant failed");
+        let output = tempfile::tempdir().expect("test invariant failed");
+        std::fs::copy(
+            boulder_file("ucb-gtfs.zip"),
+            input.path().join("ucb-gtfs.zip"),
+        )
+        .expect("test invariant failed");
+        write_file(input.path(), "not-gtfs.zip", "not a zip archive");
+        let args = boulder_args(input.path().to_string_lossy().to_string(), output.path());
+        let summary = args.run().expect("test invariant failed");
+        assert_eq!(summary.archives, 2);
+        assert_eq!(summary.skipped_archives, 0);
+        assert_eq!(summary.failed_archives.len(), 1);
+        assert_eq!(summary.edge_list_ids, vec![3]);
+        assert_eq!(
+            count_rows(&output.path().join("edges-compass-3.csv.gz")),
+            summary.edges
+        );
+
+        let strict_args = PreprocessBundleArgs {
+            ignore_bad_gtfs: false,
+            ..args
+        };
+        let result = strict_args.run();
+        assert!(matches!(result, Err(ScheduleError::BatchProcessing(_))));
+    }
+
+    #[test]
+    fn test_e2e_preprocess_bundle_outside_extent() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let extent_file = write_file(
+            tmp.path(),
+            "extent.txt",
+            "POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))",
+        );
+        let output_directory = tmp.path().join("output");
+        let args = PreprocessBundleArgs {
+            extent_file: Some(extent_file),
+            ..boulder_args(boulder_file("ucb-gtfs.zip"), &output_directory)
+        };
+        let summary = args.run().expect("test invariant failed");
+        assert_eq!(summary.archives, 1);
+        assert_eq!(summary.skipped_archives, 1);
+        assert!(summary.edge_list_ids.is_empty());
+        assert_eq!(summary.edges, 0);
+        assert!(!output_directory.join("edges-compass-3.csv.gz").exists());
+    }
+
+    #[test]
+    fn test_preprocess_bundle_missing_vertices() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let args = PreprocessBundleArgs {
+            vertices_compass_filename: tmp
+                .path()
+                .join("missing.csv.gz")
+                .to_string_lossy()
+                .to_string(),
+            ..boulder_args(boulder_file("ucb-gtfs.zip"), tmp.path())
+        };
+        let result = args.run();
+        assert!(matches!(
+            result,
+            Err(ScheduleError::FailedToCreateVertexIndex(_))
+        ));
+    }
+
+    #[test]
+    fn test_preprocess_bundle_invalid_date_mapping() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let vertices_file = write_file(
+            tmp.path(),
+            "vertices-compass.csv",
+            "vertex_id,x,y\n0,-105.27,40.01\n1,-105.26,40.01\n",
+        );
+
+        // nearest date mapping requires a date tolerance
+        let args = PreprocessBundleArgs {
+            vertices_compass_filename: vertices_file,
+            date_mapping_date_tolerance: None,
+            ..boulder_args(boulder_file("ucb-gtfs.zip"), tmp.path())
+        };
+        let result = args.run();
+        assert!(matches!(result, Err(ScheduleError::GtfsApp(_))));
+    }
+
+    #[test]
+    fn test_read_extent_file() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        assert!(read_extent_file(None)
+            .expect("test invariant failed")
+            .is_none());
+
+        let polygon = write_file(
+            tmp.path(),
+            "polygon.txt",
+            "POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))",
+        );
+        let collection = write_file(
+            tmp.path(),
+            "collection.txt",
+            "GEOMETRYCOLLECTION(POLYGON((0 0, 1 0, 1 1, 0 1, 0 0)), MULTIPOLYGON(((2 2, 3 2, 3 3, 2 2))))",
+        );
+        for file in [polygon, collection] {
+            let extent = read_extent_file(Some(&file)).expect("test invariant failed");
+            assert!(extent.is_some(), "expected extent from {file}");
+        }
+
+        let point = write_file(tmp.path(), "point.txt", "POINT(0 0)");
+        let mixed = write_file(
+            tmp.path(),
+            "mixed.txt",
+            "GEOMETRYCOLLECTION(POLYGON((0 0, 1 0, 1 1, 0 1, 0 0)), POINT(0 0))",
+        );
+        let invalid = write_file(tmp.path(), "invalid.txt", "NOT VALID WKT");
+        let missing = tmp.path().join("missing.txt").to_string_lossy().to_string();
+        for file in [point, mixed, invalid, missing] {
+            let result = read_extent_file(Some(&file));
+            assert!(
+                matches!(result, Err(ScheduleError::InvalidData(_))),
+                "expected invalid data error from {file}"
+            );
+        }
+    }
+}
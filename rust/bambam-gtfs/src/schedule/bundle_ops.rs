@@ -1,7 +1,7 @@
 use chrono::{Duration, NaiveDate, NaiveDateTime};
 use csv::QuoteStyle;
 use flate2::{write::GzEncoder, Compression};
-use geo::{Geometry, Intersects, LineString, Point};
+use geo::{Geometry, Intersects, Point};
 use geozero::ToWkt;
 use gtfs_structures::{Gtfs, Stop, StopTime};
 use itertools::Itertools;
@@ -13,22 +13,22 @@ use routee_compass_core::model::{
 };
 use serde_json::json;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet},
     fs::File,
     path::Path,
     sync::{Arc, Mutex},
 };
-use uom::si::f64::Length;
 
 use super::{GtfsBundle, GtfsEdge};
 use crate::schedule::{
     batch_processing_error,
     date::DateMapping,
-    distance_calculation_policy::{compute_haversine, DistanceCalculationPolicy},
+    distance_calculation_policy::{DistanceCalculationPolicy, ShapeSegment},
     fq_ops,
     fq_schedule_row::FullyQualifiedScheduleRow,
     schedule_error::ScheduleError,
-    DateMappingPolicy, MissingStopLocationPolicy, ScheduleRow, SortedTrip,
+    DateMappingPolicy, DistanceCalculationSummary, MapMatchSummary, MissingStopLocationPolicy,
+    PreprocessBundleSummary, ScheduleRow, SortedTrip, TripShape,
 };
 
 /// API for running batch or single bundle processing. configures the run of the GTFS import.
@@ -65,14 +65,15 @@ pub struct ProcessBundlesConfig {
 /// * `parallelism` - threads dedicated to GTFS import
 /// * `conf` - configuration for processing, see for options
 /// * `ignore_bad_gtfs` - if true, any failed processing does not terminate import and
-///   remaining archives are processed into edge list outputs. errors are logged.
+///   remaining archives are processed into edge list outputs. errors are logged and
+///   reported in the summary.
 ///
 pub fn batch_process(
     bundle_directory_path: &Path,
     parallelism: usize,
     conf: Arc<ProcessBundlesConfig>,
     ignore_bad_gtfs: bool,
-) -> Result<(), ScheduleError> {
+) -> Result<PreprocessBundleSummary, ScheduleError> {
     let archive_paths = bundle_directory_path
         .read_dir()
         .map_err(|e| ScheduleError::GtfsApp(format!("failure reading directory: {e}")))?
@@ -137,6 +138,21 @@ pub fn batch_process(
 
     eprintln!(); // end progress bar
 
+    let mut summary = PreprocessBundleSummary {
+        archives: n_archives,
+        skipped_archives: n_archives - bundles.len() - errors.len(),
+        edge_list_ids: (0..bundles.len())
+            .map(|index| conf.starting_edge_list_id + index)
+            .collect_vec(),
+        ..Default::default()
+    };
+    for bundle in bundles.iter() {
+        summary.edges += bundle.edges.len();
+        summary.map_matching += &bundle.map_matching;
+        summary.distance_calculation += &bundle.distance_calculation;
+    }
+    log::info!("{}", summary.distance_calculation);
+
     // handle errors, either by terminating early, or, logging them
     if !errors.is_empty() && !ignore_bad_gtfs {
         return Err(batch_processing_error(&errors));
@@ -144,6 +160,7 @@ pub fn batch_process(
         // log errors
         for error in errors {
             log::error!("{error}");
+            summary.failed_archives.push(error.to_string());
         }
     }
 
@@ -173,7 +190,7 @@ pub fn batch_process(
     if !write_errors.is_empty() {
         Err(batch_processing_error(&write_errors))
     } else {
-        Ok(())
+        Ok(summary)
     }
 }
 
@@ -186,11 +203,12 @@ pub fn process_bundle(
     log::debug!("process_bundle called for {bundle_file}");
     // read the GTFS archive. pre-process by removing Trips that contain stops
     // which do not map to the road network vertices within the matching distance threshold.
-    let gtfs = Arc::new(read_gtfs(
+    let (gtfs, map_matching) = read_gtfs(
         bundle_file,
         c.spatial_index.clone(),
         &c.missing_stop_location_policy,
-    )?);
+    )?;
+    let gtfs = Arc::new(gtfs);
 
     // if user provided an extent, use it to filter GTFS archives
     if let Some(extent) = c.extent.as_ref() {
@@ -208,10 +226,23 @@ pub fn process_bundle(
         .map(|(stop_id, stop)| (stop_id.clone(), get_stop_location(stop.clone(), &gtfs)))
         .collect();
 
+    // prepare trip shapes for cutting stop-to-stop geometries. the positions of the
+    // stops of each trip along its shape are found once and reused across target dates.
+    let trip_shapes: HashMap<&String, TripShape> = if c.distance_calculation_policy.uses_shapes() {
+        gtfs.shapes
+            .iter()
+            .filter_map(|(shape_id, shape)| TripShape::new(shape).map(|s| (shape_id, s)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let mut stop_positions: HashMap<String, Vec<Option<f64>>> = HashMap::new();
+
     // Construct edge lists
     let mut edge_id: EdgeId = EdgeId(0);
     let mut edges: HashMap<(VertexId, VertexId), GtfsEdge> = HashMap::new();
     let mut date_mapping: HashSet<DateMapping> = HashSet::new();
+    let mut distance_calculation = DistanceCalculationSummary::default();
     for target_date in c.date_mapping_policy.iter() {
         for raw_trip in gtfs.trips.values() {
             // sort the stop_time sequence of the trip before proceeding
@@ -241,14 +272,35 @@ pub fn process_bundle(
                 let _ = date_mapping.insert(dm);
             }
 
-            for (src, dst) in trip.stop_times.windows(2).map(|w| (&w[0], &w[1])) {
+            let trip_shape = trip.shape_id.as_ref().and_then(|id| trip_shapes.get(id));
+            let positions = trip_shape.map(|shape| {
+                &*stop_positions
+                    .entry(trip.trip_id.clone())
+                    .or_insert_with(|| locate_trip_stops(shape, &trip, &stop_locations))
+            });
+
+            for (idx, (src, dst)) in trip
+                .stop_times
+                .windows(2)
+                .map(|w| (&w[0], &w[1]))
+                .enumerate()
+            {
+                let segment = trip_shape
+                    .zip(positions)
+                    .map(|(shape, positions)| ShapeSegment {
+                        shape,
+                        start: positions[idx],
+                        end: positions[idx + 1],
+                    });
                 process_schedule(
                     &picked_date,
                     src,
                     dst,
                     &trip,
+                    segment,
                     &mut edges,
                     &mut edge_id,
+                    &mut distance_calculation,
                     c.clone(),
                     gtfs.clone(),
                     &stop_locations,
@@ -274,18 +326,20 @@ pub fn process_bundle(
         edges: edges_sorted,
         metadata,
         date_mapping,
+        distance_calculation,
+        map_matching,
     };
 
     Ok(Some(result))
 }
 
 /// reads a GTFS archive. applies the missing stop matching policy, removing any disconnected
-/// Trips that include Stops which cannot be map matched.
+/// Trips that include Stops which cannot be map matched, and reports how many were removed.
 pub fn read_gtfs(
     gtfs_file: &str,
     spatial_index: Arc<SpatialIndex>,
     missing_stop_matching_policy: &MissingStopLocationPolicy,
-) -> Result<Gtfs, ScheduleError> {
+) -> Result<(Gtfs, MapMatchSummary), ScheduleError> {
     let mut gtfs = Gtfs::new(gtfs_file)?;
     let mut disconnected_stops = HashSet::new();
     for stop in gtfs.stops.values() {
@@ -329,7 +383,11 @@ pub fn read_gtfs(
             disconnected_trips.len(),
         )
     }
-    Ok(gtfs)
+    let map_matching = MapMatchSummary {
+        dropped_stops: disconnected_stops.len(),
+        dropped_trips: disconnected_trips.len(),
+    };
+    Ok((gtfs, map_matching))
 }
 
 /// writes the provided bundle to files enumerated by the provided edge_list_id.
@@ -355,6 +413,8 @@ pub fn write_bundle(
     let fq_route_ids = construct_fq_route_id_list(bundle, edge_list_id);
     metadata["date_mapping"] = json![date_mapping];
     metadata["fq_route_ids"] = json![fq_route_ids];
+    metadata["distance_calculation"] = json![bundle.distance_calculation];
+    metadata["map_matching"] = json![bundle.map_matching];
 
     let metadata_str = serde_json::to_string_pretty(&metadata).map_err(|e| {
         ScheduleError::GtfsApp(format!("failure writing GTFS Agencies as JSON string: {e}"))
@@ -438,7 +498,8 @@ pub fn write_bundle(
 /// on the specified date with the following logic:
 ///  - checks that the departure + arrival times are within date mapping time range, if provided
 ///  - map matches the stops to the graph.
-///  - creates a [GtfsEdge] if one does not yet exist between these vertices.
+///  - creates a [GtfsEdge] if one does not yet exist between these vertices, using the
+///    trip shape segment for geometry and distance when provided and supported by policy.
 ///  - handles presence of src + dst times and constructs the datetimes to write to our schedule row
 ///  - adds this schedule row to our GtfsEdge
 #[allow(clippy::too_many_arguments)]
@@ -447,8 +508,10 @@ fn process_schedule(
     src: &StopTime,
     dst: &StopTime,
     trip: &SortedTrip,
+    segment: Option<ShapeSegment>,
     edges: &mut HashMap<(VertexId, VertexId), GtfsEdge>,
     edge_id: &mut EdgeId,
+    distance_calculation: &mut DistanceCalculationSummary,
     c: Arc<ProcessBundlesConfig>,
     gtfs: Arc<Gtfs>,
     stop_locations: &HashMap<String, Option<Point<f64>>>,
@@ -464,34 +527,27 @@ fn process_schedule(
 
     // This only gets to run if all previous conditions are met
     // it adds the edge if it has not yet been added.
-    let gtfs_edge = edges.entry((src_id, dst_id)).or_insert_with(|| {
-        let geometry = match &c.distance_calculation_policy {
-            DistanceCalculationPolicy::Haversine => LineString::new(vec![src_point.0, dst_point.0]),
-            DistanceCalculationPolicy::Shape => todo!(),
-            DistanceCalculationPolicy::Fallback => todo!(),
-        };
-
-        // Estimate distance
-        let distance: Length = match &c.distance_calculation_policy {
-            DistanceCalculationPolicy::Haversine => compute_haversine(src_point, dst_point),
-            DistanceCalculationPolicy::Shape => todo!(),
-            DistanceCalculationPolicy::Fallback => todo!(),
-        };
-
-        let edge = EdgeConfig {
-            edge_id: *edge_id,
-            src_vertex_id: src_id,
-            dst_vertex_id: dst_id,
-            distance: distance.get::<uom::si::length::meter>(),
-        };
-
-        let gtfs_edge = GtfsEdge::new(edge, geometry);
+    let gtfs_edge = match edges.entry((src_id, dst_id)) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let (geometry, distance) = c
+                .distance_calculation_policy
+                .compute_edge(src_point, dst_point, segment, distance_calculation)
+                .map_err(|e| ScheduleError::InvalidData(format!("trip {}: {e}", trip.trip_id)))?;
+
+            let edge = EdgeConfig {
+                edge_id: *edge_id,
+                src_vertex_id: src_id,
+                dst_vertex_id: dst_id,
+                distance: distance.get::<uom::si::length::meter>(),
+            };
 
-        // NOTE: edge id update completed after creating this Edge
-        *edge_id = EdgeId(edge_id.0 + 1);
+            // NOTE: edge id update completed after creating this Edge
+            *edge_id = EdgeId(edge_id.0 + 1);
 
-        gtfs_edge
-    });
+            entry.insert(GtfsEdge::new(edge, geometry))
+        }
+    };
 
     let (src_departure_time, dst_arrival_time) = match (src.departure_time, dst.arrival_time) {
         (None, Some(t)) | (Some(t), None) => {
@@ -675,6 +731,26 @@ fn map_match(
     }
 }
 
+/// finds the position of each stop of a trip along the trip's shape.
+fn locate_trip_stops(
+    shape: &TripShape,
+    trip: &SortedTrip,
+    stop_locations: &HashMap<String, Option<Point<f64>>>,
+) -> Vec<Option<f64>> {
+    let stops = trip
+        .stop_times
+        .iter()
+        .map(|st| {
+            let point = st
+                .stop
+                .as_ref()
+                .and_then(|stop| stop_locations.get(&stop.id).copied().flatten());
+            (point, st.shape_dist_traveled.map(|d| d as f64))
+        })
+        .collect_vec();
+    shape.locate_stops(&stops)
+}
+
 /// helper function for map matching stop locations to the graph.
 fn match_closest_graph_id(
     point: &Point<f64>,
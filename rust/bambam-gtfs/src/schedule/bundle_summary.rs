@@ -0,0 +1,54 @@
+use std::{fmt::Display, ops::AddAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::DistanceCalculationSummary;
+
+/// stops that could not be map matched to the road network and the trips removed
+/// because they visit one of these stops.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MapMatchSummary {
+    pub dropped_stops: usize,
+    pub dropped_trips: usize,
+}
+
+impl AddAssign<&MapMatchSummary> for MapMatchSummary {
+    fn add_assign(&mut self, rhs: &MapMatchSummary) {
+        self.dropped_stops += rhs.dropped_stops;
+        self.dropped_trips += rhs.dropped_trips;
+    }
+}
+
+/// outcome of processing one or more GTFS archives into Compass edge lists.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PreprocessBundleSummary {
+    /// GTFS archives read
+    pub archives: usize,
+    /// archives with no edges to write, such as those outside of the extent
+    pub skipped_archives: usize,
+    /// errors of archives that failed to process while bad archives are ignored
+    pub failed_archives: Vec<String>,
+    /// edge list ids of the edge lists written, one per archive
+    pub edge_list_ids: Vec<usize>,
+    /// edges written across all edge lists
+    pub edges: usize,
+    pub map_matching: MapMatchSummary,
+    pub distance_calculation: DistanceCalculationSummary,
+}
+
+impl Display for PreprocessBundleSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "wrote {} edge lists with {} edges from {} archives ({} skipped, {} failed), dropped {} unmatched stops and {} trips; {}",
+            self.edge_list_ids.len(),
+            self.edges,
+            self.archives,
+            self.skipped_archives,
+            self.failed_archives.len(),
+            self.map_matching.dropped_stops,
+            self.map_matching.dropped_trips,
+            self.distance_calculation
+        )
+    }
+}
@@ -159,6 +159,29 @@ pub fn find_nearest_add_exception(
     }
 }
 
+/// finds the date nearest to the target date among a set of known dates, such as the
+/// service dates of a processed dataset, which is within some date_tolerance. ties
+/// between dates equally far from the target are broken by choosing the earlier date.
+pub fn find_nearest_date<'a>(
+    target: &NaiveDate,
+    dates: impl IntoIterator<Item = &'a NaiveDate>,
+    date_tolerance: u64,
+    match_weekday: bool,
+) -> Option<NaiveDate> {
+    dates
+        .into_iter()
+        .filter(|d| !match_weekday || d.weekday() == target.weekday())
+        .map(|d| {
+            (
+                target.signed_duration_since(*d).num_days().unsigned_abs(),
+                *d,
+            )
+        })
+        .filter(|(days, _)| *days <= date_tolerance)
+        .min()
+        .map(|(_, d)| d)
+}
+
 /// adds (or when step is negative, subtracts) days from a date.
 pub fn step_date(date: NaiveDate, step: i64) -> Result<NaiveDate, ScheduleError> {
     if step == 0 {
@@ -387,6 +410,29 @@ mod tests {
         assert!(result); // True because no entries means no delete exceptions
     }
 
+    // Tests for find_nearest_date
+    #[test]
+    fn test_find_nearest_date() {
+        let date = |d| NaiveDate::from_ymd_opt(2023, 6, d).unwrap();
+        // 2023-06-15 is a thursday
+        let dates = [date(1), date(8), date(13), date(17), date(22)];
+        assert_eq!(
+            find_nearest_date(&date(15), &dates, 7, false),
+            Some(date(13))
+        );
+        assert_eq!(find_nearest_date(&date(15), &dates, 7, true), Some(date(8)));
+        assert_eq!(find_nearest_date(&date(15), &dates, 1, false), None);
+        assert_eq!(
+            find_nearest_date(&date(13), &dates, 0, false),
+            Some(date(13))
+        );
+        // ties choose the earlier date
+        assert_eq!(
+            find_nearest_date(&date(15), &[date(17), date(13)], 2, false),
+            Some(date(13))
+        );
+    }
+
     // Tests for find_nearest_add_exception
     #[test]
     fn test_find_nearest_add_exception_exact_match() {
@@ -1,12 +1,16 @@
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
-use crate::schedule::schedule_error::ScheduleError;
+use crate::schedule::{date::date_ops, schedule_error::ScheduleError};
 
 /// used to tag the type of mapping policy when constructing from CLI.
-#[derive(Serialize, Deserialize, Clone, Debug, ValueEnum)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
 #[serde(rename_all = "snake_case")]
 pub enum DateMappingPolicyType {
+    #[default]
     ExactDate,
     ExactRange,
     NearestDate,
@@ -75,6 +79,64 @@ pub enum DateMappingPolicyConfig {
     },
 }
 
+/// default date tolerance of the best case policy: +- 10 years.
+const BEST_CASE_DATE_TOLERANCE: u64 = 10 * 365;
+
+impl DateMappingPolicyType {
+    /// the date tolerance and weekday matching used by this policy to search for the
+    /// nearest date, or None for policies that only use exact dates. fails when a
+    /// nearest date policy is missing its arguments.
+    pub fn nearest_date_args(
+        &self,
+        date_tolerance: Option<u64>,
+        match_weekday: Option<bool>,
+    ) -> Result<Option<(u64, bool)>, ScheduleError> {
+        use DateMappingPolicyType as Type;
+        match self {
+            Type::ExactDate | Type::ExactRange | Type::ExactDateTimeRange => Ok(None),
+            Type::NearestDate | Type::NearestRange | Type::NearestDateTimeRange => {
+                let match_weekday = match_weekday.ok_or_else(|| ScheduleError::GtfsApp(String::from("for nearest-date mapping, must specify 'match_weekday' as 'true' or 'false'")))?;
+                let date_tolerance = date_tolerance.ok_or_else(|| {
+                    ScheduleError::GtfsApp(String::from(
+                        "for nearest-date mapping, must specify a date_tolerance in [0, inf)",
+                    ))
+                })?;
+                Ok(Some((date_tolerance, match_weekday)))
+            }
+            Type::BestCase => Ok(Some((
+                date_tolerance.unwrap_or(BEST_CASE_DATE_TOLERANCE),
+                match_weekday.unwrap_or(true),
+            ))),
+        }
+    }
+
+    /// picks the date to use for a target date among a set of service dates that have
+    /// already been processed, such as the dates of a multi-date GTFS-Flex import.
+    /// follows the same exact, nearest and best case rules as
+    /// [`super::DateMappingPolicy::pick_date`]. returns None if no service date
+    /// satisfies this policy.
+    pub fn pick_service_date(
+        &self,
+        target: &NaiveDate,
+        service_dates: &BTreeSet<NaiveDate>,
+        date_tolerance: Option<u64>,
+        match_weekday: Option<bool>,
+    ) -> Result<Option<NaiveDate>, ScheduleError> {
+        let exact = service_dates.get(target).cloned();
+        let nearest_args = self.nearest_date_args(date_tolerance, match_weekday)?;
+        let picked = match (self, nearest_args) {
+            (_, None) => exact,
+            (DateMappingPolicyType::BestCase, Some((tolerance, _))) => exact
+                .or_else(|| date_ops::find_nearest_date(target, service_dates, tolerance, true))
+                .or_else(|| date_ops::find_nearest_date(target, service_dates, tolerance, false)),
+            (_, Some((tolerance, match_weekday))) => {
+                date_ops::find_nearest_date(target, service_dates, tolerance, match_weekday)
+            }
+        };
+        Ok(picked)
+    }
+}
+
 impl DateMappingPolicyConfig {
     /// build a new [`DateMappingPolicy`] configuration from CLI arguments.
     pub fn new(
@@ -188,3 +250,69 @@ impl DateMappingPolicyConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DateMappingPolicyType;
+    use chrono::NaiveDate;
+    use std::collections::BTreeSet;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).expect("test invariant")
+    }
+
+    /// service dates on friday 2024-03-01 and monday 2024-03-04
+    fn service_dates() -> BTreeSet<NaiveDate> {
+        BTreeSet::from([date(1), date(4)])
+    }
+
+    #[test]
+    fn test_pick_service_date_exact() {
+        let policy = DateMappingPolicyType::ExactDate;
+        let dates = service_dates();
+        let picked = policy.pick_service_date(&date(4), &dates, None, None);
+        assert_eq!(picked.ok(), Some(Some(date(4))));
+        let picked = policy.pick_service_date(&date(5), &dates, Some(7), Some(false));
+        assert_eq!(picked.ok(), Some(None));
+    }
+
+    #[test]
+    fn test_pick_service_date_nearest() {
+        let policy = DateMappingPolicyType::NearestDate;
+        let dates = service_dates();
+        // saturday 2024-03-02 is nearest to friday
+        let picked = policy.pick_service_date(&date(2), &dates, Some(3), Some(false));
+        assert_eq!(picked.ok(), Some(Some(date(1))));
+        // monday 2024-03-11 matches the weekday of monday 2024-03-04
+        let picked = policy.pick_service_date(&date(11), &dates, Some(7), Some(true));
+        assert_eq!(picked.ok(), Some(Some(date(4))));
+        let picked = policy.pick_service_date(&date(11), &dates, Some(6), Some(true));
+        assert_eq!(picked.ok(), Some(None));
+    }
+
+    #[test]
+    fn test_pick_service_date_nearest_requires_arguments() {
+        let policy = DateMappingPolicyType::NearestDate;
+        let dates = service_dates();
+        assert!(policy
+            .pick_service_date(&date(2), &dates, None, Some(true))
+            .is_err());
+        assert!(policy
+            .pick_service_date(&date(2), &dates, Some(3), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pick_service_date_best_case() {
+        let policy = DateMappingPolicyType::BestCase;
+        let dates = service_dates();
+        let picked = policy.pick_service_date(&date(4), &dates, None, None);
+        assert_eq!(picked.ok(), Some(Some(date(4))));
+        // friday 2024-03-08 prefers friday 2024-03-01 over the nearer monday
+        let picked = policy.pick_service_date(&date(8), &dates, None, None);
+        assert_eq!(picked.ok(), Some(Some(date(1))));
+        // without a weekday match within tolerance, falls back to the nearest date
+        let picked = policy.pick_service_date(&date(6), &dates, Some(3), None);
+        assert_eq!(picked.ok(), Some(Some(date(4))));
+    }
+}
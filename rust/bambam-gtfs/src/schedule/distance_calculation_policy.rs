@@ -1,15 +1,124 @@
+use std::{fmt::Display, ops::AddAssign};
+
 use clap::ValueEnum;
 use geo::{line_string, Haversine, Length, LineString, Point};
 use serde::{Deserialize, Serialize};
 
+use crate::schedule::{schedule_error::ScheduleError, trip_shape::TripShape};
+
 #[derive(Serialize, Deserialize, Debug, ValueEnum, Clone)]
 pub enum DistanceCalculationPolicy {
+    /// straight line between stops with haversine distance
     Haversine,
+    /// cut the trip shape between stops. fails if a trip has no shape or a stop
+    /// cannot be located on its shape.
     Shape,
+    /// cut the trip shape between stops, falling back to haversine for any edge
+    /// where the shape cannot be used.
     Fallback,
 }
 
+/// the portion of a trip shape traversed between two consecutive stops, with the
+/// stop positions in meters from the start of the shape.
+pub struct ShapeSegment<'a> {
+    pub shape: &'a TripShape,
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+}
+
+/// counts of edges by the method used to compute their geometry and distance.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DistanceCalculationSummary {
+    /// edges computed as straight lines by the haversine policy
+    pub haversine: usize,
+    /// edges cut from a trip shape
+    pub shape: usize,
+    /// edges computed as straight lines after failing to cut a trip shape
+    pub fallback: usize,
+}
+
+impl DistanceCalculationPolicy {
+    /// true if this policy reads trip shapes.
+    pub fn uses_shapes(&self) -> bool {
+        !matches!(self, DistanceCalculationPolicy::Haversine)
+    }
+
+    /// computes the geometry and distance of an edge between two stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_point` - location of the source stop
+    /// * `dst_point` - location of the destination stop
+    /// * `segment` - the trip shape between these stops, if the trip has a shape
+    /// * `summary` - updated with the method used for this edge
+    ///
+    /// # Returns
+    ///
+    /// the edge geometry and distance, or an error if the shape policy is used and
+    /// the shape cannot be cut between these stops.
+    pub fn compute_edge(
+        &self,
+        src_point: Point<f64>,
+        dst_point: Point<f64>,
+        segment: Option<ShapeSegment>,
+        summary: &mut DistanceCalculationSummary,
+    ) -> Result<(LineString<f64>, uom::si::f64::Length), ScheduleError> {
+        if let DistanceCalculationPolicy::Haversine = self {
+            summary.haversine += 1;
+            return Ok(haversine_edge(src_point, dst_point));
+        }
+        let cut = segment.and_then(|s| match (s.start, s.end) {
+            (Some(start), Some(end)) => s.shape.cut(start, end),
+            _ => None,
+        });
+        match (cut, self) {
+            (Some(linestring), _) => {
+                summary.shape += 1;
+                let distance = uom::si::f64::Length::new::<uom::si::length::meter>(
+                    Haversine.length(&linestring),
+                );
+                Ok((linestring, distance))
+            }
+            (None, DistanceCalculationPolicy::Fallback) => {
+                summary.fallback += 1;
+                Ok(haversine_edge(src_point, dst_point))
+            }
+            (None, _) => Err(ScheduleError::ShapeCut(format!(
+                "unable to cut trip shape between stops at {:?} and {:?}",
+                src_point.x_y(),
+                dst_point.x_y()
+            ))),
+        }
+    }
+}
+
+impl AddAssign<&DistanceCalculationSummary> for DistanceCalculationSummary {
+    fn add_assign(&mut self, rhs: &DistanceCalculationSummary) {
+        self.haversine += rhs.haversine;
+        self.shape += rhs.shape;
+        self.fallback += rhs.fallback;
+    }
+}
+
+impl Display for DistanceCalculationSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "edge distances computed by haversine: {}, shape: {}, haversine fallback: {}",
+            self.haversine, self.shape, self.fallback
+        )
+    }
+}
+
 pub fn compute_haversine(src_point: Point<f64>, dst_point: Point<f64>) -> uom::si::f64::Length {
     let line: LineString<f64> = line_string![src_point.0, dst_point.0];
     uom::si::f64::Length::new::<uom::si::length::meter>(Haversine.length(&line))
 }
+
+fn haversine_edge(
+    src_point: Point<f64>,
+    dst_point: Point<f64>,
+) -> (LineString<f64>, uom::si::f64::Length) {
+    let geometry = LineString::new(vec![src_point.0, dst_point.0]);
+    (geometry, compute_haversine(src_point, dst_point))
+}
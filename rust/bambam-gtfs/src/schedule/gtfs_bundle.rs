@@ -1,12 +1,14 @@
 use std::collections::HashSet;
 
-use crate::schedule::{date::DateMapping, GtfsEdge};
+use crate::schedule::{date::DateMapping, DistanceCalculationSummary, GtfsEdge, MapMatchSummary};
 
 /// the result of processing one GTFS archive for Compass
 pub struct GtfsBundle {
     pub edges: Vec<GtfsEdge>,
     pub metadata: serde_json::Value,
     pub date_mapping: HashSet<DateMapping>,
+    pub distance_calculation: DistanceCalculationSummary,
+    pub map_matching: MapMatchSummary,
 }
 
 impl GtfsBundle {
@@ -16,6 +18,8 @@ impl GtfsBundle {
             edges: vec![],
             metadata: serde_json::Value::Null,
             date_mapping: HashSet::new(),
+            distance_calculation: DistanceCalculationSummary::default(),
+            map_matching: MapMatchSummary::default(),
         }
     }
 
@@ -1,3 +1,4 @@
+mod bundle_summary;
 mod date_mapping_policy_config;
 mod distance_calculation_policy;
 mod missing_stop_matching_policy;
@@ -6,6 +7,7 @@ mod schedule_error;
 mod schedule_row;
 mod sorted_trip;
 mod summary;
+mod trip_shape;
 
 pub mod app;
 pub mod bundle_ops;
@@ -17,13 +19,16 @@ mod gtfs_bundle;
 mod gtfs_edge;
 
 // pub mod date_ops;
+pub use bundle_summary::{MapMatchSummary, PreprocessBundleSummary};
 pub use date_mapping_policy::DateMappingPolicy;
 pub use date_mapping_policy_config::{DateMappingPolicyConfig, DateMappingPolicyType};
+pub use distance_calculation_policy::{DistanceCalculationPolicy, DistanceCalculationSummary};
 pub use gtfs_bundle::GtfsBundle;
 pub use gtfs_edge::GtfsEdge;
 pub use missing_stop_matching_policy::MissingStopLocationPolicy;
 pub use provider::GtfsProvider;
-pub use schedule_error::batch_processing_error;
+pub use schedule_error::{batch_processing_error, ScheduleError};
 pub use schedule_row::ScheduleRow;
 pub use sorted_trip::SortedTrip;
 pub use summary::GtfsSummary;
+pub use trip_shape::TripShape;
@@ -22,6 +22,8 @@ pub enum ScheduleError {
     MissingAllStopTimes(String),
     #[error("At least one of the stops in edge is missing shape distance traveled: {0} or {1}")]
     MissingShapeDistanceTraveled(String, String),
+    #[error("failure computing edge from trip shape: {0}")]
+    ShapeCut(String),
     #[error("Failed to create vertex index: {0}")]
     FailedToCreateVertexIndex(String),
     #[error("Cannot find service in calendar.txt with service_id: {0}")]
@@ -12,6 +12,8 @@ pub struct SortedTrip {
     pub route_id: String,
     /// service associated with this trip
     pub service_id: String,
+    /// GTFS shape_id of the path travelled by this trip, if provided
+    pub shape_id: Option<String>,
     /// list of [`StopTime`] values associated with this [`Trip`] in sorted order
     pub stop_times: Vec<StopTime>,
     // /// starting date of this trip.
@@ -29,6 +31,7 @@ impl SortedTrip {
             trip_id: trip.id.clone(),
             route_id: trip.route_id.clone(),
             service_id: trip.service_id.clone(),
+            shape_id: trip.shape_id.clone(),
             stop_times,
             // start_date,
         };
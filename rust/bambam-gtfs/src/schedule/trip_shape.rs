@@ -0,0 +1,215 @@
+use geo::{Closest, ClosestPoint, Coord, Distance, Haversine, Line, LineString, Point};
+use gtfs_structures::Shape;
+
+/// stops farther than this from the shape of their trip cannot be projected onto it.
+pub const SHAPE_PROJECTION_TOLERANCE_METERS: f64 = 100.0;
+
+/// a GTFS shape prepared for cutting into stop-to-stop geometries.
+pub struct TripShape {
+    linestring: LineString<f64>,
+    /// cumulative haversine distance in meters at each shape point
+    distances: Vec<f64>,
+    /// `shape_dist_traveled` at each shape point, if present for all points
+    dist_traveled: Option<Vec<f64>>,
+}
+
+impl TripShape {
+    /// builds a shape from the rows of shapes.txt with the same shape_id. returns None
+    /// if there are fewer than two shape points.
+    pub fn new(shape_points: &[Shape]) -> Option<Self> {
+        let mut sorted = shape_points.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|s| s.sequence);
+        if sorted.len() < 2 {
+            return None;
+        }
+        let coords = sorted
+            .iter()
+            .map(|s| Coord {
+                x: s.longitude,
+                y: s.latitude,
+            })
+            .collect::<Vec<_>>();
+        let mut distances = Vec::with_capacity(coords.len());
+        let mut acc = 0.0;
+        distances.push(acc);
+        for pair in coords.windows(2) {
+            acc += Haversine.distance(Point(pair[0]), Point(pair[1]));
+            distances.push(acc);
+        }
+        let dist_traveled = sorted
+            .iter()
+            .map(|s| s.dist_traveled.map(|d| d as f64))
+            .collect::<Option<Vec<_>>>()
+            .filter(|d| d.windows(2).all(|w| w[0] <= w[1]));
+        Some(Self {
+            linestring: LineString::new(coords),
+            distances,
+            dist_traveled,
+        })
+    }
+
+    /// finds the position of each stop of a trip along this shape, in meters from the
+    /// start of the shape. a stop is located by its `shape_dist_traveled` when both the
+    /// stop and the shape provide it, and otherwise by projecting the stop location onto
+    /// the shape, searching only beyond the previous stop so that routes which loop back
+    /// over themselves are cut in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `stops` - for each stop of the trip in order, the stop location and
+    ///   `shape_dist_traveled`, if known
+    ///
+    /// # Returns
+    ///
+    /// the position of each stop, or None for stops that could not be located.
+    pub fn locate_stops(&self, stops: &[(Option<Point<f64>>, Option<f64>)]) -> Vec<Option<f64>> {
+        let mut result = Vec::with_capacity(stops.len());
+        let mut min_line_idx = 0;
+        for (point, dist_traveled) in stops.iter() {
+            let by_dist_traveled = dist_traveled.and_then(|d| self.locate_dist_traveled(d));
+            let located = match (by_dist_traveled, point) {
+                (Some(position), _) => Some(position),
+                (None, Some(p)) => self.project(p, min_line_idx),
+                (None, None) => None,
+            };
+            if let Some((position, line_idx)) = located {
+                min_line_idx = line_idx;
+                result.push(Some(position));
+            } else {
+                result.push(None);
+            }
+        }
+        result
+    }
+
+    /// cuts the portion of the shape between two positions in meters from the start
+    /// of the shape. returns None if the end does not come after the start.
+    pub fn cut(&self, start: f64, end: f64) -> Option<LineString<f64>> {
+        if end <= start {
+            return None;
+        }
+        let (start_idx, start_coord) = self.interpolate(start);
+        let (end_idx, end_coord) = self.interpolate(end);
+        let mut coords = vec![start_coord];
+        coords.extend(
+            self.linestring.0[(start_idx + 1)..=end_idx]
+                .iter()
+                .filter(|c| **c != start_coord && **c != end_coord),
+        );
+        coords.push(end_coord);
+        Some(LineString::new(coords))
+    }
+
+    /// converts a `shape_dist_traveled` value into a position in meters, along with
+    /// the index of the shape line containing it.
+    fn locate_dist_traveled(&self, value: f64) -> Option<(f64, usize)> {
+        let dist_traveled = self.dist_traveled.as_ref()?;
+        let idx = dist_traveled
+            .windows(2)
+            .position(|w| w[0] <= value && value <= w[1])?;
+        let (d0, d1) = (dist_traveled[idx], dist_traveled[idx + 1]);
+        let fraction = if d1 > d0 {
+            (value - d0) / (d1 - d0)
+        } else {
+            0.0
+        };
+        let (m0, m1) = (self.distances[idx], self.distances[idx + 1]);
+        Some((m0 + fraction * (m1 - m0), idx))
+    }
+
+    /// projects a point onto the shape, considering only the shape lines starting at
+    /// `min_line_idx`. the first line within the projection tolerance is chosen, moving
+    /// forward while the following lines are closer to the point.
+    fn project(&self, point: &Point<f64>, min_line_idx: usize) -> Option<(f64, usize)> {
+        let mut best: Option<(f64, f64, usize)> = None;
+        for (idx, line) in self.linestring.lines().enumerate().skip(min_line_idx) {
+            let closest = match line.closest_point(point) {
+                Closest::Intersection(p) | Closest::SinglePoint(p) => p,
+                Closest::Indeterminate => continue,
+            };
+            let distance = Haversine.distance(closest, *point);
+            match best {
+                None if distance <= SHAPE_PROJECTION_TOLERANCE_METERS => {
+                    best = Some((distance, self.position_on_line(&line, idx, closest), idx));
+                }
+                Some((best_distance, _, _)) if distance < best_distance => {
+                    best = Some((distance, self.position_on_line(&line, idx, closest), idx));
+                }
+                Some(_) => break,
+                None => {}
+            }
+        }
+        best.map(|(_, position, idx)| (position, idx))
+    }
+
+    fn position_on_line(&self, line: &Line<f64>, idx: usize, point: Point<f64>) -> f64 {
+        self.distances[idx] + Haversine.distance(Point(line.start), point)
+    }
+
+    /// finds the coordinate at a position in meters along the shape, along with
+    /// the index of the shape line containing it.
+    fn interpolate(&self, position: f64) -> (usize, Coord<f64>) {
+        let last = self.distances.len() - 1;
+        let idx = self
+            .distances
+            .windows(2)
+            .position(|w| position <= w[1])
+            .unwrap_or(last - 1);
+        let (m0, m1) = (self.distances[idx], self.distances[idx + 1]);
+        let fraction = if m1 > m0 {
+            ((position - m0) / (m1 - m0)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (c0, c1) = (self.linestring.0[idx], self.linestring.0[idx + 1]);
+        let coord = Coord {
+            x: c0.x + fraction * (c1.x - c0.x),
+            y: c0.y + fraction * (c1.y - c0.y),
+        };
+        (idx, coord)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TripShape;
+    use geo::{Haversine, Length, Point};
+    use gtfs_structures::Shape;
+
+    fn shape_point(sequence: usize, lon: f64, lat: f64) -> Shape {
+        Shape {
+            id: String::from("s1"),
+            latitude: lat,
+            longitude: lon,
+            sequence,
+            dist_traveled: None,
+        }
+    }
+
+    #[test]
+    fn test_cut_shape_between_projected_stops() {
+        // an L-shaped route, with the stops offset slightly from the shape
+        let shape = TripShape::new(&[
+            shape_point(2, -105.0, 40.01),
+            shape_point(1, -105.0, 40.0),
+            shape_point(3, -104.99, 40.01),
+        ])
+        .unwrap();
+        let stops = vec![
+            (Some(Point::new(-105.0001, 40.0)), None),
+            (Some(Point::new(-104.995, 40.0101)), None),
+            (Some(Point::new(-90.0, 30.0)), None),
+        ];
+        let positions = shape.locate_stops(&stops);
+        assert!(positions[2].is_none());
+        let (start, end) = (positions[0].unwrap(), positions[1].unwrap());
+
+        let cut = shape.cut(start, end).unwrap();
+        assert_eq!(cut.0.len(), 3);
+        let straight = Haversine.length(&geo::line_string![cut.0[0], cut.0[2]]);
+        let along = Haversine.length(&cut);
+        assert!((along - (end - start)).abs() < 1.0);
+        assert!(along > straight);
+        assert!(shape.cut(end, start).is_none());
+    }
+}
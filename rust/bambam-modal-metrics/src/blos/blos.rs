@@ -0,0 +1,69 @@
+pub const MIN_BLOS_SCORE: u8 = 0; // the most comfortable BLOS score.
+pub const MAX_BLOS_SCORE: u8 = 5; // the least comfortable BLOS score.
+
+/// Bicycle Level of Service grade, from A (most comfortable) to F (least comfortable).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BlosGrade {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlosError {
+    #[error("Blos score '{0}' must be in the integer range: [0..5]")]
+    ValueError(u8),
+}
+
+impl BlosGrade {
+    /// Converts a BLOS score into a grade, where each point of stress lowers the grade by one letter.
+    pub fn from_score(score: u8) -> Result<BlosGrade, BlosError> {
+        match score {
+            0 => Ok(BlosGrade::A),
+            1 => Ok(BlosGrade::B),
+            2 => Ok(BlosGrade::C),
+            3 => Ok(BlosGrade::D),
+            4 => Ok(BlosGrade::E),
+            5 => Ok(BlosGrade::F),
+            _ => Err(BlosError::ValueError(score)),
+        }
+    }
+}
+
+impl std::fmt::Display for BlosGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            BlosGrade::A => "A",
+            BlosGrade::B => "B",
+            BlosGrade::C => "C",
+            BlosGrade::D => "D",
+            BlosGrade::E => "E",
+            BlosGrade::F => "F",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Stress from motor traffic speed (in mph), from 0 at 20 mph or below to 5 above 40 mph.
+pub fn speed_stress(traffic_speed: u8) -> u8 {
+    match traffic_speed {
+        0..=20 => 0,
+        21..=25 => 1,
+        26..=30 => 2,
+        31..=35 => 3,
+        36..=40 => 4,
+        _ => 5,
+    }
+}
+
+/// Stress from the number of motor traffic lanes in the direction of travel.
+pub fn lane_stress(lanes_per_direction: u32) -> u8 {
+    match lanes_per_direction {
+        0..=1 => 0,
+        2 => 1,
+        _ => 2,
+    }
+}
@@ -0,0 +1,205 @@
+use rstar::RTree;
+
+use crate::blos::blos::{
+    lane_stress, speed_stress, BlosError, BlosGrade, MAX_BLOS_SCORE, MIN_BLOS_SCORE,
+};
+use crate::common::cycleway_tag::CyclewayTag;
+use crate::common::edge_rtree_entry::{find_neighboring_edges, EdgeRTreeEntry};
+use crate::common::ops::{estimated_speed_from_neighbors, traffic_speed_from_maxspeed};
+use crate::network_traits::{edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge};
+
+/// The Bicycle Level of Service (BLOS) components for an edge, including the total
+/// score and all components that went into the total score.
+#[derive(Debug)]
+pub struct BlosComponents {
+    pub score: u8,
+    pub grade: BlosGrade,
+    pub speed_stress: Option<u8>,
+    pub lane_stress: Option<u8>,
+    pub facility_relief: Option<u8>,
+}
+
+impl BlosComponents {
+    /// Returns the most comfortable BLOS (no components)
+    pub fn min_blos() -> Result<Self, BlosError> {
+        Self::from_score(MIN_BLOS_SCORE)
+    }
+    /// Returns the least comfortable BLOS (no components)
+    pub fn max_blos() -> Result<Self, BlosError> {
+        Self::from_score(MAX_BLOS_SCORE)
+    }
+
+    fn from_score(score: u8) -> Result<Self, BlosError> {
+        Ok(Self {
+            score,
+            grade: BlosGrade::from_score(score)?,
+            speed_stress: None,
+            lane_stress: None,
+            facility_relief: None,
+        })
+    }
+}
+
+/// Computes the Bicycle Level of Service (BLOS) comfort score for a given edge.
+///
+/// The score adds stress from motor traffic speed and the number of lanes in the
+/// direction of travel, and subtracts relief from bike infrastructure. A buffered
+/// cycleway relieves all stress, a dedicated lane relieves two points and shared
+/// facilities relieve one point.
+pub fn compute_blos<E>(
+    rtree: &RTree<EdgeRTreeEntry<E>>,
+    entry: &EdgeRTreeEntry<E>,
+) -> Result<BlosComponents, BlosError>
+where
+    E: SpatialEdge + EdgeForModalMetric,
+{
+    // Some edges are inherently unsuitable for bikes.
+    if entry.edge.is_unbikeable() {
+        return BlosComponents::max_blos();
+    }
+    // An edge that is non-motorized is inherently comfortable
+    if entry.edge.is_non_motorized() {
+        return BlosComponents::min_blos();
+    }
+
+    let speed = traffic_speed_from_maxspeed(entry).unwrap_or_else(|| {
+        let neighboring_ways = find_neighboring_edges(entry, rtree);
+        estimated_speed_from_neighbors(entry, &neighboring_ways).unwrap_or(25.0)
+    });
+    let speed_stress = speed_stress(speed.round() as u8);
+
+    let oneway = entry.edge.is_oneway();
+    let lanes = entry.edge.get_lanes().unwrap_or(if oneway { 1 } else { 2 });
+    let lanes_per_direction = if oneway { lanes } else { lanes.div_ceil(2) };
+    let lane_stress = lane_stress(lanes_per_direction);
+
+    let stress = speed_stress + lane_stress;
+    let facility_relief = match entry
+        .edge
+        .get_cycleway_tag()
+        .unwrap_or(CyclewayTag::NoDedicatedNoFacilities)
+    {
+        CyclewayTag::DedicatedWithBuffer => stress,
+        CyclewayTag::DedicatedNoBuffer => 2,
+        CyclewayTag::NoDedicatedWithFacilities => 1,
+        CyclewayTag::NoDedicatedNoFacilities => 0,
+    };
+
+    let score = stress.saturating_sub(facility_relief).min(MAX_BLOS_SCORE);
+    Ok(BlosComponents {
+        score,
+        grade: BlosGrade::from_score(score)?,
+        speed_stress: Some(speed_stress),
+        lane_stress: Some(lane_stress),
+        facility_relief: Some(facility_relief),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::test_util::{test_edge, TestEdge};
+
+    #[test]
+    fn test_unbikeable_is_grade_f() {
+        let edge = TestEdge {
+            unbikeable: true,
+            ..test_edge()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert_eq!(blos.grade, BlosGrade::F);
+        assert_eq!(blos.speed_stress, None);
+    }
+
+    #[test]
+    fn test_non_motorized_is_grade_a() {
+        let edge = TestEdge {
+            non_motorized: true,
+            speed_limit: Some(45),
+            ..test_edge()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert_eq!(blos.grade, BlosGrade::A);
+    }
+
+    #[test]
+    fn test_arterial_without_facilities() {
+        let edge = TestEdge {
+            speed_limit: Some(45),
+            lanes: Some(4),
+            ..test_edge()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert_eq!(blos.speed_stress, Some(5));
+        assert_eq!(blos.lane_stress, Some(1));
+        assert_eq!(blos.facility_relief, Some(0));
+        assert_eq!(blos.score, MAX_BLOS_SCORE);
+        assert_eq!(blos.grade, BlosGrade::F);
+    }
+
+    #[test]
+    fn test_bike_lane_relief() {
+        let edge = TestEdge {
+            speed_limit: Some(30),
+            lanes: Some(2),
+            cycleway: Some(CyclewayTag::DedicatedNoBuffer),
+            ..test_edge()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert_eq!(blos.speed_stress, Some(2));
+        assert_eq!(blos.lane_stress, Some(0));
+        assert_eq!(blos.score, 0);
+        assert_eq!(blos.grade, BlosGrade::A);
+    }
+
+    #[test]
+    fn test_buffered_cycleway_relieves_all_stress() {
+        let edge = TestEdge {
+            speed_limit: Some(50),
+            lanes: Some(6),
+            cycleway: Some(CyclewayTag::DedicatedWithBuffer),
+            ..test_edge()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert_eq!(blos.grade, BlosGrade::A);
+    }
+
+    #[test]
+    fn test_neighbor_speed_contribution() {
+        let edge = test_edge();
+        let neighbor = TestEdge {
+            id: 43,
+            speed_limit: Some(45),
+            linestring: geo::LineString::from(vec![
+                (-105.168085, 39.773772),
+                (-105.166755, 39.773937),
+            ]),
+            ..test_edge()
+        };
+
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let neighbor_entry = EdgeRTreeEntry::new(neighbor).unwrap();
+        let mut rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+        let no_neighbors = compute_blos(&rtree, &entry).unwrap();
+
+        rtree.insert(entry.clone());
+        rtree.insert(neighbor_entry);
+        let blos = compute_blos(&rtree, &entry).unwrap();
+        assert!(blos.speed_stress > no_neighbors.speed_stress);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod blos;
+pub mod compute_blos;
@@ -1,5 +1,6 @@
+use crate::common::edge_rtree_entry::EdgeRTreeEntry;
 use crate::common::modal_metrics::{ModalMetric, ModalMetricError, ModalMetricValue};
-use crate::common::ops::load_edge_rtree_entries;
+use crate::common::ops::{create_edge_rtree_entries, load_edge_rtree_entries};
 use crate::network_traits::{
     edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge,
     vertex_for_modal_metric::VertexForModalMetric,
@@ -19,9 +20,17 @@ use std::{
 /// Bulk compute a specific modal metric for all ways in an OSM network by taking in a vertices-complete.csv
 /// and edges-complete.csv.
 ///
-/// `metric_name` can either be:
+/// The output is a CSV keyed by an `edge_id` column, the row index of each edge in
+/// edges-complete.csv, which matches the edge ids of the Compass edge list. Edges that
+/// could not be loaded are omitted. This file can be read directly by the `max_lts`
+/// constraint model and the `comfort` traversal model in bambam.
+///
+/// `metric_name` can be one of:
 /// - "WCI" for the Walking Comfort Index metric
 /// - "LTS" for the Level of Traffic Stress (cycling comfort) metric
+/// - "BLOS" for the Bicycle Level of Service comfort score
+/// - "SIDEWALK" for the share of the two sides of each street edge with a sidewalk
+/// - "CROSSING" for the intersection crossing exposure score
 pub fn bulk_compute_modal_metric<E, V>(
     metric_name: &str,
     edges_file: &str,
@@ -34,7 +43,48 @@ where
 {
     // determine the modal metric to compute based on the provided metric name.
     let metric: ModalMetric = metric_name.parse()?;
+    let values = compute_modal_metric_values::<E, V>(&metric, edges_file, vertices_file)?;
+
+    write_modal_metric_values(&metric, &values, output_file)?;
+
+    log::info!(
+        "\n\n{:?} values computed successfully.\n\nOutput file saved @ {output_file}.",
+        metric
+    );
+    Ok(())
+}
+
+/// Writes modal metric values to a CSV keyed by an `edge_id` column.
+pub fn write_modal_metric_values<E>(
+    metric: &ModalMetric,
+    values: &[(usize, EdgeRTreeEntry<E>, ModalMetricValue)],
+    output_file: &str,
+) -> Result<(), Box<dyn Error>> {
+    let file = File::create(output_file)?;
+    let mut writer = BufWriter::new(file);
+
+    metric.write_csv_header(&mut writer)?;
+    for (edge_id, _, value) in values.iter() {
+        value.write_csv_row(*edge_id, &mut writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
 
+/// Computes a modal metric for all ways in an OSM network by taking in a vertices-complete.csv
+/// and edges-complete.csv.
+///
+/// Returns each edge that could be loaded along with its edge id (the row index of the
+/// edge in edges-complete.csv) and its modal metric value, in edge id order.
+pub fn compute_modal_metric_values<E, V>(
+    metric: &ModalMetric,
+    edges_file: &str,
+    vertices_file: &str,
+) -> Result<Vec<(usize, EdgeRTreeEntry<E>, ModalMetricValue)>, Box<dyn Error>>
+where
+    E: SpatialEdge + EdgeForModalMetric + DeserializeOwned + Clone + Send + Sync,
+    V: VertexForModalMetric + DeserializeOwned + Send + Sync,
+{
     log::info!(
         "\nLoading files for {:?} modal metric computation.\n",
         metric
@@ -43,9 +93,46 @@ where
 
     // load vertices and edges.
     let vertices: Box<[V]> = read_utils::from_csv(&vertices_file, true, None, None)?;
-    let edge_rtree_entries = load_edge_rtree_entries::<E, V>(edges_file, &vertices)?;
+    let (edge_ids, edge_rtree_entries): (Vec<usize>, Vec<_>) =
+        load_edge_rtree_entries::<E, V>(edges_file, &vertices)?
+            .into_iter()
+            .unzip();
     log::info!("Edges and vertices read successfully.\n");
 
+    compute_modal_metric_values_for_entries(metric, edge_ids, edge_rtree_entries, &vertices)
+}
+
+/// Computes a modal metric for edges already held in memory, such as edges built
+/// directly from a map provider's records without an intermediate CSV.
+///
+/// Each edge is paired with its edge id. Edges whose source vertex is not found in
+/// `vertices`, which is indexed by vertex id, or which have no linestring are omitted.
+pub fn compute_modal_metric_values_from_edges<E, V>(
+    metric: &ModalMetric,
+    edges: Vec<(usize, E)>,
+    vertices: &[V],
+) -> Result<Vec<(usize, EdgeRTreeEntry<E>, ModalMetricValue)>, Box<dyn Error>>
+where
+    E: SpatialEdge + EdgeForModalMetric + Clone + Send + Sync,
+    V: VertexForModalMetric + Send + Sync,
+{
+    let (edge_ids, edge_rtree_entries): (Vec<usize>, Vec<_>) =
+        create_edge_rtree_entries(edges, vertices)
+            .into_iter()
+            .unzip();
+    compute_modal_metric_values_for_entries(metric, edge_ids, edge_rtree_entries, vertices)
+}
+
+fn compute_modal_metric_values_for_entries<E, V>(
+    metric: &ModalMetric,
+    edge_ids: Vec<usize>,
+    edge_rtree_entries: Vec<EdgeRTreeEntry<E>>,
+    vertices: &[V],
+) -> Result<Vec<(usize, EdgeRTreeEntry<E>, ModalMetricValue)>, Box<dyn Error>>
+where
+    E: SpatialEdge + EdgeForModalMetric + Clone + Send + Sync,
+    V: VertexForModalMetric + Send + Sync,
+{
     // build an RTree with the edge entries.
     let rtree = RTree::bulk_load(edge_rtree_entries.clone());
 
@@ -77,19 +164,11 @@ where
 
     eprintln!();
 
-    // write to file
-    let file = File::create(output_file)?;
-    let mut writer = BufWriter::new(file);
-
-    metric.write_csv_header(&mut writer)?;
-    for value in &values {
-        value.write_csv_row(&mut writer)?;
-    }
-    writer.flush()?;
-
-    log::info!(
-        "\n\n{:?} values computed successfully.\n\nOutput file saved @ {output_file}.",
-        metric
-    );
-    Ok(())
+    let result = edge_ids
+        .into_iter()
+        .zip(edge_rtree_entries)
+        .zip(values)
+        .map(|((edge_id, entry), value)| (edge_id, entry, value))
+        .collect();
+    Ok(result)
 }
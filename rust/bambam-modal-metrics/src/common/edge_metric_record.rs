@@ -0,0 +1,51 @@
+use routee_compass_core::model::network::EdgeId;
+use serde::{Deserialize, Serialize};
+
+/// A row of the LTS file written by [`super::bulk_compute_modal_metric`], keyed by
+/// the Compass edge id.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct EdgeLtsRecord {
+    pub edge_id: EdgeId,
+    /// Level of Traffic Stress, in the range [1..4].
+    pub lts: u8,
+}
+
+/// A row of the WCI file written by [`super::bulk_compute_modal_metric`], keyed by
+/// the Compass edge id. Components that could not be computed are empty.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct EdgeWciRecord {
+    pub edge_id: EdgeId,
+    /// total Walking Comfort Index, in the range [-6..9].
+    pub wci_total: i32,
+    pub wci_walk: Option<i32>,
+    pub wci_speed: Option<i32>,
+    pub wci_cycle: Option<i32>,
+    pub wci_signal: Option<i32>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::EdgeLtsRecord;
+    use crate::common::modal_metrics::{ModalMetric, ModalMetricValue};
+    use crate::lts::lts::Lts;
+
+    #[test]
+    fn test_read_written_lts_rows() {
+        let mut buffer: Vec<u8> = vec![];
+        ModalMetric::LevelOfTrafficStress
+            .write_csv_header(&mut buffer)
+            .unwrap();
+        ModalMetricValue::Lts(Lts::new(2).unwrap())
+            .write_csv_row(7, &mut buffer)
+            .unwrap();
+
+        let mut reader = csv::Reader::from_reader(buffer.as_slice());
+        let rows = reader
+            .deserialize::<EdgeLtsRecord>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].edge_id.0, 7);
+        assert_eq!(rows[0].lts, 2);
+    }
+}
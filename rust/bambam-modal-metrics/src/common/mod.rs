@@ -1,5 +1,8 @@
 pub mod bulk_compute_modal_metric;
 pub mod cycleway_tag;
+pub mod edge_metric_record;
 pub mod edge_rtree_entry;
 pub mod modal_metrics;
 pub mod ops;
+#[cfg(test)]
+pub mod test_util;
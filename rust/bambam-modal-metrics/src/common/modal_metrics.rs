@@ -1,4 +1,7 @@
+use crate::blos::blos::BlosError;
+use crate::blos::compute_blos::{compute_blos, BlosComponents};
 use crate::common::edge_rtree_entry::EdgeRTreeEntry;
+use crate::crossing::compute_crossing_exposure::{compute_crossing_exposure, CrossingExposure};
 use crate::lts::compute_lts::compute_lts;
 use crate::lts::lts::Lts;
 use crate::lts::lts::LtsError;
@@ -6,6 +9,9 @@ use crate::network_traits::{
     edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge,
     vertex_for_modal_metric::VertexForModalMetric,
 };
+use crate::sidewalk::compute_sidewalk_completeness::{
+    compute_sidewalk_completeness, SidewalkCompleteness,
+};
 use crate::wci::compute_wci::{compute_wci, WciComponents};
 use crate::wci::wci::WciError;
 use rstar::RTree;
@@ -19,6 +25,9 @@ pub enum ModalMetricError {
     #[error(transparent)]
     Lts(#[from] LtsError),
 
+    #[error(transparent)]
+    Blos(#[from] BlosError),
+
     #[error("Invalid modal metric name: {0}")]
     InvalidModalMetric(String),
 
@@ -31,12 +40,18 @@ pub enum ModalMetricError {
 pub enum ModalMetric {
     WalkingComfortIndex,
     LevelOfTrafficStress,
+    BicycleLevelOfService,
+    SidewalkCompleteness,
+    CrossingExposure,
 }
 
 /// Actual computed values for a modal metric.
 pub enum ModalMetricValue {
     Wci(WciComponents),
     Lts(Lts),
+    Blos(BlosComponents),
+    Sidewalk(SidewalkCompleteness),
+    Crossing(CrossingExposure),
 }
 
 impl FromStr for ModalMetric {
@@ -46,6 +61,9 @@ impl FromStr for ModalMetric {
         match s.to_ascii_uppercase().as_str() {
             "WCI" => Ok(ModalMetric::WalkingComfortIndex),
             "LTS" => Ok(ModalMetric::LevelOfTrafficStress),
+            "BLOS" => Ok(ModalMetric::BicycleLevelOfService),
+            "SIDEWALK" => Ok(ModalMetric::SidewalkCompleteness),
+            "CROSSING" => Ok(ModalMetric::CrossingExposure),
             _ => Err(ModalMetricError::InvalidModalMetric(s.to_string())),
         }
     }
@@ -72,16 +90,46 @@ impl ModalMetric {
                 let lts = compute_lts(rtree, edge_entry)?;
                 Ok(ModalMetricValue::Lts(lts))
             }
+            ModalMetric::BicycleLevelOfService => {
+                let blos = compute_blos(rtree, edge_entry)?;
+                Ok(ModalMetricValue::Blos(blos))
+            }
+            ModalMetric::SidewalkCompleteness => {
+                let sidewalk = compute_sidewalk_completeness(rtree, edge_entry);
+                Ok(ModalMetricValue::Sidewalk(sidewalk))
+            }
+            ModalMetric::CrossingExposure => {
+                let crossing = compute_crossing_exposure(rtree, edge_entry, src_vertex);
+                Ok(ModalMetricValue::Crossing(crossing))
+            }
         }
     }
-    /// Writes the CSV header for the specified modal metric.
+    /// Writes the CSV header for the specified modal metric, beginning with the `edge_id` column.
     pub fn write_csv_header(&self, writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
         match self {
             ModalMetric::WalkingComfortIndex => {
-                writeln!(writer, "wci_total,wci_walk,wci_speed,wci_cycle,wci_signal")?;
+                writeln!(
+                    writer,
+                    "edge_id,wci_total,wci_walk,wci_speed,wci_cycle,wci_signal"
+                )?;
             }
             ModalMetric::LevelOfTrafficStress => {
-                writeln!(writer, "lts")?;
+                writeln!(writer, "edge_id,lts")?;
+            }
+            ModalMetric::BicycleLevelOfService => {
+                writeln!(
+                    writer,
+                    "edge_id,blos_score,blos_grade,blos_speed,blos_lanes,blos_facility"
+                )?;
+            }
+            ModalMetric::SidewalkCompleteness => {
+                writeln!(writer, "edge_id,edge_sidewalk_completeness,sidewalk_source")?;
+            }
+            ModalMetric::CrossingExposure => {
+                writeln!(
+                    writer,
+                    "edge_id,crossing_exposure,crossing_lanes,crossing_control"
+                )?;
             }
         }
         Ok(())
@@ -89,12 +137,18 @@ impl ModalMetric {
 }
 
 impl ModalMetricValue {
-    pub fn write_csv_row(&self, writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    /// Writes this value as a CSV row for the edge with the given id.
+    pub fn write_csv_row(
+        &self,
+        edge_id: usize,
+        writer: &mut impl Write,
+    ) -> Result<(), Box<dyn Error>> {
         match self {
             ModalMetricValue::Wci(wci) => {
                 writeln!(
                     writer,
-                    "{},{},{},{},{}",
+                    "{},{},{},{},{},{}",
+                    edge_id,
                     wci.total,
                     wci.walkability
                         .as_ref()
@@ -111,7 +165,36 @@ impl ModalMetricValue {
                 )?;
             }
             ModalMetricValue::Lts(lts) => {
-                writeln!(writer, "{}", lts)?;
+                writeln!(writer, "{},{}", edge_id, lts)?;
+            }
+            ModalMetricValue::Blos(blos) => {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    edge_id,
+                    blos.score,
+                    blos.grade,
+                    blos.speed_stress.map_or(String::new(), |v| v.to_string()),
+                    blos.lane_stress.map_or(String::new(), |v| v.to_string()),
+                    blos.facility_relief
+                        .map_or(String::new(), |v| v.to_string()),
+                )?;
+            }
+            ModalMetricValue::Sidewalk(sidewalk) => {
+                writeln!(
+                    writer,
+                    "{},{},{}",
+                    edge_id,
+                    sidewalk.ratio.map_or(String::new(), |v| v.to_string()),
+                    sidewalk.source.map_or(String::new(), |v| v.to_string()),
+                )?;
+            }
+            ModalMetricValue::Crossing(crossing) => {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    edge_id, crossing.exposure, crossing.lanes_crossed, crossing.control
+                )?;
             }
         }
         Ok(())
@@ -12,26 +12,45 @@ use crate::{
 };
 
 /// Load edges from a CSV file and create R-tree entries for each edge.
+///
+/// Each entry is paired with its edge id, the row index of the edge in the file, which
+/// matches the edge ids of the Compass edge list written alongside it. Rows that cannot
+/// be loaded are skipped, so the returned edge ids may have gaps.
 pub fn load_edge_rtree_entries<E, V>(
     edges_file: &str,
     vertices: &[V],
-) -> Result<Vec<EdgeRTreeEntry<E>>, Box<dyn Error>>
+) -> Result<Vec<(usize, EdgeRTreeEntry<E>)>, Box<dyn Error>>
 where
     E: SpatialEdge + DeserializeOwned,
     V: VertexForModalMetric,
 {
     let mut edge_reader = csv::Reader::from_path(edges_file)?;
-    let mut edge_entries = Vec::new();
-
-    for record in edge_reader.deserialize::<E>() {
-        let edge = match record {
-            Ok(edge) => edge,
+    let edges = edge_reader
+        .deserialize::<E>()
+        .enumerate()
+        .filter_map(|(edge_id, record)| match record {
+            Ok(edge) => Some((edge_id, edge)),
             Err(err) => {
                 eprintln!("Error reading row: {err}");
-                continue;
+                None
             }
-        };
+        });
+    Ok(create_edge_rtree_entries(edges, vertices))
+}
+
+/// Create R-tree entries for edges paired with their edge ids. Edges whose source
+/// vertex is missing or which have no linestring are skipped.
+pub fn create_edge_rtree_entries<E, V>(
+    edges: impl IntoIterator<Item = (usize, E)>,
+    vertices: &[V],
+) -> Vec<(usize, EdgeRTreeEntry<E>)>
+where
+    E: SpatialEdge,
+    V: VertexForModalMetric,
+{
+    let mut edge_entries = Vec::new();
 
+    for (edge_id, edge) in edges {
         let src = edge.src_vertex_id();
         if vertices.get(src).is_none() {
             eprintln!(
@@ -47,10 +66,10 @@ where
             continue;
         };
 
-        edge_entries.push(entry);
+        edge_entries.push((edge_id, entry));
     }
 
-    Ok(edge_entries)
+    edge_entries
 }
 
 /// Traffic speed limit in MPH, if known.
@@ -0,0 +1,122 @@
+//! network types implementing the modal metric traits, shared by unit tests.
+use crate::common::cycleway_tag::CyclewayTag;
+use crate::network_traits::{
+    edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge,
+    vertex_for_modal_metric::VertexForModalMetric,
+};
+use geo::LineString;
+
+#[derive(Clone)]
+pub struct TestEdge {
+    pub id: usize,
+    pub src: usize,
+    pub dst: usize,
+    pub walkable: bool,
+    pub sidewalk: bool,
+    pub footway: bool,
+    pub sidewalk_sides: Option<u8>,
+    pub speed_limit: Option<i32>,
+    pub lanes: Option<u32>,
+    pub cycleway: Option<CyclewayTag>,
+    pub unbikeable: bool,
+    pub non_motorized: bool,
+    pub oneway: bool,
+    pub linestring: LineString<f32>,
+}
+
+impl TestEdge {
+    /// a two-way street with no attributes between two vertices.
+    pub fn new(id: usize, src: usize, dst: usize, coords: Vec<(f32, f32)>) -> TestEdge {
+        TestEdge {
+            id,
+            src,
+            dst,
+            walkable: true,
+            sidewalk: false,
+            footway: false,
+            sidewalk_sides: None,
+            speed_limit: None,
+            lanes: None,
+            cycleway: None,
+            unbikeable: false,
+            non_motorized: false,
+            oneway: false,
+            linestring: LineString::from(coords),
+        }
+    }
+}
+
+/// a two-way street with no attributes, the base edge of most unit tests.
+pub fn test_edge() -> TestEdge {
+    TestEdge::new(
+        42,
+        0,
+        1,
+        vec![(-105.170016, 39.773648), (-105.165381, 39.774176)],
+    )
+}
+
+impl SpatialEdge for TestEdge {
+    fn id(&self) -> String {
+        self.id.to_string()
+    }
+    fn src_vertex_id(&self) -> usize {
+        self.src
+    }
+    fn dst_vertex_id(&self) -> usize {
+        self.dst
+    }
+    fn linestring(&self) -> Option<&LineString<f32>> {
+        Some(&self.linestring)
+    }
+}
+
+impl EdgeForModalMetric for TestEdge {
+    fn get_traffic_speed_limit(&self) -> Option<i32> {
+        self.speed_limit
+    }
+    fn get_cycleway_tag(&self) -> Option<CyclewayTag> {
+        self.cycleway.clone()
+    }
+    fn get_lanes(&self) -> Option<u32> {
+        self.lanes
+    }
+    fn is_walkable(&self) -> bool {
+        self.walkable
+    }
+    fn is_walkable_highway(&self) -> bool {
+        false
+    }
+    fn is_sidewalk(&self) -> bool {
+        self.sidewalk
+    }
+    fn is_footway(&self) -> bool {
+        self.footway
+    }
+    fn get_sidewalk_sides(&self) -> Option<u8> {
+        self.sidewalk_sides
+    }
+    fn is_unbikeable(&self) -> bool {
+        self.unbikeable
+    }
+    fn is_non_motorized(&self) -> bool {
+        self.non_motorized
+    }
+    fn is_oneway(&self) -> bool {
+        self.oneway
+    }
+}
+
+pub struct TestVertex {
+    pub has_signals: bool,
+    pub has_stop: bool,
+}
+
+impl VertexForModalMetric for TestVertex {
+    fn has_traffic_signals(&self) -> bool {
+        self.has_signals
+    }
+    fn has_stop_sign(&self) -> bool {
+        self.has_stop
+    }
+}
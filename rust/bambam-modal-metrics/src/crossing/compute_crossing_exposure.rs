@@ -0,0 +1,196 @@
+use rstar::{RTree, AABB};
+
+use crate::common::edge_rtree_entry::EdgeRTreeEntry;
+use crate::network_traits::{
+    edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge,
+    vertex_for_modal_metric::VertexForModalMetric,
+};
+
+/// The traffic control at an intersection crossing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossingControl {
+    Signal,
+    StopSign,
+    Uncontrolled,
+}
+
+impl CrossingControl {
+    /// Exposure per lane crossed, where a traffic signal protects a crossing the most.
+    pub fn exposure_per_lane(&self) -> u32 {
+        match self {
+            CrossingControl::Signal => 1,
+            CrossingControl::StopSign => 2,
+            CrossingControl::Uncontrolled => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for CrossingControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrossingControl::Signal => write!(f, "signal"),
+            CrossingControl::StopSign => write!(f, "stop_sign"),
+            CrossingControl::Uncontrolled => write!(f, "uncontrolled"),
+        }
+    }
+}
+
+/// The exposure of a pedestrian crossing the intersection at the start of an edge,
+/// including the exposure score and the components that went into it. The score is
+/// the number of lanes crossed times the exposure per lane of the traffic control,
+/// so that 0 means there is no motorized street to cross.
+#[derive(Debug)]
+pub struct CrossingExposure {
+    pub exposure: u32,
+    pub lanes_crossed: u32,
+    pub control: CrossingControl,
+}
+
+/// Computes the intersection crossing exposure for a given edge (as EdgeRTreeEntry),
+/// the edge's source vertex, and the R-tree of all edges in the network.
+///
+/// The lanes crossed are the most lanes of any motorized edge meeting this edge at
+/// its source vertex, not counting edges between the same pair of vertices, such as
+/// the opposite direction of this edge. Edges without a lane count are assumed to
+/// have one lane per direction.
+pub fn compute_crossing_exposure<E, V>(
+    rtree: &RTree<EdgeRTreeEntry<E>>,
+    entry: &EdgeRTreeEntry<E>,
+    src_node: Option<&V>,
+) -> CrossingExposure
+where
+    E: SpatialEdge + EdgeForModalMetric,
+    V: VertexForModalMetric,
+{
+    let control = match src_node {
+        Some(v) if v.has_traffic_signals() => CrossingControl::Signal,
+        Some(v) if v.has_stop_sign() => CrossingControl::StopSign,
+        _ => CrossingControl::Uncontrolled,
+    };
+
+    let src_vertex_id = entry.edge.src_vertex_id();
+    let dst_vertex_id = entry.edge.dst_vertex_id();
+    let lanes_crossed = entry
+        .edge
+        .linestring()
+        .and_then(|l| l.0.first())
+        .map(|origin| {
+            rtree
+                .locate_in_envelope_intersecting(&AABB::from_point([origin.x, origin.y]))
+                .map(|neighbor| &neighbor.edge)
+                .filter(|e| {
+                    e.src_vertex_id() == src_vertex_id || e.dst_vertex_id() == src_vertex_id
+                })
+                .filter(|e| {
+                    let same_vertices = (e.src_vertex_id() == src_vertex_id
+                        && e.dst_vertex_id() == dst_vertex_id)
+                        || (e.src_vertex_id() == dst_vertex_id
+                            && e.dst_vertex_id() == src_vertex_id);
+                    !same_vertices
+                })
+                .filter(|e| !e.is_non_motorized())
+                .map(|e| e.get_lanes().unwrap_or(if e.is_oneway() { 1 } else { 2 }))
+                .max()
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    CrossingExposure {
+        exposure: lanes_crossed * control.exposure_per_lane(),
+        lanes_crossed,
+        control,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::test_util::{TestEdge, TestVertex};
+
+    /// a crosswalk beginning at vertex 0, which is also where a cross street passes
+    /// from vertex 1 to vertex 2.
+    fn build_rtree(
+        cross_street_lanes: Option<u32>,
+    ) -> (EdgeRTreeEntry<TestEdge>, RTree<EdgeRTreeEntry<TestEdge>>) {
+        let edge = TestEdge {
+            non_motorized: true,
+            ..TestEdge::new(42, 0, 3, vec![(-105.0, 39.77), (-105.0, 39.771)])
+        };
+        let cross_street_a = TestEdge {
+            lanes: cross_street_lanes,
+            ..TestEdge::new(43, 1, 0, vec![(-105.001, 39.77), (-105.0, 39.77)])
+        };
+        let cross_street_b = TestEdge {
+            lanes: cross_street_lanes,
+            ..TestEdge::new(44, 0, 2, vec![(-105.0, 39.77), (-104.999, 39.77)])
+        };
+        let unconnected = TestEdge {
+            lanes: Some(8),
+            ..TestEdge::new(45, 5, 6, vec![(-105.0, 39.769), (-105.0, 39.772)])
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let mut rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+        rtree.insert(entry.clone());
+        for e in [cross_street_a, cross_street_b, unconnected] {
+            rtree.insert(EdgeRTreeEntry::new(e).unwrap());
+        }
+        (entry, rtree)
+    }
+
+    #[test]
+    fn test_no_crossing() {
+        let edge = TestEdge::new(42, 0, 3, vec![(-105.0, 39.77), (-105.0, 39.771)]);
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let mut rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+        rtree.insert(entry.clone());
+        let src_vertex = TestVertex {
+            has_signals: false,
+            has_stop: false,
+        };
+
+        let result = compute_crossing_exposure(&rtree, &entry, Some(&src_vertex));
+        assert_eq!(result.lanes_crossed, 0);
+        assert_eq!(result.exposure, 0);
+    }
+
+    #[test]
+    fn test_uncontrolled_crossing() {
+        let (entry, rtree) = build_rtree(Some(4));
+        let src_vertex = TestVertex {
+            has_signals: false,
+            has_stop: false,
+        };
+
+        let result = compute_crossing_exposure(&rtree, &entry, Some(&src_vertex));
+        assert_eq!(result.control, CrossingControl::Uncontrolled);
+        assert_eq!(result.lanes_crossed, 4);
+        assert_eq!(result.exposure, 12);
+    }
+
+    #[test]
+    fn test_signalized_crossing() {
+        let (entry, rtree) = build_rtree(Some(4));
+        let src_vertex = TestVertex {
+            has_signals: true,
+            has_stop: true,
+        };
+
+        let result = compute_crossing_exposure(&rtree, &entry, Some(&src_vertex));
+        assert_eq!(result.control, CrossingControl::Signal);
+        assert_eq!(result.exposure, 4);
+    }
+
+    #[test]
+    fn test_stop_sign_with_default_lanes() {
+        let (entry, rtree) = build_rtree(None);
+        let src_vertex = TestVertex {
+            has_signals: false,
+            has_stop: true,
+        };
+
+        let result = compute_crossing_exposure(&rtree, &entry, Some(&src_vertex));
+        assert_eq!(result.control, CrossingControl::StopSign);
+        assert_eq!(result.lanes_crossed, 2);
+        assert_eq!(result.exposure, 4);
+    }
+}
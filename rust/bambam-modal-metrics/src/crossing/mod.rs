@@ -0,0 +1 @@
+pub mod compute_crossing_exposure;
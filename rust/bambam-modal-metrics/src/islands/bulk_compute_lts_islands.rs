@@ -0,0 +1,116 @@
+use super::components::ComponentsType;
+use super::lts_islands::{LtsEdge, LtsIslands};
+use crate::common::bulk_compute_modal_metric::compute_modal_metric_values;
+use crate::common::modal_metrics::{ModalMetric, ModalMetricError, ModalMetricValue};
+use crate::lts::lts::Lts;
+use crate::network_traits::{
+    edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge,
+    vertex_for_modal_metric::VertexForModalMetric,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// filename of the island id of each low-stress edge, keyed by edge id.
+pub const EDGE_ISLANDS_FILENAME: &str = "edges-islands.csv";
+/// filename of the GeoJSON of island hulls and gap edges.
+pub const ISLANDS_GEOJSON_FILENAME: &str = "islands.geojson";
+
+/// Bulk compute the low-stress islands of an OSM network by taking in a vertices-complete.csv
+/// and edges-complete.csv.
+///
+/// Computes the Level of Traffic Stress of every edge, finds the connected components of
+/// the edges at or below `max_lts`, and writes two files to `output_directory`:
+/// - `edges-islands.csv`: the island id of each low-stress edge, keyed by edge id
+/// - `islands.geojson`: the convex hull of each island with its size, along with the
+///   high-stress "gap" edges whose upgrade would merge the largest islands
+pub fn bulk_compute_lts_islands<E, V>(
+    edges_file: &str,
+    vertices_file: &str,
+    output_directory: &str,
+    max_lts: u8,
+    components_type: &ComponentsType,
+    max_gaps: usize,
+) -> Result<(), Box<dyn Error>>
+where
+    E: SpatialEdge + EdgeForModalMetric + DeserializeOwned + Clone + Send + Sync,
+    V: VertexForModalMetric + DeserializeOwned + Send + Sync,
+{
+    // validate the threshold before running the metric computation
+    let max_lts = Lts::new(max_lts)?.value();
+
+    let values = compute_modal_metric_values::<E, V>(
+        &ModalMetric::LevelOfTrafficStress,
+        edges_file,
+        vertices_file,
+    )?;
+    let edges = values
+        .into_iter()
+        .map(|(edge_id, entry, value)| match value {
+            ModalMetricValue::Lts(lts) => {
+                let linestring = entry.edge.linestring().cloned().ok_or_else(|| {
+                    ModalMetricError::Other(format!("edge {edge_id} has no linestring"))
+                })?;
+                Ok(LtsEdge {
+                    edge_id,
+                    src_vertex_id: entry.edge.src_vertex_id(),
+                    dst_vertex_id: entry.edge.dst_vertex_id(),
+                    lts: lts.value(),
+                    linestring,
+                })
+            }
+            _ => Err(ModalMetricError::Other(format!(
+                "expected LTS value for edge {edge_id}"
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    log::info!("finding low-stress islands with max lts {max_lts} using {components_type}");
+    let islands = LtsIslands::new(&edges, max_lts, components_type, max_gaps);
+    log_summary(&islands, edges.len());
+
+    std::fs::create_dir_all(output_directory)?;
+    let directory = Path::new(output_directory);
+
+    let edge_islands_path = directory.join(EDGE_ISLANDS_FILENAME);
+    let mut writer = BufWriter::new(File::create(&edge_islands_path)?);
+    writeln!(writer, "edge_id,island_id")?;
+    for (edge_id, island_id) in islands.edge_islands.iter() {
+        writeln!(writer, "{edge_id},{island_id}")?;
+    }
+    writer.flush()?;
+
+    let geojson_path = directory.join(ISLANDS_GEOJSON_FILENAME);
+    let geojson = islands.to_geojson()?;
+    let mut writer = BufWriter::new(File::create(&geojson_path)?);
+    serde_json::to_writer(&mut writer, &geojson)?;
+    writer.flush()?;
+
+    log::info!(
+        "low-stress islands written to {} and {}",
+        edge_islands_path.to_string_lossy(),
+        geojson_path.to_string_lossy()
+    );
+    Ok(())
+}
+
+fn log_summary(islands: &LtsIslands, n_edges: usize) {
+    let low_stress_meters: f64 = islands.islands.iter().map(|i| i.length_meters).sum();
+    let largest_share = match islands.islands.first() {
+        Some(largest) if low_stress_meters > 0.0 => largest.length_meters / low_stress_meters,
+        _ => 0.0,
+    };
+    log::info!(
+        "{} of {} edges have lts <= {}, forming {} islands. the largest island covers {:.1}% of the low-stress network length. found {} gap edges.",
+        islands.edge_islands.len(),
+        n_edges,
+        islands.max_lts,
+        islands.islands.len(),
+        largest_share * 100.0,
+        islands.gaps.len()
+    );
+}
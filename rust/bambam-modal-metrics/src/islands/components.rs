@@ -0,0 +1,171 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Connected components algorithm used to group edges into islands. Mirrors the
+/// component types used for island detection in `bambam-omf`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, ValueEnum, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentsType {
+    /// Strongly Connected Components (SCC). Edges belong to the same island only if
+    /// each can be reached from the other while respecting one-way travel.
+    Scc,
+    /// Weakly Connected Components (WCC). Treats the network as undirected, so edges
+    /// belong to the same island if they are physically connected.
+    #[default]
+    Wcc,
+}
+
+impl std::fmt::Display for ComponentsType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComponentsType::Scc => write!(f, "scc"),
+            ComponentsType::Wcc => write!(f, "wcc"),
+        }
+    }
+}
+
+/// A directed edge between two vertices, identified by its position in the edge slice.
+#[derive(Clone, Copy, Debug)]
+pub struct DirectedEdge {
+    pub src_vertex_id: usize,
+    pub dst_vertex_id: usize,
+}
+
+impl ComponentsType {
+    /// Assigns a component label to each edge. Labels are in the range `[0, n)` for
+    /// `n` components, in the order the components are found.
+    pub fn run(&self, edges: &[DirectedEdge], n_vertices: usize) -> Vec<usize> {
+        match self {
+            ComponentsType::Scc => strongly_connected_components(edges, n_vertices),
+            ComponentsType::Wcc => weakly_connected_components(edges, n_vertices),
+        }
+    }
+}
+
+/// Breadth-first search over the undirected edge graph, where two edges are adjacent
+/// if they share any vertex.
+fn weakly_connected_components(edges: &[DirectedEdge], n_vertices: usize) -> Vec<usize> {
+    let mut incident: Vec<Vec<usize>> = vec![vec![]; n_vertices];
+    for (idx, edge) in edges.iter().enumerate() {
+        incident[edge.src_vertex_id].push(idx);
+        if edge.dst_vertex_id != edge.src_vertex_id {
+            incident[edge.dst_vertex_id].push(idx);
+        }
+    }
+
+    let mut labels: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut n_components = 0;
+    for start in 0..edges.len() {
+        if labels[start].is_some() {
+            continue;
+        }
+        let label = n_components;
+        n_components += 1;
+        labels[start] = Some(label);
+        let mut queue = VecDeque::from([start]);
+        while let Some(curr) = queue.pop_front() {
+            let edge = edges[curr];
+            for vertex_id in [edge.src_vertex_id, edge.dst_vertex_id] {
+                for &next in incident[vertex_id].iter() {
+                    if labels[next].is_none() {
+                        labels[next] = Some(label);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+    labels.into_iter().flatten().collect()
+}
+
+/// Kosaraju's algorithm over the directed edge graph, where edge `a` leads to edge `b`
+/// if `a` ends at the vertex where `b` begins.
+fn strongly_connected_components(edges: &[DirectedEdge], n_vertices: usize) -> Vec<usize> {
+    let mut out_edges: Vec<Vec<usize>> = vec![vec![]; n_vertices];
+    let mut in_edges: Vec<Vec<usize>> = vec![vec![]; n_vertices];
+    for (idx, edge) in edges.iter().enumerate() {
+        out_edges[edge.src_vertex_id].push(idx);
+        in_edges[edge.dst_vertex_id].push(idx);
+    }
+
+    // Pass 1: forward DFS to get post-order finishing times
+    let mut visited = vec![false; edges.len()];
+    let mut post_order = Vec::with_capacity(edges.len());
+    for start in 0..edges.len() {
+        if visited[start] {
+            continue;
+        }
+        let mut stack = vec![(start, false)];
+        while let Some((curr, is_post)) = stack.pop() {
+            if is_post {
+                post_order.push(curr);
+                continue;
+            }
+            if visited[curr] {
+                continue;
+            }
+            visited[curr] = true;
+            stack.push((curr, true));
+            for &next in out_edges[edges[curr].dst_vertex_id].iter() {
+                if !visited[next] {
+                    stack.push((next, false));
+                }
+            }
+        }
+    }
+
+    // Pass 2: backward BFS in reverse post-order to label components
+    let mut labels: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut n_components = 0;
+    for &start in post_order.iter().rev() {
+        if labels[start].is_some() {
+            continue;
+        }
+        let label = n_components;
+        n_components += 1;
+        labels[start] = Some(label);
+        let mut queue = VecDeque::from([start]);
+        while let Some(curr) = queue.pop_front() {
+            for &next in in_edges[edges[curr].src_vertex_id].iter() {
+                if labels[next].is_none() {
+                    labels[next] = Some(label);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    labels.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ComponentsType, DirectedEdge};
+
+    fn edge(src_vertex_id: usize, dst_vertex_id: usize) -> DirectedEdge {
+        DirectedEdge {
+            src_vertex_id,
+            dst_vertex_id,
+        }
+    }
+
+    #[test]
+    fn test_wcc_vs_scc() {
+        // a two-way street 0 <-> 1, a one-way street 1 -> 2, and a separate two-way
+        // street 3 <-> 4.
+        let edges = vec![edge(0, 1), edge(1, 0), edge(1, 2), edge(3, 4), edge(4, 3)];
+
+        let wcc = ComponentsType::Wcc.run(&edges, 5);
+        assert_eq!(wcc, vec![0, 0, 0, 1, 1]);
+
+        let scc = ComponentsType::Scc.run(&edges, 5);
+        assert_eq!(scc[0], scc[1]);
+        assert_ne!(scc[0], scc[2]);
+        assert_eq!(scc[3], scc[4]);
+        assert_ne!(scc[0], scc[3]);
+        let mut unique = scc.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 3);
+    }
+}
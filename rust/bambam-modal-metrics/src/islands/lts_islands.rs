@@ -0,0 +1,270 @@
+use super::components::{ComponentsType, DirectedEdge};
+use geo::{Convert, ConvexHull, Geometry, Haversine, Length, LineString, MultiPoint, Polygon};
+use geozero::ToJson;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// An edge of the road network with its Level of Traffic Stress.
+#[derive(Clone, Debug)]
+pub struct LtsEdge {
+    /// edge id, the row index of the edge in edges-complete.csv
+    pub edge_id: usize,
+    pub src_vertex_id: usize,
+    pub dst_vertex_id: usize,
+    pub lts: u8,
+    pub linestring: LineString<f32>,
+}
+
+/// A connected set of low-stress edges.
+#[derive(Serialize, Clone, Debug)]
+pub struct Island {
+    /// island id, assigned in descending order of length so that island 0 is the largest
+    pub island_id: usize,
+    /// number of edges in the island
+    pub n_edges: usize,
+    /// total length of the edges in the island
+    pub length_meters: f64,
+    /// convex hull of the edges in the island
+    #[serde(skip)]
+    pub hull: Option<Polygon<f32>>,
+}
+
+/// A high-stress edge that directly connects two low-stress islands, such that
+/// upgrading it to a low-stress edge would merge them.
+#[derive(Serialize, Clone, Debug)]
+pub struct GapEdge {
+    pub edge_id: usize,
+    pub lts: u8,
+    /// the larger of the two islands connected by this edge
+    pub island_a: usize,
+    /// the smaller of the two islands connected by this edge
+    pub island_b: usize,
+    /// total length of the merged island if this edge were upgraded
+    pub merged_length_meters: f64,
+    #[serde(skip)]
+    pub linestring: LineString<f32>,
+}
+
+/// The low-stress islands of a road network: the connected components of the
+/// subgraph of edges with a Level of Traffic Stress at or below some maximum.
+pub struct LtsIslands {
+    pub max_lts: u8,
+    /// (edge id, island id) for each low-stress edge, in edge id order
+    pub edge_islands: Vec<(usize, usize)>,
+    /// islands ordered by descending length
+    pub islands: Vec<Island>,
+    /// gap edges ordered by descending length of the smaller island they connect,
+    /// so that the first gaps merge the largest islands
+    pub gaps: Vec<GapEdge>,
+}
+
+impl LtsIslands {
+    /// finds the low-stress islands of a set of edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - edges of the road network with their LTS
+    /// * `max_lts` - highest LTS of an edge in the low-stress subgraph
+    /// * `components_type` - algorithm used to group low-stress edges into islands
+    /// * `max_gaps` - number of gap edges to keep. only single high-stress edges are
+    ///   considered, not longer chains of high-stress edges between islands.
+    pub fn new(
+        edges: &[LtsEdge],
+        max_lts: u8,
+        components_type: &ComponentsType,
+        max_gaps: usize,
+    ) -> LtsIslands {
+        let (low_stress, high_stress): (Vec<&LtsEdge>, Vec<&LtsEdge>) =
+            edges.iter().partition(|e| e.lts <= max_lts);
+
+        let n_vertices = low_stress
+            .iter()
+            .map(|e| e.src_vertex_id.max(e.dst_vertex_id) + 1)
+            .max()
+            .unwrap_or_default();
+        let directed = low_stress
+            .iter()
+            .map(|e| DirectedEdge {
+                src_vertex_id: e.src_vertex_id,
+                dst_vertex_id: e.dst_vertex_id,
+            })
+            .collect::<Vec<_>>();
+        let labels = components_type.run(&directed, n_vertices);
+
+        // summarize each component
+        let n_components = labels.iter().max().map(|l| l + 1).unwrap_or_default();
+        let mut n_edges = vec![0; n_components];
+        let mut lengths = vec![0.0; n_components];
+        let mut coords = vec![vec![]; n_components];
+        for (edge, label) in low_stress.iter().zip(labels.iter()) {
+            n_edges[*label] += 1;
+            lengths[*label] += Haversine.length(&edge.linestring) as f64;
+            coords[*label].extend(edge.linestring.points());
+        }
+
+        // assign island ids by descending length
+        let mut order = (0..n_components).collect::<Vec<_>>();
+        order.sort_by(|a, b| lengths[*b].total_cmp(&lengths[*a]).then(a.cmp(b)));
+        let mut island_ids = vec![0; n_components];
+        for (island_id, label) in order.iter().enumerate() {
+            island_ids[*label] = island_id;
+        }
+        let islands = order
+            .iter()
+            .enumerate()
+            .map(|(island_id, label)| {
+                let points = MultiPoint::new(std::mem::take(&mut coords[*label]));
+                Island {
+                    island_id,
+                    n_edges: n_edges[*label],
+                    length_meters: lengths[*label],
+                    hull: Some(points.convex_hull()),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let edge_islands = low_stress
+            .iter()
+            .zip(labels.iter())
+            .map(|(e, label)| (e.edge_id, island_ids[*label]))
+            .collect::<Vec<_>>();
+
+        // islands touching each vertex
+        let mut vertex_islands: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+        for (edge, label) in low_stress.iter().zip(labels.iter()) {
+            for vertex_id in [edge.src_vertex_id, edge.dst_vertex_id] {
+                vertex_islands
+                    .entry(vertex_id)
+                    .or_default()
+                    .insert(island_ids[*label]);
+            }
+        }
+
+        let mut gaps = high_stress
+            .iter()
+            .filter_map(|edge| {
+                let src = vertex_islands.get(&edge.src_vertex_id)?;
+                let dst = vertex_islands.get(&edge.dst_vertex_id)?;
+                // island ids are ordered by length, so the best pair minimizes the larger id
+                let (island_a, island_b) = src
+                    .iter()
+                    .flat_map(|a| dst.iter().map(move |b| (*a.min(b), *a.max(b))))
+                    .filter(|(a, b)| a != b)
+                    .min_by_key(|(a, b)| (*b, *a))?;
+                Some(GapEdge {
+                    edge_id: edge.edge_id,
+                    lts: edge.lts,
+                    island_a,
+                    island_b,
+                    merged_length_meters: islands[island_a].length_meters
+                        + islands[island_b].length_meters,
+                    linestring: edge.linestring.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        gaps.sort_by(|a, b| {
+            (a.island_b, a.island_a, a.edge_id).cmp(&(b.island_b, b.island_a, b.edge_id))
+        });
+        gaps.truncate(max_gaps);
+
+        LtsIslands {
+            max_lts,
+            edge_islands,
+            islands,
+            gaps,
+        }
+    }
+
+    /// builds a GeoJSON FeatureCollection with the convex hull of each island and the
+    /// linestring of each gap edge. features are distinguished by a `type` property of
+    /// either `island` or `gap`.
+    pub fn to_geojson(&self) -> Result<serde_json::Value, String> {
+        let mut features = vec![];
+        for island in self.islands.iter() {
+            let geometry = match island.hull.as_ref() {
+                Some(hull) => to_geojson_geometry(Geometry::Polygon(hull.convert()))?,
+                None => serde_json::Value::Null,
+            };
+            features.push(feature(geometry, "island", island)?);
+        }
+        for gap in self.gaps.iter() {
+            let geometry = to_geojson_geometry(Geometry::LineString(gap.linestring.convert()))?;
+            features.push(feature(geometry, "gap", gap)?);
+        }
+        Ok(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }))
+    }
+}
+
+fn to_geojson_geometry(geometry: Geometry<f64>) -> Result<serde_json::Value, String> {
+    let json_str = geometry
+        .to_json()
+        .map_err(|e| format!("failure writing geometry as GeoJSON: {e}"))?;
+    serde_json::from_str(&json_str).map_err(|e| format!("failure reading GeoJSON geometry: {e}"))
+}
+
+fn feature<T: Serialize>(
+    geometry: serde_json::Value,
+    feature_type: &str,
+    properties: &T,
+) -> Result<serde_json::Value, String> {
+    let mut properties = serde_json::to_value(properties)
+        .map_err(|e| format!("failure serializing {feature_type} properties: {e}"))?;
+    if let Some(map) = properties.as_object_mut() {
+        map.insert(String::from("type"), serde_json::json!(feature_type));
+    }
+    Ok(serde_json::json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LtsEdge, LtsIslands};
+    use crate::islands::components::ComponentsType;
+    use geo::line_string;
+
+    fn edge(edge_id: usize, src: usize, dst: usize, lts: u8) -> LtsEdge {
+        let x0 = -105.0 + src as f32 * 0.001;
+        let x1 = -105.0 + dst as f32 * 0.001;
+        LtsEdge {
+            edge_id,
+            src_vertex_id: src,
+            dst_vertex_id: dst,
+            lts,
+            linestring: line_string![(x: x0, y: 40.0), (x: x1, y: 40.0)],
+        }
+    }
+
+    #[test]
+    fn test_islands_and_gaps() {
+        // a long low-stress street 0-1-2-3, a short one 5-6, joined by a high-stress
+        // edge 3-5, and a high-stress edge 3-4 that leads nowhere.
+        let edges = vec![
+            edge(0, 0, 1, 1),
+            edge(1, 1, 2, 2),
+            edge(2, 2, 3, 1),
+            edge(3, 3, 5, 4),
+            edge(4, 3, 4, 3),
+            edge(5, 5, 6, 2),
+        ];
+        let result = LtsIslands::new(&edges, 2, &ComponentsType::Wcc, 10);
+
+        assert_eq!(result.islands.len(), 2);
+        assert_eq!(result.islands[0].n_edges, 3);
+        assert_eq!(result.islands[1].n_edges, 1);
+        assert!(result.islands[0].length_meters > result.islands[1].length_meters);
+        assert_eq!(result.edge_islands, vec![(0, 0), (1, 0), (2, 0), (5, 1)]);
+
+        assert_eq!(result.gaps.len(), 1);
+        assert_eq!(result.gaps[0].edge_id, 3);
+        assert_eq!((result.gaps[0].island_a, result.gaps[0].island_b), (0, 1));
+
+        let geojson = result.to_geojson().unwrap();
+        assert_eq!(geojson["features"].as_array().unwrap().len(), 3);
+    }
+}
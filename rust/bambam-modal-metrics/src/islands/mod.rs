@@ -0,0 +1,3 @@
+pub mod bulk_compute_lts_islands;
+pub mod components;
+pub mod lts_islands;
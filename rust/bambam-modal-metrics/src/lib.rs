@@ -1,4 +1,8 @@
+pub mod blos;
 pub mod common;
+pub mod crossing;
+pub mod islands;
 pub mod lts;
 pub mod network_traits;
+pub mod sidewalk;
 pub mod wci;
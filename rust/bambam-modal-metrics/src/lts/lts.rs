@@ -26,6 +26,11 @@ impl Lts {
             Ok(Lts(value))
         }
     }
+    /// The LTS score, in the range [1..4].
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
     /// Computes the LTS value for a given edge based on traffic speed, cycleway tag, and oneway status.
     pub fn from_table_lookup(
         traffic_speed: u8, // assumed in mph.
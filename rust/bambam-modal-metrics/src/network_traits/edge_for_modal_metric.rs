@@ -15,6 +15,9 @@ pub trait EdgeForModalMetric {
     /// Returns `None` when the edge has no cycleway attribute, in which case
     /// the compute layer may infer a score from neighboring edges.
     fn get_cycleway_tag(&self) -> Option<CyclewayTag>;
+    // BLOS and crossing exposure
+    /// the number of through lanes for motor traffic across both directions, if known.
+    fn get_lanes(&self) -> Option<u32>;
     // WCI - only
     /// returns true if the edge is walk-eligible based solely on its own attributes.
     fn is_walkable(&self) -> bool;
@@ -24,6 +27,11 @@ pub trait EdgeForModalMetric {
     fn is_sidewalk(&self) -> bool;
     /// returns true if the edge is a footway.
     fn is_footway(&self) -> bool;
+    // Sidewalk completeness - only
+    /// the number of sides of this edge (0, 1 or 2) with an attached sidewalk, if known.
+    /// Returns `None` when sidewalks are not recorded on the edge, or are mapped as
+    /// separate edges, in which case the compute layer may infer them from neighbors.
+    fn get_sidewalk_sides(&self) -> Option<u8>;
     // LTS - only
     /// returns true if the edge is unbikeable.
     fn is_unbikeable(&self) -> bool;
@@ -14,4 +14,6 @@ pub trait SpatialEdge {
     fn linestring(&self) -> Option<&LineString<f32>>;
     /// the source vertex of the edge.
     fn src_vertex_id(&self) -> usize;
+    /// the destination vertex of the edge.
+    fn dst_vertex_id(&self) -> usize;
 }
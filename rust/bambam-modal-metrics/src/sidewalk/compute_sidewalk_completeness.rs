@@ -0,0 +1,172 @@
+use geo::{Distance, Euclidean, Haversine, Length};
+use rstar::{RTree, RTreeObject, AABB};
+
+use crate::common::edge_rtree_entry::{EdgeRTreeEntry, MIN_DISTANCE_RTREE_NEIGHBOR};
+use crate::network_traits::{edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge};
+
+/// How the sidewalk completeness of an edge was determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidewalkSource {
+    /// from the sidewalk attributes of the edge itself
+    Tag,
+    /// from separately mapped sidewalks running alongside the edge
+    Neighbors,
+}
+
+impl std::fmt::Display for SidewalkSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SidewalkSource::Tag => write!(f, "tag"),
+            SidewalkSource::Neighbors => write!(f, "neighbors"),
+        }
+    }
+}
+
+/// The share of the two sides of a street edge that have a sidewalk, from 0.0
+/// (no sidewalk on either side) to 1.0 (sidewalks on both sides).
+///
+/// Completeness is reported per street edge, not per block face. A block face
+/// spanning several edges, such as one split by a driveway or a mid-block crossing,
+/// has one value per edge, which may be weighted by edge length to summarize the block.
+#[derive(Debug, Default)]
+pub struct SidewalkCompleteness {
+    /// the completeness ratio, or `None` if the edge is not a street with sides to
+    /// walk along, such as a footway or cycleway.
+    pub ratio: Option<f64>,
+    pub source: Option<SidewalkSource>,
+}
+
+/// Computes the sidewalk completeness of a street edge.
+///
+/// When the edge records which of its sides have sidewalks, those are used directly.
+/// Otherwise, the total length of separately mapped sidewalks within
+/// [`MIN_DISTANCE_RTREE_NEIGHBOR`] of the edge is compared against twice the edge
+/// length, one length for each side. This is an approximation which may count
+/// sidewalks along cross streets near the ends of short edges.
+pub fn compute_sidewalk_completeness<E>(
+    rtree: &RTree<EdgeRTreeEntry<E>>,
+    entry: &EdgeRTreeEntry<E>,
+) -> SidewalkCompleteness
+where
+    E: SpatialEdge + EdgeForModalMetric,
+{
+    if let Some(sides) = entry.edge.get_sidewalk_sides() {
+        return SidewalkCompleteness {
+            ratio: Some(sides.min(2) as f64 / 2.0),
+            source: Some(SidewalkSource::Tag),
+        };
+    }
+    // footways, cycleways and other non-motorized edges have no sides to walk along
+    if entry.edge.is_footway() || entry.edge.is_non_motorized() {
+        return SidewalkCompleteness::default();
+    }
+    let Some(linestring) = entry.edge.linestring() else {
+        return SidewalkCompleteness::default();
+    };
+    let edge_length = Haversine.length(linestring) as f64;
+    if edge_length <= 0.0 {
+        return SidewalkCompleteness::default();
+    }
+
+    let envelope = entry.envelope();
+    let (lower, upper) = (envelope.lower(), envelope.upper());
+    let search_envelope = AABB::from_corners(
+        [
+            lower[0] - MIN_DISTANCE_RTREE_NEIGHBOR,
+            lower[1] - MIN_DISTANCE_RTREE_NEIGHBOR,
+        ],
+        [
+            upper[0] + MIN_DISTANCE_RTREE_NEIGHBOR,
+            upper[1] + MIN_DISTANCE_RTREE_NEIGHBOR,
+        ],
+    );
+    let query_id = entry.edge.id();
+    let sidewalk_length: f64 = rtree
+        .locate_in_envelope_intersecting(&search_envelope)
+        .filter(|neighbor| neighbor.edge.id() != query_id)
+        .filter(|neighbor| is_separate_sidewalk(&neighbor.edge))
+        .filter(|neighbor| {
+            Euclidean.distance(&neighbor.centroid, linestring) <= MIN_DISTANCE_RTREE_NEIGHBOR
+        })
+        .filter_map(|neighbor| neighbor.edge.linestring())
+        .map(|l| Haversine.length(l) as f64)
+        .sum();
+
+    SidewalkCompleteness {
+        ratio: Some((sidewalk_length / (2.0 * edge_length)).min(1.0)),
+        source: Some(SidewalkSource::Neighbors),
+    }
+}
+
+/// a separately mapped sidewalk is a footway that is also a sidewalk.
+fn is_separate_sidewalk<E: EdgeForModalMetric>(edge: &E) -> bool {
+    edge.is_footway() && edge.is_sidewalk()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::test_util::TestEdge;
+
+    fn street() -> TestEdge {
+        TestEdge::new(42, 0, 1, vec![(-105.0, 39.77), (-104.999, 39.77)])
+    }
+
+    fn sidewalk(id: usize, y: f32) -> TestEdge {
+        TestEdge {
+            sidewalk: true,
+            footway: true,
+            non_motorized: true,
+            ..TestEdge::new(id, 10 + id, 11 + id, vec![(-105.0, y), (-104.999, y)])
+        }
+    }
+
+    #[test]
+    fn test_sidewalk_sides_from_tag() {
+        let edge = TestEdge {
+            sidewalk_sides: Some(1),
+            ..street()
+        };
+        let entry = EdgeRTreeEntry::new(edge).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let result = compute_sidewalk_completeness(&rtree, &entry);
+        assert_eq!(result.ratio, Some(0.5));
+        assert_eq!(result.source, Some(SidewalkSource::Tag));
+    }
+
+    #[test]
+    fn test_footway_has_no_sides() {
+        let entry = EdgeRTreeEntry::new(sidewalk(1, 39.77)).unwrap();
+        let rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+
+        let result = compute_sidewalk_completeness(&rtree, &entry);
+        assert_eq!(result.ratio, None);
+    }
+
+    #[test]
+    fn test_no_sidewalk_neighbors() {
+        let entry = EdgeRTreeEntry::new(street()).unwrap();
+        let mut rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+        rtree.insert(entry.clone());
+
+        let result = compute_sidewalk_completeness(&rtree, &entry);
+        assert_eq!(result.ratio, Some(0.0));
+        assert_eq!(result.source, Some(SidewalkSource::Neighbors));
+    }
+
+    #[test]
+    fn test_separate_sidewalks_on_both_sides() {
+        let entry = EdgeRTreeEntry::new(street()).unwrap();
+        let mut rtree: RTree<EdgeRTreeEntry<TestEdge>> = RTree::new();
+        rtree.insert(entry.clone());
+        // about 10 meters north and south of the street
+        rtree.insert(EdgeRTreeEntry::new(sidewalk(1, 39.77009)).unwrap());
+        let one_side = compute_sidewalk_completeness(&rtree, &entry);
+        assert!((one_side.ratio.unwrap() - 0.5).abs() < 0.01);
+
+        rtree.insert(EdgeRTreeEntry::new(sidewalk(2, 39.76991)).unwrap());
+        let both_sides = compute_sidewalk_completeness(&rtree, &entry);
+        assert!((both_sides.ratio.unwrap() - 1.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1 @@
+pub mod compute_sidewalk_completeness;
@@ -91,85 +91,14 @@ where
 mod test {
     use super::*;
     use crate::common::cycleway_tag::CyclewayTag;
-    use geo::LineString;
-
-    #[derive(Clone)]
-    struct TestEdge {
-        id: usize,
-        walkable: bool,
-        sidewalk: bool,
-        footway: bool,
-        speed_limit: Option<i32>,
-        cycleway: Option<CyclewayTag>,
-        linestring: LineString<f32>,
-    }
-
-    impl SpatialEdge for TestEdge {
-        fn id(&self) -> String {
-            self.id.to_string()
-        }
-        fn src_vertex_id(&self) -> usize {
-            0
-        }
-        fn linestring(&self) -> std::option::Option<&geo::LineString<f32>> {
-            Some(&self.linestring)
-        }
-    }
-
-    impl EdgeForModalMetric for TestEdge {
-        fn get_traffic_speed_limit(&self) -> Option<i32> {
-            self.speed_limit
-        }
-        fn get_cycleway_tag(&self) -> Option<CyclewayTag> {
-            self.cycleway.clone()
-        }
-        fn is_walkable(&self) -> bool {
-            self.walkable
-        }
-        fn is_walkable_highway(&self) -> bool {
-            false
-        }
-        fn is_sidewalk(&self) -> bool {
-            self.sidewalk
-        }
-        fn is_footway(&self) -> bool {
-            self.footway
-        }
-        fn is_unbikeable(&self) -> bool {
-            false
-        }
-        fn is_non_motorized(&self) -> bool {
-            false
-        }
-        fn is_oneway(&self) -> bool {
-            false
-        }
-    }
-
-    struct TestVertex {
-        has_signals: bool,
-        has_stop: bool,
-    }
-
-    impl VertexForModalMetric for TestVertex {
-        fn has_traffic_signals(&self) -> bool {
-            self.has_signals
-        }
-        fn has_stop_sign(&self) -> bool {
-            self.has_stop
-        }
-    }
+    use crate::common::test_util::{test_edge, TestEdge, TestVertex};
 
     #[test]
     fn test_min_wci() {
         let edge = TestEdge {
-            id: 42,
             walkable: false,
-            sidewalk: false,
-            footway: false,
             speed_limit: Some(65),
-            cycleway: None,
-            linestring: LineString::from(vec![(-105.170016, 39.773648), (-105.165381, 39.774176)]),
+            ..test_edge()
         };
         let src_vertex = TestVertex {
             has_signals: false,
@@ -186,13 +115,8 @@ mod test {
     #[test]
     fn test_max_wci() {
         let edge = TestEdge {
-            id: 42,
-            walkable: true,
-            sidewalk: false,
             footway: true,
-            speed_limit: None,
-            cycleway: None,
-            linestring: LineString::from(vec![(-105.170016, 39.773648), (-105.165381, 39.774176)]),
+            ..test_edge()
         };
         let src_vertex = TestVertex {
             has_signals: false,
@@ -209,13 +133,9 @@ mod test {
     #[test]
     fn test_positive_wci() {
         let edge = TestEdge {
-            id: 42,
-            walkable: true,
-            sidewalk: false,
-            footway: false,
             speed_limit: Some(25),
             cycleway: Some(CyclewayTag::NoDedicatedWithFacilities),
-            linestring: LineString::from(vec![(-105.170016, 39.773648), (-105.165381, 39.774176)]),
+            ..test_edge()
         };
         let src_vertex = TestVertex {
             has_signals: false,
@@ -236,13 +156,8 @@ mod test {
     #[test]
     fn test_negative_wci() {
         let edge = TestEdge {
-            id: 42,
-            walkable: true,
-            sidewalk: false,
-            footway: false,
             speed_limit: Some(45),
-            cycleway: None,
-            linestring: LineString::from(vec![(-105.170016, 39.773648), (-105.165381, 39.774176)]),
+            ..test_edge()
         };
         let src_vertex = TestVertex {
             has_signals: false,
@@ -265,23 +180,19 @@ mod test {
     fn test_neighbor_wci_contribution() {
         const WAY_SCORE_NO_NEIGHBORS: i32 = -4;
         let edge = TestEdge {
-            id: 42,
-            walkable: true,
-            sidewalk: false,
-            footway: false,
             speed_limit: Some(45),
-            cycleway: None,
-            linestring: LineString::from(vec![(-105.170016, 39.773648), (-105.165381, 39.774176)]),
+            ..test_edge()
         };
 
         let neighbor = TestEdge {
-            id: 43,
-            walkable: true,
-            sidewalk: false,
-            footway: false,
             speed_limit: Some(25),
             cycleway: Some(CyclewayTag::DedicatedNoBuffer),
-            linestring: LineString::from(vec![(-105.168085, 39.773772), (-105.166755, 39.773937)]),
+            ..TestEdge::new(
+                43,
+                2,
+                3,
+                vec![(-105.168085, 39.773772), (-105.166755, 39.773937)],
+            )
         };
 
         let src_vertex = TestVertex {
@@ -0,0 +1,258 @@
+use std::path::Path;
+
+use crate::{
+    app::{
+        network::{read_local, run_collector},
+        CliBoundingBox,
+    },
+    collection::{
+        ObjectStoreSource, OvertureMapsCollectionError, ReleaseVersion, TransportationCollection,
+    },
+    diff::{self, ChangeSummary, ConnectorChanges},
+    util,
+};
+
+/// a release of the transportation theme to compare, either downloaded from an
+/// object store or read from the raw JSON export of a previous network import.
+pub struct DiffSource<'a> {
+    pub release: &'a ReleaseVersion,
+    pub local_source: Option<&'a Path>,
+}
+
+impl DiffSource<'_> {
+    fn collect(
+        &self,
+        bbox: Option<&CliBoundingBox>,
+        object_store: ObjectStoreSource,
+        object_store_location: Option<&str>,
+    ) -> Result<TransportationCollection, OvertureMapsCollectionError> {
+        match self.local_source {
+            Some(path) => read_local(path),
+            None => run_collector(
+                bbox,
+                self.release.clone(),
+                object_store,
+                object_store_location,
+            ),
+        }
+    }
+
+    fn label(&self, collection: &TransportationCollection) -> String {
+        match self.local_source {
+            Some(local) => format!("file://{}", local.to_string_lossy()),
+            None => collection.release.clone(),
+        }
+    }
+}
+
+/// compares the transportation segments and connectors of two releases for the same
+/// region, writing the segment change set as CSV and GeoJSON along with a summary of
+/// changes by road class.
+pub fn run(
+    bbox: Option<&CliBoundingBox>,
+    previous: &DiffSource,
+    current: &DiffSource,
+    output_directory: &Path,
+    object_store: ObjectStoreSource,
+    object_store_location: Option<&str>,
+) -> Result<(), OvertureMapsCollectionError> {
+    let previous_collection = previous.collect(bbox, object_store, object_store_location)?;
+    let current_collection = current.collect(bbox, object_store, object_store_location)?;
+    let previous_label = previous.label(&previous_collection);
+    let current_label = current.label(&current_collection);
+    log::info!("comparing releases {previous_label} and {current_label}");
+
+    let changes =
+        diff::detect_segment_changes(&previous_collection.segments, &current_collection.segments);
+    let connectors = ConnectorChanges::new(
+        &previous_collection.connectors,
+        &current_collection.connectors,
+    );
+    let summary = ChangeSummary::new(
+        &previous_label,
+        &current_label,
+        &changes,
+        &current_collection.segments,
+        connectors,
+    );
+    log::info!("found {} changed segments", summary.segment_changes());
+    log::info!("{summary}");
+
+    util::fs::create_dirs(output_directory)?;
+    util::fs::serialize_into_csv(
+        changes.iter(),
+        diff::SEGMENT_CHANGES_CSV_FILENAME,
+        output_directory,
+        true,
+        "write segment changes",
+    )?;
+    util::fs::serialize_into_csv(
+        summary.classes.iter(),
+        diff::CLASS_CHANGE_SUMMARY_FILENAME,
+        output_directory,
+        true,
+        "write segment change summary",
+    )?;
+
+    let geojson = diff::to_geojson(&changes)?;
+    let geojson_path = output_directory.join(diff::SEGMENT_CHANGES_GEOJSON_FILENAME);
+    let geojson_str = serde_json::to_string(&geojson).map_err(|e| {
+        OvertureMapsCollectionError::SerializationError(format!(
+            "failure serializing segment changes as GeoJSON: {e}"
+        ))
+    })?;
+    std::fs::write(&geojson_path, geojson_str).map_err(|e| {
+        OvertureMapsCollectionError::WriteError {
+            path: geojson_path.clone(),
+            message: e.to_string(),
+        }
+    })?;
+
+    let summary_toml = toml::to_string_pretty(&summary).map_err(|e| {
+        OvertureMapsCollectionError::InternalError(format!(
+            "failure serializing change summary TOML: {e}"
+        ))
+    })?;
+    let summary_path = output_directory.join("diff-summary.toml");
+    std::fs::write(&summary_path, &summary_toml).map_err(|e| {
+        OvertureMapsCollectionError::WriteError {
+            path: summary_path.clone(),
+            message: e.to_string(),
+        }
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, DiffSource};
+    use crate::{
+        collection::{
+            ObjectStoreSource, ReleaseVersion, SegmentClass, TransportationCollection,
+            TransportationSegmentRecord,
+        },
+        diff::{self, ChangeSummary},
+    };
+    use flate2::read::GzDecoder;
+    use geo::{line_string, Geometry};
+    use std::{fs::File, path::Path};
+
+    fn segment(id: &str, x: f32, class: SegmentClass) -> TransportationSegmentRecord {
+        TransportationSegmentRecord {
+            id: id.to_string(),
+            geometry: Some(Geometry::LineString(
+                line_string![(x: x, y: 0.0), (x: x + 0.001, y: 0.0)],
+            )),
+            class: Some(class),
+            ..Default::default()
+        }
+    }
+
+    /// writes a collection as the raw JSON export of a network import, returning its path.
+    fn write_local(directory: &Path, segments: Vec<TransportationSegmentRecord>) -> String {
+        let collection = TransportationCollection {
+            release: String::from("test"),
+            connectors: vec![],
+            segments,
+        };
+        std::fs::create_dir_all(directory).expect("test invariant failed");
+        collection
+            .to_json(directory)
+            .expect("test invariant failed");
+        directory.join("omf-raw.json").to_string_lossy().to_string()
+    }
+
+    fn read_csv(path: &Path) -> Vec<Vec<String>> {
+        let file = File::open(path).expect("test invariant failed");
+        csv::Reader::from_reader(GzDecoder::new(file))
+            .records()
+            .map(|r| {
+                let record = r.expect("test invariant failed");
+                record.iter().map(String::from).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_writes_change_set() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let previous_path = write_local(
+            &tmp.path().join("previous"),
+            vec![
+                segment("unchanged", 0.0, SegmentClass::Residential),
+                segment("reclassified", 1.0, SegmentClass::Residential),
+                segment("demolished", 3.0, SegmentClass::Service),
+            ],
+        );
+        let current_path = write_local(
+            &tmp.path().join("current"),
+            vec![
+                segment("unchanged", 0.0, SegmentClass::Residential),
+                segment("reclassified", 1.0, SegmentClass::Tertiary),
+                segment("built", 4.0, SegmentClass::Service),
+            ],
+        );
+        let release = ReleaseVersion::Latest;
+        let previous = DiffSource {
+            release: &release,
+            local_source: Some(Path::new(&previous_path)),
+        };
+        let current = DiffSource {
+            release: &release,
+            local_source: Some(Path::new(&current_path)),
+        };
+        let output_directory = tmp.path().join("output");
+        run(
+            None,
+            &previous,
+            &current,
+            &output_directory,
+            ObjectStoreSource::AmazonS3,
+            None,
+        )
+        .expect("test invariant failed");
+
+        let changes = read_csv(&output_directory.join(diff::SEGMENT_CHANGES_CSV_FILENAME));
+        let rows = changes
+            .iter()
+            .map(|r| (r[0].as_str(), r[2].as_str(), r[3].as_str(), r[4].as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                ("reclassified", "modified", "tertiary", "residential"),
+                ("built", "added", "service", ""),
+                ("demolished", "removed", "service", ""),
+            ]
+        );
+
+        let classes = read_csv(&output_directory.join(diff::CLASS_CHANGE_SUMMARY_FILENAME));
+        assert_eq!(
+            classes,
+            vec![
+                vec!["residential", "0", "0", "0", "1"],
+                vec!["service", "1", "1", "0", "0"],
+                vec!["tertiary", "0", "0", "1", "0"],
+            ]
+        );
+
+        let geojson_str =
+            std::fs::read_to_string(output_directory.join(diff::SEGMENT_CHANGES_GEOJSON_FILENAME))
+                .expect("test invariant failed");
+        let geojson: serde_json::Value =
+            serde_json::from_str(&geojson_str).expect("test invariant failed");
+        let features = geojson["features"]
+            .as_array()
+            .expect("test invariant failed");
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[1]["properties"]["segment_id"], "built");
+        assert_eq!(features[1]["geometry"]["type"], "LineString");
+
+        let summary_str = std::fs::read_to_string(output_directory.join("diff-summary.toml"))
+            .expect("test invariant failed");
+        let summary: ChangeSummary = toml::from_str(&summary_str).expect("test invariant failed");
+        assert_eq!(summary.previous_release, format!("file://{previous_path}"));
+        assert_eq!(summary.segment_changes(), 3);
+    }
+}
@@ -2,5 +2,7 @@ mod cli_bbox;
 mod omf_app;
 
 pub use omf_app::OmfApp;
+pub mod diff;
+pub mod modal_metric;
 pub mod network;
 pub use cli_bbox::CliBoundingBox;
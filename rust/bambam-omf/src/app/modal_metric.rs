@@ -0,0 +1,366 @@
+use std::{collections::HashMap, path::Path};
+
+use bambam_modal_metrics::common::{
+    bulk_compute_modal_metric::{
+        compute_modal_metric_values_from_edges, write_modal_metric_values,
+    },
+    modal_metrics::ModalMetric,
+};
+use geo::Geometry;
+
+use crate::{
+    app::{
+        network::{
+            apply_extent_to_collection, read_local, run_collector, NetworkEdgeListConfiguration,
+        },
+        CliBoundingBox,
+    },
+    collection::{
+        ObjectStoreSource, OvertureMapsCollectionError, ReleaseVersion,
+        TransportationConnectorRecord,
+    },
+    graph::{island_detection::IslandDetectionAlgorithm, OmfGraphVectorized, OmfModalEdge},
+    util,
+};
+
+/// name of the file written to each edge list directory with the values of a modal
+/// metric, such as `edges-modal-metric-lts.csv`.
+pub fn modal_metric_filename(metric_name: &str) -> String {
+    format!(
+        "edges-modal-metric-{}.csv",
+        metric_name.to_ascii_lowercase()
+    )
+}
+
+/// computes modal metrics for each edge list of an OMF network import, writing one
+/// CSV per metric into the directory of each edge list. the network is built exactly
+/// as in [`crate::app::network::run`], so for the same inputs and configuration, the
+/// `edge_id` column matches the edge ids of the imported Compass edge lists.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    bbox: Option<&CliBoundingBox>,
+    modes: &[NetworkEdgeListConfiguration],
+    output_directory: &Path,
+    local_source: Option<&Path>,
+    island_detection_configuration: Option<IslandDetectionAlgorithm>,
+    metric_names: &[String],
+    extent: Option<Geometry<f32>>,
+    object_store: ObjectStoreSource,
+    object_store_location: Option<&str>,
+) -> Result<(), OvertureMapsCollectionError> {
+    let metrics = metric_names
+        .iter()
+        .map(|name| {
+            let metric = name.parse::<ModalMetric>().map_err(|e| {
+                OvertureMapsCollectionError::InvalidUserInput(format!(
+                    "{e}, expected one of WCI, LTS, BLOS, SIDEWALK or CROSSING"
+                ))
+            })?;
+            Ok((modal_metric_filename(name), metric))
+        })
+        .collect::<Result<Vec<_>, OvertureMapsCollectionError>>()?;
+
+    let collection = match local_source {
+        Some(src_path) => read_local(src_path),
+        None => run_collector(
+            bbox,
+            ReleaseVersion::Latest,
+            object_store,
+            object_store_location,
+        ),
+    }?;
+    let collection = match extent {
+        Some(ext_geom) => apply_extent_to_collection(collection, ext_geom),
+        None => collection,
+    };
+
+    let graph = OmfGraphVectorized::new(&collection, modes, island_detection_configuration)?;
+    let vertices = connectors_by_vertex_id(&graph, &collection.connectors)?;
+
+    for (edge_list, edge_list_config) in graph.edge_lists.iter().zip(graph.edge_list_config.iter())
+    {
+        let mode_dir = output_directory.join(&edge_list_config.mode);
+        util::fs::create_dirs(&mode_dir)?;
+        let edges = OmfModalEdge::from_edge_list(edge_list)?;
+
+        for (filename, metric) in metrics.iter() {
+            log::info!(
+                "computing {metric:?} for {} edges of edge list '{}'",
+                edges.len(),
+                edge_list_config.mode
+            );
+            let values = compute_modal_metric_values_from_edges(metric, edges.clone(), &vertices)
+                .map_err(|e| {
+                OvertureMapsCollectionError::InternalError(format!(
+                    "failure computing {metric:?} for edge list '{}': {e}",
+                    edge_list_config.mode
+                ))
+            })?;
+            let output_file = mode_dir.join(filename);
+            write_modal_metric_values(metric, &values, &output_file.to_string_lossy()).map_err(
+                |e| OvertureMapsCollectionError::WriteError {
+                    path: output_file.clone(),
+                    message: e.to_string(),
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// orders the connector records by the index of their vertex in the graph.
+fn connectors_by_vertex_id(
+    graph: &OmfGraphVectorized,
+    connectors: &[TransportationConnectorRecord],
+) -> Result<Vec<TransportationConnectorRecord>, OvertureMapsCollectionError> {
+    let lookup = connectors
+        .iter()
+        .map(|c| (c.id.as_str(), c))
+        .collect::<HashMap<_, _>>();
+    let mut result: Vec<Option<TransportationConnectorRecord>> = vec![None; graph.vertices.len()];
+    for (connector_id, vertex_id) in graph.vertex_lookup.iter() {
+        let connector = lookup.get(connector_id.as_str()).ok_or_else(|| {
+            OvertureMapsCollectionError::InternalError(format!(
+                "vertex {vertex_id} has no connector record with id {connector_id}"
+            ))
+        })?;
+        if let Some(slot) = result.get_mut(*vertex_id) {
+            *slot = Some((*connector).clone());
+        }
+    }
+    result
+        .into_iter()
+        .enumerate()
+        .map(|(vertex_id, connector)| {
+            connector.ok_or_else(|| {
+                OvertureMapsCollectionError::InternalError(format!(
+                    "vertex {vertex_id} is missing from the connector lookup"
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{connectors_by_vertex_id, modal_metric_filename, run};
+    use crate::{
+        app::network::{self, NetworkEdgeListConfiguration},
+        collection::{
+            filter::TravelModeFilter, record::segment::ConnectorReference, ObjectStoreSource,
+            SegmentClass, SegmentSubclass, SegmentSubtype, TransportationCollection,
+            TransportationConnectorRecord, TransportationSegmentRecord,
+        },
+        graph::{OmfGraphVectorized, OmfModalEdge},
+    };
+    use bambam_modal_metrics::network_traits::vertex_for_modal_metric::VertexForModalMetric;
+    use flate2::read::GzDecoder;
+    use geo::{Geometry, LineString, Point};
+    use geozero::{CoordDimensions, ToWkb};
+    use std::{fs::File, path::Path};
+
+    fn connector(id: &str, x: f64, y: f64) -> TransportationConnectorRecord {
+        let wkb = Geometry::Point(Point::new(x, y))
+            .to_wkb(CoordDimensions::xy())
+            .expect("test invariant failed");
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "geometry": hex::encode(wkb),
+            "bbox": {},
+            "version": 0
+        }))
+        .expect("test invariant failed: unable to mock connector")
+    }
+
+    fn segment(
+        id: &str,
+        class: SegmentClass,
+        subclass: Option<SegmentSubclass>,
+        coords: [(f32, f32); 2],
+        connectors: [&str; 2],
+    ) -> TransportationSegmentRecord {
+        TransportationSegmentRecord {
+            id: id.to_string(),
+            geometry: Some(Geometry::LineString(LineString::from(coords.to_vec()))),
+            subtype: Some(SegmentSubtype::Road),
+            class: Some(class),
+            subclass,
+            connectors: Some(vec![
+                ConnectorReference {
+                    connector_id: connectors[0].to_string(),
+                    at: 0.0,
+                },
+                ConnectorReference {
+                    connector_id: connectors[1].to_string(),
+                    at: 1.0,
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    /// a residential street (a, b), a primary road (b, c) with a posted speed limit
+    /// and a sidewalk (b, d). connectors are not listed in vertex order.
+    fn collection() -> TransportationCollection {
+        let (a, b, c, d) = (
+            (-105.0, 40.0),
+            (-104.999, 40.0),
+            (-104.998, 40.0),
+            (-104.999, 40.001),
+        );
+        let mut primary = segment("s2", SegmentClass::Primary, None, [b, c], ["b", "c"]);
+        primary.speed_limits = serde_json::from_value(serde_json::json!([
+            {"max_speed": {"value": 35, "unit": "mph"}}
+        ]))
+        .expect("test invariant failed: unable to mock speed limits");
+        TransportationCollection {
+            release: String::from("test"),
+            connectors: vec![
+                connector("c", c.0 as f64, c.1 as f64),
+                connector("a", a.0 as f64, a.1 as f64),
+                connector("d", d.0 as f64, d.1 as f64),
+                connector("b", b.0 as f64, b.1 as f64),
+            ],
+            segments: vec![
+                segment("s1", SegmentClass::Residential, None, [a, b], ["a", "b"]),
+                primary,
+                segment(
+                    "s3",
+                    SegmentClass::Footway,
+                    Some(SegmentSubclass::Sidewalk),
+                    [b, d],
+                    ["b", "d"],
+                ),
+            ],
+        }
+    }
+
+    fn modes() -> Vec<NetworkEdgeListConfiguration> {
+        vec![NetworkEdgeListConfiguration {
+            mode: String::from("walk"),
+            filter: vec![TravelModeFilter::MatchesSubtype {
+                subtype: SegmentSubtype::Road,
+            }],
+            island_algorithm_config: None,
+        }]
+    }
+
+    /// (edge_id, src_vertex_id, dst_vertex_id) of each row of a Compass edge list.
+    fn read_compass_edges(path: &Path) -> Vec<(usize, usize, usize)> {
+        let file = File::open(path).expect("test invariant failed");
+        csv::Reader::from_reader(GzDecoder::new(file))
+            .deserialize::<(usize, usize, usize, f64)>()
+            .map(|row| {
+                let (edge_id, src, dst, _) = row.expect("test invariant failed");
+                (edge_id, src, dst)
+            })
+            .collect()
+    }
+
+    fn read_metric_edge_ids(path: &Path) -> Vec<usize> {
+        csv::Reader::from_path(path)
+            .expect("test invariant failed")
+            .records()
+            .map(|row| {
+                let row = row.expect("test invariant failed");
+                row[0].parse().expect("test invariant failed")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_modal_metric_edges_align_with_network_import() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        collection()
+            .to_json(tmp.path())
+            .expect("test invariant failed");
+        let local_source = tmp.path().join("omf-raw.json");
+        let network_dir = tmp.path().join("network");
+        let metric_dir = tmp.path().join("metrics");
+
+        network::run(
+            "test",
+            None,
+            &modes(),
+            &network_dir,
+            Some(&local_source),
+            false,
+            None,
+            false,
+            None,
+            ObjectStoreSource::AmazonS3,
+            None,
+        )
+        .expect("test invariant failed");
+        let metrics = vec![String::from("LTS"), String::from("WCI")];
+        run(
+            None,
+            &modes(),
+            &metric_dir,
+            Some(&local_source),
+            None,
+            &metrics,
+            None,
+            ObjectStoreSource::AmazonS3,
+            None,
+        )
+        .expect("test invariant failed");
+
+        let compass_edges =
+            read_compass_edges(&network_dir.join("walk").join("edges-compass.csv.gz"));
+        assert_eq!(compass_edges.len(), 6);
+        let compass_edge_ids = compass_edges.iter().map(|e| e.0).collect::<Vec<_>>();
+        for metric in metrics.iter() {
+            let path = metric_dir.join("walk").join(modal_metric_filename(metric));
+            assert_eq!(read_metric_edge_ids(&path), compass_edge_ids, "{metric}");
+        }
+
+        // the modal edges are built from the same graph, so each edge id refers to the
+        // same pair of vertices as in the Compass edge list
+        let graph =
+            OmfGraphVectorized::new(&collection(), &modes(), None).expect("test invariant failed");
+        let modal_edges =
+            OmfModalEdge::from_edge_list(&graph.edge_lists[0]).expect("test invariant failed");
+        let modal_vertices = modal_edges
+            .iter()
+            .map(|(edge_id, e)| (*edge_id, e.src_vertex_id, e.dst_vertex_id))
+            .collect::<Vec<_>>();
+        assert_eq!(modal_vertices, compass_edges);
+        for (_, e) in modal_edges.iter() {
+            match e.segment_id.as_str() {
+                "s2" => assert_eq!(e.attributes.speed_limit_mph.map(f64::round), Some(35.0)),
+                _ => assert_eq!(e.attributes.speed_limit_mph, None),
+            }
+            assert_eq!(
+                e.class.2 == Some(SegmentSubclass::Sidewalk),
+                e.segment_id == "s3"
+            );
+        }
+    }
+
+    #[test]
+    fn test_connectors_by_vertex_id() {
+        let collection = collection();
+        let graph =
+            OmfGraphVectorized::new(&collection, &modes(), None).expect("test invariant failed");
+        let vertices =
+            connectors_by_vertex_id(&graph, &collection.connectors).expect("test invariant failed");
+        assert_eq!(vertices.len(), graph.vertices.len());
+        for (connector_id, vertex_id) in graph.vertex_lookup.iter() {
+            assert_eq!(&vertices[*vertex_id].id, connector_id);
+            let vertex = &graph.vertices[*vertex_id];
+            let expected = vertices[*vertex_id]
+                .try_to_vertex(*vertex_id)
+                .expect("test invariant failed");
+            assert_eq!((vertex.x(), vertex.y()), (expected.x(), expected.y()));
+        }
+        // connectors do not record traffic controls
+        assert!(vertices
+            .iter()
+            .all(|v| !v.has_traffic_signals() && !v.has_stop_sign()));
+
+        let missing = &collection.connectors[1..];
+        assert!(connectors_by_vertex_id(&graph, missing).is_err());
+    }
+}
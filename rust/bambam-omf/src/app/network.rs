@@ -51,10 +51,17 @@ pub fn run(
     island_detection_configuration: Option<IslandDetectionAlgorithm>,
     export_omf_ids: bool,
     extent: Option<Geometry<f32>>,
+    object_store: ObjectStoreSource,
+    object_store_location: Option<&str>,
 ) -> Result<(), OvertureMapsCollectionError> {
     let collection: TransportationCollection = match local_source {
         Some(src_path) => read_local(src_path),
-        None => run_collector(bbox),
+        None => run_collector(
+            bbox,
+            ReleaseVersion::Latest,
+            object_store,
+            object_store_location,
+        ),
     }?;
 
     if write_json {
@@ -85,7 +92,9 @@ pub fn run(
     Ok(())
 }
 
-fn read_local(path: &Path) -> Result<TransportationCollection, OvertureMapsCollectionError> {
+pub(crate) fn read_local(
+    path: &Path,
+) -> Result<TransportationCollection, OvertureMapsCollectionError> {
     let contents = std::fs::read(path).map_err(|e| OvertureMapsCollectionError::ReadError {
         path: path.to_owned(),
         message: e.to_string(),
@@ -100,27 +109,33 @@ fn read_local(path: &Path) -> Result<TransportationCollection, OvertureMapsColle
     Ok(collection)
 }
 
-/// retrieve a TransportationCollection from a URL.
-fn run_collector(
+/// retrieve a TransportationCollection from an object store, either the public
+/// OvertureMaps bucket or a mirror of it at the provided location.
+pub(crate) fn run_collector(
     bbox_arg: Option<&CliBoundingBox>,
+    release: ReleaseVersion,
+    object_store: ObjectStoreSource,
+    object_store_location: Option<&str>,
 ) -> Result<TransportationCollection, OvertureMapsCollectionError> {
-    let object_store = ObjectStoreSource::AmazonS3;
     let rg_chunk_size = 4;
     let file_concurrency_limit = 64;
-    let collector = OvertureMapsCollectorConfig::new(
+    let mut collector_config = OvertureMapsCollectorConfig::new(
         object_store,
         Some(rg_chunk_size),
         Some(file_concurrency_limit),
-    )
-    .build()?;
-    let release = ReleaseVersion::Latest;
+    );
+    if let Some(location) = object_store_location {
+        collector_config = collector_config.with_location(location);
+    }
+    let collector = collector_config.build()?;
+    let location = object_store_location.unwrap_or_default();
     let bbox = bbox_arg.ok_or_else(|| {
         let msg = String::from("must provide bbox argument for download");
         OvertureMapsCollectionError::InvalidUserInput(msg)
     })?;
     log::info!(
         "running OMF import with
-        object store {object_store:?}
+        object store {object_store} {location}
         rg_chunk_size {rg_chunk_size}
         file_concurrency_limit {file_concurrency_limit}
         release {release}
@@ -135,7 +150,7 @@ fn run_collector(
 /// a second pass is performed over the connectors collection to reomve all connectors not referenced
 /// by the remaining segments.
 /// empty geometries are ignored (filtered out).
-fn apply_extent_to_collection(
+pub(crate) fn apply_extent_to_collection(
     collection: TransportationCollection,
     extent: Geometry<f32>,
 ) -> TransportationCollection {
@@ -1,14 +1,17 @@
-use std::{fs, path::Path};
+use std::{fs, path::Path, str::FromStr};
 
 use clap::{Parser, Subcommand};
 use config::{Config, File};
-use geo::MapCoords;
+use geo::{Geometry, MapCoords};
 use geozero::{wkt::Wkt as WktReader, ToGeo};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app::{cli_bbox::parse_bbox, network::NetworkEdgeListConfiguration, CliBoundingBox},
-    collection::OvertureMapsCollectionError,
+    app::{
+        cli_bbox::parse_bbox, diff::DiffSource, network::NetworkEdgeListConfiguration,
+        CliBoundingBox,
+    },
+    collection::{ObjectStoreSource, OvertureMapsCollectionError, ReleaseVersion},
     graph::island_detection::IslandDetectionAlgorithm,
 };
 
@@ -59,6 +62,92 @@ pub enum OmfOperation {
         /// Optional WKT extent in json format. expects a json file with a single "extent" key
         #[arg(short, long)]
         extent_file: Option<String>,
+
+        /// object store holding the OvertureMaps release tree to download from
+        #[arg(long, value_enum, default_value_t = ObjectStoreSource::AmazonS3)]
+        object_store: ObjectStoreSource,
+
+        /// location of a mirrored release tree, required for the azure (az://<container>[/<prefix>])
+        /// and fs (directory containing `release/`) object stores
+        #[arg(long)]
+        object_store_location: Option<String>,
+    },
+    /// compute modal metrics such as the Level of Traffic Stress (LTS) or Walking
+    /// Comfort Index (WCI) for each edge list of an OMF network import. values are
+    /// keyed by the edge ids written by the network operation for the same inputs.
+    ModalMetric {
+        /// modal metrics to compute, any of "WCI", "LTS", "BLOS", "SIDEWALK" or "CROSSING"
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        metrics: Vec<String>,
+
+        /// configuration file defining how the network is imported and separated
+        /// into mode-specific edge lists.
+        #[arg(short, long)]
+        configuration_file: String,
+
+        /// location on disk to write output files. if not provided,
+        /// use the current working directory.
+        #[arg(short, long)]
+        output_directory: Option<String>,
+
+        /// use a stored raw data export from a previous run of OmfOperation::Network
+        /// which is a JSON file containing a TransportationCollection.
+        #[arg(short, long)]
+        local_source: Option<String>,
+
+        /// bounding box to filter data (format: xmin,xmax,ymin,ymax)
+        #[arg(short, long, value_parser = parse_bbox, allow_hyphen_values(true))]
+        bbox: Option<CliBoundingBox>,
+
+        /// Optional WKT extent in json format. expects a json file with a single "extent" key
+        #[arg(short, long)]
+        extent_file: Option<String>,
+
+        /// object store holding the OvertureMaps release tree to download from
+        #[arg(long, value_enum, default_value_t = ObjectStoreSource::AmazonS3)]
+        object_store: ObjectStoreSource,
+
+        /// location of a mirrored release tree, required for the azure (az://<container>[/<prefix>])
+        /// and fs (directory containing `release/`) object stores
+        #[arg(long)]
+        object_store_location: Option<String>,
+    },
+    /// compare the transportation data of two OMF releases for a region, reporting
+    /// added, removed and modified segments
+    Diff {
+        /// release to compare from, either "latest" or a release such as 2025-01-22.0
+        #[arg(long, value_parser = ReleaseVersion::from_str)]
+        previous_release: ReleaseVersion,
+
+        /// release to compare to, either "latest" or a release such as 2025-01-22.0
+        #[arg(long, value_parser = ReleaseVersion::from_str, default_value = "latest")]
+        current_release: ReleaseVersion,
+
+        /// use a stored raw data export in place of downloading the previous release
+        #[arg(long)]
+        previous_local_source: Option<String>,
+
+        /// use a stored raw data export in place of downloading the current release
+        #[arg(long)]
+        current_local_source: Option<String>,
+
+        /// location on disk to write output files. if not provided,
+        /// use the current working directory.
+        #[arg(short, long)]
+        output_directory: Option<String>,
+
+        /// bounding box to filter data (format: xmin,xmax,ymin,ymax)
+        #[arg(short, long, value_parser = parse_bbox, allow_hyphen_values(true))]
+        bbox: Option<CliBoundingBox>,
+
+        /// object store holding the OvertureMaps release tree to download from
+        #[arg(long, value_enum, default_value_t = ObjectStoreSource::AmazonS3)]
+        object_store: ObjectStoreSource,
+
+        /// location of a mirrored release tree, required for the azure (az://<container>[/<prefix>])
+        /// and fs (directory containing `release/`) object stores
+        #[arg(long)]
+        object_store_location: Option<String>,
     },
 }
 
@@ -74,60 +163,17 @@ impl OmfOperation {
                 bbox,
                 omf_ids,
                 extent_file,
+                object_store,
+                object_store_location,
             } => {
-                let filepath = Path::new(configuration_file);
-                let config = Config::builder()
-                    .add_source(File::from(filepath))
-                    .build()
-                    .map_err(|e| {
-                        let msg = format!("file '{configuration_file}' produced error: {e}");
-                        OvertureMapsCollectionError::InvalidUserInput(msg)
-                    })?;
-                let network_config = config
-                    .get::<Vec<NetworkEdgeListConfiguration>>("edge_lists")
-                    .map_err(|e| {
-                        let msg = format!(
-                            "error reading 'edge_lists' key in '{configuration_file}': {e}"
-                        );
-                        OvertureMapsCollectionError::InvalidUserInput(msg)
-                    })?;
-                let island_algorithm_configuration = config
-                    .get::<Option<IslandDetectionAlgorithm>>(
-                        "island_algorithm_configuration",
-                    )
-                    .map_err(|e| {
-                        let msg = format!(
-                            "error reading 'island_algorithm_configuration' key in '{configuration_file}': {e}"
-                        );
-                        OvertureMapsCollectionError::InvalidUserInput(msg)
-                    })?;
+                let (network_config, island_algorithm_configuration) =
+                    read_network_configuration(configuration_file)?;
                 let outdir = match output_directory {
                     Some(out) => Path::new(out),
                     None => Path::new(""),
                 };
                 let local = local_source.as_ref().map(Path::new);
-                let extent = extent_file
-                    .as_ref()
-                    .map(|extent_path| {
-                        let wkt_str = fs::read_to_string(extent_path).map_err(|e| {
-                            OvertureMapsCollectionError::InvalidUserInput(format!(
-                                "failed to load extent file {extent_path}: {e}"
-                            ))
-                        })?;
-
-                        let geometry_f64 = WktReader(wkt_str.trim()).to_geo().map_err(|e| {
-                            OvertureMapsCollectionError::InvalidUserInput(format!(
-                                "failed to parse string into WKT from {extent_path}: {e}"
-                            ))
-                        })?;
-                        let polygon = geometry_f64.map_coords(|geo::Coord { x, y }| geo::Coord {
-                            x: x as f32,
-                            y: y as f32,
-                        });
-
-                        Ok(polygon)
-                    })
-                    .transpose()?;
+                let extent = extent_file.as_deref().map(read_extent).transpose()?;
                 crate::app::network::run(
                     name,
                     bbox.as_ref(),
@@ -138,8 +184,127 @@ impl OmfOperation {
                     island_algorithm_configuration,
                     *omf_ids,
                     extent,
+                    *object_store,
+                    object_store_location.as_deref(),
+                )
+            }
+            OmfOperation::ModalMetric {
+                metrics,
+                configuration_file,
+                output_directory,
+                local_source,
+                bbox,
+                extent_file,
+                object_store,
+                object_store_location,
+            } => {
+                let (network_config, island_algorithm_configuration) =
+                    read_network_configuration(configuration_file)?;
+                let outdir = match output_directory {
+                    Some(out) => Path::new(out),
+                    None => Path::new(""),
+                };
+                let local = local_source.as_ref().map(Path::new);
+                let extent = extent_file.as_deref().map(read_extent).transpose()?;
+                crate::app::modal_metric::run(
+                    bbox.as_ref(),
+                    &network_config,
+                    outdir,
+                    local,
+                    island_algorithm_configuration,
+                    metrics,
+                    extent,
+                    *object_store,
+                    object_store_location.as_deref(),
+                )
+            }
+            OmfOperation::Diff {
+                previous_release,
+                current_release,
+                previous_local_source,
+                current_local_source,
+                output_directory,
+                bbox,
+                object_store,
+                object_store_location,
+            } => {
+                let outdir = match output_directory {
+                    Some(out) => Path::new(out),
+                    None => Path::new(""),
+                };
+                let previous = DiffSource {
+                    release: previous_release,
+                    local_source: previous_local_source.as_ref().map(Path::new),
+                };
+                let current = DiffSource {
+                    release: current_release,
+                    local_source: current_local_source.as_ref().map(Path::new),
+                };
+                crate::app::diff::run(
+                    bbox.as_ref(),
+                    &previous,
+                    &current,
+                    outdir,
+                    *object_store,
+                    object_store_location.as_deref(),
                 )
             }
         }
     }
 }
+
+/// reads the edge list and island detection configurations of a network import.
+fn read_network_configuration(
+    configuration_file: &str,
+) -> Result<
+    (
+        Vec<NetworkEdgeListConfiguration>,
+        Option<IslandDetectionAlgorithm>,
+    ),
+    OvertureMapsCollectionError,
+> {
+    let filepath = Path::new(configuration_file);
+    let config = Config::builder()
+        .add_source(File::from(filepath))
+        .build()
+        .map_err(|e| {
+            let msg = format!("file '{configuration_file}' produced error: {e}");
+            OvertureMapsCollectionError::InvalidUserInput(msg)
+        })?;
+    let network_config = config
+        .get::<Vec<NetworkEdgeListConfiguration>>("edge_lists")
+        .map_err(|e| {
+            let msg = format!("error reading 'edge_lists' key in '{configuration_file}': {e}");
+            OvertureMapsCollectionError::InvalidUserInput(msg)
+        })?;
+    let island_algorithm_configuration = config
+        .get::<Option<IslandDetectionAlgorithm>>("island_algorithm_configuration")
+        .map_err(|e| {
+            let msg = format!(
+                "error reading 'island_algorithm_configuration' key in '{configuration_file}': {e}"
+            );
+            OvertureMapsCollectionError::InvalidUserInput(msg)
+        })?;
+    Ok((network_config, island_algorithm_configuration))
+}
+
+/// reads an extent geometry from a file containing WKT.
+fn read_extent(extent_path: &str) -> Result<Geometry<f32>, OvertureMapsCollectionError> {
+    let wkt_str = fs::read_to_string(extent_path).map_err(|e| {
+        OvertureMapsCollectionError::InvalidUserInput(format!(
+            "failed to load extent file {extent_path}: {e}"
+        ))
+    })?;
+
+    let geometry_f64 = WktReader(wkt_str.trim()).to_geo().map_err(|e| {
+        OvertureMapsCollectionError::InvalidUserInput(format!(
+            "failed to parse string into WKT from {extent_path}: {e}"
+        ))
+    })?;
+    let polygon = geometry_f64.map_coords(|geo::Coord { x, y }| geo::Coord {
+        x: x as f32,
+        y: y as f32,
+    });
+
+    Ok(polygon)
+}
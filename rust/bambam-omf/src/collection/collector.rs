@@ -119,7 +119,13 @@ impl OvertureMapsCollector {
             .block_on(filemeta_stream.collect::<Vec<_>>())
             .into_iter()
             .collect::<Result<Vec<ObjectMeta>, _>>()
-            .map_err(|e| OvertureMapsCollectionError::MetadataError(e.to_string()))?;
+            .map_err(|e| OvertureMapsCollectionError::MetadataError(e.to_string()))?
+            .into_iter()
+            .filter(|meta| meta.location.extension() == Some("parquet"))
+            .collect::<Vec<_>>();
+        if meta_objects.is_empty() {
+            log::warn!("no parquet files found under path '{path}'");
+        }
 
         // Prepare the filter predicates
         let opt_bbox_filter = row_filter_config
@@ -6,9 +6,12 @@ use super::OvertureMapsCollector;
 
 /// Serializable configuration for OvertureMapsCollector Object.
 /// Builds to a [`OvertureMapsCollector`]
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OvertureMapsCollectorConfig {
     obj_store_type: ObjectStoreSource,
+    // Location of a mirrored release tree, required for the `azure` and `fs` object stores
+    #[serde(default)]
+    location: Option<String>,
     // Number of row groups to schedule for each process. Defaults to 4
     rg_chunk_size: Option<usize>,
     // Limit to the number of files to process simultaneously. Defaults to 64
@@ -19,6 +22,7 @@ impl Default for OvertureMapsCollectorConfig {
     fn default() -> Self {
         Self {
             obj_store_type: ObjectStoreSource::AmazonS3,
+            location: None,
             rg_chunk_size: Some(4),
             file_concurrency_limit: Some(64),
         }
@@ -33,14 +37,21 @@ impl OvertureMapsCollectorConfig {
     ) -> Self {
         Self {
             obj_store_type,
+            location: None,
             rg_chunk_size,
             file_concurrency_limit,
         }
     }
 
+    /// sets the location of the mirrored release tree read by the `azure` and `fs` object stores
+    pub fn with_location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_string());
+        self
+    }
+
     pub fn build(&self) -> Result<OvertureMapsCollector, OvertureMapsCollectionError> {
         Ok(OvertureMapsCollector::new(
-            self.obj_store_type.build()?,
+            self.obj_store_type.build(self.location.as_deref())?,
             self.rg_chunk_size.unwrap_or(4),
             self.file_concurrency_limit.unwrap_or(64),
         ))
@@ -41,7 +41,9 @@ pub enum TravelModeFilter {
     ///   - if "heading" is present, it must be "forward"
     ///   - if "using" or "recognized" modifiers are present, returns false
     ///     - these imply some special user type, we want to ignore any of these for now
-    ///   - "during", and "vehicle" modifiers are ignored.
+    ///   - denials with a "during" modifier are ignored, as they are only in effect some of the
+    ///     time. these are written to the time restrictions table of the edge list instead.
+    ///   - "vehicle" modifiers are ignored.
     #[serde(rename = "access_mode")]
     MatchesModeAccess { modes: Vec<SegmentMode> },
 }
@@ -107,6 +109,11 @@ impl ModeAccessAccumulator {
 
     /// updates the accumulator with an additional restriction
     pub fn add_restriction(&mut self, r: &SegmentAccessRestriction) {
+        // time-dependent denials never remove a segment from the network
+        if r.access_type == SegmentAccessType::Denied && r.is_time_dependent() {
+            return;
+        }
+
         // unpack values from the restriction relevant to this travel mode
         let has_mode = r.when.as_ref().and_then(|x| {
             x.mode
@@ -1,37 +1,97 @@
 use clap::ValueEnum;
-use object_store::{aws::AmazonS3Builder, ObjectStore};
+use object_store::{
+    aws::AmazonS3Builder, azure::MicrosoftAzureBuilder, local::LocalFileSystem,
+    prefix::PrefixStore, ObjectStore,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use super::OvertureMapsCollectionError;
 
+/// URL of the public OvertureMaps release bucket
+pub const OVERTURE_S3_URL: &str = "s3://overturemaps-us-west-2/";
+
+/// the kind of object store holding an OvertureMaps release tree. every store must
+/// share the layout of the public bucket, `release/<version>/theme=<theme>/type=<type>/*.parquet`.
 #[derive(Debug, Serialize, Deserialize, Copy, Clone, ValueEnum)]
 pub enum ObjectStoreSource {
+    /// the public OvertureMaps bucket on Amazon S3
     #[serde(rename = "s3")]
+    #[value(name = "s3")]
     AmazonS3,
+    /// a mirror in Azure Blob Storage, located by `az://<container>[/<prefix>]`.
+    /// the storage account and credentials are read from `AZURE_STORAGE_*` environment variables.
     #[serde(rename = "azure")]
+    #[value(name = "azure")]
     Azure,
+    /// a mirror on the local filesystem, located by the directory containing `release/`
     #[serde(rename = "fs")]
+    #[value(name = "fs")]
     FileSystem,
 }
 
 impl ObjectStoreSource {
-    pub fn build(&self) -> Result<Arc<dyn ObjectStore>, OvertureMapsCollectionError> {
+    /// builds the object store. the location of the mirrored release tree is
+    /// required for [`ObjectStoreSource::Azure`] and [`ObjectStoreSource::FileSystem`].
+    pub fn build(
+        &self,
+        location: Option<&str>,
+    ) -> Result<Arc<dyn ObjectStore>, OvertureMapsCollectionError> {
         match self {
             ObjectStoreSource::AmazonS3 => {
+                if let Some(location) = location {
+                    log::warn!("ignoring location '{location}' for the OvertureMaps S3 bucket");
+                }
                 let object_store = AmazonS3Builder::new()
                     .with_region("us-west-2")
                     .with_skip_signature(true)
-                    .with_url("s3://overturemaps-us-west-2/")
+                    .with_url(OVERTURE_S3_URL)
                     .build()
                     .map_err(|e| OvertureMapsCollectionError::ConnectionError(e.to_string()))?;
 
                 Ok(Arc::new(object_store))
             }
-            ObjectStoreSource::Azure => todo!(),
-            ObjectStoreSource::FileSystem => todo!(),
+            ObjectStoreSource::Azure => {
+                let location = self.require_location(location)?;
+                let (container, prefix) = parse_azure_location(location)?;
+                let object_store = MicrosoftAzureBuilder::from_env()
+                    .with_container_name(container)
+                    .build()
+                    .map_err(|e| OvertureMapsCollectionError::ConnectionError(e.to_string()))?;
+                match prefix {
+                    Some(prefix) => Ok(Arc::new(PrefixStore::new(object_store, prefix))),
+                    None => Ok(Arc::new(object_store)),
+                }
+            }
+            ObjectStoreSource::FileSystem => {
+                let location = self.require_location(location)?;
+                let root = std::path::Path::new(location);
+                if !root.join("release").is_dir() {
+                    let msg = format!(
+                        "local OvertureMaps mirror '{location}' must contain a 'release' directory"
+                    );
+                    return Err(OvertureMapsCollectionError::InvalidUserInput(msg));
+                }
+                let object_store = LocalFileSystem::new_with_prefix(root).map_err(|e| {
+                    OvertureMapsCollectionError::ReadError {
+                        path: root.to_path_buf(),
+                        message: e.to_string(),
+                    }
+                })?;
+                Ok(Arc::new(object_store))
+            }
         }
     }
+
+    fn require_location<'a>(
+        &self,
+        location: Option<&'a str>,
+    ) -> Result<&'a str, OvertureMapsCollectionError> {
+        location.ok_or_else(|| {
+            let msg = format!("object store '{self}' requires a location");
+            OvertureMapsCollectionError::InvalidUserInput(msg)
+        })
+    }
 }
 
 impl std::fmt::Display for ObjectStoreSource {
@@ -44,3 +104,115 @@ impl std::fmt::Display for ObjectStoreSource {
         write!(f, "{s}")
     }
 }
+
+/// splits an `az://<container>[/<prefix>]` location into its container and optional prefix.
+fn parse_azure_location(
+    location: &str,
+) -> Result<(&str, Option<&str>), OvertureMapsCollectionError> {
+    let invalid = || {
+        let msg =
+            format!("azure location '{location}' must have the form az://<container>[/<prefix>]");
+        OvertureMapsCollectionError::InvalidUserInput(msg)
+    };
+    let path = location.strip_prefix("az://").ok_or_else(invalid)?;
+    let (container, prefix) = match path.split_once('/') {
+        Some((container, prefix)) => (container, prefix.trim_matches('/')),
+        None => (path, ""),
+    };
+    if container.is_empty() {
+        return Err(invalid());
+    }
+    let prefix = if prefix.is_empty() {
+        None
+    } else {
+        Some(prefix)
+    };
+    Ok((container, prefix))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_azure_location, ObjectStoreSource};
+    use crate::collection::{OvertureMapsCollectorConfig, OvertureRecord, OvertureRecordType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_azure_location() {
+        assert_eq!(
+            parse_azure_location("az://overture").unwrap(),
+            ("overture", None)
+        );
+        assert_eq!(
+            parse_azure_location("az://overture/mirror/omf/").unwrap(),
+            ("overture", Some("mirror/omf"))
+        );
+        assert!(parse_azure_location("overture/mirror").is_err());
+        assert!(parse_azure_location("az:///mirror").is_err());
+    }
+
+    #[test]
+    fn test_filesystem_requires_release_tree() {
+        let source = ObjectStoreSource::FileSystem;
+        assert!(source.build(None).is_err());
+
+        let root = tempfile::tempdir().unwrap();
+        assert!(source.build(root.path().to_str()).is_err());
+
+        std::fs::create_dir_all(root.path().join("release")).unwrap();
+        assert!(source.build(root.path().to_str()).is_ok());
+    }
+
+    /// collects the three connectors of the release tree fixture in `src/test`, which
+    /// mirrors the layout of the OvertureMaps bucket.
+    #[test]
+    fn test_filesystem_collection() {
+        let location = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("test");
+        let collector = OvertureMapsCollectorConfig::new(ObjectStoreSource::FileSystem, None, None)
+            .with_location(location.to_str().unwrap())
+            .build()
+            .unwrap();
+
+        let release = collector.get_latest_release().unwrap();
+        assert_eq!(release, "2025-01-22.0");
+
+        let records = collector
+            .collect_from_release(&release, &OvertureRecordType::Connector, None)
+            .unwrap();
+        let mut ids = records
+            .iter()
+            .map(|r| match r {
+                OvertureRecord::Connector(c) => c.id.clone(),
+                other => panic!("expected a connector, found {other:?}"),
+            })
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(
+            ids,
+            vec!["08f26c6f2a4bffff", "08f26c6f2a4cffff", "08f26c6f2a4dffff"]
+        );
+
+        let vertex = match &records[0] {
+            OvertureRecord::Connector(c) => c.try_to_vertex(0).unwrap(),
+            _ => unreachable!(),
+        };
+        assert!((vertex.x() + 105.22).abs() < 0.01);
+        assert!((vertex.y() - 39.755).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_filesystem_collection_missing_release_is_empty() {
+        let location = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("test");
+        let collector = OvertureMapsCollectorConfig::new(ObjectStoreSource::FileSystem, None, None)
+            .with_location(location.to_str().unwrap())
+            .build()
+            .unwrap();
+        let records = collector
+            .collect_from_release("2024-01-01.0", &OvertureRecordType::Connector, None)
+            .unwrap();
+        assert!(records.is_empty());
+    }
+}
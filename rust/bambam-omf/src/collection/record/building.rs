@@ -41,6 +41,18 @@ impl BuildingsRecord {
     pub fn get_geometry(&self) -> Option<Geometry<f32>> {
         self.geometry.clone()
     }
+
+    pub fn get_height(&self) -> Option<f64> {
+        self.height
+    }
+
+    pub fn get_num_floors(&self) -> Option<i32> {
+        self.num_floors
+    }
+
+    pub fn get_is_underground(&self) -> bool {
+        self.is_underground.unwrap_or_default()
+    }
 }
 
 impl TryFrom<OvertureRecord> for BuildingsRecord {
@@ -1,5 +1,6 @@
 use opening_hours_syntax::rules::OpeningHoursExpression;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// the opening hours for an OvertureMaps record. because of the existence of
 /// invalid data, this is provided with a fallback Unexpected variant when
@@ -15,6 +16,36 @@ pub enum DuringExpression {
     Unexpected(String),
 }
 
+impl DuringExpression {
+    /// the parsed opening hours expression, or None if the source value was not valid
+    /// opening hours syntax.
+    pub fn as_opening_hours(&self) -> Option<&OpeningHoursExpression> {
+        match self {
+            DuringExpression::Osm(expression) => Some(expression),
+            DuringExpression::Unexpected(_) => None,
+        }
+    }
+
+    /// parses an OSM opening hours value, such as the `opening_hours` tag of a POI,
+    /// falling back to [`DuringExpression::Unexpected`] when the value is not valid
+    /// opening hours syntax.
+    pub fn parse(value: &str) -> DuringExpression {
+        match opening_hours_syntax::parse(value) {
+            Ok(expression) => DuringExpression::Osm(expression),
+            Err(_) => DuringExpression::Unexpected(value.to_string()),
+        }
+    }
+
+    /// reads the `opening_hours` tag from a set of OSM tags, if present.
+    pub fn from_osm_tags(tags: &HashMap<String, String>) -> Option<DuringExpression> {
+        tags.get(OSM_OPENING_HOURS_TAG)
+            .map(|value| DuringExpression::parse(value.trim()))
+    }
+}
+
+/// the OSM tag holding the opening hours of a POI
+pub const OSM_OPENING_HOURS_TAG: &str = "opening_hours";
+
 mod opening_hours_codec {
     use opening_hours_syntax::rules::OpeningHoursExpression;
     use serde::Deserialize;
@@ -50,4 +81,15 @@ mod tests {
         let result: DuringExpression = serde_json::from_str(json).unwrap();
         assert!(matches!(result, DuringExpression::Unexpected(s) if s == "sunset"));
     }
+
+    #[test]
+    fn test_parse_osm_poi_tag() {
+        let tags = HashMap::from([(
+            String::from("opening_hours"),
+            String::from("Mo-Sa 08:00-21:00"),
+        )]);
+        let result = DuringExpression::from_osm_tags(&tags);
+        assert!(matches!(result, Some(DuringExpression::Osm(_))));
+        assert!(DuringExpression::from_osm_tags(&HashMap::new()).is_none());
+    }
 }
@@ -9,15 +9,17 @@ mod transportation_collection;
 mod transportation_connector;
 
 pub use building::BuildingsRecord;
+pub use during_expression::DuringExpression;
 pub use overture_record::OvertureRecord;
 pub use place::PlacesRecord;
 pub use record_type::OvertureRecordType;
 pub use segment::{
     SegmentAccessRestriction, SegmentAccessRestrictionWhen, SegmentAccessRestrictionWhenVehicle,
     SegmentAccessType, SegmentClass, SegmentDestination, SegmentFullType, SegmentHeading,
-    SegmentLengthUnit, SegmentMode, SegmentRecognized, SegmentSpeedLimit, SegmentSpeedUnit,
-    SegmentSubclass, SegmentSubtype, SegmentUnit, SegmentUsing, SegmentVehicleComparator,
-    SegmentVehicleDimension, SpeedLimitWithUnit, TransportationSegmentRecord,
+    SegmentLengthUnit, SegmentMode, SegmentProhibitedTransitions, SegmentRecognized,
+    SegmentRoadFlags, SegmentRoadSurfaceType, SegmentSpeedLimit, SegmentSpeedUnit, SegmentSubclass,
+    SegmentSubtype, SegmentUnit, SegmentUsing, SegmentVehicleComparator, SegmentVehicleDimension,
+    SpeedLimitWithUnit, TransportationSegmentRecord,
 };
 pub use transportation_collection::TransportationCollection;
 pub use transportation_connector::TransportationConnectorRecord;
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt};
 
 use super::geometry_wkb_codec;
+use super::DuringExpression;
 use super::{OvertureMapsBbox, OvertureMapsNames, OvertureMapsSource, OvertureRecord};
 use crate::collection::OvertureMapsCollectionError;
 
@@ -25,6 +26,9 @@ pub struct PlacesRecord {
     phones: Option<Vec<String>>,
     brand: Option<OvertureMapsPlacesBrand>,
     addresses: Option<Vec<OvertureMapsPlacesAddresses>>,
+    /// not part of every release schema, so this may be missing from the source data
+    #[serde(default)]
+    opening_hours: Option<DuringExpression>,
 }
 
 impl TryFrom<OvertureRecord> for PlacesRecord {
@@ -66,6 +70,10 @@ impl PlacesRecord {
     pub fn get_geometry(&self) -> Option<Geometry<f32>> {
         self.geometry.clone()
     }
+
+    pub fn get_opening_hours(&self) -> Option<&DuringExpression> {
+        self.opening_hours.as_ref()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
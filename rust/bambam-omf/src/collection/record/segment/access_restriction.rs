@@ -27,4 +27,13 @@ impl SegmentAccessRestriction {
             .map(|m| m.contains(mode))
             .unwrap_or_default()
     }
+
+    /// true if this restriction is only in effect during a valid opening hours expression.
+    /// restrictions with an unparseable `during` value are treated as always in effect.
+    pub fn is_time_dependent(&self) -> bool {
+        self.when
+            .as_ref()
+            .and_then(|w| w.during.as_ref())
+            .is_some_and(|d| d.as_opening_hours().is_some())
+    }
 }
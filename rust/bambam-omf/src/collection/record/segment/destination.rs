@@ -82,22 +82,28 @@ pub struct SegmentDestinationWhen {
     pub mode: Option<Vec<SegmentMode>>,
 }
 
+/// A sequence of connectors and segments that travel is prohibited from following
+/// after leaving this segment, such as a turn restriction.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SegmentProhibitedTransitions {
+    /// Ordered connectors and segments that make up the prohibited transition.
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    sequence: Option<Vec<SegmentProhibitedTransitionsSequence>>,
+    pub sequence: Option<Vec<SegmentProhibitedTransitionsSequence>>,
+    /// Heading of travel along the final segment of the sequence.
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    final_heading: Option<SegmentHeading>,
+    pub final_heading: Option<SegmentHeading>,
+    /// Properties defining the travel headings, modes and times that match this rule.
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    when: Option<SegmentAccessRestrictionWhen>,
+    pub when: Option<SegmentAccessRestrictionWhen>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    between: Option<Vec<f64>>,
+    pub between: Option<Vec<f64>>,
 }
 
+/// One step of a prohibited transition, entering `segment` at `connector`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SegmentProhibitedTransitionsSequence {
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    connector: Option<String>,
+    pub connector: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    segment: Option<String>,
+    pub segment: Option<String>,
 }
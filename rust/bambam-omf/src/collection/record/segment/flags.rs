@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -12,6 +14,21 @@ pub enum SegmentRoadSurfaceType {
     Metal,
 }
 
+impl fmt::Display for SegmentRoadSurfaceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SegmentRoadSurfaceType::Unknown => "unknown",
+            SegmentRoadSurfaceType::Paved => "paved",
+            SegmentRoadSurfaceType::Unpaved => "unpaved",
+            SegmentRoadSurfaceType::Gravel => "gravel",
+            SegmentRoadSurfaceType::Dirt => "dirt",
+            SegmentRoadSurfaceType::PavingStones => "paving_stones",
+            SegmentRoadSurfaceType::Metal => "metal",
+        };
+        f.write_str(s)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum SegmentRoadFlags {
@@ -24,6 +41,21 @@ pub enum SegmentRoadFlags {
     IsIndoor,
 }
 
+impl fmt::Display for SegmentRoadFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SegmentRoadFlags::IsBridge => "is_bridge",
+            SegmentRoadFlags::IsLink => "is_link",
+            SegmentRoadFlags::IsTunnel => "is_tunnel",
+            SegmentRoadFlags::IsUnderConstruction => "is_under_construction",
+            SegmentRoadFlags::IsAbandoned => "is_abandoned",
+            SegmentRoadFlags::IsCovered => "is_covered",
+            SegmentRoadFlags::IsIndoor => "is_indoor",
+        };
+        f.write_str(s)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 #[allow(clippy::enum_variant_names)]
@@ -75,4 +75,20 @@ impl<T: Debug> SegmentValueBetween<T> {
 
         Ok(start < *high && end > *low)
     }
+
+    /// given a sub-segment linear reference (start, end), compute the total overlapping portion.
+    /// a value without a `between` range covers the whole segment.
+    pub fn get_linear_reference_portion(
+        &self,
+        start: f64,
+        end: f64,
+    ) -> Result<f64, OvertureMapsCollectionError> {
+        match self.between.as_ref() {
+            Some(b_vector) => {
+                let (low, high) = validate_between_vector(b_vector)?;
+                Ok((high.min(end) - low.max(start)).max(0.))
+            }
+            None => Ok(end - start),
+        }
+    }
 }
@@ -2,6 +2,7 @@ use crate::collection::{OvertureMapsCollectionError, OvertureRecord};
 
 use super::geometry_wkb_codec;
 use super::{OvertureMapsBbox, OvertureMapsSource};
+use bambam_modal_metrics::network_traits::vertex_for_modal_metric::VertexForModalMetric;
 use geo::Geometry;
 use routee_compass_core::model::network::Vertex;
 use serde::{Deserialize, Serialize};
@@ -60,3 +61,15 @@ impl TransportationConnectorRecord {
         Ok(Vertex::new(idx, x, y))
     }
 }
+
+/// Overture connectors do not record traffic controls, so connectors are treated as
+/// uncontrolled intersections.
+impl VertexForModalMetric for TransportationConnectorRecord {
+    fn has_traffic_signals(&self) -> bool {
+        false
+    }
+
+    fn has_stop_sign(&self) -> bool {
+        false
+    }
+}
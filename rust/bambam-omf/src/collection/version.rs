@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
@@ -39,3 +41,45 @@ impl std::fmt::Display for ReleaseVersion {
         write!(f, "{}", String::from(self))
     }
 }
+
+impl FromStr for ReleaseVersion {
+    type Err = String;
+
+    /// parses either "latest" or a monthly release identifier such as
+    /// "2025-01-22" or "2025-01-22.0".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("latest") {
+            return Ok(ReleaseVersion::Latest);
+        }
+        let (date_str, version_str) = match s.split_once('.') {
+            Some((date, version)) => (date, Some(version)),
+            None => (s, None),
+        };
+        let datetime = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+            format!("release '{s}' must be 'latest' or a date in the format YYYY-MM-DD[.N]: {e}")
+        })?;
+        let version = version_str
+            .map(|v| {
+                v.parse::<u8>()
+                    .map_err(|e| format!("release '{s}' has invalid version number '{v}': {e}"))
+            })
+            .transpose()?;
+        Ok(ReleaseVersion::Monthly { datetime, version })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReleaseVersion;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_release_version_round_trip() {
+        let release = ReleaseVersion::from_str("2025-01-22.0").unwrap();
+        assert_eq!(release.to_string(), "2025-01-22.0");
+        let release = ReleaseVersion::from_str("latest").unwrap();
+        assert!(matches!(release, ReleaseVersion::Latest));
+        assert!(ReleaseVersion::from_str("2025-01").is_err());
+    }
+}
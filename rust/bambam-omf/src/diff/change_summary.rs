@@ -0,0 +1,223 @@
+use std::{collections::HashSet, fmt};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{TransportationConnectorRecord, TransportationSegmentRecord},
+    diff::{segment_change::class_label, SegmentChange, SegmentChangeType},
+};
+
+pub const CLASS_CHANGE_SUMMARY_FILENAME: &str = "segment-changes-by-class.csv.gz";
+
+/// counts of segment changes for one road class.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ClassChangeSummary {
+    pub class: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub unchanged: usize,
+}
+
+/// counts of connectors that were added or removed between releases, matched by GERS id.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConnectorChanges {
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl ConnectorChanges {
+    pub fn new(
+        previous: &[TransportationConnectorRecord],
+        current: &[TransportationConnectorRecord],
+    ) -> Self {
+        let previous_ids = previous
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect::<HashSet<_>>();
+        let current_ids = current
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect::<HashSet<_>>();
+        Self {
+            added: current_ids.difference(&previous_ids).count(),
+            removed: previous_ids.difference(&current_ids).count(),
+        }
+    }
+}
+
+/// summarizes the changes between two releases, with segment changes counted by road class.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChangeSummary {
+    pub previous_release: String,
+    pub current_release: String,
+    pub classes: Vec<ClassChangeSummary>,
+    pub connectors: ConnectorChanges,
+}
+
+impl ChangeSummary {
+    /// counts the segment changes by road class. changes are counted under the class in
+    /// the current release, or in the previous release for removed segments. any segment
+    /// of the current release without a change is counted as unchanged.
+    pub fn new(
+        previous_release: &str,
+        current_release: &str,
+        changes: &[SegmentChange],
+        current_segments: &[TransportationSegmentRecord],
+        connectors: ConnectorChanges,
+    ) -> Self {
+        let mut classes: IndexMap<String, ClassChangeSummary> = IndexMap::new();
+        for segment in current_segments.iter() {
+            let class = class_label(segment);
+            classes
+                .entry(class.clone())
+                .or_insert_with(|| ClassChangeSummary {
+                    class,
+                    ..Default::default()
+                })
+                .unchanged += 1;
+        }
+        for change in changes.iter() {
+            let summary =
+                classes
+                    .entry(change.class.clone())
+                    .or_insert_with(|| ClassChangeSummary {
+                        class: change.class.clone(),
+                        ..Default::default()
+                    });
+            match change.change_type {
+                SegmentChangeType::Added => {
+                    summary.added += 1;
+                    summary.unchanged = summary.unchanged.saturating_sub(1);
+                }
+                SegmentChangeType::Modified => {
+                    summary.modified += 1;
+                    summary.unchanged = summary.unchanged.saturating_sub(1);
+                }
+                SegmentChangeType::Removed => summary.removed += 1,
+            }
+        }
+        classes.sort_keys();
+        Self {
+            previous_release: previous_release.to_string(),
+            current_release: current_release.to_string(),
+            classes: classes.into_values().collect(),
+            connectors,
+        }
+    }
+
+    /// the total number of changed segments.
+    pub fn segment_changes(&self) -> usize {
+        self.classes
+            .iter()
+            .map(|c| c.added + c.removed + c.modified)
+            .sum()
+    }
+}
+
+impl fmt::Display for ChangeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "changes from {} to {}",
+            self.previous_release, self.current_release
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:>10} {:>10} {:>10} {:>10}",
+            "class", "added", "removed", "modified", "unchanged"
+        )?;
+        for c in self.classes.iter() {
+            writeln!(
+                f,
+                "{:<16} {:>10} {:>10} {:>10} {:>10}",
+                c.class, c.added, c.removed, c.modified, c.unchanged
+            )?;
+        }
+        write!(
+            f,
+            "connectors added: {}, removed: {}",
+            self.connectors.added, self.connectors.removed
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChangeSummary, ConnectorChanges};
+    use crate::{
+        collection::{SegmentClass, TransportationSegmentRecord},
+        diff::detect_segment_changes,
+    };
+    use geo::{line_string, Geometry};
+
+    fn segment(id: &str, x: f32, class: SegmentClass) -> TransportationSegmentRecord {
+        TransportationSegmentRecord {
+            id: id.to_string(),
+            geometry: Some(Geometry::LineString(
+                line_string![(x: x, y: 0.0), (x: x + 0.001, y: 0.0)],
+            )),
+            class: Some(class),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_change_summary_by_class() {
+        let previous = vec![
+            segment("unchanged", 0.0, SegmentClass::Residential),
+            segment("reclassified", 1.0, SegmentClass::Residential),
+            segment("reissued-old", 2.0, SegmentClass::Primary),
+            segment("demolished", 3.0, SegmentClass::Service),
+            segment("quiet", 5.0, SegmentClass::Residential),
+        ];
+        let current = vec![
+            segment("unchanged", 0.0, SegmentClass::Residential),
+            segment("reclassified", 1.0, SegmentClass::Tertiary),
+            segment("reissued-new", 2.0, SegmentClass::Primary),
+            segment("built", 4.0, SegmentClass::Service),
+            segment("quiet", 5.0, SegmentClass::Residential),
+        ];
+        let changes = detect_segment_changes(&previous, &current);
+        let connectors = ConnectorChanges {
+            added: 2,
+            removed: 1,
+        };
+        let summary = ChangeSummary::new(
+            "2025-01-22.0",
+            "2025-02-19.0",
+            &changes,
+            &current,
+            connectors,
+        );
+
+        let rows = summary
+            .classes
+            .iter()
+            .map(|c| {
+                (
+                    c.class.as_str(),
+                    c.added,
+                    c.removed,
+                    c.modified,
+                    c.unchanged,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                ("primary", 0, 0, 1, 0),
+                ("residential", 0, 0, 0, 2),
+                ("service", 1, 1, 0, 0),
+                ("tertiary", 0, 0, 1, 0),
+            ]
+        );
+        assert_eq!(summary.segment_changes(), 4);
+
+        let display = summary.to_string();
+        assert!(display.starts_with("changes from 2025-01-22.0 to 2025-02-19.0"));
+        assert!(display.ends_with("connectors added: 2, removed: 1"));
+    }
+}
@@ -0,0 +1,14 @@
+//! # Diff
+//!
+//! change detection between two OvertureMaps releases of the transportation theme
+//! for the same region.
+mod change_summary;
+mod segment_change;
+
+pub use change_summary::{
+    ChangeSummary, ClassChangeSummary, ConnectorChanges, CLASS_CHANGE_SUMMARY_FILENAME,
+};
+pub use segment_change::{
+    detect_segment_changes, to_geojson, SegmentChange, SegmentChangeType,
+    SEGMENT_CHANGES_CSV_FILENAME, SEGMENT_CHANGES_GEOJSON_FILENAME,
+};
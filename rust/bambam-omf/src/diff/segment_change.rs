@@ -0,0 +1,559 @@
+use std::collections::{HashMap, HashSet};
+
+use geo::{Convert, Coord, Geometry, LineString};
+use geozero::ToJson;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::collection::{OvertureMapsCollectionError, TransportationSegmentRecord};
+
+pub const SEGMENT_CHANGES_CSV_FILENAME: &str = "segment-changes.csv.gz";
+pub const SEGMENT_CHANGES_GEOJSON_FILENAME: &str = "segment-changes.geojson";
+
+/// coordinates are compared after rounding to 1e-6 degrees (about 10 centimeters)
+const COORDINATE_PRECISION: f64 = 1e6;
+
+/// a segment with a new id matches a removed segment when both pairs of endpoints
+/// and the Hausdorff distance between their linestrings are within this distance.
+const GEOMETRY_MATCH_TOLERANCE_METERS: f64 = 5.0;
+
+/// size of the grid cells used to look up removed segments by their first coordinate.
+/// cells must be wider than the match tolerance at any latitude covered by a region.
+const MATCH_CELL_DEGREES: f64 = 1e-3;
+
+/// mean earth radius used to project coordinates to meters when matching geometries
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentChangeType {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// a segment that differs between two releases.
+#[derive(Serialize, Debug, Clone)]
+pub struct SegmentChange {
+    /// GERS id of the segment in the current release, or in the previous
+    /// release for removed segments
+    pub segment_id: String,
+    /// GERS id of the segment in the previous release, when it was matched
+    /// by geometry to a segment with a new id
+    pub previous_segment_id: Option<String>,
+    pub change_type: SegmentChangeType,
+    /// road class in the current release, or in the previous release for removed segments
+    pub class: String,
+    /// road class in the previous release, when the class changed
+    pub previous_class: Option<String>,
+    pub geometry_changed: bool,
+    pub class_changed: bool,
+    pub access_restrictions_changed: bool,
+    pub connectors_changed: bool,
+    /// geometry in the current release, or in the previous release for removed segments
+    #[serde(skip)]
+    pub geometry: Option<Geometry<f32>>,
+}
+
+impl SegmentChange {
+    fn added(segment: &TransportationSegmentRecord) -> Self {
+        Self::new(segment, SegmentChangeType::Added)
+    }
+
+    fn removed(segment: &TransportationSegmentRecord) -> Self {
+        Self::new(segment, SegmentChangeType::Removed)
+    }
+
+    fn new(segment: &TransportationSegmentRecord, change_type: SegmentChangeType) -> Self {
+        Self {
+            segment_id: segment.id.clone(),
+            previous_segment_id: None,
+            change_type,
+            class: class_label(segment),
+            previous_class: None,
+            geometry_changed: false,
+            class_changed: false,
+            access_restrictions_changed: false,
+            connectors_changed: false,
+            geometry: segment.geometry.clone(),
+        }
+    }
+
+    /// compares a segment across releases, returning None if it is unchanged.
+    fn modified(
+        previous: &TransportationSegmentRecord,
+        current: &TransportationSegmentRecord,
+    ) -> Option<Self> {
+        let geometry_changed = geometry_key(previous) != geometry_key(current);
+        let class_changed = previous.class != current.class;
+        let access_restrictions_changed =
+            json_differs(&previous.access_restrictions, &current.access_restrictions);
+        let connectors_changed = json_differs(&previous.connectors, &current.connectors);
+        let id_changed = previous.id != current.id;
+        if !(geometry_changed
+            || class_changed
+            || access_restrictions_changed
+            || connectors_changed
+            || id_changed)
+        {
+            return None;
+        }
+        Some(Self {
+            previous_segment_id: id_changed.then(|| previous.id.clone()),
+            previous_class: class_changed.then(|| class_label(previous)),
+            geometry_changed,
+            class_changed,
+            access_restrictions_changed,
+            connectors_changed,
+            ..Self::new(current, SegmentChangeType::Modified)
+        })
+    }
+}
+
+/// matches the segments of two releases and reports those that were added, removed
+/// or modified. segments are first matched by GERS id. segments whose id is not found
+/// in the other release are then matched by geometry, which catches segments that were
+/// re-issued under a new id. geometries match when their endpoints and linestrings are
+/// within [`GEOMETRY_MATCH_TOLERANCE_METERS`] of each other, so a re-issued segment
+/// is still matched after small coordinate edits or added vertices.
+///
+/// # Returns
+///
+/// the changed segments, with modified and added segments in the order of the current
+/// release followed by removed segments in the order of the previous release.
+pub fn detect_segment_changes(
+    previous: &[TransportationSegmentRecord],
+    current: &[TransportationSegmentRecord],
+) -> Vec<SegmentChange> {
+    let previous_by_id = previous
+        .iter()
+        .map(|s| (s.id.as_str(), s))
+        .collect::<HashMap<_, _>>();
+    let current_ids = current
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect::<HashSet<_>>();
+
+    // segments missing from the current release, indexed by geometry
+    let mut removed_without_geometry = vec![];
+    let mut removed_by_geometry = RemovedSegmentIndex::default();
+    for segment in previous.iter() {
+        if current_ids.contains(segment.id.as_str()) {
+            continue;
+        }
+        match segment.get_linestring() {
+            Ok(linestring) => removed_by_geometry.insert(segment, linestring),
+            Err(_) => removed_without_geometry.push(segment),
+        }
+    }
+
+    let mut changes = vec![];
+    for segment in current.iter() {
+        let matched = match previous_by_id.get(segment.id.as_str()) {
+            Some(previous) => Some(*previous),
+            None => segment
+                .get_linestring()
+                .ok()
+                .and_then(|linestring| removed_by_geometry.take_match(linestring)),
+        };
+        match matched {
+            Some(previous) => changes.extend(SegmentChange::modified(previous, segment)),
+            None => changes.push(SegmentChange::added(segment)),
+        }
+    }
+
+    let mut removed = removed_by_geometry
+        .into_segments()
+        .chain(removed_without_geometry)
+        .collect::<Vec<_>>();
+    let previous_order = previous
+        .iter()
+        .enumerate()
+        .map(|(idx, s)| (s.id.as_str(), idx))
+        .collect::<HashMap<_, _>>();
+    removed.sort_by_key(|s| previous_order.get(s.id.as_str()).copied());
+    changes.extend(removed.into_iter().map(SegmentChange::removed));
+    changes
+}
+
+/// builds a GeoJSON FeatureCollection of segment changes, with the change attributes
+/// as feature properties.
+pub fn to_geojson(
+    changes: &[SegmentChange],
+) -> Result<serde_json::Value, OvertureMapsCollectionError> {
+    let features = changes
+        .iter()
+        .map(|change| {
+            let geometry = match change.geometry.as_ref() {
+                Some(g) => {
+                    let g_f64: Geometry<f64> = g.convert();
+                    let json_str = g_f64.to_json().map_err(|e| {
+                        OvertureMapsCollectionError::SerializationError(format!(
+                            "failure writing geometry of segment {} as GeoJSON: {e}",
+                            change.segment_id
+                        ))
+                    })?;
+                    serde_json::from_str(&json_str).map_err(|e| {
+                        OvertureMapsCollectionError::SerializationError(format!(
+                            "failure reading GeoJSON geometry of segment {}: {e}",
+                            change.segment_id
+                        ))
+                    })?
+                }
+                None => serde_json::Value::Null,
+            };
+            let properties = serde_json::to_value(change).map_err(|e| {
+                OvertureMapsCollectionError::SerializationError(format!(
+                    "failure serializing change for segment {}: {e}",
+                    change.segment_id
+                ))
+            })?;
+            Ok(serde_json::json!({
+                "type": "Feature",
+                "geometry": geometry,
+                "properties": properties,
+            }))
+        })
+        .collect::<Result<Vec<_>, OvertureMapsCollectionError>>()?;
+    Ok(serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features,
+    }))
+}
+
+/// label for the road class of a segment, used to group changes.
+pub(crate) fn class_label(segment: &TransportationSegmentRecord) -> String {
+    segment
+        .class
+        .as_ref()
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| String::from("none"))
+}
+
+/// rounded coordinates of a segment linestring, used to detect geometry edits of a
+/// matched segment.
+fn geometry_key(segment: &TransportationSegmentRecord) -> Option<Vec<(i64, i64)>> {
+    let linestring = segment.get_linestring().ok()?;
+    let key = linestring
+        .coords()
+        .map(|c| {
+            (
+                (c.x as f64 * COORDINATE_PRECISION).round() as i64,
+                (c.y as f64 * COORDINATE_PRECISION).round() as i64,
+            )
+        })
+        .collect();
+    Some(key)
+}
+
+/// removed segments indexed by the grid cell of their first coordinate, used to find
+/// the previous segment that a segment with a new id replaced.
+#[derive(Default)]
+struct RemovedSegmentIndex<'a> {
+    cells: IndexMap<(i64, i64), Vec<&'a TransportationSegmentRecord>>,
+}
+
+impl<'a> RemovedSegmentIndex<'a> {
+    fn insert(&mut self, segment: &'a TransportationSegmentRecord, linestring: &LineString<f32>) {
+        if let Some(first) = linestring.0.first() {
+            self.cells
+                .entry(grid_cell(first))
+                .or_default()
+                .push(segment);
+        }
+    }
+
+    /// removes and returns the removed segment closest to a linestring, if any is
+    /// within the match tolerance. candidates are searched in the grid cells around
+    /// the first coordinate of the linestring.
+    fn take_match(
+        &mut self,
+        linestring: &LineString<f32>,
+    ) -> Option<&'a TransportationSegmentRecord> {
+        let (x, y) = grid_cell(linestring.0.first()?);
+        let mut best: Option<((i64, i64), usize, f64)> = None;
+        for cell in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy))) {
+            let Some(candidates) = self.cells.get(&cell) else {
+                continue;
+            };
+            for (idx, candidate) in candidates.iter().enumerate() {
+                let Ok(candidate_linestring) = candidate.get_linestring() else {
+                    continue;
+                };
+                let distance = match matching_distance(candidate_linestring, linestring) {
+                    Some(distance) => distance,
+                    None => continue,
+                };
+                if best.is_none_or(|(_, _, d)| distance < d) {
+                    best = Some((cell, idx, distance));
+                }
+            }
+        }
+        let (cell, idx, _) = best?;
+        self.cells.get_mut(&cell).map(|c| c.remove(idx))
+    }
+
+    fn into_segments(self) -> impl Iterator<Item = &'a TransportationSegmentRecord> {
+        self.cells.into_values().flatten()
+    }
+}
+
+fn grid_cell(coord: &Coord<f32>) -> (i64, i64) {
+    (
+        (coord.x as f64 / MATCH_CELL_DEGREES).floor() as i64,
+        (coord.y as f64 / MATCH_CELL_DEGREES).floor() as i64,
+    )
+}
+
+/// Hausdorff distance in meters between two linestrings, measured from the vertices of
+/// each linestring to the other linestring. returns None if either pair of endpoints
+/// or the Hausdorff distance exceeds the match tolerance.
+fn matching_distance(a: &LineString<f32>, b: &LineString<f32>) -> Option<f64> {
+    let origin = *a.0.first()?;
+    let a = to_local_meters(a, &origin);
+    let b = to_local_meters(b, &origin);
+    let endpoints_match = [(a.first()?, b.first()?), (a.last()?, b.last()?)]
+        .iter()
+        .all(|(p, q)| point_distance(p, q) <= GEOMETRY_MATCH_TOLERANCE_METERS);
+    if !endpoints_match {
+        return None;
+    }
+    let distance = directed_hausdorff(&a, &b).max(directed_hausdorff(&b, &a));
+    (distance <= GEOMETRY_MATCH_TOLERANCE_METERS).then_some(distance)
+}
+
+/// projects coordinates to meters east and north of an origin. the equirectangular
+/// approximation is accurate over the length of a road segment.
+fn to_local_meters(linestring: &LineString<f32>, origin: &Coord<f32>) -> Vec<(f64, f64)> {
+    let lat_scale = EARTH_RADIUS_METERS.to_radians();
+    let lon_scale = lat_scale * (origin.y as f64).to_radians().cos();
+    linestring
+        .coords()
+        .map(|c| {
+            (
+                (c.x - origin.x) as f64 * lon_scale,
+                (c.y - origin.y) as f64 * lat_scale,
+            )
+        })
+        .collect()
+}
+
+/// the largest distance from a vertex of `a` to the polyline `b`.
+fn directed_hausdorff(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    a.iter()
+        .map(|p| match b {
+            [q] => point_distance(p, q),
+            _ => b
+                .windows(2)
+                .map(|w| point_segment_distance(p, &w[0], &w[1]))
+                .fold(f64::INFINITY, f64::min),
+        })
+        .fold(0.0, f64::max)
+}
+
+fn point_distance(p: &(f64, f64), q: &(f64, f64)) -> f64 {
+    (p.0 - q.0).hypot(p.1 - q.1)
+}
+
+fn point_segment_distance(p: &(f64, f64), start: &(f64, f64), end: &(f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return point_distance(p, start);
+    }
+    let t = (((p.0 - start.0) * dx + (p.1 - start.1) * dy) / length_squared).clamp(0.0, 1.0);
+    point_distance(p, &(start.0 + t * dx, start.1 + t * dy))
+}
+
+/// compares two optional record attributes by their serialized form, as most
+/// record attribute types do not implement equality.
+fn json_differs<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect_segment_changes, matching_distance, to_geojson, SegmentChangeType};
+    use crate::collection::{SegmentClass, TransportationSegmentRecord};
+    use geo::{line_string, Geometry, LineString};
+
+    fn segment(id: &str, x: f32, class: SegmentClass) -> TransportationSegmentRecord {
+        segment_along(id, &[(x, 0.0), (x + 0.001, 0.0)], class)
+    }
+
+    fn segment_along(
+        id: &str,
+        coords: &[(f32, f32)],
+        class: SegmentClass,
+    ) -> TransportationSegmentRecord {
+        TransportationSegmentRecord {
+            id: id.to_string(),
+            geometry: Some(Geometry::LineString(LineString::from(coords.to_vec()))),
+            class: Some(class),
+            ..Default::default()
+        }
+    }
+
+    fn change_rows(
+        previous: &[TransportationSegmentRecord],
+        current: &[TransportationSegmentRecord],
+    ) -> Vec<(String, SegmentChangeType)> {
+        detect_segment_changes(previous, current)
+            .into_iter()
+            .map(|c| (c.segment_id, c.change_type))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_segment_changes() {
+        let previous = vec![
+            segment("unchanged", 0.0, SegmentClass::Residential),
+            segment("reclassified", 1.0, SegmentClass::Residential),
+            segment("reissued-old", 2.0, SegmentClass::Primary),
+            segment("demolished", 3.0, SegmentClass::Service),
+        ];
+        let current = vec![
+            segment("unchanged", 0.0, SegmentClass::Residential),
+            segment("reclassified", 1.0, SegmentClass::Tertiary),
+            segment("reissued-new", 2.0, SegmentClass::Primary),
+            segment("built", 4.0, SegmentClass::Service),
+        ];
+
+        let changes = detect_segment_changes(&previous, &current);
+        let rows = changes
+            .iter()
+            .map(|c| (c.segment_id.as_str(), c.change_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                ("reclassified", SegmentChangeType::Modified),
+                ("reissued-new", SegmentChangeType::Modified),
+                ("built", SegmentChangeType::Added),
+                ("demolished", SegmentChangeType::Removed),
+            ]
+        );
+        assert!(changes[0].class_changed && !changes[0].geometry_changed);
+        assert_eq!(changes[0].previous_class.as_deref(), Some("residential"));
+        assert_eq!(
+            changes[1].previous_segment_id.as_deref(),
+            Some("reissued-old")
+        );
+        assert!(!changes[1].geometry_changed);
+    }
+
+    #[test]
+    fn test_reissued_segment_matched_within_tolerance() {
+        // the re-issued segment is shifted about 1 meter north and gains a vertex
+        let previous = vec![segment("old", 2.0, SegmentClass::Primary)];
+        let current = vec![segment_along(
+            "new",
+            &[(2.0, 0.00001), (2.0005, 0.00002), (2.001, 0.00001)],
+            SegmentClass::Primary,
+        )];
+        let changes = detect_segment_changes(&previous, &current);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].segment_id, "new");
+        assert_eq!(changes[0].change_type, SegmentChangeType::Modified);
+        assert_eq!(changes[0].previous_segment_id.as_deref(), Some("old"));
+        assert!(changes[0].geometry_changed);
+        assert!(!changes[0].class_changed);
+    }
+
+    #[test]
+    fn test_reissued_segment_matches_closest_candidate() {
+        let previous = vec![
+            segment_along(
+                "far",
+                &[(2.0, 0.00003), (2.001, 0.00003)],
+                SegmentClass::Primary,
+            ),
+            segment_along(
+                "near",
+                &[(2.0, 0.00001), (2.001, 0.00001)],
+                SegmentClass::Primary,
+            ),
+        ];
+        let current = vec![segment("new", 2.0, SegmentClass::Primary)];
+        let changes = detect_segment_changes(&previous, &current);
+        assert_eq!(changes[0].previous_segment_id.as_deref(), Some("near"));
+        assert_eq!(changes[1].segment_id, "far");
+        assert_eq!(changes[1].change_type, SegmentChangeType::Removed);
+    }
+
+    #[test]
+    fn test_segments_beyond_tolerance_are_not_matched() {
+        let previous = vec![segment("old", 2.0, SegmentClass::Primary)];
+        let expected = vec![
+            (String::from("new"), SegmentChangeType::Added),
+            (String::from("old"), SegmentChangeType::Removed),
+        ];
+
+        // an endpoint moved about 11 meters
+        let moved_endpoint = vec![segment_along(
+            "new",
+            &[(2.0, 0.0), (2.0011, 0.0)],
+            SegmentClass::Primary,
+        )];
+        assert_eq!(change_rows(&previous, &moved_endpoint), expected);
+
+        // the same endpoints with a midpoint about 22 meters off the previous line
+        let bowed = vec![segment_along(
+            "new",
+            &[(2.0, 0.0), (2.0005, 0.0002), (2.001, 0.0)],
+            SegmentClass::Primary,
+        )];
+        assert_eq!(change_rows(&previous, &bowed), expected);
+    }
+
+    #[test]
+    fn test_matching_distance() {
+        let a: LineString<f32> = line_string![(x: -105.0, y: 40.0), (x: -104.999, y: 40.0)];
+        let b: LineString<f32> = line_string![
+            (x: -105.0, y: 40.00002),
+            (x: -104.9995, y: 40.00002),
+            (x: -104.999, y: 40.00002),
+        ];
+        let distance = matching_distance(&a, &b).expect("test invariant failed");
+        let expected = (40.00002f32 - 40.0f32) as f64 * super::EARTH_RADIUS_METERS.to_radians();
+        assert!(
+            (distance - expected).abs() < 1e-6,
+            "distance was {distance}"
+        );
+        assert_eq!(matching_distance(&b, &a).map(|d| d.round()), Some(2.0));
+
+        let reversed: LineString<f32> = a.0.iter().rev().cloned().collect();
+        assert_eq!(matching_distance(&a, &reversed), None);
+    }
+
+    #[test]
+    fn test_to_geojson() {
+        let previous = vec![segment("demolished", 3.0, SegmentClass::Service)];
+        let mut current = vec![segment("built", 4.0, SegmentClass::Residential)];
+        current.push(TransportationSegmentRecord {
+            id: String::from("no-geometry"),
+            ..Default::default()
+        });
+        let changes = detect_segment_changes(&previous, &current);
+        let geojson = to_geojson(&changes).expect("test invariant failed");
+
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"]
+            .as_array()
+            .expect("test invariant failed");
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[0]["geometry"]["type"], "LineString");
+        let end_x = features[0]["geometry"]["coordinates"][1][0]
+            .as_f64()
+            .expect("test invariant failed");
+        assert!((end_x - 4.001).abs() < 1e-6);
+        assert_eq!(features[0]["properties"]["segment_id"], "built");
+        assert_eq!(features[0]["properties"]["change_type"], "added");
+        assert_eq!(features[0]["properties"]["class"], "residential");
+        assert!(features[0]["properties"].get("geometry").is_none());
+        assert!(features[1]["geometry"].is_null());
+        assert_eq!(features[1]["properties"]["class"], "none");
+        assert_eq!(features[2]["properties"]["change_type"], "removed");
+        assert_eq!(features[2]["properties"]["class"], "service");
+    }
+}
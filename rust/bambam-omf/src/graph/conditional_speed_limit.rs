@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use routee_compass_core::model::network::EdgeId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{
+        record::SegmentMode, OvertureMapsCollectionError, SegmentSpeedLimit,
+        TransportationSegmentRecord,
+    },
+    graph::segment_split::SegmentSplit,
+};
+
+/// the speed limits of an edge under one set of conditions, such as for a vehicle type
+/// or during a time window. speeds are averaged over the portion of the edge each limit
+/// covers. empty condition columns mean the limit applies regardless of that condition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConditionalSpeedLimit {
+    pub edge_id: EdgeId,
+    /// travel modes the limit applies to, joined by [`ConditionalSpeedLimit::DELIMITER`]
+    pub mode: String,
+    /// vehicle conditions of the limit, as JSON
+    pub vehicle: String,
+    /// OSM opening hours expression during which the limit applies
+    pub during: String,
+    pub min_speed_mph: Option<f64>,
+    pub max_speed_mph: Option<f64>,
+    pub is_max_speed_variable: bool,
+}
+
+impl ConditionalSpeedLimit {
+    pub const DELIMITER: &str = ";";
+}
+
+/// the conditions that separate the speed limits of an edge
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ConditionKey {
+    mode: String,
+    vehicle: String,
+    during: String,
+}
+
+/// length-weighted sums of the speed limits sharing a [`ConditionKey`]
+#[derive(Default)]
+struct SpeedAccumulator {
+    min_speed: (f64, f64),
+    max_speed: (f64, f64),
+    is_max_speed_variable: bool,
+}
+
+impl SpeedAccumulator {
+    fn add(&mut self, limit: &SegmentSpeedLimit, portion: f64) {
+        if let Some(min_speed) = limit.min_speed.as_ref() {
+            let mph = min_speed
+                .to_uom_value()
+                .get::<uom::si::velocity::mile_per_hour>();
+            self.min_speed.0 += mph * portion;
+            self.min_speed.1 += portion;
+        }
+        if let Some(max_speed) = limit.max_speed.as_ref() {
+            let mph = max_speed
+                .to_uom_value()
+                .get::<uom::si::velocity::mile_per_hour>();
+            self.max_speed.0 += mph * portion;
+            self.max_speed.1 += portion;
+        }
+        self.is_max_speed_variable |= limit.is_max_speed_variable.unwrap_or_default();
+    }
+
+    fn weighted((sum, weight): (f64, f64)) -> Option<f64> {
+        (weight > 0.0).then(|| sum / weight)
+    }
+}
+
+/// resolves the speed limits of each split separately for each combination of mode,
+/// vehicle and time window conditions, where each split is the edge with the same index.
+/// limits with a heading that does not match the split, with `using` or `recognized`
+/// conditions, or with modes that do not match the edge list `modes` are skipped.
+pub fn find_conditional_speed_limits(
+    segments: &[&TransportationSegmentRecord],
+    segment_lookup: &HashMap<String, usize>,
+    splits: &[SegmentSplit],
+    modes: Option<&[SegmentMode]>,
+) -> Result<Vec<ConditionalSpeedLimit>, OvertureMapsCollectionError> {
+    let mut result = vec![];
+    for (idx, split) in splits.iter().enumerate() {
+        let segment = split.get_segment(segments, segment_lookup)?;
+        let Some(limits) = segment.speed_limits.as_ref() else {
+            continue;
+        };
+        let start = split.src.linear_reference.0;
+        let end = split.dst.linear_reference.0;
+
+        let mut groups: IndexMap<ConditionKey, SpeedAccumulator> = IndexMap::new();
+        for limit in limits.iter() {
+            let Some(key) = condition_key(limit, split, modes) else {
+                continue;
+            };
+            let portion = limit.get_linear_reference_portion(start, end)?;
+            if portion <= 0.0 {
+                continue;
+            }
+            groups.entry(key).or_default().add(limit, portion);
+        }
+
+        for (key, acc) in groups.into_iter() {
+            result.push(ConditionalSpeedLimit {
+                edge_id: EdgeId(idx),
+                mode: key.mode,
+                vehicle: key.vehicle,
+                during: key.during,
+                min_speed_mph: SpeedAccumulator::weighted(acc.min_speed),
+                max_speed_mph: SpeedAccumulator::weighted(acc.max_speed),
+                is_max_speed_variable: acc.is_max_speed_variable,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// builds the conditions of a speed limit, or None if the limit does not apply
+/// to this split and edge list.
+fn condition_key(
+    limit: &SegmentSpeedLimit,
+    split: &SegmentSplit,
+    modes: Option<&[SegmentMode]>,
+) -> Option<ConditionKey> {
+    let Some(when) = limit.when.as_ref() else {
+        return Some(ConditionKey {
+            mode: String::new(),
+            vehicle: String::new(),
+            during: String::new(),
+        });
+    };
+    if when.heading.as_ref().is_some_and(|h| *h != split.heading)
+        || when.using.is_some()
+        || when.recognized.is_some()
+    {
+        return None;
+    }
+    let mode = match (when.mode.as_ref(), modes) {
+        (None, _) => String::new(),
+        (Some(limit_modes), Some(modes)) if !limit_modes.iter().any(|m| modes.contains(m)) => {
+            return None
+        }
+        (Some(limit_modes), _) => limit_modes
+            .iter()
+            .filter_map(|m| serde_json::to_value(m).ok())
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect::<Vec<_>>()
+            .join(ConditionalSpeedLimit::DELIMITER),
+    };
+    let vehicle = when
+        .vehicle
+        .as_ref()
+        .and_then(|v| serde_json::to_string(v).ok())
+        .unwrap_or_default();
+    let during = when
+        .during
+        .as_ref()
+        .and_then(|d| serde_json::to_value(d).ok())
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    Some(ConditionKey {
+        mode,
+        vehicle,
+        during,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::find_conditional_speed_limits;
+    use crate::{
+        collection::{
+            record::{SegmentHeading, SegmentMode},
+            TransportationSegmentRecord,
+        },
+        graph::{ConnectorInSegment, SegmentSplit},
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_school_zone_and_truck_limits() {
+        let mut segment = TransportationSegmentRecord {
+            id: String::from("s1"),
+            ..Default::default()
+        };
+        segment.speed_limits = serde_json::from_value(serde_json::json!([
+            {"max_speed": {"value": 35, "unit": "mph"}},
+            {"max_speed": {"value": 20, "unit": "mph"}, "between": [0.0, 0.5],
+             "when": {"during": "Mo-Fr 07:00-09:00"}},
+            {"max_speed": {"value": 30, "unit": "mph"}, "when": {"mode": ["hgv"]}},
+            {"min_speed": {"value": 40, "unit": "mph"}, "when": {"heading": "backward"}}
+        ]))
+        .unwrap();
+        let segments = vec![&segment];
+        let lookup = HashMap::from([(String::from("s1"), 0)]);
+        let splits = vec![
+            SegmentSplit::new(
+                ConnectorInSegment::new(String::from("s1"), String::from("a"), 0.0),
+                ConnectorInSegment::new(String::from("s1"), String::from("b"), 0.25),
+                SegmentHeading::Forward,
+            ),
+            SegmentSplit::new(
+                ConnectorInSegment::new(String::from("s1"), String::from("b"), 0.25),
+                ConnectorInSegment::new(String::from("s1"), String::from("c"), 1.0),
+                SegmentHeading::Forward,
+            ),
+        ];
+
+        let modes = [SegmentMode::Car];
+        let result =
+            find_conditional_speed_limits(&segments, &lookup, &splits, Some(&modes)).unwrap();
+        let rows = result
+            .iter()
+            .map(|r| {
+                (
+                    r.edge_id.0,
+                    r.during.as_str(),
+                    r.max_speed_mph.map(f64::round),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "", Some(35.0)),
+                (0, "Mo-Fr 07:00-09:00", Some(20.0)),
+                (1, "", Some(35.0)),
+                (1, "Mo-Fr 07:00-09:00", Some(20.0)),
+            ]
+        );
+
+        let modes = [SegmentMode::Hgv];
+        let result =
+            find_conditional_speed_limits(&segments, &lookup, &splits, Some(&modes)).unwrap();
+        let hgv = result
+            .iter()
+            .filter(|r| r.mode == "hgv")
+            .map(|r| (r.edge_id.0, r.max_speed_mph.map(f64::round)))
+            .collect::<Vec<_>>();
+        assert_eq!(hgv, vec![(0, Some(30.0)), (1, Some(30.0))]);
+    }
+}
@@ -1,14 +1,24 @@
+mod conditional_speed_limit;
 mod connector_in_segment;
 pub mod island_detection;
+mod modal_attributes;
 mod omf_graph;
+mod omf_modal_edge;
 mod segment_split;
 mod serialize_ops;
 mod summary;
+mod time_restriction;
+mod turn_restriction;
 mod vertex_serializable;
 
 pub mod consts;
 pub mod segment_ops;
+pub use conditional_speed_limit::{find_conditional_speed_limits, ConditionalSpeedLimit};
 pub use connector_in_segment::ConnectorInSegment;
+pub use modal_attributes::ModalAttributes;
 pub use omf_graph::OmfGraphVectorized;
+pub use omf_modal_edge::OmfModalEdge;
 pub use segment_split::SegmentSplit;
 pub use summary::{ClassStats, EdgeListStats, OmfGraphSource, OmfGraphStats, OmfGraphSummary};
+pub use time_restriction::{find_time_restrictions, TimeRestriction};
+pub use turn_restriction::{find_turn_restrictions, TurnRestriction, TurnRestrictionRow};
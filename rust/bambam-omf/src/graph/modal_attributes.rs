@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// attributes of an edge used to compute modal metrics such as LTS or WCI which are
+/// not otherwise retained on an edge list.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModalAttributes {
+    /// speed limit for motor vehicles in miles per hour that applies at all times
+    /// along this edge, if any is posted.
+    pub speed_limit_mph: Option<f64>,
+    /// true if foot travel is permitted along the heading of this edge.
+    pub foot_access: bool,
+    /// true if bicycles are permitted along the heading of this edge.
+    pub bicycle_access: bool,
+    /// true if the segment is designated for bicycles, such as a marked bike lane.
+    pub bicycle_designated: bool,
+    /// number of headings (0, 1 or 2) along the segment open to motor vehicles.
+    pub motor_vehicle_headings: u8,
+}
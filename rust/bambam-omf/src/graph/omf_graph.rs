@@ -7,13 +7,18 @@ use super::serialize_ops as ops;
 use crate::{
     app::network::NetworkEdgeListConfiguration,
     collection::{
-        record::SegmentHeading, OvertureMapsCollectionError, SegmentAccessRestrictionWhen,
-        SegmentFullType, TransportationCollection, TransportationSegmentRecord,
+        record::{SegmentHeading, SegmentRoadFlags, SegmentRoadSurfaceType},
+        OvertureMapsCollectionError, SegmentAccessRestrictionWhen, SegmentFullType,
+        TransportationCollection, TransportationSegmentRecord,
     },
     graph::{
+        conditional_speed_limit::{self, ConditionalSpeedLimit},
         island_detection::IslandDetectionAlgorithm,
+        modal_attributes::ModalAttributes,
         segment_ops,
         serialize_ops::{clean_omf_edge_list, compute_vertex_remapping},
+        time_restriction::{self, TimeRestriction},
+        turn_restriction::{self, TurnRestriction, TurnRestrictionRow},
         vertex_serializable::VertexSerializable,
         OmfGraphSummary,
     },
@@ -34,6 +39,11 @@ pub const SPEED_MAPPING_FILENAME: &str = "edges-classes-speed-mapping.csv.gz";
 pub const OMF_SEGMENT_IDS_FILENAME: &str = "edges-omf-segment-ids.csv.gz";
 pub const OMF_CONNECTOR_IDS_FILENAME: &str = "vertices-omf-connector-ids.txt.gz";
 pub const BEARINGS_FILENAME: &str = "edges-bearings-enumerated.txt.gz";
+pub const SURFACES_FILENAME: &str = "edges-surfaces-enumerated.txt.gz";
+pub const ROAD_FLAGS_FILENAME: &str = "edges-road-flags.csv.gz";
+pub const CONDITIONAL_SPEED_LIMITS_FILENAME: &str = "edges-conditional-speed-limits.csv.gz";
+pub const TURN_RESTRICTIONS_FILENAME: &str = "edges-turn-restrictions.csv.gz";
+pub const TIME_RESTRICTIONS_FILENAME: &str = "edges-time-restrictions.csv.gz";
 pub const GLOBAL_AVG_SPEED_KEY: &str = "_global_";
 
 pub struct OmfGraphVectorized {
@@ -52,7 +62,13 @@ pub struct OmfEdgeList {
     pub speeds: Vec<f64>,
     pub speed_lookup: HashMap<String, f64>,
     pub bearings: Vec<f64>,
+    pub surfaces: Vec<SegmentRoadSurfaceType>,
+    pub road_flags: Vec<Vec<SegmentRoadFlags>>,
+    pub modal_attributes: Vec<ModalAttributes>,
+    pub conditional_speed_limits: Vec<ConditionalSpeedLimit>,
     pub omf_segment_ids: Vec<(String, f64)>,
+    pub turn_restrictions: Vec<TurnRestriction>,
+    pub time_restrictions: Vec<TimeRestriction>,
 }
 
 impl OmfGraphVectorized {
@@ -120,8 +136,24 @@ impl OmfGraphVectorized {
             log::info!("Creating classes");
             let classes = ops::create_segment_full_types(&segments, &segment_lookup, &splits)?;
 
+            log::info!("Creating surfaces");
+            let surfaces = ops::create_surfaces(&segments, &segment_lookup, &splits)?;
+            log::info!("Creating road flags");
+            let road_flags = ops::create_road_flags(&segments, &segment_lookup, &splits)?;
+            log::info!("Creating modal attributes");
+            let modal_attributes =
+                ops::create_modal_attributes(&segments, &segment_lookup, &splits)?;
+
             log::info!("Creating speeds");
-            let speeds = ops::create_speeds(&segments, &segment_lookup, &splits)?;
+            let when = SegmentAccessRestrictionWhen::from(edge_list_config);
+            let speeds = ops::create_speeds(&segments, &segment_lookup, &splits, &when)?;
+            log::info!("Creating conditional speed limits");
+            let conditional_speed_limits = conditional_speed_limit::find_conditional_speed_limits(
+                &segments,
+                &segment_lookup,
+                &splits,
+                when.mode.as_deref(),
+            )?;
             log::info!("Creating speed lookup");
             let speed_lookup = ops::create_speed_by_segment_type_lookup(
                 &speeds,
@@ -136,6 +168,20 @@ impl OmfGraphVectorized {
             let global_speed =
                 ops::get_global_average_speed(&speeds, &segments, &segment_lookup, &splits)?;
 
+            // prohibited transitions that apply to the modes of this edge list
+            log::info!("Creating turn restrictions");
+            let turn_restrictions =
+                turn_restriction::find_turn_restrictions(&segments, &splits, when.mode.as_deref());
+
+            // access restrictions that are only in effect during some time window
+            log::info!("Creating time restrictions");
+            let time_restrictions = time_restriction::find_time_restrictions(
+                &segments,
+                &segment_lookup,
+                &splits,
+                &when,
+            )?;
+
             // omf ids
             log::info!("Computing omf_ids");
             let omf_segment_ids = ops::get_segment_omf_ids(&segments, &segment_lookup, &splits)?;
@@ -171,7 +217,13 @@ impl OmfGraphVectorized {
                 speeds,
                 speed_lookup,
                 bearings,
+                surfaces,
+                road_flags,
+                modal_attributes,
+                conditional_speed_limits,
                 omf_segment_ids,
+                turn_restrictions,
+                time_restrictions,
             };
             edge_lists.push(edge_list);
         }
@@ -370,6 +422,59 @@ impl OmfGraphVectorized {
                 "write bearings",
             )?;
 
+            // Write surfaces
+            serialize_into_enumerated_txt(
+                edge_list.surfaces.iter().map(|s| s.to_string()),
+                SURFACES_FILENAME,
+                &mode_dir,
+                overwrite,
+                "write surfaces",
+            )?;
+
+            // Write road flags of flagged edges
+            serialize_into_csv(
+                edge_list
+                    .road_flags
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, flags)| !flags.is_empty())
+                    .map(|(edge_id, flags)| (edge_id, flags.iter().join(";"))),
+                ROAD_FLAGS_FILENAME,
+                &mode_dir,
+                overwrite,
+                "write road flags",
+            )?;
+
+            // Write conditional speed limits
+            serialize_into_csv(
+                &edge_list.conditional_speed_limits,
+                CONDITIONAL_SPEED_LIMITS_FILENAME,
+                &mode_dir,
+                overwrite,
+                "write conditional speed limits",
+            )?;
+
+            // Write turn restrictions
+            serialize_into_csv(
+                edge_list
+                    .turn_restrictions
+                    .iter()
+                    .map(TurnRestrictionRow::from),
+                TURN_RESTRICTIONS_FILENAME,
+                &mode_dir,
+                overwrite,
+                "write turn restrictions",
+            )?;
+
+            // Write time restrictions
+            serialize_into_csv(
+                &edge_list.time_restrictions,
+                TIME_RESTRICTIONS_FILENAME,
+                &mode_dir,
+                overwrite,
+                "write time restrictions",
+            )?;
+
             // Write OMF ids
             if export_omf_ids {
                 serialize_into_csv(
@@ -0,0 +1,328 @@
+use bambam_modal_metrics::{
+    common::cycleway_tag::CyclewayTag,
+    network_traits::{edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge},
+};
+use geo::LineString;
+
+use crate::{
+    collection::{
+        record::{SegmentClass, SegmentRoadFlags, SegmentSubclass},
+        OvertureMapsCollectionError, SegmentFullType,
+    },
+    graph::{modal_attributes::ModalAttributes, omf_graph::OmfEdgeList},
+};
+
+/// an edge of an OMF edge list with the attributes needed to compute modal metrics,
+/// derived from the class, subclass, speed limits, road flags and access restrictions
+/// of the segment it was split from.
+#[derive(Debug, Clone)]
+pub struct OmfModalEdge {
+    /// GERS id of the segment this edge was split from
+    pub segment_id: String,
+    pub src_vertex_id: usize,
+    pub dst_vertex_id: usize,
+    pub class: SegmentFullType,
+    pub road_flags: Vec<SegmentRoadFlags>,
+    pub attributes: ModalAttributes,
+    pub linestring: LineString<f32>,
+}
+
+impl OmfModalEdge {
+    /// creates a modal edge for each edge in an edge list, paired with its edge id.
+    pub fn from_edge_list(
+        edge_list: &OmfEdgeList,
+    ) -> Result<Vec<(usize, OmfModalEdge)>, OvertureMapsCollectionError> {
+        let n_edges = edge_list.edges.0.len();
+        let lengths = [
+            edge_list.geometries.len(),
+            edge_list.classes.len(),
+            edge_list.road_flags.len(),
+            edge_list.modal_attributes.len(),
+            edge_list.omf_segment_ids.len(),
+        ];
+        if lengths.iter().any(|n| *n != n_edges) {
+            return Err(OvertureMapsCollectionError::InternalError(format!(
+                "edge list {} has {n_edges} edges but attribute lengths {lengths:?}",
+                edge_list.edge_list_id
+            )));
+        }
+        let result = edge_list
+            .edges
+            .0
+            .iter()
+            .enumerate()
+            .map(|(idx, edge)| {
+                let modal_edge = OmfModalEdge {
+                    segment_id: edge_list.omf_segment_ids[idx].0.clone(),
+                    src_vertex_id: edge.src_vertex_id.0,
+                    dst_vertex_id: edge.dst_vertex_id.0,
+                    class: edge_list.classes[idx].clone(),
+                    road_flags: edge_list.road_flags[idx].clone(),
+                    attributes: edge_list.modal_attributes[idx].clone(),
+                    linestring: edge_list.geometries[idx].clone(),
+                };
+                (edge.edge_id.0, modal_edge)
+            })
+            .collect();
+        Ok(result)
+    }
+
+    fn segment_class(&self) -> &SegmentClass {
+        &self.class.1
+    }
+
+    fn segment_subclass(&self) -> Option<&SegmentSubclass> {
+        self.class.2.as_ref()
+    }
+
+    /// true if the segment is flagged as closed to all travel.
+    fn is_closed(&self) -> bool {
+        self.road_flags.iter().any(|f| {
+            matches!(
+                f,
+                SegmentRoadFlags::IsUnderConstruction | SegmentRoadFlags::IsAbandoned
+            )
+        })
+    }
+}
+
+/// OMF segment modal metric implementation. Overture does not publish lane counts or
+/// sidewalks attached to roads, so these are left for the compute layer to infer.
+impl EdgeForModalMetric for OmfModalEdge {
+    fn get_traffic_speed_limit(&self) -> Option<i32> {
+        self.attributes
+            .speed_limit_mph
+            .map(|mph| mph.round() as i32)
+    }
+
+    /// cycleways are separated from traffic, while designated bicycle access on a road
+    /// is treated as a marked lane.
+    fn get_cycleway_tag(&self) -> Option<CyclewayTag> {
+        if self.segment_class() == &SegmentClass::Cycleway {
+            Some(CyclewayTag::DedicatedWithBuffer)
+        } else if self.segment_subclass() == Some(&SegmentSubclass::CycleCrossing) {
+            Some(CyclewayTag::NoDedicatedWithFacilities)
+        } else if self.attributes.bicycle_designated {
+            Some(CyclewayTag::DedicatedNoBuffer)
+        } else {
+            None
+        }
+    }
+
+    fn get_lanes(&self) -> Option<u32> {
+        None
+    }
+
+    fn is_walkable(&self) -> bool {
+        let permitted = self.attributes.foot_access && !self.is_closed();
+        permitted && (self.is_sidewalk() || self.is_footway() || self.is_walkable_highway())
+    }
+
+    fn is_walkable_highway(&self) -> bool {
+        matches!(
+            self.segment_class(),
+            SegmentClass::Residential
+                | SegmentClass::Unclassified
+                | SegmentClass::LivingStreet
+                | SegmentClass::Service
+                | SegmentClass::Pedestrian
+                | SegmentClass::Track
+                | SegmentClass::Footway
+                | SegmentClass::Bridleway
+                | SegmentClass::Steps
+                | SegmentClass::Path
+        )
+    }
+
+    fn is_sidewalk(&self) -> bool {
+        self.segment_subclass() == Some(&SegmentSubclass::Sidewalk)
+    }
+
+    fn is_footway(&self) -> bool {
+        matches!(
+            self.segment_subclass(),
+            Some(SegmentSubclass::Sidewalk) | Some(SegmentSubclass::Crosswalk)
+        )
+    }
+
+    fn get_sidewalk_sides(&self) -> Option<u8> {
+        None
+    }
+
+    fn is_unbikeable(&self) -> bool {
+        let motorway = matches!(
+            self.segment_class(),
+            SegmentClass::Motorway | SegmentClass::Trunk
+        );
+        motorway || !self.attributes.bicycle_access || self.is_closed()
+    }
+
+    fn is_non_motorized(&self) -> bool {
+        let non_motorized_class = matches!(
+            self.segment_class(),
+            SegmentClass::Cycleway
+                | SegmentClass::Path
+                | SegmentClass::Footway
+                | SegmentClass::Pedestrian
+                | SegmentClass::LivingStreet
+                | SegmentClass::Steps
+                | SegmentClass::Bridleway
+        );
+        non_motorized_class || self.attributes.motor_vehicle_headings == 0
+    }
+
+    fn is_oneway(&self) -> bool {
+        self.attributes.motor_vehicle_headings == 1
+    }
+}
+
+impl SpatialEdge for OmfModalEdge {
+    /// the segment id, so that both headings of a segment and its other splits are
+    /// excluded from the neighbors of an edge, as with the way id of an OSM edge.
+    fn id(&self) -> String {
+        self.segment_id.clone()
+    }
+
+    fn linestring(&self) -> Option<&LineString<f32>> {
+        Some(&self.linestring)
+    }
+
+    fn src_vertex_id(&self) -> usize {
+        self.src_vertex_id
+    }
+
+    fn dst_vertex_id(&self) -> usize {
+        self.dst_vertex_id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OmfModalEdge;
+    use crate::{
+        collection::{
+            record::{SegmentClass, SegmentRoadFlags, SegmentSubclass, SegmentSubtype},
+            SegmentFullType,
+        },
+        graph::modal_attributes::ModalAttributes,
+    };
+    use bambam_modal_metrics::{
+        common::cycleway_tag::CyclewayTag,
+        network_traits::{edge_for_modal_metric::EdgeForModalMetric, spatial_edge::SpatialEdge},
+    };
+    use geo::line_string;
+
+    fn edge(class: SegmentClass, subclass: Option<SegmentSubclass>) -> OmfModalEdge {
+        OmfModalEdge {
+            segment_id: String::from("s0"),
+            src_vertex_id: 0,
+            dst_vertex_id: 1,
+            class: SegmentFullType(SegmentSubtype::Road, class, subclass),
+            road_flags: vec![],
+            attributes: ModalAttributes {
+                speed_limit_mph: Some(24.6),
+                foot_access: true,
+                bicycle_access: true,
+                bicycle_designated: false,
+                motor_vehicle_headings: 2,
+            },
+            linestring: line_string![(x: -105.0, y: 40.0), (x: -105.001, y: 40.0)],
+        }
+    }
+
+    #[test]
+    fn test_segment_attributes_mapped_to_modal_metric_fields() {
+        let sidewalk = edge(SegmentClass::Footway, Some(SegmentSubclass::Sidewalk));
+        assert!(sidewalk.is_sidewalk() && sidewalk.is_footway() && sidewalk.is_walkable());
+        assert!(sidewalk.is_non_motorized());
+
+        let mut primary = edge(SegmentClass::Primary, None);
+        assert_eq!(primary.get_traffic_speed_limit(), Some(25));
+        assert!(!primary.is_walkable() && !primary.is_oneway());
+        assert!(primary.get_cycleway_tag().is_none());
+        primary.attributes.bicycle_designated = true;
+        primary.attributes.motor_vehicle_headings = 1;
+        assert!(primary.get_cycleway_tag().is_some() && primary.is_oneway());
+
+        let mut residential = edge(SegmentClass::Residential, None);
+        assert!(residential.is_walkable() && !residential.is_unbikeable());
+        residential.attributes.foot_access = false;
+        residential.attributes.bicycle_access = false;
+        assert!(!residential.is_walkable() && residential.is_unbikeable());
+    }
+
+    #[test]
+    fn test_sidewalk_and_crosswalk_subclasses() {
+        let sidewalk = edge(SegmentClass::Footway, Some(SegmentSubclass::Sidewalk));
+        assert!(sidewalk.is_sidewalk() && sidewalk.is_footway());
+
+        let crosswalk = edge(SegmentClass::Footway, Some(SegmentSubclass::Crosswalk));
+        assert!(!crosswalk.is_sidewalk() && crosswalk.is_footway());
+        assert!(crosswalk.is_walkable());
+
+        // a sidewalk mapped along a road class is walkable through its subclass
+        let road_sidewalk = edge(SegmentClass::Primary, Some(SegmentSubclass::Sidewalk));
+        assert!(road_sidewalk.is_walkable() && !road_sidewalk.is_walkable_highway());
+
+        let footway = edge(SegmentClass::Footway, None);
+        assert!(!footway.is_sidewalk() && !footway.is_footway());
+        assert!(footway.is_walkable_highway() && footway.is_walkable());
+        assert_eq!(footway.get_sidewalk_sides(), None);
+    }
+
+    #[test]
+    fn test_cycleway_tags() {
+        let cycleway = edge(SegmentClass::Cycleway, None);
+        assert!(matches!(
+            cycleway.get_cycleway_tag(),
+            Some(CyclewayTag::DedicatedWithBuffer)
+        ));
+        let crossing = edge(SegmentClass::Footway, Some(SegmentSubclass::CycleCrossing));
+        assert!(matches!(
+            crossing.get_cycleway_tag(),
+            Some(CyclewayTag::NoDedicatedWithFacilities)
+        ));
+        let mut bike_lane = edge(SegmentClass::Secondary, None);
+        bike_lane.attributes.bicycle_designated = true;
+        assert!(matches!(
+            bike_lane.get_cycleway_tag(),
+            Some(CyclewayTag::DedicatedNoBuffer)
+        ));
+    }
+
+    #[test]
+    fn test_road_flags_and_motor_vehicle_access() {
+        for flag in [
+            SegmentRoadFlags::IsUnderConstruction,
+            SegmentRoadFlags::IsAbandoned,
+        ] {
+            let mut closed = edge(SegmentClass::Residential, None);
+            closed.road_flags = vec![SegmentRoadFlags::IsBridge, flag];
+            assert!(!closed.is_walkable() && closed.is_unbikeable());
+        }
+        let mut bridge = edge(SegmentClass::Residential, None);
+        bridge.road_flags = vec![SegmentRoadFlags::IsBridge, SegmentRoadFlags::IsLink];
+        assert!(bridge.is_walkable() && !bridge.is_unbikeable());
+
+        assert!(edge(SegmentClass::Motorway, None).is_unbikeable());
+        assert!(edge(SegmentClass::Trunk, None).is_unbikeable());
+
+        let mut closed_to_cars = edge(SegmentClass::Residential, None);
+        assert!(!closed_to_cars.is_non_motorized());
+        closed_to_cars.attributes.motor_vehicle_headings = 0;
+        assert!(closed_to_cars.is_non_motorized() && !closed_to_cars.is_oneway());
+
+        let mut unposted = edge(SegmentClass::Residential, None);
+        unposted.attributes.speed_limit_mph = None;
+        assert_eq!(unposted.get_traffic_speed_limit(), None);
+        assert_eq!(unposted.get_lanes(), None);
+    }
+
+    #[test]
+    fn test_spatial_edge_uses_segment_id() {
+        let e = edge(SegmentClass::Residential, None);
+        assert_eq!(e.id(), "s0");
+        assert_eq!((e.src_vertex_id(), e.dst_vertex_id()), (0, 1));
+        assert_eq!(e.linestring(), Some(&e.linestring));
+    }
+}
@@ -101,7 +101,8 @@ pub fn get_headings(
 /// - Multiple restrictions can combine (e.g., "Denied all" + "Allowed specific" = "allowed only for specific")
 /// - A restriction applies if its heading and when conditions match the query
 /// - The final decision is: allowed if any Allowed restriction applies AND no Denied restriction applies
-fn is_heading_valid(
+/// - Time-dependent denials are skipped, as they only close the heading some of the time
+pub(crate) fn is_heading_valid(
     heading: SegmentHeading,
     when: Option<&SegmentAccessRestrictionWhen>,
     access_restrictions: Option<&Vec<SegmentAccessRestriction>>,
@@ -117,6 +118,7 @@ fn is_heading_valid(
     // Partition applicable restrictions by heading-specificity
     let (heading_specific, general): (Vec<_>, Vec<_>) = restrictions
         .iter()
+        .filter(|r| !(r.access_type == SAT::Denied && r.is_time_dependent()))
         .filter(|r| restriction_applies_to(r, &heading, when))
         .partition(|r| r.when.as_ref().and_then(|w| w.heading.as_ref()).is_some());
 
@@ -147,7 +149,7 @@ fn is_heading_valid(
 /// 2. The when conditions match:
 ///    - If querying with when=None: restriction must have empty/minimal conditions (applies broadly)
 ///    - If querying with when=Some: the query conditions must be compatible with restriction
-fn restriction_applies_to(
+pub(crate) fn restriction_applies_to(
     restriction: &SegmentAccessRestriction,
     heading: &SegmentHeading,
     when: Option<&SegmentAccessRestrictionWhen>,
@@ -191,7 +193,7 @@ fn restriction_applies_to(
 /// * `segment_restrictions` - Restriction conditions (e.g., "Car and Bicycle modes" or None for all modes)
 ///
 /// Note: Heading compatibility is handled by `restriction_applies_to`, not here.
-fn when_is_compatible(
+pub(crate) fn when_is_compatible(
     when: &SegmentAccessRestrictionWhen,
     segment_restrictions: Option<&SegmentAccessRestrictionWhen>,
 ) -> bool {
@@ -6,10 +6,17 @@ use routee_compass_core::model::network::{Edge, EdgeId, EdgeListId, Vertex, Vert
 
 use crate::{
     collection::{
-        record::SegmentHeading, OvertureMapsCollectionError, SegmentFullType, SegmentSpeedLimit,
-        TransportationSegmentRecord,
+        record::{
+            SegmentAccessType, SegmentHeading, SegmentMode, SegmentRoadFlags,
+            SegmentRoadSurfaceType,
+        },
+        OvertureMapsCollectionError, SegmentAccessRestrictionWhen, SegmentFullType,
+        SegmentSpeedLimit, TransportationSegmentRecord,
+    },
+    graph::{
+        connector_in_segment::ConnectorInSegment, consts, modal_attributes::ModalAttributes,
+        segment_ops,
     },
-    graph::{connector_in_segment::ConnectorInSegment, consts},
 };
 
 /// splits at the connectors (vertices) ignoring linear-referenced split points
@@ -27,6 +34,15 @@ impl SegmentSplit {
         Self { src, dst, heading }
     }
 
+    /// the connectors where travel along this split begins and ends, which are
+    /// reversed from the segment order when the heading is backward.
+    pub fn travel_connectors(&self) -> (&ConnectorInSegment, &ConnectorInSegment) {
+        match self.heading {
+            SegmentHeading::Forward => (&self.src, &self.dst),
+            SegmentHeading::Backward => (&self.dst, &self.src),
+        }
+    }
+
     /// identifies any locations where additional coordinates are needed.
     /// when creating any missing connectors, call [ConnectorInSegment::new_without_connector_id]
     /// which generates a new connector_id based on the segment_id and linear referencing position.
@@ -171,11 +187,14 @@ impl SegmentSplit {
 
     /// returns the average `max_speed` of this split according to the speed limits
     /// that match linear reference. Each element in the matching set is averaged
-    /// based on relative length.
+    /// based on relative length. only limits that apply at all times to the edge list
+    /// conditions in `when` are used; vehicle-specific and time-dependent limits are
+    /// resolved separately, see [`crate::graph::find_conditional_speed_limits`].
     pub fn get_split_speed(
         &self,
         segments: &[&TransportationSegmentRecord],
         segment_lookup: &HashMap<String, usize>,
+        when: &SegmentAccessRestrictionWhen,
     ) -> Result<Option<f64>, OvertureMapsCollectionError> {
         use OvertureMapsCollectionError as E;
 
@@ -191,6 +210,7 @@ impl SegmentSplit {
         let speed_limits_with_heading = speed_limits
             .iter()
             .filter(|s| has_max_speed_for_heading(s, heading))
+            .filter(|s| is_unconditional_for(s, when))
             .collect_vec();
 
         // Compute the intersecting portion of each limit
@@ -269,6 +289,95 @@ impl SegmentSplit {
         }
     }
 
+    /// returns the road surface covering the largest portion of this split, if any.
+    pub fn get_split_surface(
+        &self,
+        segments: &[&TransportationSegmentRecord],
+        segment_lookup: &HashMap<String, usize>,
+    ) -> Result<Option<SegmentRoadSurfaceType>, OvertureMapsCollectionError> {
+        let segment = self.get_segment(segments, segment_lookup)?;
+        let start = self.src.linear_reference.0;
+        let end = self.dst.linear_reference.0;
+
+        let rules = match segment.road_surface.as_ref() {
+            Some(rules) => rules,
+            None => return Ok(None),
+        };
+        let mut longest: Option<(&SegmentRoadSurfaceType, f64)> = None;
+        for rule in rules.iter() {
+            let Some(surface) = rule.value.as_ref() else {
+                continue;
+            };
+            let portion = rule.get_linear_reference_portion(start, end)?;
+            if portion > 0.0 && longest.is_none_or(|(_, p)| portion > p) {
+                longest = Some((surface, portion));
+            }
+        }
+        Ok(longest.map(|(surface, _)| surface.clone()))
+    }
+
+    /// returns all road flags set on any portion of this split.
+    pub fn get_split_flags(
+        &self,
+        segments: &[&TransportationSegmentRecord],
+        segment_lookup: &HashMap<String, usize>,
+    ) -> Result<Vec<SegmentRoadFlags>, OvertureMapsCollectionError> {
+        let segment = self.get_segment(segments, segment_lookup)?;
+        let start = self.src.linear_reference.0;
+        let end = self.dst.linear_reference.0;
+
+        let mut flags = vec![];
+        for rule in segment.road_flags.iter().flatten() {
+            if rule.get_linear_reference_portion(start, end)? <= 0.0 {
+                continue;
+            }
+            for flag in rule.value.iter().flatten() {
+                if !flags.contains(flag) {
+                    flags.push(flag.clone());
+                }
+            }
+        }
+        Ok(flags)
+    }
+
+    /// returns the attributes of this split used to compute modal metrics. access is
+    /// tested for each mode along with its parent modes, so that a restriction on
+    /// `vehicle` also applies to bicycles. the speed limit is the one posted for
+    /// motor vehicles, regardless of the travel mode of the edge list.
+    pub fn get_split_modal_attributes(
+        &self,
+        segments: &[&TransportationSegmentRecord],
+        segment_lookup: &HashMap<String, usize>,
+    ) -> Result<ModalAttributes, OvertureMapsCollectionError> {
+        let segment = self.get_segment(segments, segment_lookup)?;
+        let restrictions = segment.access_restrictions.as_ref();
+
+        let foot = mode_restriction_when(SegmentMode::Foot);
+        let bicycle = mode_restriction_when(SegmentMode::Bicycle);
+        let car = mode_restriction_when(SegmentMode::Car);
+
+        let speed_limit_mph = self.get_split_speed(segments, segment_lookup, &car)?;
+        let foot_access =
+            segment_ops::is_heading_valid(self.heading.clone(), Some(&foot), restrictions);
+        let bicycle_access =
+            segment_ops::is_heading_valid(self.heading.clone(), Some(&bicycle), restrictions);
+        let bicycle_designated = restrictions.into_iter().flatten().any(|r| {
+            r.access_type == SegmentAccessType::Designated && r.contains_mode(&SegmentMode::Bicycle)
+        });
+        let motor_vehicle_headings = [SegmentHeading::Forward, SegmentHeading::Backward]
+            .into_iter()
+            .filter(|h| segment_ops::is_heading_valid(h.clone(), Some(&car), restrictions))
+            .count() as u8;
+
+        Ok(ModalAttributes {
+            speed_limit_mph,
+            foot_access,
+            bicycle_access,
+            bicycle_designated,
+            motor_vehicle_headings,
+        })
+    }
+
     /// get Haversine distance along the LineString of the segment between start and end of the split
     pub fn get_split_length_meters(
         &self,
@@ -293,7 +402,7 @@ impl SegmentSplit {
     }
 
     /// get a reference to the segment that contains this split
-    fn get_segment<'a>(
+    pub(crate) fn get_segment<'a>(
         &self,
         segments: &'a [&TransportationSegmentRecord],
         segment_lookup: &HashMap<String, usize>,
@@ -312,6 +421,16 @@ impl SegmentSplit {
     }
 }
 
+/// helper function which builds access conditions for a travel mode and all of
+/// its parent modes, such as `[car, motor_vehicle, vehicle]` for cars.
+fn mode_restriction_when(mode: SegmentMode) -> SegmentAccessRestrictionWhen {
+    let modes = std::iter::successors(Some(mode), |m| m.parent()).collect_vec();
+    SegmentAccessRestrictionWhen {
+        mode: Some(modes),
+        ..Default::default()
+    }
+}
+
 /// helper function which confirms that speed data exists and that it matches the current heading
 fn has_max_speed_for_heading(s: &SegmentSpeedLimit, heading: &SegmentHeading) -> bool {
     // no max speed? return early
@@ -331,12 +450,255 @@ fn has_max_speed_for_heading(s: &SegmentSpeedLimit, heading: &SegmentHeading) ->
     }
 }
 
+/// helper function which confirms that a speed limit applies at all times to travel
+/// matching the edge list conditions. limits with a `during`, `using`, `recognized` or
+/// `vehicle` condition, or a mode that does not match, are conditional.
+fn is_unconditional_for(s: &SegmentSpeedLimit, when: &SegmentAccessRestrictionWhen) -> bool {
+    let limit_when = match s.when.as_ref() {
+        Some(w) => w,
+        None => return true,
+    };
+    limit_when.during.is_none()
+        && limit_when.using.is_none()
+        && limit_when.recognized.is_none()
+        && limit_when.vehicle.is_none()
+        && segment_ops::when_is_compatible(when, Some(limit_when))
+}
+
 #[cfg(test)]
 mod test {
-    use crate::collection::{
-        record::{SegmentHeading, SpeedLimitWithUnit},
-        SegmentAccessRestrictionWhen, SegmentSpeedLimit, SegmentSpeedUnit,
+    use super::SegmentSplit;
+    use crate::{
+        collection::{
+            record::{
+                SegmentHeading, SegmentRoadFlags, SegmentRoadSurfaceType, SpeedLimitWithUnit,
+            },
+            SegmentAccessRestrictionWhen, SegmentSpeedLimit, SegmentSpeedUnit,
+            TransportationSegmentRecord,
+        },
+        graph::{ConnectorInSegment, ModalAttributes},
     };
+    use geo::{line_string, Geometry};
+    use std::collections::HashMap;
+
+    fn segment(id: &str, mut attributes: serde_json::Value) -> TransportationSegmentRecord {
+        attributes["id"] = serde_json::json!(id);
+        attributes["bbox"] = serde_json::json!({});
+        attributes["version"] = serde_json::json!(0);
+        let mut record: TransportationSegmentRecord = serde_json::from_value(attributes)
+            .expect("test invariant failed: unable to mock segment attributes");
+        record.geometry = Some(Geometry::LineString(
+            line_string![(x: -105.0, y: 40.0), (x: -105.001, y: 40.0)],
+        ));
+        record
+    }
+
+    fn split(segment: &str, start: f64, end: f64, heading: SegmentHeading) -> SegmentSplit {
+        SegmentSplit::new(
+            ConnectorInSegment::new(segment.to_string(), String::from("a"), start),
+            ConnectorInSegment::new(segment.to_string(), String::from("b"), end),
+            heading,
+        )
+    }
+
+    fn modal_attributes(
+        segment: &TransportationSegmentRecord,
+        split: &SegmentSplit,
+    ) -> ModalAttributes {
+        let lookup = HashMap::from([(segment.id.clone(), 0)]);
+        split
+            .get_split_modal_attributes(&[segment], &lookup)
+            .expect("test invariant failed")
+    }
+
+    #[test]
+    fn test_split_modal_attributes_speed_limits() {
+        use SegmentHeading as H;
+        let s1 = segment(
+            "s1",
+            serde_json::json!({
+                "speed_limits": [
+                    {"max_speed": {"value": 25, "unit": "mph"}, "between": [0.0, 0.5]},
+                    {"max_speed": {"value": 45, "unit": "mph"}, "between": [0.5, 1.0]},
+                    {"max_speed": {"value": 55, "unit": "mph"}, "when": {"heading": "backward"}},
+                    {"max_speed": {"value": 15, "unit": "mph"}, "when": {"during": "Mo-Fr 07:30-09:00"}},
+                    {"max_speed": {"value": 10, "unit": "mph"}, "when": {"mode": ["bicycle"]}},
+                    {"min_speed": {"value": 5, "unit": "mph"}}
+                ]
+            }),
+        );
+        let speed = |start: f64, end: f64, heading: H| {
+            modal_attributes(&s1, &split("s1", start, end, heading))
+                .speed_limit_mph
+                .map(f64::round)
+        };
+        // limits are weighted by the portion of the split they cover. conditional
+        // limits and limits for other modes are ignored.
+        assert_eq!(speed(0.0, 1.0, H::Forward), Some(35.0));
+        assert_eq!(speed(0.0, 0.5, H::Forward), Some(25.0));
+        assert_eq!(speed(0.0, 1.0, H::Backward), Some(45.0));
+
+        let kmh = segment(
+            "kmh",
+            serde_json::json!({
+                "speed_limits": [{"max_speed": {"value": 50, "unit": "km/h"}}]
+            }),
+        );
+        let mph = modal_attributes(&kmh, &split("kmh", 0.0, 1.0, H::Forward))
+            .speed_limit_mph
+            .expect("test invariant failed");
+        assert!((mph - 31.07).abs() < 0.01);
+
+        let unposted = segment("unposted", serde_json::json!({}));
+        let attributes = modal_attributes(&unposted, &split("unposted", 0.0, 1.0, H::Forward));
+        assert_eq!(attributes.speed_limit_mph, None);
+    }
+
+    #[test]
+    fn test_split_modal_attributes_access_restrictions() {
+        use SegmentHeading as H;
+        let attributes = |restrictions: serde_json::Value, heading: H| {
+            let record = segment(
+                "s1",
+                serde_json::json!({ "access_restrictions": restrictions }),
+            );
+            modal_attributes(&record, &split("s1", 0.0, 1.0, heading))
+        };
+
+        let open = attributes(serde_json::json!([]), H::Forward);
+        assert!(open.foot_access && open.bicycle_access && !open.bicycle_designated);
+        assert_eq!(open.motor_vehicle_headings, 2);
+
+        let no_foot = attributes(
+            serde_json::json!([{"access_type": "denied", "when": {"mode": ["foot"]}}]),
+            H::Forward,
+        );
+        assert!(!no_foot.foot_access && no_foot.bicycle_access);
+        assert_eq!(no_foot.motor_vehicle_headings, 2);
+
+        // a restriction on vehicles applies to bicycles and cars through their parent mode
+        let no_vehicles = attributes(
+            serde_json::json!([{"access_type": "denied", "when": {"mode": ["vehicle"]}}]),
+            H::Forward,
+        );
+        assert!(no_vehicles.foot_access && !no_vehicles.bicycle_access);
+        assert_eq!(no_vehicles.motor_vehicle_headings, 0);
+
+        // one-way for motor vehicles, while bicycles may travel both ways
+        let oneway = serde_json::json!([
+            {"access_type": "denied", "when": {"heading": "backward", "mode": ["motor_vehicle"]}}
+        ]);
+        let forward = attributes(oneway.clone(), H::Forward);
+        let backward = attributes(oneway, H::Backward);
+        assert_eq!(forward.motor_vehicle_headings, 1);
+        assert_eq!(backward.motor_vehicle_headings, 1);
+        assert!(backward.bicycle_access && backward.foot_access);
+
+        let bike_lane = attributes(
+            serde_json::json!([{"access_type": "designated", "when": {"mode": ["bicycle"]}}]),
+            H::Forward,
+        );
+        assert!(bike_lane.bicycle_designated && bike_lane.bicycle_access);
+
+        // time-dependent closures do not remove access
+        let school_zone = attributes(
+            serde_json::json!([{"access_type": "denied", "when": {"during": "Mo-Fr 07:30-09:00"}}]),
+            H::Forward,
+        );
+        assert!(school_zone.foot_access && school_zone.bicycle_access);
+        assert_eq!(school_zone.motor_vehicle_headings, 2);
+    }
+
+    #[test]
+    fn test_split_flags() {
+        let s1 = segment(
+            "s1",
+            serde_json::json!({
+                "road_flags": [
+                    {"value": ["is_bridge"], "between": [0.2, 0.4]},
+                    {"value": ["is_tunnel", "is_link"], "between": [0.6, 1.0]},
+                    {"value": ["is_link"]}
+                ]
+            }),
+        );
+        let lookup = HashMap::from([(String::from("s1"), 0)]);
+        let flags = |start: f64, end: f64| {
+            split("s1", start, end, SegmentHeading::Forward)
+                .get_split_flags(&[&s1], &lookup)
+                .expect("test invariant failed")
+        };
+        assert_eq!(
+            flags(0.0, 0.5),
+            vec![SegmentRoadFlags::IsBridge, SegmentRoadFlags::IsLink]
+        );
+        assert_eq!(
+            flags(0.5, 1.0),
+            vec![SegmentRoadFlags::IsTunnel, SegmentRoadFlags::IsLink]
+        );
+        // flags touching the split only at an endpoint do not apply
+        assert_eq!(flags(0.4, 0.6), vec![SegmentRoadFlags::IsLink]);
+        assert_eq!(flags(0.0, 0.2), vec![SegmentRoadFlags::IsLink]);
+        // a range ending at the split points covers the whole split
+        assert_eq!(
+            flags(0.2, 0.4),
+            vec![SegmentRoadFlags::IsBridge, SegmentRoadFlags::IsLink]
+        );
+        // a range covering part of the split applies to all of it
+        assert_eq!(
+            flags(0.3, 0.5),
+            vec![SegmentRoadFlags::IsBridge, SegmentRoadFlags::IsLink]
+        );
+    }
+
+    #[test]
+    fn test_split_surface() {
+        let s1 = segment(
+            "s1",
+            serde_json::json!({
+                "road_surface": [
+                    {"value": "paved", "between": [0.0, 0.5]},
+                    {"value": "gravel", "between": [0.5, 1.0]}
+                ]
+            }),
+        );
+        let s2 = segment(
+            "s2",
+            serde_json::json!({
+                "road_surface": [{"value": "dirt", "between": [0.2, 0.4]}]
+            }),
+        );
+        let unknown = segment("unknown", serde_json::json!({}));
+        let lookup = HashMap::from([
+            (String::from("s1"), 0),
+            (String::from("s2"), 1),
+            (String::from("unknown"), 2),
+        ]);
+        let segments = [&s1, &s2, &unknown];
+        let surface = |segment: &str, start: f64, end: f64| {
+            split(segment, start, end, SegmentHeading::Forward)
+                .get_split_surface(&segments, &lookup)
+                .expect("test invariant failed")
+        };
+        // ranges ending at the split points, where the neighboring surface only touches
+        assert_eq!(surface("s1", 0.0, 0.5), Some(SegmentRoadSurfaceType::Paved));
+        assert_eq!(
+            surface("s1", 0.5, 1.0),
+            Some(SegmentRoadSurfaceType::Gravel)
+        );
+        // splits crossing the change in surface take the surface covering more of the split
+        assert_eq!(
+            surface("s1", 0.4, 1.0),
+            Some(SegmentRoadSurfaceType::Gravel)
+        );
+        assert_eq!(surface("s1", 0.3, 0.6), Some(SegmentRoadSurfaceType::Paved));
+        // a surface covering part of the split applies to all of it
+        assert_eq!(surface("s2", 0.3, 0.6), Some(SegmentRoadSurfaceType::Dirt));
+        assert_eq!(surface("s2", 0.0, 0.3), Some(SegmentRoadSurfaceType::Dirt));
+        // a surface touching the split only at an endpoint does not apply
+        assert_eq!(surface("s2", 0.4, 1.0), None);
+        assert_eq!(surface("s2", 0.0, 0.2), None);
+        assert_eq!(surface("unknown", 0.0, 1.0), None);
+    }
 
     #[test]
     fn no_maxspeed_entry() {
@@ -6,10 +6,14 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     collection::{
+        record::{SegmentRoadFlags, SegmentRoadSurfaceType},
         OvertureMapsCollectionError, SegmentAccessRestrictionWhen, SegmentFullType,
         TransportationConnectorRecord, TransportationSegmentRecord,
     },
-    graph::{consts, omf_graph::OmfEdgeList, segment_split::SegmentSplit},
+    graph::{
+        consts, modal_attributes::ModalAttributes, omf_graph::OmfEdgeList,
+        segment_split::SegmentSplit, ConditionalSpeedLimit, TimeRestriction, TurnRestriction,
+    },
 };
 
 /// serializes the Connector records into Vertices and creates a GERS id -> index mapping.
@@ -116,13 +120,50 @@ pub fn create_speeds(
     segments: &[&TransportationSegmentRecord],
     segment_lookup: &HashMap<String, usize>,
     splits: &[SegmentSplit],
+    when: &SegmentAccessRestrictionWhen,
 ) -> Result<Vec<Option<f64>>, OvertureMapsCollectionError> {
     splits
         .par_iter()
-        .map(|split| split.get_split_speed(segments, segment_lookup))
+        .map(|split| split.get_split_speed(segments, segment_lookup, when))
         .collect::<Result<Vec<Option<f64>>, OvertureMapsCollectionError>>()
 }
 
+pub fn create_surfaces(
+    segments: &[&TransportationSegmentRecord],
+    segment_lookup: &HashMap<String, usize>,
+    splits: &[SegmentSplit],
+) -> Result<Vec<SegmentRoadSurfaceType>, OvertureMapsCollectionError> {
+    splits
+        .par_iter()
+        .map(|split| {
+            let surface = split.get_split_surface(segments, segment_lookup)?;
+            Ok(surface.unwrap_or(SegmentRoadSurfaceType::Unknown))
+        })
+        .collect::<Result<Vec<SegmentRoadSurfaceType>, OvertureMapsCollectionError>>()
+}
+
+pub fn create_road_flags(
+    segments: &[&TransportationSegmentRecord],
+    segment_lookup: &HashMap<String, usize>,
+    splits: &[SegmentSplit],
+) -> Result<Vec<Vec<SegmentRoadFlags>>, OvertureMapsCollectionError> {
+    splits
+        .par_iter()
+        .map(|split| split.get_split_flags(segments, segment_lookup))
+        .collect::<Result<Vec<Vec<SegmentRoadFlags>>, OvertureMapsCollectionError>>()
+}
+
+pub fn create_modal_attributes(
+    segments: &[&TransportationSegmentRecord],
+    segment_lookup: &HashMap<String, usize>,
+    splits: &[SegmentSplit],
+) -> Result<Vec<ModalAttributes>, OvertureMapsCollectionError> {
+    splits
+        .par_iter()
+        .map(|split| split.get_split_modal_attributes(segments, segment_lookup))
+        .collect::<Result<Vec<ModalAttributes>, OvertureMapsCollectionError>>()
+}
+
 pub fn create_segment_full_types(
     segments: &[&TransportationSegmentRecord],
     segment_lookup: &HashMap<String, usize>,
@@ -374,6 +415,36 @@ pub fn clean_omf_edge_list(
         .map(|(_, b)| b)
         .collect();
 
+    let surfaces = omf_list
+        .surfaces
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| mask[*idx])
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(_, s)| s)
+        .collect();
+
+    let road_flags = omf_list
+        .road_flags
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| mask[*idx])
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(_, f)| f)
+        .collect();
+
+    let modal_attributes = omf_list
+        .modal_attributes
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| mask[*idx])
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(_, a)| a)
+        .collect();
+
     let omf_segment_ids = omf_list
         .omf_segment_ids
         .into_iter()
@@ -384,6 +455,52 @@ pub fn clean_omf_edge_list(
         .map(|(_, i)| i)
         .collect();
 
+    // renumber the edges of each turn restriction, dropping restrictions over removed edges
+    let mut next_id = 0;
+    let edge_remapping = mask
+        .iter()
+        .map(|keep| {
+            keep.then(|| {
+                next_id += 1;
+                EdgeId(next_id - 1)
+            })
+        })
+        .collect::<Vec<_>>();
+    let turn_restrictions = omf_list
+        .turn_restrictions
+        .into_iter()
+        .filter_map(|restriction| {
+            let edge_ids = restriction
+                .edge_ids
+                .iter()
+                .map(|edge_id| edge_remapping.get(edge_id.0).copied().flatten())
+                .collect::<Option<Vec<_>>>()?;
+            Some(TurnRestriction { edge_ids })
+        })
+        .collect();
+    let time_restrictions = omf_list
+        .time_restrictions
+        .into_iter()
+        .filter_map(|restriction| {
+            let edge_id = edge_remapping
+                .get(restriction.edge_id.0)
+                .copied()
+                .flatten()?;
+            Some(TimeRestriction {
+                edge_id,
+                ..restriction
+            })
+        })
+        .collect();
+    let conditional_speed_limits = omf_list
+        .conditional_speed_limits
+        .into_iter()
+        .filter_map(|limit| {
+            let edge_id = edge_remapping.get(limit.edge_id.0).copied().flatten()?;
+            Some(ConditionalSpeedLimit { edge_id, ..limit })
+        })
+        .collect();
+
     Ok(OmfEdgeList {
         edge_list_id: omf_list.edge_list_id,
         edges,
@@ -392,6 +509,101 @@ pub fn clean_omf_edge_list(
         speeds,
         speed_lookup: omf_list.speed_lookup,
         bearings,
+        surfaces,
+        road_flags,
+        modal_attributes,
+        conditional_speed_limits,
         omf_segment_ids,
+        turn_restrictions,
+        time_restrictions,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::{create_road_flags, create_surfaces};
+    use crate::{
+        collection::{
+            record::{SegmentHeading, SegmentRoadFlags, SegmentRoadSurfaceType},
+            TransportationSegmentRecord,
+        },
+        graph::{segment_split::SegmentSplit, ConnectorInSegment},
+    };
+    use std::collections::HashMap;
+
+    /// a segment split at 0.5 where the surface and flags change at the split point
+    /// and at 0.75, part of the way along the second split.
+    fn split_segment() -> (TransportationSegmentRecord, Vec<SegmentSplit>) {
+        let mut segment = TransportationSegmentRecord {
+            id: String::from("s1"),
+            ..Default::default()
+        };
+        segment.road_surface = serde_json::from_value(serde_json::json!([
+            {"value": "paved", "between": [0.0, 0.5]},
+            {"value": "unpaved", "between": [0.75, 1.0]}
+        ]))
+        .unwrap();
+        segment.road_flags = serde_json::from_value(serde_json::json!([
+            {"value": ["is_bridge"], "between": [0.0, 0.5]},
+            {"value": ["is_tunnel"], "between": [0.75, 1.0]}
+        ]))
+        .unwrap();
+        let connector = |id: &str, linear_reference: f64| {
+            ConnectorInSegment::new(String::from("s1"), String::from(id), linear_reference)
+        };
+        let splits = vec![
+            SegmentSplit::new(
+                connector("a", 0.0),
+                connector("b", 0.5),
+                SegmentHeading::Forward,
+            ),
+            SegmentSplit::new(
+                connector("b", 0.5),
+                connector("c", 1.0),
+                SegmentHeading::Forward,
+            ),
+        ];
+        (segment, splits)
+    }
+
+    #[test]
+    fn test_create_surfaces_at_split_points() {
+        let (segment, splits) = split_segment();
+        let lookup = HashMap::from([(String::from("s1"), 0)]);
+        let result = create_surfaces(&[&segment], &lookup, &splits).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                SegmentRoadSurfaceType::Paved,
+                SegmentRoadSurfaceType::Unpaved
+            ]
+        );
+
+        let unknown = TransportationSegmentRecord {
+            id: String::from("s1"),
+            ..Default::default()
+        };
+        let result = create_surfaces(&[&unknown], &lookup, &splits).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                SegmentRoadSurfaceType::Unknown,
+                SegmentRoadSurfaceType::Unknown
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_road_flags_at_split_points() {
+        let (segment, splits) = split_segment();
+        let lookup = HashMap::from([(String::from("s1"), 0)]);
+        let result = create_road_flags(&[&segment], &lookup, &splits).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                vec![SegmentRoadFlags::IsBridge],
+                vec![SegmentRoadFlags::IsTunnel]
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use routee_compass_core::model::network::EdgeId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{
+        record::{SegmentAccessRestriction, SegmentAccessType},
+        OvertureMapsCollectionError, SegmentAccessRestrictionWhen, TransportationSegmentRecord,
+    },
+    graph::{segment_ops, segment_split::SegmentSplit},
+};
+
+/// an access restriction on an edge that is only in effect during the times described
+/// by an OSM opening hours expression.
+///
+/// - a denied restriction closes the edge while the expression is open
+/// - an allowed or designated restriction opens the edge only while the expression is open.
+///   these are only written for edges that would otherwise be closed to the edge list modes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TimeRestriction {
+    pub edge_id: EdgeId,
+    pub access_type: SegmentAccessType,
+    pub during: String,
+}
+
+/// finds the time-dependent access restrictions of each split that apply to an edge list,
+/// where each split is the edge with the same index. restrictions are matched to the
+/// edge list following the same rules used when finding the headings of a segment,
+/// see [`segment_ops::get_headings`].
+pub fn find_time_restrictions(
+    segments: &[&TransportationSegmentRecord],
+    segment_lookup: &HashMap<String, usize>,
+    splits: &[SegmentSplit],
+    when: &SegmentAccessRestrictionWhen,
+) -> Result<Vec<TimeRestriction>, OvertureMapsCollectionError> {
+    let mut result = vec![];
+    for (idx, split) in splits.iter().enumerate() {
+        let segment = split.get_segment(segments, segment_lookup)?;
+        let restrictions = match segment.access_restrictions.as_ref() {
+            Some(r) if r.iter().any(|r| r.is_time_dependent()) => r,
+            _ => continue,
+        };
+        let (timed, fixed): (
+            Vec<&SegmentAccessRestriction>,
+            Vec<&SegmentAccessRestriction>,
+        ) = restrictions.iter().partition(|r| r.is_time_dependent());
+
+        // whether this heading is open without any time-dependent allowances
+        let fixed = fixed.into_iter().cloned().collect::<Vec<_>>();
+        let always_open =
+            segment_ops::is_heading_valid(split.heading.clone(), Some(when), Some(&fixed));
+
+        for r in timed {
+            if !segment_ops::restriction_applies_to(r, &split.heading, Some(when)) {
+                continue;
+            }
+            let is_denied = r.access_type == SegmentAccessType::Denied;
+            if !is_denied && always_open {
+                continue;
+            }
+            let during = r
+                .when
+                .as_ref()
+                .and_then(|w| w.during.as_ref())
+                .and_then(|d| d.as_opening_hours())
+                .ok_or_else(|| {
+                    let msg = format!(
+                        "time-dependent restriction on segment {} has no opening hours expression",
+                        segment.id
+                    );
+                    OvertureMapsCollectionError::InternalError(msg)
+                })?;
+            result.push(TimeRestriction {
+                edge_id: EdgeId(idx),
+                access_type: r.access_type.clone(),
+                during: during.to_string(),
+            });
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::find_time_restrictions;
+    use crate::{
+        collection::{
+            record::{SegmentAccessType, SegmentHeading, SegmentMode},
+            SegmentAccessRestrictionWhen, TransportationSegmentRecord,
+        },
+        graph::{ConnectorInSegment, SegmentSplit},
+    };
+    use routee_compass_core::model::network::EdgeId;
+    use std::collections::HashMap;
+
+    fn segment(id: &str, restrictions: serde_json::Value) -> TransportationSegmentRecord {
+        let mut record = TransportationSegmentRecord {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        record.access_restrictions = serde_json::from_value(restrictions)
+            .expect("test invariant failed: unable to mock access restrictions");
+        record
+    }
+
+    fn split(segment: &str, heading: SegmentHeading) -> SegmentSplit {
+        SegmentSplit::new(
+            ConnectorInSegment::new(segment.to_string(), String::from("a"), 0.0),
+            ConnectorInSegment::new(segment.to_string(), String::from("b"), 1.0),
+            heading,
+        )
+    }
+
+    #[test]
+    fn test_school_zone_and_bus_lane() {
+        // s1 is closed to all traffic during school hours.
+        // s2 is a bus lane that cars may use outside of rush hour.
+        let s1 = segment(
+            "s1",
+            serde_json::json!([
+                {"access_type": "denied", "when": {"during": "Mo-Fr 07:30-09:00"}}
+            ]),
+        );
+        let s2 = segment(
+            "s2",
+            serde_json::json!([
+                {"access_type": "denied"},
+                {"access_type": "allowed", "when": {"mode": ["bus"]}},
+                {"access_type": "allowed", "when": {"mode": ["car"], "during": "Mo-Fr 09:00-16:00"}}
+            ]),
+        );
+        let segments = vec![&s1, &s2];
+        let lookup = HashMap::from([(String::from("s1"), 0), (String::from("s2"), 1)]);
+        let splits = vec![
+            split("s1", SegmentHeading::Forward),
+            split("s2", SegmentHeading::Forward),
+        ];
+        let when = SegmentAccessRestrictionWhen {
+            mode: Some(vec![SegmentMode::Car]),
+            ..Default::default()
+        };
+
+        let result = find_time_restrictions(&segments, &lookup, &splits, &when).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].edge_id, EdgeId(0));
+        assert_eq!(result[0].access_type, SegmentAccessType::Denied);
+        assert_eq!(result[1].edge_id, EdgeId(1));
+        assert_eq!(result[1].access_type, SegmentAccessType::Allowed);
+
+        // buses may always use the bus lane, so only the school closure applies
+        let when = SegmentAccessRestrictionWhen {
+            mode: Some(vec![SegmentMode::Bus]),
+            ..Default::default()
+        };
+        let result = find_time_restrictions(&segments, &lookup, &splits, &when).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].edge_id, EdgeId(0));
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use routee_compass_core::model::network::EdgeId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{
+        record::{SegmentMode, SegmentProhibitedTransitions},
+        OvertureMapsCollectionError, TransportationSegmentRecord,
+    },
+    graph::segment_split::SegmentSplit,
+};
+
+/// a sequence of edges that may not be traversed in order, such as a turn from
+/// the first edge onto the last. intermediate edges are present when a restriction
+/// spans more than one transition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TurnRestriction {
+    pub edge_ids: Vec<EdgeId>,
+}
+
+/// row of a turn restrictions file, with the edge ids of the restriction joined by
+/// [`TurnRestrictionRow::DELIMITER`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TurnRestrictionRow {
+    pub edge_ids: String,
+}
+
+impl TurnRestrictionRow {
+    pub const DELIMITER: &str = ";";
+
+    /// parses the edge ids of this row in travel order.
+    pub fn edge_ids(&self) -> Result<Vec<EdgeId>, OvertureMapsCollectionError> {
+        self.edge_ids
+            .split(Self::DELIMITER)
+            .map(|id| {
+                id.trim().parse::<usize>().map(EdgeId).map_err(|e| {
+                    let msg = format!("invalid edge id '{id}' in turn restriction: {e}");
+                    OvertureMapsCollectionError::DeserializeError(msg)
+                })
+            })
+            .collect()
+    }
+}
+
+impl From<&TurnRestriction> for TurnRestrictionRow {
+    fn from(value: &TurnRestriction) -> Self {
+        let edge_ids = value
+            .edge_ids
+            .iter()
+            .map(|e| e.0.to_string())
+            .join(Self::DELIMITER);
+        TurnRestrictionRow { edge_ids }
+    }
+}
+
+/// translates the prohibited transitions of a set of segments into sequences of edge ids,
+/// where each split is the edge with the same index. only prohibited transitions that apply
+/// to the edge list's travel modes are kept:
+///   - transitions without a `mode` condition apply to all edge lists
+///   - transitions with a `mode` condition apply if any mode matches the edge list modes
+///   - transitions with `using` or `recognized` conditions are ignored, these imply some
+///     special user type, following [`crate::collection::filter::TravelModeFilter`]
+///
+/// `during` and `vehicle` conditions are not modeled, and those transitions are always
+/// prohibited. transitions referencing segments or connectors missing from this edge list
+/// are skipped.
+pub fn find_turn_restrictions(
+    segments: &[&TransportationSegmentRecord],
+    splits: &[SegmentSplit],
+    modes: Option<&[SegmentMode]>,
+) -> Vec<TurnRestriction> {
+    let index = SplitIndex::new(splits);
+    let mut result = vec![];
+    let mut skipped = 0;
+    for segment in segments.iter() {
+        let transitions = match segment.prohibited_transitions.as_ref() {
+            Some(t) => t,
+            None => continue,
+        };
+        for transition in transitions.iter().filter(|t| applies_to_modes(t, modes)) {
+            let restrictions = index.resolve(&segment.id, transition);
+            if restrictions.is_empty() {
+                skipped += 1;
+            }
+            result.extend(restrictions);
+        }
+    }
+    if skipped > 0 {
+        log::info!("{skipped} prohibited transitions did not match edges in this edge list");
+    }
+    result.into_iter().unique().collect_vec()
+}
+
+/// tests whether a prohibited transition applies to an edge list with the given travel modes.
+fn applies_to_modes(
+    transition: &SegmentProhibitedTransitions,
+    modes: Option<&[SegmentMode]>,
+) -> bool {
+    let when = match transition.when.as_ref() {
+        Some(w) => w,
+        None => return true,
+    };
+    if when.using.is_some() || when.recognized.is_some() {
+        return false;
+    }
+    match (when.mode.as_ref(), modes) {
+        (None, _) => true,
+        (Some(restricted), Some(modes)) => restricted.iter().any(|m| modes.contains(m)),
+        (Some(_), None) => false,
+    }
+}
+
+/// lookup from a segment and the connector where travel enters or exits an edge
+/// to the edges on that segment.
+struct SplitIndex<'a> {
+    entering: HashMap<(&'a str, &'a str), Vec<(EdgeId, &'a SegmentSplit)>>,
+    exiting: HashMap<(&'a str, &'a str), Vec<(EdgeId, &'a SegmentSplit)>>,
+}
+
+impl<'a> SplitIndex<'a> {
+    fn new(splits: &'a [SegmentSplit]) -> Self {
+        let mut entering: HashMap<_, Vec<_>> = HashMap::new();
+        let mut exiting: HashMap<_, Vec<_>> = HashMap::new();
+        for (idx, split) in splits.iter().enumerate() {
+            let (src, dst) = split.travel_connectors();
+            let segment_id = split.src.segment_id.as_str();
+            entering
+                .entry((segment_id, src.connector_id.as_str()))
+                .or_default()
+                .push((EdgeId(idx), split));
+            exiting
+                .entry((segment_id, dst.connector_id.as_str()))
+                .or_default()
+                .push((EdgeId(idx), split));
+        }
+        Self { entering, exiting }
+    }
+
+    /// builds all edge sequences matching a prohibited transition from a segment. there may be
+    /// more than one when the heading of travel on a segment is not specified.
+    fn resolve(
+        &self,
+        segment_id: &str,
+        transition: &SegmentProhibitedTransitions,
+    ) -> Vec<TurnRestriction> {
+        let sequence = match transition.sequence.as_ref() {
+            Some(s) if !s.is_empty() => s,
+            _ => return vec![],
+        };
+        let steps = sequence
+            .iter()
+            .map(|step| step.connector.as_deref().zip(step.segment.as_deref()))
+            .collect::<Option<Vec<_>>>();
+        let steps = match steps {
+            Some(steps) => steps,
+            None => {
+                log::warn!("prohibited transition on segment {segment_id} has a sequence entry without a connector or segment, skipping");
+                return vec![];
+            }
+        };
+
+        // edges along the restricted segment that arrive at the first connector
+        let heading = transition.when.as_ref().and_then(|w| w.heading.as_ref());
+        let (first_connector, _) = steps[0];
+        let mut paths: Vec<Vec<EdgeId>> = self
+            .exiting
+            .get(&(segment_id, first_connector))
+            .into_iter()
+            .flatten()
+            .filter(|(_, split)| heading.is_none_or(|h| *h == split.heading))
+            .map(|(edge_id, _)| vec![*edge_id])
+            .collect();
+
+        // extend each path through every step of the sequence
+        for (idx, (connector, step_segment)) in steps.iter().enumerate() {
+            let next_connector = steps.get(idx + 1).map(|(c, _)| *c);
+            let candidates = self
+                .entering
+                .get(&(*step_segment, *connector))
+                .into_iter()
+                .flatten()
+                .collect_vec();
+            let mut extensions: Vec<Vec<EdgeId>> = vec![];
+            for (edge_id, split) in candidates {
+                match next_connector {
+                    // final step: a single edge leaving the last connector
+                    None => {
+                        let final_heading = transition.final_heading.as_ref();
+                        if final_heading.is_none_or(|h| *h == split.heading) {
+                            extensions.push(vec![*edge_id]);
+                        }
+                    }
+                    // intermediate step: follow the segment until the next connector
+                    Some(next) => {
+                        if let Some(edges) = self.follow(*edge_id, split, next) {
+                            extensions.push(edges);
+                        }
+                    }
+                }
+            }
+            paths = paths
+                .iter()
+                .cartesian_product(extensions.iter())
+                .map(|(path, ext)| path.iter().chain(ext.iter()).cloned().collect())
+                .collect();
+            if paths.is_empty() {
+                break;
+            }
+        }
+
+        paths
+            .into_iter()
+            .map(|edge_ids| TurnRestriction { edge_ids })
+            .collect()
+    }
+
+    /// follows the edges of a segment in one heading from a starting edge until reaching
+    /// the target connector. returns None if the target is not reached along the segment.
+    fn follow(
+        &self,
+        edge_id: EdgeId,
+        split: &SegmentSplit,
+        target_connector: &str,
+    ) -> Option<Vec<EdgeId>> {
+        let segment_id = split.src.segment_id.as_str();
+        let mut edges = vec![edge_id];
+        let mut current = split;
+        // bound the walk, a segment can never have more edges than the whole edge list
+        let limit = self.entering.len();
+        for _ in 0..limit {
+            let (_, dst) = current.travel_connectors();
+            if dst.connector_id == target_connector {
+                return Some(edges);
+            }
+            let (next_id, next_split) = self
+                .entering
+                .get(&(segment_id, dst.connector_id.as_str()))?
+                .iter()
+                .find(|(_, s)| s.heading == current.heading)?;
+            edges.push(*next_id);
+            current = next_split;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_turn_restrictions, TurnRestriction, TurnRestrictionRow};
+    use crate::{
+        collection::{
+            record::{SegmentHeading, SegmentMode},
+            TransportationSegmentRecord,
+        },
+        graph::{ConnectorInSegment, SegmentSplit},
+    };
+    use routee_compass_core::model::network::EdgeId;
+
+    fn split(segment: &str, src: &str, dst: &str, heading: SegmentHeading) -> SegmentSplit {
+        SegmentSplit::new(
+            ConnectorInSegment::new(segment.to_string(), src.to_string(), 0.0),
+            ConnectorInSegment::new(segment.to_string(), dst.to_string(), 1.0),
+            heading,
+        )
+    }
+
+    fn segment(id: &str, transitions: serde_json::Value) -> TransportationSegmentRecord {
+        let mut record = TransportationSegmentRecord {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        record.prohibited_transitions = serde_json::from_value(transitions)
+            .expect("test invariant failed: unable to mock prohibited transitions");
+        record
+    }
+
+    #[test]
+    fn test_no_left_turn() {
+        use SegmentHeading as H;
+        // (a) -[s1]-> (b) -[s2]-> (c), with s1 and s2 both two-way.
+        // travel from s1 onto s2 (forward) at connector b is prohibited for cars.
+        let splits = vec![
+            split("s1", "a", "b", H::Forward),
+            split("s1", "a", "b", H::Backward),
+            split("s2", "b", "c", H::Forward),
+            split("s2", "b", "c", H::Backward),
+        ];
+        let s1 = segment(
+            "s1",
+            serde_json::json!([{
+                "sequence": [{"connector": "b", "segment": "s2"}],
+                "final_heading": "forward",
+                "when": {"heading": "forward", "mode": ["car"]}
+            }]),
+        );
+        let segments = vec![&s1];
+
+        let drive = find_turn_restrictions(&segments, &splits, Some(&[SegmentMode::Car]));
+        assert_eq!(
+            drive,
+            vec![TurnRestriction {
+                edge_ids: vec![EdgeId(0), EdgeId(2)]
+            }]
+        );
+
+        let walk = find_turn_restrictions(&segments, &splits, Some(&[SegmentMode::Foot]));
+        assert!(walk.is_empty());
+
+        let row = TurnRestrictionRow::from(&drive[0]);
+        assert_eq!(row.edge_ids, "0;2");
+        assert_eq!(row.edge_ids().unwrap(), vec![EdgeId(0), EdgeId(2)]);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod app;
 pub mod collection;
+pub mod diff;
 pub mod graph;
 pub mod util;
@@ -1,6 +1,7 @@
 use crate::algorithm::consolidation::WayConsolidation;
 use crate::algorithm::*;
 use crate::model::osm::graph::AdjacencyDirection;
+use crate::model::osm::graph::ConflictStage;
 use crate::model::osm::graph::OsmNodeData;
 use crate::model::osm::graph::OsmWayData;
 use crate::model::osm::graph::OsmWayId;
@@ -330,7 +331,12 @@ fn consolidate_nodes(node_ids: Vec<OsmNodeId>, graph: &mut OsmGraph) -> Result<(
     for way_consolidation in way_consolidation_records.iter_mut() {
         let (src, dst) = way_consolidation.get_src_dst(&new_node_id);
         log::debug!("Adding adjacency from {src} to {dst}");
-        graph.add_new_adjacency(&src, &dst, way_consolidation.drain_ways())?;
+        graph.insert_ways(
+            &src,
+            &dst,
+            way_consolidation.drain_ways(),
+            ConflictStage::Consolidate,
+        )?;
     }
 
     Ok(())
@@ -1,5 +1,7 @@
 use crate::model::osm::{
-    graph::{osm_way_data::OsmWayData, AdjacencyDirection, OsmGraph, OsmNodeId, Path3},
+    graph::{
+        osm_way_data::OsmWayData, AdjacencyDirection, ConflictStage, OsmGraph, OsmNodeId, Path3,
+    },
     OsmError,
 };
 use itertools::Itertools;
@@ -189,7 +191,7 @@ fn update_graph_adjacencies(
     for way in way_iter {
         let src = way.src_node_id()?;
         let dst = way.dst_node_id()?;
-        graph.replace_ways(&src, &dst, vec![way])?;
+        graph.insert_ways(&src, &dst, vec![way], ConflictStage::Simplify)?;
     }
     eprintln!();
 
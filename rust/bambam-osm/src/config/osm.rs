@@ -1,6 +1,13 @@
 use crate::{
     algorithm::truncation::ComponentFilter,
-    model::{osm::graph::osm_element_filter::ElementFilter, OsmCliError},
+    model::{
+        osm::{
+            graph::{osm_element_filter::ElementFilter, MultiedgeConflictPolicy},
+            pedestrian::PedestrianNetworkConfig,
+            speed::SpeedModelConfig,
+        },
+        OsmCliError,
+    },
 };
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +23,14 @@ pub struct OsmImportConfiguration {
     pub consolidate: bool,
     pub parallelize: bool,
     pub overwrite: bool,
+    /// if provided, the import derives a pedestrian network from sidewalk and
+    /// crossing tags instead of using all road centerlines.
+    pub pedestrian_network: Option<PedestrianNetworkConfig>,
+    /// if provided, writes tag-aware free-flow and time period speeds for each edge.
+    pub speed_model: Option<SpeedModelConfig>,
+    /// if provided, resolves multiple ways between the same pair of nodes down to
+    /// a single way. by default, all ways are kept.
+    pub multiedge_conflict_policy: Option<MultiedgeConflictPolicy>,
 }
 
 impl Default for OsmImportConfiguration {
@@ -30,6 +45,9 @@ impl Default for OsmImportConfiguration {
             consolidate: true,
             parallelize: true,
             overwrite: false,
+            pedestrian_network: None,
+            speed_model: None,
+            multiedge_conflict_policy: None,
         }
     }
 }
@@ -1,12 +1,5 @@
-use bambam_osm::{
-    config::OsmImportConfiguration,
-    model::{
-        osm::{graph::CompassWriter, OsmSource},
-        OsmCliError,
-    },
-};
+use bambam_osm::model::{run_pbf_import, OsmCliError};
 use clap::{Parser, Subcommand};
-use std::path::Path;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -39,36 +32,25 @@ pub fn run(app: &App) -> Result<(), OsmCliError> {
             configuration_file, // network_filter,
             output_directory,
         } => {
-            let conf = match configuration_file {
-                None => Ok(OsmImportConfiguration::default()),
-                Some(f) => {
-                    log::info!("reading bambam configuration from {f}");
-                    OsmImportConfiguration::try_from(f)
-                }
-            }?;
-            let consolidation_threshold = conf.get_consolidation_threshold();
-            let out_path = Path::new(output_directory);
-            let pbf_config = OsmSource::Pbf {
-                pbf_filepath: pbf_file.clone(),
-                extent_filter_filepath: extent_file.clone(),
-                network_filter: Some(conf.element_filter),
-                component_filter: Some(conf.component_filter),
-                truncate_by_edge: conf.truncate_by_edge,
-                ignore_errors: conf.ignore_osm_parsing_errors,
-                simplify: conf.simplify,
-                consolidate: conf.consolidate,
-                consolidation_threshold,
-                parallelize: conf.parallelize,
-            };
-            let graph = pbf_config.import()?;
-            match graph.write_compass(out_path, true) {
-                Ok(_) => {
+            match run_pbf_import(
+                pbf_file,
+                extent_file.as_deref(),
+                configuration_file.as_deref(),
+                output_directory,
+            ) {
+                Ok(summary) => {
+                    log::info!(
+                        "wrote network with {} nodes, {} ways to {}",
+                        summary.nodes,
+                        summary.ways,
+                        summary.output_directory
+                    );
                     eprintln!("finished.");
                     Ok(())
                 }
                 Err(e) => {
                     log::error!("bambam-osm failed: {e}");
-                    Err(e)?
+                    Err(e)
                 }
             }
         }
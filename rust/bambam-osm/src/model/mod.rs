@@ -1,4 +1,6 @@
 pub mod feature;
 pub mod osm;
 mod osm_cli_error;
+mod pbf_import;
 pub use osm_cli_error::OsmCliError;
+pub use pbf_import::{run_pbf_import, PbfImportSummary};
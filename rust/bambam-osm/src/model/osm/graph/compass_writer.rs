@@ -31,6 +31,7 @@ mod filenames {
     pub const GEOMETRIES_ENUMERATED: &str = "edges-geometries-enumerated.txt.gz";
     pub const MAXSPEEDS_AVGFILL: &str = "speed-maxspeed-avgfill-enumerated.txt.gz";
     pub const HIGHWAY_TAG: &str = "edges-highway-tag-enumerated.txt.gz";
+    pub const MULTIEDGE_CONFLICTS: &str = "edges-multiedge-conflicts.csv.gz";
 }
 
 impl CompassWriter for OsmGraphVectorized {
@@ -184,6 +185,30 @@ impl CompassWriter for OsmGraphVectorized {
         }
         eprintln!();
 
+        // MULTIEDGE CONFLICT DIAGNOSTICS
+        let conflicts = self.reference_graph.conflicts();
+        if !conflicts.is_empty() {
+            log::info!(
+                "multiedge conflict policy '{}' resolved {} conflicts",
+                self.reference_graph.conflict_policy(),
+                conflicts.len()
+            );
+            let mut conflict_writer = create_writer(
+                output_directory,
+                filenames::MULTIEDGE_CONFLICTS,
+                true,
+                QuoteStyle::Necessary,
+                overwrite,
+            );
+            if let Some(ref mut writer) = conflict_writer {
+                for conflict in conflicts.iter() {
+                    writer.serialize(conflict).map_err(|e| {
+                        OsmError::CsvWriteError(String::from(filenames::MULTIEDGE_CONFLICTS), e)
+                    })?;
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -191,7 +216,7 @@ impl CompassWriter for OsmGraphVectorized {
 /// helper function to build a filewriter for writing either .csv.gz or
 /// .txt.gz files for compass datasets while respecting the user's overwrite
 /// preferences and properly formatting WKT outputs.
-fn create_writer(
+pub(crate) fn create_writer(
     directory: &Path,
     filename: &str,
     has_headers: bool,
@@ -2,6 +2,8 @@ mod adjacency_direction;
 mod compass_writer;
 mod example;
 pub mod fill_value_lookup;
+mod multiedge_conflict;
+mod multiedge_conflict_policy;
 pub mod osm_element_filter;
 pub mod osm_graph;
 mod osm_graph_vectorized;
@@ -18,8 +20,11 @@ mod way_overwrite_policy;
 
 use crate::model::osm::OsmError;
 pub use adjacency_direction::AdjacencyDirection;
+pub(crate) use compass_writer::create_writer;
 pub use compass_writer::CompassWriter;
 use itertools::Itertools;
+pub use multiedge_conflict::{ConflictStage, MultiedgeConflict};
+pub use multiedge_conflict_policy::MultiedgeConflictPolicy;
 pub use osm_graph::OsmGraph;
 pub use osm_graph_vectorized::OsmGraphVectorized;
 pub use osm_node_data::OsmNodeData;
@@ -0,0 +1,39 @@
+use super::{OsmNodeId, OsmWayId, WayOverwritePolicy};
+use serde::{Deserialize, Serialize};
+
+/// the graph operation that produced a multiedge conflict
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStage {
+    Build,
+    Simplify,
+    Consolidate,
+}
+
+impl ConflictStage {
+    /// how ways are written at this stage when no ranking policy applies
+    pub fn default_overwrite_policy(&self) -> WayOverwritePolicy {
+        match self {
+            ConflictStage::Build => WayOverwritePolicy::Append,
+            ConflictStage::Simplify => WayOverwritePolicy::Replace,
+            ConflictStage::Consolidate => WayOverwritePolicy::Append,
+        }
+    }
+}
+
+/// a record of ways found between the same pair of nodes and how the
+/// [`super::MultiedgeConflictPolicy`] resolved them. serialized as a row of the
+/// multiedge conflicts diagnostics file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiedgeConflict {
+    pub src_node_id: OsmNodeId,
+    pub dst_node_id: OsmNodeId,
+    pub stage: ConflictStage,
+    pub policy: String,
+    pub kept_way_id: OsmWayId,
+    pub kept_value: Option<String>,
+    /// semicolon-delimited way ids that were dropped
+    pub dropped_way_ids: String,
+    /// semicolon-delimited ranked values of the dropped ways, in the same order
+    pub dropped_values: String,
+}
@@ -0,0 +1,78 @@
+use super::{OsmWayData, WayOverwritePolicy};
+use crate::model::osm::OsmError;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// configures how the graph resolves multiple ways found between the same pair
+/// of nodes, such as a service driveway mapped over the top of a primary road.
+/// the policy is applied when the graph is built, simplified and consolidated.
+/// ranking policies only compare ways that duplicate each other's geometry, so
+/// distinct parallel ways between a node pair are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum MultiedgeConflictPolicy {
+    /// keep every way between a node pair (the default). simplification still
+    /// replaces existing ways with the simplified ones.
+    #[default]
+    KeepAll,
+    /// of each set of duplicate ways, keep only the way with the highest-ranked highway
+    /// tag, such as primary over service
+    CompareExistingHighwayTag,
+    /// of each set of duplicate ways, keep only the way whose value for the tag appears
+    /// earliest in the ranking.
+    /// ways with values missing from the ranking are ranked last.
+    RankByTag { tag: String, ranking: Vec<String> },
+}
+
+impl MultiedgeConflictPolicy {
+    /// confirms that a rank-by-tag policy names a known way tag
+    pub fn validate(&self) -> Result<(), OsmError> {
+        match self {
+            MultiedgeConflictPolicy::RankByTag { tag, ranking } => {
+                if ranking.is_empty() {
+                    return Err(OsmError::ConfigurationError(format!(
+                        "multiedge conflict policy for tag '{tag}' has an empty ranking"
+                    )));
+                }
+                OsmWayData::default()
+                    .get_string_at_field(tag)
+                    .map_err(|e| {
+                        OsmError::ConfigurationError(format!(
+                            "multiedge conflict policy has invalid tag: {e}"
+                        ))
+                    })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// the overwrite policy used when adding ways to the graph, or None if ways
+    /// should be written with the default behavior of the calling operation.
+    pub fn overwrite_policy(&self) -> Option<WayOverwritePolicy> {
+        match self {
+            MultiedgeConflictPolicy::KeepAll => None,
+            MultiedgeConflictPolicy::CompareExistingHighwayTag => {
+                Some(WayOverwritePolicy::CompareExistingHighwayTag)
+            }
+            MultiedgeConflictPolicy::RankByTag { tag, ranking } => {
+                Some(WayOverwritePolicy::RankByTag {
+                    tag: tag.clone(),
+                    ranking: ranking.clone(),
+                })
+            }
+        }
+    }
+}
+
+impl Display for MultiedgeConflictPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultiedgeConflictPolicy::KeepAll => write!(f, "keep_all"),
+            MultiedgeConflictPolicy::CompareExistingHighwayTag => {
+                write!(f, "compare_existing_highway_tag")
+            }
+            MultiedgeConflictPolicy::RankByTag { tag, .. } => write!(f, "rank_by_tag:{tag}"),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use super::{
     osm_node_data::OsmNodeData, osm_way_data::OsmWayData, AdjacencyDirection as Dir,
-    AdjacencyList3, OsmNodeId, OsmNodes, OsmWays, OsmWaysByOd, WayOverwritePolicy as WriteMode,
+    AdjacencyList3, ConflictStage, MultiedgeConflict, MultiedgeConflictPolicy, OsmNodeId, OsmNodes,
+    OsmWays, OsmWaysByOd, WayOverwritePolicy as WriteMode,
 };
-use crate::model::osm::OsmError;
+use crate::model::osm::{graph::osm_way_ops::extract_between_nodes, OsmError};
+use geo::{Coord, Distance, Haversine, Length, LineInterpolatePoint, LineString};
 use itertools::Itertools;
 use kdam::tqdm;
 use serde::{Deserialize, Serialize};
@@ -19,6 +21,12 @@ pub struct OsmGraph {
     ways: OsmWaysByOd,
     /// forward and reverse adjacency list
     adj: AdjacencyList3,
+    /// how to resolve multiple ways found between the same pair of nodes
+    #[serde(default)]
+    conflict_policy: MultiedgeConflictPolicy,
+    /// every multiedge conflict resolved by the conflict policy
+    #[serde(default)]
+    conflicts: Vec<MultiedgeConflict>,
 }
 
 impl OsmGraph {
@@ -27,13 +35,30 @@ impl OsmGraph {
             nodes: HashMap::new(),
             ways: HashMap::new(),
             adj: HashMap::new(),
+            conflict_policy: MultiedgeConflictPolicy::default(),
+            conflicts: vec![],
         }
     }
 
     /// creates a new graph to model the relationship between the provided
     /// nodes and ways.
     pub fn new(nodes: OsmNodes, ways: OsmWays) -> Result<OsmGraph, OsmError> {
-        let mut graph = OsmGraph::empty();
+        OsmGraph::new_with_conflict_policy(nodes, ways, MultiedgeConflictPolicy::default())
+    }
+
+    /// creates a new graph to model the relationship between the provided
+    /// nodes and ways, resolving ways that share a node pair with the given policy.
+    /// the policy is retained and applied again during simplification and consolidation.
+    pub fn new_with_conflict_policy(
+        nodes: OsmNodes,
+        ways: OsmWays,
+        conflict_policy: MultiedgeConflictPolicy,
+    ) -> Result<OsmGraph, OsmError> {
+        conflict_policy.validate()?;
+        let mut graph = OsmGraph {
+            conflict_policy,
+            ..OsmGraph::empty()
+        };
         for way in ways.into_values() {
             for (src_id, dst_id) in way.nodes.iter().tuple_windows() {
                 // confirm node exists in source dataset or fail
@@ -51,12 +76,22 @@ impl OsmGraph {
                 if !graph.contains_node(dst_id) {
                     graph.create_isolated_node(dst_node.clone())?;
                 }
-                graph.add_new_adjacency(src_id, dst_id, vec![way.clone()])?;
+                graph.insert_ways(src_id, dst_id, vec![way.clone()], ConflictStage::Build)?;
             }
         }
         Ok(graph)
     }
 
+    /// the multiedge conflict policy used by this graph
+    pub fn conflict_policy(&self) -> &MultiedgeConflictPolicy {
+        &self.conflict_policy
+    }
+
+    /// every multiedge conflict resolved while building and modifying this graph
+    pub fn conflicts(&self) -> &[MultiedgeConflict] {
+        &self.conflicts
+    }
+
     // /// creates a new graph to model the relationship between the provided
     // /// nodes and ways.
     // pub fn new(nodes: OsmNodes, ways: OsmWays) -> Result<OsmGraph, OsmError> {
@@ -503,9 +538,8 @@ impl OsmGraph {
         dst: &OsmNodeId,
         ways: Vec<OsmWayData>,
     ) -> Result<(), OsmError> {
-        add_ways_to_graph(self, src, dst, ways, &WriteMode::Append)
-        // add_ways_to_graph(self, src, dst, ways, &WriteMode::Append)?;
-        // Ok(())
+        add_ways_to_graph(self, src, dst, ways, &WriteMode::Append)?;
+        Ok(())
     }
 
     /// updates a way in the graph, or fails if the way is missing.
@@ -545,9 +579,52 @@ impl OsmGraph {
         dst: &OsmNodeId,
         ways: Vec<OsmWayData>,
     ) -> Result<(), OsmError> {
-        add_ways_to_graph(self, src, dst, ways, &WriteMode::Replace)
-        // add_ways_to_graph(self, src, dst, ways, &WriteMode::Replace)?;
-        // Ok(())
+        add_ways_to_graph(self, src, dst, ways, &WriteMode::Replace)?;
+        Ok(())
+    }
+
+    /// adds ways between an od pair using the graph's multiedge conflict policy.
+    /// without a ranking policy, ways are written with the default behavior of the
+    /// stage (append while building and consolidating, replace while simplifying).
+    /// with a ranking policy, the new ways and any existing ways that duplicate each
+    /// other's geometry compete and only the top-ranked way of each duplicate group
+    /// is kept. geometrically distinct parallel ways, such as the two halves of a
+    /// roundabout, are all kept. each conflict is recorded for diagnostics.
+    ///
+    /// # Arguments
+    /// * `src` - source node
+    /// * `dst` - destination node
+    /// * `ways` - ways to add
+    /// * `stage` - the graph operation adding these ways
+    pub fn insert_ways(
+        &mut self,
+        src: &OsmNodeId,
+        dst: &OsmNodeId,
+        ways: Vec<OsmWayData>,
+        stage: ConflictStage,
+    ) -> Result<(), OsmError> {
+        let policy = self
+            .conflict_policy
+            .overwrite_policy()
+            .unwrap_or_else(|| stage.default_overwrite_policy());
+        let resolutions = add_ways_to_graph(self, src, dst, ways, &policy)?;
+        for (kept, dropped) in resolutions {
+            let dropped_values = dropped
+                .iter()
+                .map(|w| policy.ranked_value(w).map(|v| v.unwrap_or_default()))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.conflicts.push(MultiedgeConflict {
+                src_node_id: *src,
+                dst_node_id: *dst,
+                stage,
+                policy: self.conflict_policy.to_string(),
+                kept_way_id: kept.osmid,
+                kept_value: policy.ranked_value(&kept)?,
+                dropped_way_ids: dropped.iter().map(|w| w.osmid.to_string()).join(";"),
+                dropped_values: dropped_values.join(";"),
+            });
+        }
+        Ok(())
     }
 
     /// removes an OsmNodeData entry for the given OsmNodeId. has no effect on the
@@ -698,14 +775,15 @@ fn remove_adjacency_list_entry(
 }
 
 /// adds a connection between two nodes in some direction to the adjacency list.
-/// also serves as an update method (with overwrite=true)
+/// also serves as an update method (with overwrite=true). when a ranking policy
+/// drops ways, returns the kept way and the dropped ways of each duplicate group.
 fn add_ways_to_graph(
     graph: &mut OsmGraph,
     src: &OsmNodeId,
     dst: &OsmNodeId,
     ways: Vec<OsmWayData>,
     overwrite_policy: &WriteMode,
-) -> Result<(), OsmError> {
+) -> Result<Vec<(OsmWayData, Vec<OsmWayData>)>, OsmError> {
     use WriteMode as P;
 
     if ways.is_empty() {
@@ -715,6 +793,7 @@ fn add_ways_to_graph(
     }
 
     let key = (*src, *dst);
+    let mut resolutions = vec![];
 
     match overwrite_policy {
         P::Append => {
@@ -741,6 +820,27 @@ fn add_ways_to_graph(
             };
             *prev = ways[0].clone();
         }
+        P::CompareExistingHighwayTag | P::RankByTag { .. } => {
+            // existing copies of the incoming ways are updated, not treated as conflicts
+            let incoming_ids = ways.iter().map(|w| w.osmid).collect::<HashSet<_>>();
+            let existing = graph.ways.remove(&key).unwrap_or_default();
+            let candidates = existing
+                .into_iter()
+                .filter(|w| !incoming_ids.contains(&w.osmid))
+                .chain(ways)
+                .collect_vec();
+            let groups = group_duplicate_ways(graph, src, dst, candidates);
+            let mut kept_ways = vec![];
+            for group in groups {
+                let (kept, dropped) = rank_multiedge_ways(group, overwrite_policy)?;
+                kept_ways.push(kept.clone());
+                if !dropped.is_empty() {
+                    resolutions.push((kept, dropped));
+                }
+            }
+            kept_ways.sort_by_key(|w| w.osmid);
+            let _ = graph.ways.insert(key, kept_ways);
+        }
     }
 
     // update adjacencies for ways
@@ -759,7 +859,94 @@ fn add_ways_to_graph(
         })
         .or_insert(HashSet::from([*src]));
 
-    Ok(())
+    Ok(resolutions)
+}
+
+/// ways between the same node pair are considered duplicates of each other when their
+/// lengths and midpoints are within this distance, in meters.
+const DUPLICATE_WAY_TOLERANCE_METERS: f64 = 5.0;
+
+/// partitions the ways between an od pair into groups of geometric duplicates. since
+/// the ways share their endpoints, they are duplicates when they have similar lengths
+/// and their midpoints are within [`DUPLICATE_WAY_TOLERANCE_METERS`] of each other.
+/// each group is ranked separately so that distinct parallel ways are not dropped.
+fn group_duplicate_ways(
+    graph: &OsmGraph,
+    src: &OsmNodeId,
+    dst: &OsmNodeId,
+    ways: Vec<OsmWayData>,
+) -> Vec<Vec<OsmWayData>> {
+    let mut groups: Vec<(LineString<f64>, Vec<OsmWayData>)> = vec![];
+    for way in ways.into_iter().sorted_by_key(|w| w.osmid) {
+        let geometry = way_geometry_between(graph, src, dst, &way);
+        match groups
+            .iter_mut()
+            .find(|(g, _)| is_duplicate_geometry(g, &geometry))
+        {
+            Some((_, group)) => group.push(way),
+            None => groups.push((geometry, vec![way])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect_vec()
+}
+
+/// the geometry of a way between the src and dst node. ways that were not simplified
+/// carry every node of the original way, so only the nodes between src and dst are
+/// used, falling back to a straight line. nodes missing from the graph are skipped.
+fn way_geometry_between(
+    graph: &OsmGraph,
+    src: &OsmNodeId,
+    dst: &OsmNodeId,
+    way: &OsmWayData,
+) -> LineString<f64> {
+    let node_ids = match extract_between_nodes(src, dst, &way.nodes) {
+        Some(forward) => forward.to_vec(),
+        None => match extract_between_nodes(dst, src, &way.nodes) {
+            Some(reverse) => reverse.iter().rev().copied().collect_vec(),
+            None => vec![*src, *dst],
+        },
+    };
+    let coords = node_ids
+        .iter()
+        .filter_map(|id| graph.nodes.get(id))
+        .map(|n| Coord::from((n.x as f64, n.y as f64)))
+        .collect_vec();
+    LineString::new(coords)
+}
+
+/// true when two lines with shared endpoints have lengths and midpoints that are
+/// within [`DUPLICATE_WAY_TOLERANCE_METERS`].
+fn is_duplicate_geometry(a: &LineString<f64>, b: &LineString<f64>) -> bool {
+    let length_difference = (Haversine.length(a) - Haversine.length(b)).abs();
+    if length_difference > DUPLICATE_WAY_TOLERANCE_METERS {
+        return false;
+    }
+    match (a.line_interpolate_point(0.5), b.line_interpolate_point(0.5)) {
+        (Some(mid_a), Some(mid_b)) => {
+            Haversine.distance(mid_a, mid_b) <= DUPLICATE_WAY_TOLERANCE_METERS
+        }
+        _ => true,
+    }
+}
+
+/// orders ways by their rank under a ranking policy, where ways without a ranked
+/// value come last and ties are broken by the lowest way id, so that the result does
+/// not depend on the order ways were added to the graph. returns the top-ranked
+/// way along with the remaining ways.
+fn rank_multiedge_ways(
+    ways: Vec<OsmWayData>,
+    policy: &WriteMode,
+) -> Result<(OsmWayData, Vec<OsmWayData>), OsmError> {
+    let mut ranked = ways
+        .into_iter()
+        .map(|w| policy.rank(&w).map(|rank| (rank, w)))
+        .collect::<Result<Vec<_>, _>>()?;
+    ranked.sort_by_key(|(rank, w)| (rank.is_none(), *rank, w.osmid));
+    let mut ranked_iter = ranked.into_iter().map(|(_, w)| w);
+    let kept = ranked_iter.next().ok_or_else(|| {
+        OsmError::InternalError(String::from("ranking multiedge ways with no ways to rank"))
+    })?;
+    Ok((kept, ranked_iter.collect_vec()))
 }
 
 /// removes a way between two nodes in some direction to the adjacency list. accounts for
@@ -965,3 +1152,195 @@ fn remove_way_from_adjacency(
 //     );
 // }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::OsmGraph;
+    use crate::model::osm::graph::{
+        osm_node_data::OsmNodeData, osm_way_data::OsmWayData, ConflictStage,
+        MultiedgeConflictPolicy, OsmNodeId, OsmNodes, OsmWayId, OsmWays,
+    };
+    use itertools::Itertools;
+
+    /// two nodes connected by a primary road and a service driveway mapped over it
+    fn duplicated_ways() -> (OsmNodes, OsmWays) {
+        let nodes = (0..2)
+            .map(|i| {
+                let node = OsmNodeData {
+                    osmid: OsmNodeId(i),
+                    x: i as f32,
+                    ..Default::default()
+                };
+                (node.osmid, node)
+            })
+            .collect();
+        let ways = [(1, "service", "asphalt"), (2, "primary", "gravel")]
+            .into_iter()
+            .map(|(id, highway, surface)| {
+                let way = OsmWayData {
+                    osmid: OsmWayId(id),
+                    nodes: vec![OsmNodeId(0), OsmNodeId(1)],
+                    highway: Some(highway.to_string()),
+                    surface: Some(surface.to_string()),
+                    ..Default::default()
+                };
+                (way.osmid, way)
+            })
+            .collect();
+        (nodes, ways)
+    }
+
+    #[test]
+    fn test_keep_all_multiedges() {
+        let (nodes, ways) = duplicated_ways();
+        let graph = OsmGraph::new(nodes, ways).expect("test invariant");
+        let result = graph
+            .get_ways_from_od(&OsmNodeId(0), &OsmNodeId(1))
+            .expect("test invariant");
+        assert_eq!(result.len(), 2);
+        assert!(graph.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_compare_existing_highway_tag() {
+        let (nodes, ways) = duplicated_ways();
+        let policy = MultiedgeConflictPolicy::CompareExistingHighwayTag;
+        let graph =
+            OsmGraph::new_with_conflict_policy(nodes, ways, policy).expect("test invariant");
+        let result = graph
+            .get_ways_from_od(&OsmNodeId(0), &OsmNodeId(1))
+            .expect("test invariant");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].osmid, OsmWayId(2));
+        let conflicts = graph.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kept_way_id, OsmWayId(2));
+        assert_eq!(conflicts[0].dropped_way_ids, "1");
+        assert_eq!(conflicts[0].dropped_values, "service");
+    }
+
+    #[test]
+    fn test_rank_by_tag() {
+        let (nodes, ways) = duplicated_ways();
+        let policy = MultiedgeConflictPolicy::RankByTag {
+            tag: String::from("surface"),
+            ranking: vec![String::from("asphalt"), String::from("concrete")],
+        };
+        let graph =
+            OsmGraph::new_with_conflict_policy(nodes, ways, policy).expect("test invariant");
+        let result = graph
+            .get_ways_from_od(&OsmNodeId(0), &OsmNodeId(1))
+            .expect("test invariant");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].osmid, OsmWayId(1));
+    }
+
+    /// a service way and a primary way that both connect node 0 to node 1, one
+    /// bending north through node 2 and the other bending south through node 3.
+    fn parallel_ways() -> (OsmNodes, OsmWays) {
+        let nodes = [
+            (0, 0.0, 0.0),
+            (1, 0.002, 0.0),
+            (2, 0.001, 0.001),
+            (3, 0.001, -0.001),
+        ]
+        .into_iter()
+        .map(|(i, x, y)| {
+            let node = OsmNodeData {
+                osmid: OsmNodeId(i),
+                x,
+                y,
+                ..Default::default()
+            };
+            (node.osmid, node)
+        })
+        .collect();
+        let ways = [(1, "service", 2), (2, "primary", 3)]
+            .into_iter()
+            .map(|(id, highway, via)| {
+                let way = OsmWayData {
+                    osmid: OsmWayId(id),
+                    nodes: vec![OsmNodeId(0), OsmNodeId(via), OsmNodeId(1)],
+                    highway: Some(highway.to_string()),
+                    ..Default::default()
+                };
+                (way.osmid, way)
+            })
+            .collect();
+        (nodes, ways)
+    }
+
+    #[test]
+    fn test_ranking_keeps_distinct_parallel_ways() {
+        let (nodes, ways) = parallel_ways();
+        let policy = MultiedgeConflictPolicy::CompareExistingHighwayTag;
+        let mut graph = OsmGraph::new_with_conflict_policy(nodes, ways.clone(), policy)
+            .expect("test invariant");
+        // simplification replaces the two segments of each way with a single edge
+        for simplified in ways.into_values() {
+            graph
+                .insert_ways(
+                    &OsmNodeId(0),
+                    &OsmNodeId(1),
+                    vec![simplified],
+                    ConflictStage::Simplify,
+                )
+                .expect("test invariant");
+        }
+        let result = graph
+            .get_ways_from_od(&OsmNodeId(0), &OsmNodeId(1))
+            .expect("test invariant");
+        let way_ids = result.iter().map(|w| w.osmid).collect::<Vec<_>>();
+        assert_eq!(way_ids, vec![OsmWayId(1), OsmWayId(2)]);
+        assert!(graph.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_ranking_drops_duplicate_among_parallel_ways() {
+        let (nodes, ways) = parallel_ways();
+        let policy = MultiedgeConflictPolicy::CompareExistingHighwayTag;
+        let mut graph = OsmGraph::new_with_conflict_policy(nodes, ways.clone(), policy)
+            .expect("test invariant");
+        // a proposed road mapped over the top of the northern service way
+        let duplicate = OsmWayData {
+            osmid: OsmWayId(3),
+            nodes: vec![OsmNodeId(0), OsmNodeId(2), OsmNodeId(1)],
+            highway: Some(String::from("proposed")),
+            ..Default::default()
+        };
+        let simplified = ways
+            .into_values()
+            .sorted_by_key(|w| w.osmid)
+            .chain([duplicate])
+            .collect::<Vec<_>>();
+        for way in simplified {
+            graph
+                .insert_ways(
+                    &OsmNodeId(0),
+                    &OsmNodeId(1),
+                    vec![way],
+                    ConflictStage::Simplify,
+                )
+                .expect("test invariant");
+        }
+        let result = graph
+            .get_ways_from_od(&OsmNodeId(0), &OsmNodeId(1))
+            .expect("test invariant");
+        let way_ids = result.iter().map(|w| w.osmid).collect::<Vec<_>>();
+        assert_eq!(way_ids, vec![OsmWayId(1), OsmWayId(2)]);
+        let conflicts = graph.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kept_way_id, OsmWayId(1));
+        assert_eq!(conflicts[0].dropped_way_ids, "3");
+    }
+
+    #[test]
+    fn test_rank_by_unknown_tag_fails() {
+        let (nodes, ways) = duplicated_ways();
+        let policy = MultiedgeConflictPolicy::RankByTag {
+            tag: String::from("not_a_tag"),
+            ranking: vec![String::from("a")],
+        };
+        assert!(OsmGraph::new_with_conflict_policy(nodes, ways, policy).is_err());
+    }
+}
@@ -11,7 +11,7 @@ use super::OsmNodeId;
 /// if this is an aggregated record, then:
 /// - consolidated_ids contains the [`OsmNodeId`]s of any subsumed ids
 /// - x and y positions are the centroid of the subsumed nodes
-/// - "highway", "ele", "junction", "railway", and "_ref" attributes
+/// - "highway", "ele", "junction", "railway", "_ref" and "crossing" attributes
 ///   are whitespace-delimited strings of the unique values from all
 ///   subsumed nodes.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +24,8 @@ pub struct OsmNodeData {
     pub junction: Option<String>,
     pub railway: Option<String>,
     pub _ref: Option<String>,
+    /// `crossing` tag of a `highway=crossing` node, where a footway meets a road
+    pub crossing: Option<String>,
     /// when nodes are consolidated, the list of composite node ids are stored here
     pub consolidated_ids: Vec<OsmNodeId>,
 }
@@ -44,6 +46,14 @@ impl OsmNodeData {
         })
     }
 
+    /// true if this node is tagged `highway=crossing`, the usual way of mapping where
+    /// a footway crosses a road.
+    pub fn is_crossing(&self) -> bool {
+        self.highway
+            .as_ref()
+            .is_some_and(|h| h.split(Self::VALUE_DELIMITER).any(|v| v == "crossing"))
+    }
+
     pub fn get_point(&self) -> Point<f32> {
         Point::new(self.x, self.y)
     }
@@ -82,6 +92,7 @@ impl OsmNodeData {
         let junction: Option<String> = collect_attribute(nodes, |n| n.junction.clone());
         let railway: Option<String> = collect_attribute(nodes, |n| n.railway.clone());
         let _ref: Option<String> = collect_attribute(nodes, |n| n._ref.clone());
+        let crossing: Option<String> = collect_attribute(nodes, |n| n.crossing.clone());
 
         let result = OsmNodeData {
             osmid: *new_node_id,
@@ -92,6 +103,7 @@ impl OsmNodeData {
             junction,
             railway,
             _ref,
+            crossing,
             consolidated_ids: node_ids,
         };
         Ok(result)
@@ -121,6 +133,7 @@ impl From<&osmpbf::elements::Node<'_>> for OsmNodeData {
                 "ele" => out.ele = Some(String::from(v)),
                 "ele:ft" => out.ele = v.parse::<f64>().ok().map(|f| format!("{}", f * 1.60934)),
                 "ref" => out._ref = Some(String::from(v)),
+                "crossing" => out.crossing = Some(String::from(v)),
                 _ => {}
             }
         }
@@ -145,6 +158,7 @@ impl From<&osmpbf::dense::DenseNode<'_>> for OsmNodeData {
                 "ele" => out.ele = Some(String::from(v)),
                 "ele:ft" => out.ele = v.parse::<f64>().ok().map(|f| format!("{}", f * 1.60934)),
                 "ref" => out._ref = Some(String::from(v)),
+                "crossing" => out.crossing = Some(String::from(v)),
                 _ => {}
             }
         }
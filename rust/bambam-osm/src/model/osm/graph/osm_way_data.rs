@@ -20,14 +20,19 @@ pub struct OsmWayData {
     pub sidewalk: Option<String>,
     pub cycleway: Option<String>,
     pub footway: Option<String>,
+    pub crossing: Option<String>,
     pub junction: Option<String>,
     pub landuse: Option<String>,
     pub lanes: Option<String>,
     pub maxspeed: Option<String>,
+    pub maxspeed_conditional: Option<String>,
+    pub maxspeed_type: Option<String>,
+    pub zone_maxspeed: Option<String>,
     pub name: Option<String>,
     pub oneway: Option<String>,
     pub _ref: Option<String>,
     pub service: Option<String>,
+    pub surface: Option<String>,
     pub tunnel: Option<String>,
     pub width: Option<String>,
     /// when ways are simplified, the list of composite way ids are stored here.
@@ -65,14 +70,21 @@ impl OsmWayData {
                 "sidewalk" => out.sidewalk = Some(String::from(v.trim())),
                 "cycleway" => out.cycleway = Some(String::from(v.trim())),
                 "footway" => out.footway = Some(String::from(v.trim())),
+                "crossing" => out.crossing = Some(String::from(v.trim())),
                 "junction" => out.junction = Some(String::from(v.trim())),
                 "landuse" => out.landuse = Some(String::from(v.trim())),
                 "lanes" => out.lanes = Some(String::from(v.trim())),
                 "maxspeed" => out.maxspeed = Some(String::from(v.trim())),
+                "maxspeed:conditional" => out.maxspeed_conditional = Some(String::from(v.trim())),
+                "maxspeed:type" | "source:maxspeed" => {
+                    out.maxspeed_type = Some(String::from(v.trim()))
+                }
+                "zone:maxspeed" => out.zone_maxspeed = Some(String::from(v.trim())),
                 "name" => out.name = Some(String::from(v.trim())),
                 "oneway" => out.oneway = Some(String::from(v.trim())),
                 "ref" => out._ref = Some(String::from(v.trim())),
                 "service" => out.service = Some(String::from(v.trim())),
+                "surface" => out.surface = Some(String::from(v.trim())),
                 "tunnel" => out.tunnel = Some(String::from(v.trim())),
                 "width" => out.width = Some(String::from(v.trim())),
                 _ => {}
@@ -119,14 +131,19 @@ impl OsmWayData {
             "sidewalk" => Ok(self.sidewalk.clone()),
             "footway" => Ok(self.footway.clone()),
             "cycleway" => Ok(self.cycleway.clone()),
+            "crossing" => Ok(self.crossing.clone()),
             "junction" => Ok(self.junction.clone()),
             "landuse" => Ok(self.landuse.clone()),
             "lanes" => Ok(self.lanes.clone()),
             "maxspeed" => Ok(self.maxspeed.clone()),
+            "maxspeed_conditional" => Ok(self.maxspeed_conditional.clone()),
+            "maxspeed_type" => Ok(self.maxspeed_type.clone()),
+            "zone_maxspeed" => Ok(self.zone_maxspeed.clone()),
             "name" => Ok(self.name.clone()),
             "oneway" => Ok(self.oneway.clone()),
             "ref" => Ok(self._ref.clone()),
             "service" => Ok(self.service.clone()),
+            "surface" => Ok(self.surface.clone()),
             "tunnel" => Ok(self.tunnel.clone()),
             "width" => Ok(self.width.clone()),
             _ => Err(format!("unknown edge field {fieldname}")),
@@ -296,13 +313,19 @@ impl TryFrom<&[&OsmWayData]> for OsmWayData {
         let sidewalk = merge_fieldname(ways, "sidewalk", Self::VALUE_DELIMITER)?;
         let cycleway = merge_fieldname(ways, "cycleway", Self::VALUE_DELIMITER)?;
         let footway = merge_fieldname(ways, "footway", Self::VALUE_DELIMITER)?;
+        let crossing = merge_fieldname(ways, "crossing", Self::VALUE_DELIMITER)?;
         let landuse = merge_fieldname(ways, "landuse", Self::VALUE_DELIMITER)?;
         let lanes = merge_fieldname(ways, "lanes", Self::VALUE_DELIMITER)?;
         let maxspeed = merge_fieldname(ways, "maxspeed", Self::VALUE_DELIMITER)?;
+        let maxspeed_conditional =
+            merge_fieldname(ways, "maxspeed_conditional", Self::VALUE_DELIMITER)?;
+        let maxspeed_type = merge_fieldname(ways, "maxspeed_type", Self::VALUE_DELIMITER)?;
+        let zone_maxspeed = merge_fieldname(ways, "zone_maxspeed", Self::VALUE_DELIMITER)?;
         let name = merge_fieldname(ways, "name", Self::VALUE_DELIMITER)?;
         let oneway = Some(String::from("true"));
         let _ref = merge_fieldname(ways, "ref", Self::VALUE_DELIMITER)?;
         let service = merge_fieldname(ways, "service", Self::VALUE_DELIMITER)?;
+        let surface = merge_fieldname(ways, "surface", Self::VALUE_DELIMITER)?;
         let tunnel = merge_fieldname(ways, "tunnel", Self::VALUE_DELIMITER)?;
         let width = merge_fieldname(ways, "width", Self::VALUE_DELIMITER)?;
 
@@ -317,14 +340,19 @@ impl TryFrom<&[&OsmWayData]> for OsmWayData {
             sidewalk,
             cycleway,
             footway,
+            crossing,
             junction,
             landuse,
             lanes,
             maxspeed,
+            maxspeed_conditional,
+            maxspeed_type,
+            zone_maxspeed,
             name,
             oneway,
             _ref,
             service,
+            surface,
             tunnel,
             width,
             way_ids,
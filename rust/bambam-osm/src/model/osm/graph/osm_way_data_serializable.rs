@@ -28,15 +28,27 @@ pub struct OsmWayDataSerializable {
     pub sidewalk: Option<String>,
     pub cycleway: Option<String>,
     pub footway: Option<String>,
+    /// crossing tags of any crossings traversed by this way. not deduplicated
+    /// so that aggregated ways retain one entry per crossing.
+    pub crossing: Option<String>,
+    /// crossing tags of the `highway=crossing` nodes reached along this way, which
+    /// excludes its source node. crossing nodes without a `crossing` tag are
+    /// recorded as "yes".
+    #[serde(default)]
+    pub crossing_nodes: Option<String>,
     pub junction: Option<String>,
     pub landuse: Option<String>,
     pub lanes: Option<String>,
     pub maxspeed: Option<String>,
     pub maxspeed_raw: Option<String>,
+    pub maxspeed_conditional: Option<String>,
+    pub maxspeed_type: Option<String>,
+    pub zone_maxspeed: Option<String>,
     pub name: Option<String>,
     pub oneway: Option<String>,
     pub _ref: Option<String>,
     pub service: Option<String>,
+    pub surface: Option<String>,
     pub tunnel: Option<String>,
     pub width: Option<String>,
     /// when ways are simplified, the list of composite way ids are stored here.
@@ -118,6 +130,7 @@ impl OsmWayDataSerializable {
         let linestring_f64: LineString<f64> = linestring.convert();
         let length_meters = Haversine.length(&linestring_f64);
         let highway = top_highway(&way.highway, OsmWayData::VALUE_DELIMITER)?;
+        let crossing_nodes = crossing_node_tags(way, &src_node_id, &dst_node_id, graph);
 
         let row = Self {
             osmid: way.osmid,
@@ -138,14 +151,20 @@ impl OsmWayDataSerializable {
             bridge: unique(way.bridge.as_ref()),
             cycleway: unique(way.cycleway.as_ref()),
             footway: unique(way.footway.as_ref()),
+            crossing: replace_delimiter(way.crossing.as_ref()),
+            crossing_nodes,
             junction: unique(way.junction.as_ref()),
             landuse: unique(way.landuse.as_ref()),
             maxspeed_raw: replace_delimiter(way.maxspeed.as_ref()),
+            maxspeed_conditional: replace_delimiter(way.maxspeed_conditional.as_ref()),
+            maxspeed_type: unique(way.maxspeed_type.as_ref()),
+            zone_maxspeed: unique(way.zone_maxspeed.as_ref()),
             name: unique(way.name.as_ref()),
             nodes: join_node_ids(&way.nodes),
             oneway: unique(way.oneway.as_ref()),
             sidewalk: unique(way.sidewalk.as_ref()),
             service: unique(way.service.as_ref()),
+            surface: unique(way.surface.as_ref()),
             tunnel: unique(way.tunnel.as_ref()),
             way_ids: join_way_ids(way.way_ids.as_ref()),
         };
@@ -162,15 +181,21 @@ impl OsmWayDataSerializable {
             "sidewalk" => Ok(self.sidewalk.clone()),
             "cycleway" => Ok(self.cycleway.clone()),
             "footway" => Ok(self.footway.clone()),
+            "crossing" => Ok(self.crossing.clone()),
+            "crossing_nodes" => Ok(self.crossing_nodes.clone()),
             "junction" => Ok(self.junction.clone()),
             "landuse" => Ok(self.landuse.clone()),
             "lanes" => Ok(self.lanes.clone()),
             "maxspeed" => Ok(self.maxspeed.clone()),
             "maxspeed_raw" => Ok(self.maxspeed_raw.clone()),
+            "maxspeed_conditional" => Ok(self.maxspeed_conditional.clone()),
+            "maxspeed_type" => Ok(self.maxspeed_type.clone()),
+            "zone_maxspeed" => Ok(self.zone_maxspeed.clone()),
             "name" => Ok(self.name.clone()),
             "oneway" => Ok(self.oneway.clone()),
             "ref" => Ok(self._ref.clone()),
             "service" => Ok(self.service.clone()),
+            "surface" => Ok(self.surface.clone()),
             "tunnel" => Ok(self.tunnel.clone()),
             "width" => Ok(self.width.clone()),
             _ => Err(format!("unknown edge field {fieldname}")),
@@ -215,6 +240,17 @@ impl EdgeForModalMetric for OsmWayDataSerializable {
         self.cycleway.as_ref().map(|tag| CyclewayTag::new(tag))
     }
 
+    /// the fewest lanes of any aggregated way, so that a simplified edge is not
+    /// treated as wider than its narrowest part.
+    fn get_lanes(&self) -> Option<u32> {
+        self.lanes.as_ref().and_then(|lanes| {
+            lanes
+                .split(Self::VALUE_DELIMITER)
+                .filter_map(|l| l.trim().parse::<u32>().ok())
+                .min()
+        })
+    }
+
     fn is_walkable(&self) -> bool {
         let is_sidewalk = self.is_sidewalk();
 
@@ -257,6 +293,24 @@ impl EdgeForModalMetric for OsmWayDataSerializable {
             .is_some_and(|s| s != "no" && s != "none")
     }
 
+    /// reads the sidewalk tag, see https://wiki.openstreetmap.org/wiki/Key:sidewalk.
+    /// "separate" sidewalks, mapped as their own ways, are treated as unknown. when
+    /// aggregated ways disagree, the fewest sides are used.
+    fn get_sidewalk_sides(&self) -> Option<u8> {
+        let sides = self
+            .sidewalk
+            .as_ref()?
+            .split(Self::VALUE_DELIMITER)
+            .map(|s| match s.trim() {
+                "both" => Some(2),
+                "left" | "right" => Some(1),
+                "no" | "none" => Some(0),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()?;
+        sides.into_iter().min()
+    }
+
     fn is_unbikeable(&self) -> bool {
         matches!(
             self.highway,
@@ -292,6 +346,10 @@ impl SpatialEdge for OsmWayDataSerializable {
     fn src_vertex_id(&self) -> usize {
         self.src_vertex_id.0
     }
+
+    fn dst_vertex_id(&self) -> usize {
+        self.dst_vertex_id.0
+    }
 }
 /// shorten the value, assumed a delimited string of categoricals, so that
 /// it contains only the unique set of categories.
@@ -400,6 +458,40 @@ fn join_way_ids(value: &[OsmWayId]) -> Option<String> {
     }
 }
 
+/// collects the crossing tags of the `highway=crossing` nodes along the src->dst path
+/// of a way, skipping the source node so that a crossing at an intersection is
+/// counted once, by the ways arriving at it.
+fn crossing_node_tags(
+    way: &OsmWayData,
+    src_node_id: &OsmNodeId,
+    dst_node_id: &OsmNodeId,
+    graph: &OsmGraph,
+) -> Option<String> {
+    let path = osm_way_ops::extract_between_nodes(src_node_id, dst_node_id, &way.nodes)?;
+    let tags = path
+        .iter()
+        .filter(|n| *n != src_node_id)
+        .filter_map(|n| graph.get_node_data(n).ok())
+        .filter(|n| n.is_crossing())
+        .map(|n| {
+            n.crossing
+                .as_deref()
+                .map(|c| {
+                    c.replace(
+                        OsmNodeData::VALUE_DELIMITER,
+                        OsmWayDataSerializable::VALUE_DELIMITER,
+                    )
+                })
+                .unwrap_or_else(|| String::from("yes"))
+        })
+        .collect_vec();
+    if tags.is_empty() {
+        None
+    } else {
+        Some(tags.join(OsmWayDataSerializable::VALUE_DELIMITER))
+    }
+}
+
 pub fn create_linestring_for_od_path(
     src: &OsmNodeId,
     dst: &OsmNodeId,
@@ -454,3 +546,61 @@ fn top_highway(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::osm::graph::{OsmNodes, OsmWays};
+
+    /// a footway through nodes 0..=4 where node 2 is a marked crossing
+    fn crossing_way_graph() -> (OsmWayData, OsmGraph) {
+        let nodes: OsmNodes = (0..5)
+            .map(|i| {
+                let mut node = OsmNodeData {
+                    osmid: OsmNodeId(i),
+                    x: i as f32,
+                    ..Default::default()
+                };
+                if i == 2 {
+                    node.highway = Some(String::from("crossing"));
+                    node.crossing = Some(String::from("marked"));
+                }
+                (node.osmid, node)
+            })
+            .collect();
+        let way = OsmWayData {
+            osmid: OsmWayId(1),
+            nodes: (0..5).map(OsmNodeId).collect_vec(),
+            highway: Some(String::from("footway")),
+            ..Default::default()
+        };
+        let ways: OsmWays = HashMap::from([(way.osmid, way.clone())]);
+        let graph = OsmGraph::new(nodes, ways).expect("test invariant");
+        (way, graph)
+    }
+
+    #[test]
+    fn test_crossing_node_tags_only_on_edge_reaching_crossing() {
+        let (way, graph) = crossing_way_graph();
+        let tags =
+            |src: i64, dst: i64| crossing_node_tags(&way, &OsmNodeId(src), &OsmNodeId(dst), &graph);
+        assert_eq!(tags(0, 1), None);
+        assert_eq!(tags(1, 2), Some(String::from("marked")));
+        assert_eq!(tags(2, 3), None);
+        assert_eq!(tags(3, 4), None);
+        assert_eq!(tags(0, 2), Some(String::from("marked")));
+        assert_eq!(tags(2, 4), None);
+    }
+
+    #[test]
+    fn test_crossing_node_tags_reverse_edges() {
+        let (mut way, graph) = crossing_way_graph();
+        way.nodes.reverse();
+        let tags =
+            |src: i64, dst: i64| crossing_node_tags(&way, &OsmNodeId(src), &OsmNodeId(dst), &graph);
+        assert_eq!(tags(4, 3), None);
+        assert_eq!(tags(3, 2), Some(String::from("marked")));
+        assert_eq!(tags(2, 1), None);
+        assert_eq!(tags(1, 0), None);
+    }
+}
@@ -1,3 +1,6 @@
+use super::OsmWayData;
+use crate::model::osm::OsmError;
+
 /// internal enumeration used to disambiguate graph update methods associated with
 /// the adjacency list.
 pub enum WayOverwritePolicy {
@@ -7,7 +10,46 @@ pub enum WayOverwritePolicy {
     UpdateAtIndex { index: usize },
     /// overwrite the set of multiedges on this relation
     Replace,
-    // /// way replacement: if a previously-existing way exists, use the OSM Highway
-    // /// tag as a tie-breaker to determine which way to keep.
-    // CompareExistingHighwayTag,
+    /// way replacement: if a previously-existing way exists, use the OSM Highway
+    /// tag as a tie-breaker to determine which way to keep.
+    CompareExistingHighwayTag,
+    /// way replacement: if a previously-existing way exists, keep the way whose value
+    /// for the tag appears earliest in the ranking. ways with values missing from the
+    /// ranking are ranked last.
+    RankByTag { tag: String, ranking: Vec<String> },
+}
+
+impl WayOverwritePolicy {
+    /// the rank of a way under a ranking policy, where lower ranks win. ways without
+    /// a ranked value return None. aggregated ways take the best rank among their values.
+    pub fn rank(&self, way: &OsmWayData) -> Result<Option<usize>, OsmError> {
+        match self {
+            WayOverwritePolicy::CompareExistingHighwayTag => {
+                Ok(way.get_highway()?.map(|h| h.hierarchy() as usize))
+            }
+            WayOverwritePolicy::RankByTag { ranking, .. } => {
+                let rank = self.ranked_value(way)?.and_then(|value| {
+                    value
+                        .split(OsmWayData::VALUE_DELIMITER)
+                        .filter_map(|v| ranking.iter().position(|r| r == v.trim()))
+                        .min()
+                });
+                Ok(rank)
+            }
+            _ => Err(OsmError::InternalError(String::from(
+                "rank called on a way overwrite policy that does not rank ways",
+            ))),
+        }
+    }
+
+    /// the tag value used to rank this way, for reporting
+    pub fn ranked_value(&self, way: &OsmWayData) -> Result<Option<String>, OsmError> {
+        match self {
+            WayOverwritePolicy::CompareExistingHighwayTag => Ok(way.highway.clone()),
+            WayOverwritePolicy::RankByTag { tag, .. } => way
+                .get_string_at_field(tag)
+                .map_err(OsmError::ConfigurationError),
+            _ => Ok(None),
+        }
+    }
 }
@@ -3,7 +3,9 @@ pub mod import_ops;
 mod osm_error;
 mod osm_source;
 pub mod overpass;
+pub mod pedestrian;
 pub mod poi;
+pub mod speed;
 
 pub use osm_error::OsmError;
 pub use osm_source::OsmSource;
@@ -1,11 +1,15 @@
-use super::{graph::osm_element_filter::ElementFilter, OsmError};
+use super::{
+    graph::osm_element_filter::ElementFilter,
+    pedestrian::{pedestrian_ops, PedestrianNetworkConfig},
+    OsmError,
+};
 use crate::{
     algorithm::{
         consolidation, simplification,
         truncation::{self, ComponentFilter},
     },
     model::osm::{
-        graph::{OsmGraph, OsmGraphVectorized},
+        graph::{MultiedgeConflictPolicy, OsmGraph, OsmGraphVectorized},
         import_ops,
     },
 };
@@ -27,6 +31,8 @@ pub enum OsmSource {
         consolidate: bool,
         consolidation_threshold: uom::si::f64::Length,
         parallelize: bool,
+        pedestrian_network: Option<PedestrianNetworkConfig>,
+        multiedge_conflict_policy: MultiedgeConflictPolicy,
     },
 }
 
@@ -44,6 +50,8 @@ impl OsmSource {
                 consolidate,
                 consolidation_threshold,
                 parallelize,
+                pedestrian_network,
+                multiedge_conflict_policy,
             } => {
                 let net_ftr = network_filter.clone().unwrap_or_default();
                 let extent_opt = extent_filter_filepath
@@ -56,8 +64,16 @@ impl OsmSource {
                 // # create buffered graph from the downloaded data
                 eprintln!();
                 log::info!("  (((1))) reading PBF source");
-                let (nodes, ways) = import_ops::read_pbf(pbf_filepath, net_ftr, &extent_opt)?;
-                let mut graph = OsmGraph::new(nodes, ways)?;
+                let (nodes, mut ways) = import_ops::read_pbf(pbf_filepath, net_ftr, &extent_opt)?;
+                if let Some(ped_conf) = pedestrian_network {
+                    log::info!("  (((1b))) filtering ways for pedestrian network");
+                    pedestrian_ops::filter_pedestrian_ways(&mut ways, ped_conf)?;
+                }
+                let mut graph = OsmGraph::new_with_conflict_policy(
+                    nodes,
+                    ways,
+                    multiedge_conflict_policy.clone(),
+                )?;
 
                 // rjf: this is handled above in import_ops::read_pbf for performance reasons
                 // # truncate buffered graph to the buffered polygon and retain_all for
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// classification of a pedestrian crossing, interpreted from the OSM `crossing` key.
+/// see <https://wiki.openstreetmap.org/wiki/Key:crossing> for details.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossingType {
+    /// crossing controlled by traffic signals
+    Signalized,
+    /// crossing with road markings but without signals
+    Marked,
+    /// crossing without signals or priority for pedestrians
+    Uncontrolled,
+    /// crossing location with no markings or signals
+    Unmarked,
+}
+
+impl CrossingType {
+    /// interprets a single OSM `crossing` value. values that state there is no
+    /// crossing (`no`) produce None, while unrecognized values are treated as
+    /// uncontrolled crossings.
+    pub fn from_osm_value(value: &str) -> Option<CrossingType> {
+        match value.trim() {
+            "" | "no" => None,
+            "traffic_signals" | "pelican" | "toucan" | "pegasus" => Some(CrossingType::Signalized),
+            "marked" | "zebra" => Some(CrossingType::Marked),
+            "unmarked" | "informal" => Some(CrossingType::Unmarked),
+            _ => Some(CrossingType::Uncontrolled),
+        }
+    }
+}
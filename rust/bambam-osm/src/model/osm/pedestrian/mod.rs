@@ -0,0 +1,6 @@
+mod crossing_type;
+mod pedestrian_network_config;
+pub mod pedestrian_ops;
+
+pub use crossing_type::CrossingType;
+pub use pedestrian_network_config::{CrossingDelays, PedestrianNetworkConfig};
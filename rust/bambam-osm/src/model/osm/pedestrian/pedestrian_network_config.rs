@@ -0,0 +1,78 @@
+use super::CrossingType;
+use serde::{Deserialize, Serialize};
+
+/// configures an import that derives a pedestrian network from the road and
+/// footway data found in OSM. when provided, ways are filtered by their
+/// sidewalk and footway tags and a walk speed file is written alongside the
+/// compass dataset.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PedestrianNetworkConfig {
+    /// walking speed in kilometers per hour, used to convert crossing delays
+    /// into effective edge speeds.
+    #[serde(default = "PedestrianNetworkConfig::default_walk_speed_kph")]
+    pub walk_speed_kph: f64,
+    /// when true, road centerlines tagged `sidewalk=separate` are removed since
+    /// their sidewalks are mapped as `footway=sidewalk` ways.
+    #[serde(default = "PedestrianNetworkConfig::default_true")]
+    pub prefer_separate_sidewalks: bool,
+    /// when true, road centerlines without a `sidewalk` tag remain walkable.
+    #[serde(default = "PedestrianNetworkConfig::default_true")]
+    pub keep_untagged_centerlines: bool,
+    /// if provided, delays applied to edges that traverse a crossing.
+    #[serde(default)]
+    pub crossing_delays: Option<CrossingDelays>,
+}
+
+impl Default for PedestrianNetworkConfig {
+    fn default() -> Self {
+        Self {
+            walk_speed_kph: Self::default_walk_speed_kph(),
+            prefer_separate_sidewalks: true,
+            keep_untagged_centerlines: true,
+            crossing_delays: None,
+        }
+    }
+}
+
+impl PedestrianNetworkConfig {
+    fn default_walk_speed_kph() -> f64 {
+        5.0
+    }
+
+    fn default_true() -> bool {
+        true
+    }
+}
+
+/// delay in seconds incurred when traversing each type of crossing.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CrossingDelays {
+    pub signalized: f64,
+    pub marked: f64,
+    pub uncontrolled: f64,
+    pub unmarked: f64,
+}
+
+impl Default for CrossingDelays {
+    fn default() -> Self {
+        Self {
+            signalized: 30.0,
+            marked: 5.0,
+            uncontrolled: 10.0,
+            unmarked: 15.0,
+        }
+    }
+}
+
+impl CrossingDelays {
+    /// the delay in seconds for crossing type
+    pub fn get(&self, crossing_type: &CrossingType) -> f64 {
+        match crossing_type {
+            CrossingType::Signalized => self.signalized,
+            CrossingType::Marked => self.marked,
+            CrossingType::Uncontrolled => self.uncontrolled,
+            CrossingType::Unmarked => self.unmarked,
+        }
+    }
+}
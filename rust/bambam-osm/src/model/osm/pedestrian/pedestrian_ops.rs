@@ -0,0 +1,330 @@
+use super::{CrossingDelays, CrossingType, PedestrianNetworkConfig};
+use crate::model::{
+    feature::highway::Highway,
+    osm::{
+        graph::{
+            create_writer, OsmGraphVectorized, OsmWayData, OsmWayDataSerializable, OsmWayId,
+            OsmWays,
+        },
+        OsmError,
+    },
+};
+use csv::QuoteStyle;
+use kdam::tqdm;
+use std::path::Path;
+
+pub mod filenames {
+    pub const SPEED_PEDESTRIAN: &str = "speed-pedestrian-enumerated.txt.gz";
+    pub const CROSSING_DELAY: &str = "edges-crossing-delay-enumerated.txt.gz";
+}
+
+/// removes ways that should not be part of a pedestrian network:
+/// - pedestrian infrastructure (footways, paths, steps, ...) is always kept,
+///   including separately-mapped `footway=sidewalk` and `footway=crossing` ways
+/// - road centerlines tagged `sidewalk=no` are removed
+/// - road centerlines tagged `sidewalk=separate` are removed if the configuration
+///   prefers the separately-mapped sidewalk ways
+/// - road centerlines tagged `sidewalk=both|left|right|yes` are kept
+/// - motorways and trunk roads are removed unless they explicitly have a sidewalk
+pub fn filter_pedestrian_ways(
+    ways: &mut OsmWays,
+    conf: &PedestrianNetworkConfig,
+) -> Result<(), OsmError> {
+    let n_ways = ways.len();
+    let mut rejected: Vec<OsmWayId> = vec![];
+    let ways_iter = tqdm!(
+        ways.values(),
+        total = n_ways,
+        desc = "filter ways for pedestrian network"
+    );
+    for way in ways_iter {
+        if !accept_pedestrian_way(way, conf)? {
+            rejected.push(way.osmid);
+        }
+    }
+    eprintln!();
+
+    for way_id in rejected.iter() {
+        ways.remove(way_id);
+    }
+    log::info!(
+        "pedestrian network filter removed {} of {} ways",
+        rejected.len(),
+        n_ways
+    );
+    if ways.is_empty() {
+        return Err(OsmError::NoWaysFound);
+    }
+    Ok(())
+}
+
+/// tests whether a single (raw, unaggregated) way belongs in a pedestrian network.
+pub fn accept_pedestrian_way(
+    way: &OsmWayData,
+    conf: &PedestrianNetworkConfig,
+) -> Result<bool, OsmError> {
+    let highway = match way.get_highway()? {
+        Some(h) => h,
+        None => return Ok(false),
+    };
+    if is_pedestrian_highway(&highway) || is_sidewalk_or_crossing(way) {
+        return Ok(true);
+    }
+    let sidewalk = way.sidewalk.as_deref().map(str::trim);
+    match sidewalk {
+        Some("no") | Some("none") => Ok(false),
+        Some("separate") => Ok(!conf.prefer_separate_sidewalks),
+        Some("both") | Some("left") | Some("right") | Some("yes") => Ok(true),
+        _ if is_pedestrian_prohibited(&highway) => Ok(false),
+        _ => Ok(conf.keep_untagged_centerlines),
+    }
+}
+
+/// collects the crossings traversed by this way. aggregated ways may traverse
+/// more than one crossing. ways mapped as crossings without a `crossing` tag
+/// are treated as uncontrolled.
+///
+/// crossings are also commonly mapped as `highway=crossing` nodes where a footway
+/// meets the road. these are counted for pedestrian infrastructure that is not
+/// itself mapped as a crossing, since the crossing way already accounts for
+/// them, and never for road centerlines, which pass through the node without
+/// crossing the road.
+pub fn crossing_types(way: &OsmWayDataSerializable) -> Vec<CrossingType> {
+    crossing_types_from_tags(
+        &way.highway,
+        way.footway.as_deref(),
+        way.crossing.as_deref(),
+        way.crossing_nodes.as_deref(),
+    )
+}
+
+fn crossing_types_from_tags(
+    highway: &Highway,
+    footway: Option<&str>,
+    crossing: Option<&str>,
+    crossing_nodes: Option<&str>,
+) -> Vec<CrossingType> {
+    let parse = |tags: &str| {
+        tags.split(OsmWayDataSerializable::VALUE_DELIMITER)
+            .filter_map(CrossingType::from_osm_value)
+            .collect::<Vec<_>>()
+    };
+    let is_crossing_way = *highway == Highway::Crossing
+        || footway.is_some_and(|f| {
+            f.split(OsmWayDataSerializable::VALUE_DELIMITER)
+                .any(|v| v == "crossing")
+        });
+    match (crossing, crossing_nodes) {
+        (Some(crossing), _) => parse(crossing),
+        (None, _) if is_crossing_way => vec![CrossingType::Uncontrolled],
+        (None, Some(nodes)) if is_pedestrian_highway(highway) => parse(nodes),
+        _ => vec![],
+    }
+}
+
+/// total delay in seconds incurred by the crossings along this way.
+pub fn crossing_delay_seconds(way: &OsmWayDataSerializable, delays: &CrossingDelays) -> f64 {
+    crossing_types(way).iter().map(|c| delays.get(c)).sum()
+}
+
+/// the speed in kph which, applied over the length of the way, produces the same
+/// travel time as walking the way at the walk speed and then waiting out the delay.
+pub fn effective_walk_speed_kph(
+    length_meters: f64,
+    walk_speed_kph: f64,
+    delay_seconds: f64,
+) -> f64 {
+    if length_meters <= 0.0 || delay_seconds <= 0.0 {
+        return walk_speed_kph;
+    }
+    let walk_speed_mps = walk_speed_kph / 3.6;
+    let travel_time_seconds = length_meters / walk_speed_mps + delay_seconds;
+    (length_meters / travel_time_seconds) * 3.6
+}
+
+/// writes the pedestrian-specific enumerated files to the output directory. edges are
+/// enumerated in the same order as the files written by [`crate::model::osm::graph::CompassWriter`]:
+/// - a walk speed (kph) per edge which accounts for crossing delays. this can be
+///   consumed by a speed traversal model in place of a fixed walk speed.
+/// - the crossing delay (seconds) per edge
+pub fn write_pedestrian_dataset(
+    graph: &OsmGraphVectorized,
+    conf: &PedestrianNetworkConfig,
+    output_directory: &Path,
+    overwrite: bool,
+) -> Result<(), OsmError> {
+    let mut speed_writer = create_writer(
+        output_directory,
+        filenames::SPEED_PEDESTRIAN,
+        false,
+        QuoteStyle::Never,
+        overwrite,
+    );
+    let mut delay_writer = create_writer(
+        output_directory,
+        filenames::CROSSING_DELAY,
+        false,
+        QuoteStyle::Never,
+        overwrite,
+    );
+
+    let e_iter = tqdm!(
+        graph.ways.iter(),
+        total = graph.ways.len(),
+        desc = "write pedestrian dataset"
+    );
+    for row in e_iter {
+        let delay = conf
+            .crossing_delays
+            .as_ref()
+            .map(|delays| crossing_delay_seconds(row, delays))
+            .unwrap_or_default();
+        if let Some(ref mut writer) = speed_writer {
+            let speed = effective_walk_speed_kph(row.length_meters, conf.walk_speed_kph, delay);
+            writer.serialize(speed).map_err(|e| {
+                OsmError::CsvWriteError(String::from(filenames::SPEED_PEDESTRIAN), e)
+            })?;
+        }
+        if let Some(ref mut writer) = delay_writer {
+            writer
+                .serialize(delay)
+                .map_err(|e| OsmError::CsvWriteError(String::from(filenames::CROSSING_DELAY), e))?;
+        }
+    }
+    eprintln!();
+
+    Ok(())
+}
+
+/// highway types that are pedestrian infrastructure in their own right
+fn is_pedestrian_highway(highway: &Highway) -> bool {
+    matches!(
+        highway,
+        Highway::Footway
+            | Highway::Sidewalk
+            | Highway::Crossing
+            | Highway::Pedestrian
+            | Highway::LivingStreet
+            | Highway::Path
+            | Highway::Steps
+            | Highway::Corridor
+            | Highway::Elevator
+            | Highway::Track
+            | Highway::Bridleway
+    )
+}
+
+/// highway types where walking is not expected without an explicit sidewalk
+fn is_pedestrian_prohibited(highway: &Highway) -> bool {
+    matches!(
+        highway,
+        Highway::Motorway | Highway::MotorwayLink | Highway::Trunk | Highway::TrunkLink
+    )
+}
+
+/// separately-mapped sidewalks and crossings, as in `footway=sidewalk` or `footway=crossing`
+fn is_sidewalk_or_crossing(way: &OsmWayData) -> bool {
+    matches!(
+        way.footway.as_deref().map(str::trim),
+        Some("sidewalk") | Some("crossing")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn way(highway: &str, sidewalk: Option<&str>, footway: Option<&str>) -> OsmWayData {
+        OsmWayData {
+            highway: Some(highway.to_string()),
+            sidewalk: sidewalk.map(String::from),
+            footway: footway.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accept_pedestrian_way() {
+        let conf = PedestrianNetworkConfig::default();
+        let accept = |w: &OsmWayData| accept_pedestrian_way(w, &conf).expect("test invariant");
+        assert!(accept(&way("footway", None, Some("sidewalk"))));
+        assert!(accept(&way("footway", None, Some("crossing"))));
+        assert!(accept(&way("residential", Some("both"), None)));
+        assert!(accept(&way("primary", Some("right"), None)));
+        assert!(accept(&way("residential", None, None)));
+        assert!(!accept(&way("residential", Some("no"), None)));
+        assert!(!accept(&way("secondary", Some("separate"), None)));
+        assert!(!accept(&way("motorway", None, None)));
+    }
+
+    #[test]
+    fn test_accept_pedestrian_way_keep_separate() {
+        let conf = PedestrianNetworkConfig {
+            prefer_separate_sidewalks: false,
+            keep_untagged_centerlines: false,
+            ..Default::default()
+        };
+        let accept = |w: &OsmWayData| accept_pedestrian_way(w, &conf).expect("test invariant");
+        assert!(accept(&way("secondary", Some("separate"), None)));
+        assert!(!accept(&way("residential", None, None)));
+    }
+
+    #[test]
+    fn test_effective_walk_speed() {
+        // 100m at 3.6 kph (1 m/s) takes 100 seconds, plus 100 seconds of delay
+        let speed = effective_walk_speed_kph(100.0, 3.6, 100.0);
+        assert!((speed - 1.8).abs() < 1e-9);
+        assert_eq!(effective_walk_speed_kph(100.0, 5.0, 0.0), 5.0);
+        assert_eq!(effective_walk_speed_kph(0.0, 5.0, 30.0), 5.0);
+    }
+
+    #[test]
+    fn test_crossing_types_from_crossing_nodes() {
+        // a sidewalk arriving at a signalized highway=crossing node
+        assert_eq!(
+            crossing_types_from_tags(
+                &Highway::Footway,
+                Some("sidewalk"),
+                None,
+                Some("traffic_signals")
+            ),
+            vec![CrossingType::Signalized]
+        );
+        // crossing nodes without a crossing tag are uncontrolled
+        assert_eq!(
+            crossing_types_from_tags(&Highway::Footway, None, None, Some("yes;zebra")),
+            vec![CrossingType::Uncontrolled, CrossingType::Marked]
+        );
+        // road centerlines pass through the crossing node without crossing
+        assert!(
+            crossing_types_from_tags(&Highway::Residential, None, None, Some("zebra")).is_empty()
+        );
+        // crossing ways count their own crossing once, not again for their nodes
+        assert_eq!(
+            crossing_types_from_tags(
+                &Highway::Footway,
+                Some("crossing"),
+                Some("marked"),
+                Some("zebra")
+            ),
+            vec![CrossingType::Marked]
+        );
+        assert_eq!(
+            crossing_types_from_tags(&Highway::Footway, Some("crossing"), None, Some("zebra")),
+            vec![CrossingType::Uncontrolled]
+        );
+    }
+
+    #[test]
+    fn test_crossing_type_from_osm_value() {
+        assert_eq!(
+            CrossingType::from_osm_value("traffic_signals"),
+            Some(CrossingType::Signalized)
+        );
+        assert_eq!(
+            CrossingType::from_osm_value("zebra"),
+            Some(CrossingType::Marked)
+        );
+        assert_eq!(CrossingType::from_osm_value("no"), None);
+    }
+}
@@ -0,0 +1,188 @@
+use super::time_period_profile::{parse_time_of_day, time_ranges_overlap, Weekdays};
+use crate::model::osm::graph::osm_way_ops;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// a single `maxspeed:conditional` entry, such as `30 @ (Mo-Fr 07:00-09:00)`.
+/// see <https://wiki.openstreetmap.org/wiki/Conditional_restrictions>.
+///
+/// only the weekday and time-of-day portions of a condition are interpreted.
+/// conditions without a time range (such as `wet` or `weight>7.5`) are retained
+/// but never considered active, as are rules with other selectors such as `PH`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionalSpeed {
+    pub speed_kph: f64,
+    pub condition: String,
+    pub time_ranges: Vec<ConditionalTimeRange>,
+}
+
+/// a time-of-day range of a condition along with the days it applies to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConditionalTimeRange {
+    pub days: Weekdays,
+    /// minutes after midnight
+    pub start_minute: u32,
+    /// minutes after midnight
+    pub end_minute: u32,
+}
+
+impl ConditionalSpeed {
+    /// parses all entries in a `maxspeed:conditional` value. entries are separated
+    /// by semicolons outside of parentheses. entries that cannot be parsed are skipped.
+    pub fn parse_all(value: &str) -> Vec<ConditionalSpeed> {
+        split_top_level(value)
+            .into_iter()
+            .filter_map(ConditionalSpeed::parse)
+            .collect()
+    }
+
+    /// parses a single `<speed> @ <condition>` entry.
+    pub fn parse(entry: &str) -> Option<ConditionalSpeed> {
+        let (speed_str, condition_str) = entry.split_once('@')?;
+        let speed = osm_way_ops::deserialize_speed(speed_str.trim(), None, true).ok()??;
+        let condition = condition_str
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim()
+            .to_string();
+        let time_ranges = condition
+            .split(';')
+            .flat_map(parse_rule_time_ranges)
+            .collect();
+        Some(ConditionalSpeed {
+            speed_kph: speed.get::<uom::si::velocity::kilometer_per_hour>(),
+            condition,
+            time_ranges,
+        })
+    }
+
+    pub fn is_time_based(&self) -> bool {
+        !self.time_ranges.is_empty()
+    }
+
+    /// true if any time range of this condition overlaps the provided range on
+    /// any of the provided days.
+    pub fn is_active_during(&self, days: &Weekdays, start_minute: u32, end_minute: u32) -> bool {
+        self.time_ranges.iter().any(|range| {
+            range.days.intersects(days)
+                && time_ranges_overlap(
+                    (range.start_minute, range.end_minute),
+                    (start_minute, end_minute),
+                )
+        })
+    }
+}
+
+/// parses the time ranges of one rule of a condition, such as `Mo-Fr 07:00-09:00,16:00-18:00`.
+/// the weekday selector precedes the first time range and applies to all of them.
+fn parse_rule_time_ranges(rule: &str) -> Vec<ConditionalTimeRange> {
+    let captures = time_range_regex().captures_iter(rule).collect::<Vec<_>>();
+    let Some(first) = captures.first().and_then(|c| c.get(0)) else {
+        return vec![];
+    };
+    let selector = rule[..first.start()].trim();
+    let days = if selector.is_empty() {
+        Weekdays::default()
+    } else {
+        match Weekdays::parse(selector) {
+            Some(days) => days,
+            None => return vec![],
+        }
+    };
+    captures
+        .iter()
+        .filter_map(|c| {
+            let start_minute = parse_time_of_day(c.get(1)?.as_str()).ok()?;
+            let end_minute = parse_time_of_day(c.get(2)?.as_str()).ok()?;
+            Some(ConditionalTimeRange {
+                days,
+                start_minute,
+                end_minute,
+            })
+        })
+        .collect()
+}
+
+fn time_range_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(\d{1,2}:\d{2})\s*-\s*(\d{1,2}:\d{2})").expect("time range regex is valid")
+    })
+}
+
+/// splits on semicolons that are not enclosed in parentheses.
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth: i32 = 0;
+    let mut start = 0;
+    for (idx, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' if depth <= 0 => {
+                result.push(&value[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&value[start..]);
+    result
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConditionalSpeed;
+    use crate::model::osm::speed::Weekdays;
+
+    fn all_days() -> Weekdays {
+        Weekdays::default()
+    }
+
+    #[test]
+    fn test_parse_conditional_speeds() {
+        let value = "30 @ (Mo-Fr 07:00-09:00; Sa 10:00-12:00); 80 @ wet";
+        let parsed = ConditionalSpeed::parse_all(value);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].speed_kph, 30.0);
+        let ranges = parsed[0]
+            .time_ranges
+            .iter()
+            .map(|r| (r.start_minute, r.end_minute))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(420, 540), (600, 720)]);
+        assert!(parsed[0].is_active_during(&all_days(), 480, 600));
+        assert!(!parsed[0].is_active_during(&all_days(), 900, 960));
+        assert!(!parsed[1].is_time_based());
+    }
+
+    #[test]
+    fn test_parse_conditional_speed_mph_overnight() {
+        let parsed = ConditionalSpeed::parse_all("20 mph @ (22:00-06:00)");
+        assert_eq!(parsed.len(), 1);
+        assert!((parsed[0].speed_kph - 32.18688).abs() < 1e-6);
+        assert!(parsed[0].is_active_during(&all_days(), 0, 60));
+        assert!(!parsed[0].is_active_during(&all_days(), 720, 780));
+    }
+
+    #[test]
+    fn test_weekday_condition_inactive_on_weekend() {
+        let parsed = ConditionalSpeed::parse_all("30 @ (Mo-Fr 07:00-09:00; Sa 10:00-12:00)");
+        let weekdays = Weekdays::parse("Mo-Fr").unwrap();
+        let saturday = Weekdays::parse("Sa").unwrap();
+        let sunday = Weekdays::parse("Su").unwrap();
+        assert!(parsed[0].is_active_during(&weekdays, 420, 540));
+        assert!(!parsed[0].is_active_during(&saturday, 420, 540));
+        assert!(parsed[0].is_active_during(&saturday, 600, 720));
+        assert!(!parsed[0].is_active_during(&sunday, 0, 1440));
+
+        // rules with selectors other than weekdays are never active
+        let holiday = ConditionalSpeed::parse_all("30 @ (PH 07:00-09:00)");
+        assert!(!holiday[0].is_time_based());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+const KPH_PER_MPH: f64 = 1.609344;
+
+/// countries where numeric zone values are posted in miles per hour
+const MPH_COUNTRIES: [&str; 4] = ["US", "GB", "UK", "LR"];
+
+/// lookup for implicit speed limits, such as `DE:urban` or `US:zone25`, which
+/// are used in the `maxspeed`, `zone:maxspeed`, and `maxspeed:type` OSM keys.
+/// see <https://wiki.openstreetmap.org/wiki/Default_speed_limits>.
+///
+/// speeds are stored in kilometers per hour.
+#[derive(Debug, Clone)]
+pub struct ImplicitSpeedTable {
+    speeds: HashMap<String, f64>,
+}
+
+impl Default for ImplicitSpeedTable {
+    fn default() -> Self {
+        let mph = |v: f64| v * KPH_PER_MPH;
+        let speeds = [
+            ("AT:urban", 50.0),
+            ("AT:rural", 100.0),
+            ("AT:motorway", 130.0),
+            ("CH:urban", 50.0),
+            ("CH:rural", 80.0),
+            ("CH:trunk", 100.0),
+            ("CH:motorway", 120.0),
+            ("DE:living_street", 7.0),
+            ("DE:bicycle_road", 30.0),
+            ("DE:urban", 50.0),
+            ("DE:rural", 100.0),
+            ("DE:motorway", 130.0),
+            ("FR:urban", 50.0),
+            ("FR:rural", 80.0),
+            ("FR:motorway", 130.0),
+            ("GB:nsl_single", mph(60.0)),
+            ("GB:nsl_dual", mph(70.0)),
+            ("GB:motorway", mph(70.0)),
+            ("IT:urban", 50.0),
+            ("IT:rural", 90.0),
+            ("IT:motorway", 130.0),
+            ("NL:urban", 50.0),
+            ("NL:rural", 80.0),
+            ("NL:motorway", 130.0),
+            ("RU:living_street", 20.0),
+            ("RU:urban", 60.0),
+            ("RU:rural", 90.0),
+            ("RU:motorway", 110.0),
+            ("US:urban", mph(25.0)),
+            ("US:rural", mph(55.0)),
+            ("US:motorway", mph(65.0)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (String::from(k), v))
+        .collect();
+        Self { speeds }
+    }
+}
+
+impl ImplicitSpeedTable {
+    /// builds the default table with user-provided entries (in kph) taking precedence.
+    pub fn new(overrides: &HashMap<String, f64>) -> ImplicitSpeedTable {
+        let mut table = ImplicitSpeedTable::default();
+        for (k, v) in overrides.iter() {
+            table.speeds.insert(k.clone(), *v);
+        }
+        table
+    }
+
+    /// finds the speed in kph for an implicit value. values found in the table
+    /// are used directly. otherwise, numeric zones such as `DE:30`, `DE:zone30`
+    /// or `US:zone25` are parsed, using mph for countries that post speeds in mph.
+    pub fn get(&self, value: &str) -> Option<f64> {
+        let value = value.trim();
+        if let Some(kph) = self.speeds.get(value) {
+            return Some(*kph);
+        }
+        let (country, zone) = value.split_once(':')?;
+        let zone = zone.trim_start_matches("zone").trim();
+        let (number, is_mph) = match zone.strip_suffix("mph") {
+            Some(n) => (n.trim(), true),
+            None => (zone, MPH_COUNTRIES.contains(&country)),
+        };
+        let speed = number.parse::<f64>().ok().filter(|s| *s > 0.0)?;
+        if is_mph {
+            Some(speed * KPH_PER_MPH)
+        } else {
+            Some(speed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImplicitSpeedTable;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_implicit_speed_lookup() {
+        let table = ImplicitSpeedTable::default();
+        assert_eq!(table.get("DE:urban"), Some(50.0));
+        assert_eq!(table.get("DE:zone30"), Some(30.0));
+        assert_eq!(table.get("FR:30"), Some(30.0));
+        let us_zone = table.get("US:zone25").expect("should parse zone");
+        assert!((us_zone - 40.2336).abs() < 1e-6);
+        assert_eq!(table.get("sign"), None);
+        assert_eq!(table.get("XX:unknown"), None);
+    }
+
+    #[test]
+    fn test_implicit_speed_overrides() {
+        let overrides = HashMap::from([(String::from("US:urban"), 48.0)]);
+        let table = ImplicitSpeedTable::new(&overrides);
+        assert_eq!(table.get("US:urban"), Some(48.0));
+    }
+}
@@ -0,0 +1,12 @@
+mod conditional_speed;
+mod implicit_speed;
+mod speed_model_config;
+pub mod speed_ops;
+mod speed_provenance;
+mod time_period_profile;
+
+pub use conditional_speed::{ConditionalSpeed, ConditionalTimeRange};
+pub use implicit_speed::ImplicitSpeedTable;
+pub use speed_model_config::{LanesAdjustment, SpeedModelConfig};
+pub use speed_provenance::{SpeedProvenance, SpeedRule};
+pub use time_period_profile::{TimePeriod, TimePeriodProfile, Weekdays};
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// configures the drive speed model written alongside the compass dataset.
+/// when provided, each edge receives a free-flow speed inferred from (in order):
+/// numeric `maxspeed`, implicit `maxspeed` (e.g. `US:urban`), `zone:maxspeed`,
+/// `maxspeed:type`, and finally the highway-class and global averages of `maxspeed`.
+/// lanes and surface adjustments are then applied, and if a time period profile
+/// is provided, a speed file is written for each period.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SpeedModelConfig {
+    /// implicit speed limits in kph keyed by their OSM value, such as `US:urban`.
+    /// these take precedence over the built-in defaults.
+    #[serde(default)]
+    pub implicit_speeds: HashMap<String, f64>,
+    /// if provided, adjusts speeds that were filled from network averages by the
+    /// number of lanes in each direction.
+    #[serde(default)]
+    pub lanes_adjustment: Option<LanesAdjustment>,
+    /// factors applied to speeds by OSM `surface` value. an empty collection
+    /// disables the surface adjustment.
+    #[serde(default = "SpeedModelConfig::default_surface_factors")]
+    pub surface_factors: HashMap<String, f64>,
+    /// optional CSV file with speed factors by time period and highway class,
+    /// with columns `period,start_time,end_time,highway,speed_factor` and an optional `days` column.
+    #[serde(default)]
+    pub time_period_profile_input_file: Option<String>,
+}
+
+impl Default for SpeedModelConfig {
+    fn default() -> Self {
+        Self {
+            implicit_speeds: HashMap::new(),
+            lanes_adjustment: None,
+            surface_factors: Self::default_surface_factors(),
+            time_period_profile_input_file: None,
+        }
+    }
+}
+
+impl SpeedModelConfig {
+    fn default_surface_factors() -> HashMap<String, f64> {
+        [
+            ("compacted", 0.8),
+            ("fine_gravel", 0.75),
+            ("gravel", 0.7),
+            ("unpaved", 0.6),
+            ("cobblestone", 0.7),
+            ("sett", 0.8),
+            ("dirt", 0.5),
+            ("earth", 0.5),
+            ("ground", 0.5),
+            ("grass", 0.4),
+            ("sand", 0.4),
+            ("mud", 0.3),
+        ]
+        .into_iter()
+        .map(|(k, v)| (String::from(k), v))
+        .collect()
+    }
+}
+
+/// speed factors by lanes per direction. for two-way roads, the OSM `lanes`
+/// tag is split evenly between directions.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LanesAdjustment {
+    pub single_lane_factor: f64,
+    pub multi_lane_factor: f64,
+}
+
+impl Default for LanesAdjustment {
+    fn default() -> Self {
+        Self {
+            single_lane_factor: 0.9,
+            multi_lane_factor: 1.1,
+        }
+    }
+}
+
+impl LanesAdjustment {
+    /// the factor for a way with the given number of lanes per direction
+    pub fn factor(&self, lanes_per_direction: f64) -> f64 {
+        if lanes_per_direction <= 1.0 {
+            self.single_lane_factor
+        } else {
+            self.multi_lane_factor
+        }
+    }
+}
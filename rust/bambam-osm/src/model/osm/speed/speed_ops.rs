@@ -0,0 +1,277 @@
+use super::{
+    ConditionalSpeed, ImplicitSpeedTable, LanesAdjustment, SpeedModelConfig, SpeedProvenance,
+    SpeedRule, TimePeriod, TimePeriodProfile,
+};
+use crate::model::osm::{
+    graph::{
+        create_writer, fill_value_lookup::FillValueLookup, osm_way_ops, OsmGraphVectorized,
+        OsmWayDataSerializable,
+    },
+    OsmError,
+};
+use csv::QuoteStyle;
+use kdam::tqdm;
+use std::{collections::HashMap, path::Path};
+
+pub mod filenames {
+    pub const SPEED_FREE_FLOW: &str = "speed-free-flow-enumerated.txt.gz";
+    pub const SPEED_PROVENANCE: &str = "edges-speed-provenance-enumerated.txt.gz";
+
+    /// speed file for a named time period
+    pub fn speed_for_period(period: &str) -> String {
+        format!("speed-{period}-enumerated.txt.gz")
+    }
+}
+
+/// a speed in kph along with the rules used to infer it
+#[derive(Debug, Clone, Copy)]
+pub struct InferredSpeed {
+    pub speed_kph: f64,
+    pub provenance: SpeedProvenance,
+}
+
+/// infers edge speeds from OSM speed tags, falling back to network averages.
+pub struct SpeedModel {
+    implicit_speeds: ImplicitSpeedTable,
+    fill_lookup: FillValueLookup,
+    lanes_adjustment: Option<LanesAdjustment>,
+    surface_factors: HashMap<String, f64>,
+    pub profile: Option<TimePeriodProfile>,
+}
+
+impl SpeedModel {
+    pub fn new(
+        conf: &SpeedModelConfig,
+        ways: &[OsmWayDataSerializable],
+    ) -> Result<SpeedModel, OsmError> {
+        let maxspeed_cb = |r: &OsmWayDataSerializable| {
+            r.get_speed("maxspeed", true)
+                .map_err(OsmError::InternalError)
+                .map(|maxspeed_opt| {
+                    maxspeed_opt
+                        .map(|maxspeed| maxspeed.get::<uom::si::velocity::kilometer_per_hour>())
+                })
+        };
+        let fill_lookup = FillValueLookup::new(ways, "highway", "maxspeed", maxspeed_cb)?;
+        let profile = conf
+            .time_period_profile_input_file
+            .as_deref()
+            .map(TimePeriodProfile::from_csv)
+            .transpose()?;
+        Ok(SpeedModel {
+            implicit_speeds: ImplicitSpeedTable::new(&conf.implicit_speeds),
+            fill_lookup,
+            lanes_adjustment: conf.lanes_adjustment.clone(),
+            surface_factors: conf.surface_factors.clone(),
+            profile,
+        })
+    }
+
+    /// the free-flow speed of a way, which ignores conditional speeds.
+    pub fn free_flow(&self, way: &OsmWayDataSerializable) -> InferredSpeed {
+        let (speed_kph, rule) = match self.tagged_speed(way) {
+            Some(tagged) => tagged,
+            None => self.fill_speed(way),
+        };
+        self.adjust(way, speed_kph, SpeedProvenance::new(rule))
+    }
+
+    /// the speed of a way during a time period. time-based conditional speeds active
+    /// during the period replace the tagged or filled speed, and the period's speed
+    /// factor for the way's highway class is applied last.
+    pub fn during_period(
+        &self,
+        way: &OsmWayDataSerializable,
+        period: &TimePeriod,
+    ) -> InferredSpeed {
+        let conditional = way
+            .maxspeed_conditional
+            .as_deref()
+            .map(ConditionalSpeed::parse_all)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| c.is_active_during(&period.days, period.start_minute, period.end_minute))
+            .map(|c| c.speed_kph)
+            .min_by(|a, b| a.total_cmp(b));
+        let mut result = match conditional {
+            Some(speed_kph) => {
+                self.adjust(way, speed_kph, SpeedProvenance::new(SpeedRule::Conditional))
+            }
+            None => self.free_flow(way),
+        };
+        let factor = self
+            .profile
+            .as_ref()
+            .map(|p| p.speed_factor(&period.name, &way.highway))
+            .unwrap_or(1.0);
+        if factor != 1.0 {
+            result.speed_kph *= factor;
+            result.provenance.period_adjusted = true;
+        }
+        result
+    }
+
+    /// finds a speed from the speed tags on this way, if any
+    fn tagged_speed(&self, way: &OsmWayDataSerializable) -> Option<(f64, SpeedRule)> {
+        let delimiter = OsmWayDataSerializable::VALUE_DELIMITER;
+        if let Some(raw) = &way.maxspeed_raw {
+            if let Ok(Some(speed)) = osm_way_ops::deserialize_speed(raw, Some(delimiter), true) {
+                let kph = speed.get::<uom::si::velocity::kilometer_per_hour>();
+                return Some((kph, SpeedRule::Maxspeed));
+            }
+            if let Some(kph) = self.min_implicit_speed(raw) {
+                return Some((kph, SpeedRule::ImplicitMaxspeed));
+            }
+        }
+        if let Some(kph) = way
+            .zone_maxspeed
+            .as_deref()
+            .and_then(|z| self.min_implicit_speed(z))
+        {
+            return Some((kph, SpeedRule::ZoneMaxspeed));
+        }
+        way.maxspeed_type
+            .as_deref()
+            .and_then(|t| self.min_implicit_speed(t))
+            .map(|kph| (kph, SpeedRule::MaxspeedType))
+    }
+
+    /// falls back to the average speed of the highway class or of the whole network
+    fn fill_speed(&self, way: &OsmWayDataSerializable) -> (f64, SpeedRule) {
+        let highway = way.highway.to_string();
+        match self.fill_lookup.values_by_class.get(&highway) {
+            Some(speed) => (*speed, SpeedRule::HighwayFill),
+            None => (self.fill_lookup.global_average, SpeedRule::GlobalFill),
+        }
+    }
+
+    /// applies lanes (filled speeds only) and surface adjustments
+    fn adjust(
+        &self,
+        way: &OsmWayDataSerializable,
+        speed_kph: f64,
+        mut provenance: SpeedProvenance,
+    ) -> InferredSpeed {
+        let mut speed = speed_kph;
+        if let (Some(adjustment), true) = (&self.lanes_adjustment, provenance.rule.is_fill()) {
+            if let Some(lanes) = lanes_per_direction(way) {
+                speed *= adjustment.factor(lanes);
+                provenance.lanes_adjusted = true;
+            }
+        }
+        let surface_factor = way.surface.as_ref().and_then(|surface| {
+            surface
+                .split(OsmWayDataSerializable::VALUE_DELIMITER)
+                .filter_map(|s| self.surface_factors.get(s.trim()))
+                .cloned()
+                .min_by(|a, b| a.total_cmp(b))
+        });
+        if let Some(factor) = surface_factor {
+            speed *= factor;
+            provenance.surface_adjusted = true;
+        }
+        InferredSpeed {
+            speed_kph: speed,
+            provenance,
+        }
+    }
+
+    fn min_implicit_speed(&self, value: &str) -> Option<f64> {
+        value
+            .split(OsmWayDataSerializable::VALUE_DELIMITER)
+            .filter_map(|v| self.implicit_speeds.get(v))
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+/// writes the speed model files to the output directory, enumerated in the same
+/// order as the edges written by [`crate::model::osm::graph::CompassWriter`]:
+/// - free-flow speeds (kph) per edge
+/// - the provenance of each free-flow speed, such as `highway_fill+lanes`
+/// - speeds (kph) per edge for each time period in the time period profile, if provided
+pub fn write_speed_dataset(
+    graph: &OsmGraphVectorized,
+    conf: &SpeedModelConfig,
+    output_directory: &Path,
+    overwrite: bool,
+) -> Result<(), OsmError> {
+    let model = SpeedModel::new(conf, &graph.ways)?;
+    let periods = model
+        .profile
+        .as_ref()
+        .map(|p| p.periods.clone())
+        .unwrap_or_default();
+
+    let mut speed_writer = create_writer(
+        output_directory,
+        filenames::SPEED_FREE_FLOW,
+        false,
+        QuoteStyle::Never,
+        overwrite,
+    );
+    let mut provenance_writer = create_writer(
+        output_directory,
+        filenames::SPEED_PROVENANCE,
+        false,
+        QuoteStyle::Never,
+        overwrite,
+    );
+    let mut period_writers = periods
+        .iter()
+        .map(|p| {
+            let filename = filenames::speed_for_period(&p.name);
+            let writer = create_writer(
+                output_directory,
+                &filename,
+                false,
+                QuoteStyle::Never,
+                overwrite,
+            );
+            (p, filename, writer)
+        })
+        .collect::<Vec<_>>();
+
+    let e_iter = tqdm!(
+        graph.ways.iter(),
+        total = graph.ways.len(),
+        desc = "write speed dataset"
+    );
+    for row in e_iter {
+        let free_flow = model.free_flow(row);
+        if let Some(ref mut writer) = speed_writer {
+            writer.serialize(free_flow.speed_kph).map_err(|e| {
+                OsmError::CsvWriteError(String::from(filenames::SPEED_FREE_FLOW), e)
+            })?;
+        }
+        if let Some(ref mut writer) = provenance_writer {
+            writer
+                .serialize(free_flow.provenance.to_string())
+                .map_err(|e| {
+                    OsmError::CsvWriteError(String::from(filenames::SPEED_PROVENANCE), e)
+                })?;
+        }
+        for (period, filename, period_writer) in period_writers.iter_mut() {
+            if let Some(writer) = period_writer {
+                let speed = model.during_period(row, *period);
+                writer
+                    .serialize(speed.speed_kph)
+                    .map_err(|e| OsmError::CsvWriteError(filename.clone(), e))?;
+            }
+        }
+    }
+    eprintln!();
+
+    Ok(())
+}
+
+/// lanes per direction, splitting the `lanes` tag between directions on two-way roads.
+fn lanes_per_direction(way: &OsmWayDataSerializable) -> Option<f64> {
+    let lanes = way.lanes.as_ref()?.parse::<f64>().ok()?;
+    if lanes <= 0.0 {
+        None
+    } else if way.oneway.as_deref().is_some_and(|o| o != "no") {
+        Some(lanes)
+    } else {
+        Some((lanes / 2.0).max(1.0))
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// the rule that produced the base speed for an edge, in order of precedence.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedRule {
+    /// numeric `maxspeed` tag
+    Maxspeed,
+    /// implicit `maxspeed` tag such as `DE:urban`
+    ImplicitMaxspeed,
+    /// `zone:maxspeed` tag such as `DE:30`
+    ZoneMaxspeed,
+    /// `maxspeed:type` or `source:maxspeed` tag such as `US:urban`
+    MaxspeedType,
+    /// time-based `maxspeed:conditional` value active during a time period
+    Conditional,
+    /// length-weighted average `maxspeed` of ways with the same highway class
+    HighwayFill,
+    /// length-weighted average `maxspeed` of all ways
+    GlobalFill,
+}
+
+impl SpeedRule {
+    /// true if the speed was filled from network averages instead of a tag on the way
+    pub fn is_fill(&self) -> bool {
+        matches!(self, SpeedRule::HighwayFill | SpeedRule::GlobalFill)
+    }
+}
+
+impl Display for SpeedRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SpeedRule::Maxspeed => "maxspeed",
+            SpeedRule::ImplicitMaxspeed => "implicit_maxspeed",
+            SpeedRule::ZoneMaxspeed => "zone_maxspeed",
+            SpeedRule::MaxspeedType => "maxspeed_type",
+            SpeedRule::Conditional => "conditional",
+            SpeedRule::HighwayFill => "highway_fill",
+            SpeedRule::GlobalFill => "global_fill",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// describes how the speed of an edge was inferred: the base rule along
+/// with any adjustments applied on top of it. serializes as the rule name
+/// followed by each adjustment, such as `highway_fill+lanes+surface`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeedProvenance {
+    pub rule: SpeedRule,
+    pub lanes_adjusted: bool,
+    pub surface_adjusted: bool,
+    pub period_adjusted: bool,
+}
+
+impl SpeedProvenance {
+    pub fn new(rule: SpeedRule) -> SpeedProvenance {
+        SpeedProvenance {
+            rule,
+            lanes_adjusted: false,
+            surface_adjusted: false,
+            period_adjusted: false,
+        }
+    }
+}
+
+impl Display for SpeedProvenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rule)?;
+        if self.lanes_adjusted {
+            write!(f, "+lanes")?;
+        }
+        if self.surface_adjusted {
+            write!(f, "+surface")?;
+        }
+        if self.period_adjusted {
+            write!(f, "+period")?;
+        }
+        Ok(())
+    }
+}
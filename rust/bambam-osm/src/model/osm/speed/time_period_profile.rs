@@ -0,0 +1,260 @@
+use crate::model::{feature::highway::Highway, osm::OsmError};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// a named time-of-day range, such as an AM peak period. ranges where the end
+/// time precedes the start time wrap around midnight.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TimePeriod {
+    pub name: String,
+    /// minutes after midnight
+    pub start_minute: u32,
+    /// minutes after midnight
+    pub end_minute: u32,
+    /// days of the week this period applies to
+    #[serde(default)]
+    pub days: Weekdays,
+}
+
+/// a set of days of the week, Monday first, as in an OSM opening hours weekday
+/// selector such as `Mo-Fr` or `Sa,Su`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weekdays([bool; 7]);
+
+impl Default for Weekdays {
+    fn default() -> Self {
+        Weekdays([true; 7])
+    }
+}
+
+impl Weekdays {
+    /// parses a weekday selector of comma-separated days (`Sa`) and day ranges
+    /// (`Mo-Fr`), where ranges may wrap around the week (`Fr-Mo`). returns None
+    /// for selectors with anything other than days of the week, such as holidays.
+    pub fn parse(selector: &str) -> Option<Weekdays> {
+        let mut days = [false; 7];
+        for part in selector.split(',').map(str::trim) {
+            let day = |d: &str| WEEKDAY_ABBREVIATIONS.iter().position(|w| *w == d.trim());
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (day(start)?, day(end)?);
+                    let mut idx = start;
+                    loop {
+                        days[idx] = true;
+                        if idx == end {
+                            break;
+                        }
+                        idx = (idx + 1) % 7;
+                    }
+                }
+                None => days[day(part)?] = true,
+            }
+        }
+        Some(Weekdays(days))
+    }
+
+    /// true if the two sets share any day.
+    pub fn intersects(&self, other: &Weekdays) -> bool {
+        self.0.iter().zip(other.0.iter()).any(|(a, b)| *a && *b)
+    }
+}
+
+impl TimePeriod {
+    /// true if this period shares any time with the range [start_minute, end_minute).
+    pub fn overlaps(&self, start_minute: u32, end_minute: u32) -> bool {
+        time_ranges_overlap(
+            (self.start_minute, self.end_minute),
+            (start_minute, end_minute),
+        )
+    }
+}
+
+/// a row in a time period speed profile file. rows without a highway class
+/// apply to all classes within that period.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TimePeriodProfileRow {
+    period: String,
+    start_time: String,
+    end_time: String,
+    highway: Option<String>,
+    speed_factor: f64,
+    #[serde(default)]
+    days: Option<String>,
+}
+
+/// speed factors relative to free-flow speeds for a set of time periods, used
+/// to build congested speed profiles. read from a CSV with the columns
+/// `period,start_time,end_time,highway,speed_factor` and an optional `days`
+/// column, where times are `HH:MM`, highways are OSM `highway` tags such as
+/// `motorway_link` and days are a weekday selector such as `Mo-Fr`. periods
+/// without days apply to every day of the week.
+#[derive(Debug, Clone)]
+pub struct TimePeriodProfile {
+    pub periods: Vec<TimePeriod>,
+    factors: HashMap<(String, Option<Highway>), f64>,
+}
+
+impl TimePeriodProfile {
+    pub fn from_csv(filepath: &str) -> Result<TimePeriodProfile, OsmError> {
+        let mut reader = csv::Reader::from_path(Path::new(filepath)).map_err(|e| {
+            OsmError::ConfigurationError(format!("unable to read file {filepath}: {e}"))
+        })?;
+        let mut periods: Vec<TimePeriod> = vec![];
+        let mut factors: HashMap<(String, Option<Highway>), f64> = HashMap::new();
+        for row_result in reader.deserialize() {
+            let row: TimePeriodProfileRow = row_result.map_err(|e| {
+                OsmError::ConfigurationError(format!("failure decoding row in {filepath}: {e}"))
+            })?;
+            validate_period_name(&row.period)?;
+            let days = match row.days.as_deref().map(str::trim) {
+                None | Some("") => Weekdays::default(),
+                Some(selector) => Weekdays::parse(selector).ok_or_else(|| {
+                    OsmError::ConfigurationError(format!(
+                        "invalid days '{selector}' for period '{}' in {filepath}",
+                        row.period
+                    ))
+                })?,
+            };
+            let period = TimePeriod {
+                name: row.period.clone(),
+                start_minute: parse_time_of_day(&row.start_time)?,
+                end_minute: parse_time_of_day(&row.end_time)?,
+                days,
+            };
+            match periods.iter().find(|p| p.name == period.name) {
+                Some(existing) if existing != &period => {
+                    return Err(OsmError::ConfigurationError(format!(
+                        "period '{}' in {filepath} has inconsistent start and end times or days",
+                        period.name
+                    )));
+                }
+                Some(_) => {}
+                None => periods.push(period),
+            }
+            let highway = row
+                .highway
+                .filter(|h| !h.trim().is_empty())
+                .map(|h| Highway::from_str(&h))
+                .transpose()
+                .map_err(|e| {
+                    OsmError::ConfigurationError(format!("invalid highway in {filepath}: {e}"))
+                })?;
+            factors.insert((row.period, highway), row.speed_factor);
+        }
+        if periods.is_empty() {
+            return Err(OsmError::ConfigurationError(format!(
+                "time period profile {filepath} has no rows"
+            )));
+        }
+        Ok(TimePeriodProfile { periods, factors })
+    }
+
+    /// the speed factor for a highway class during a period. falls back to the
+    /// period-wide factor and then to 1.0 (free-flow).
+    pub fn speed_factor(&self, period: &str, highway: &Highway) -> f64 {
+        self.factors
+            .get(&(period.to_string(), Some(highway.clone())))
+            .or_else(|| self.factors.get(&(period.to_string(), None)))
+            .cloned()
+            .unwrap_or(1.0)
+    }
+}
+
+/// parses a `HH:MM` time of day into minutes after midnight. `24:00` is accepted
+/// as the end of the day.
+pub fn parse_time_of_day(value: &str) -> Result<u32, OsmError> {
+    let invalid = || OsmError::ConfigurationError(format!("invalid time of day '{value}'"));
+    let (h, m) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let hours = h.parse::<u32>().map_err(|_| invalid())?;
+    let minutes = m.parse::<u32>().map_err(|_| invalid())?;
+    if hours > 24 || minutes >= 60 {
+        return Err(invalid());
+    }
+    let total = hours * 60 + minutes;
+    if total > MINUTES_PER_DAY {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// tests whether two time-of-day ranges overlap, where either range may wrap
+/// around midnight.
+pub fn time_ranges_overlap(a: (u32, u32), b: (u32, u32)) -> bool {
+    let a_parts = unwrap_range(a);
+    let b_parts = unwrap_range(b);
+    a_parts
+        .iter()
+        .any(|(a0, a1)| b_parts.iter().any(|(b0, b1)| a0 < b1 && b0 < a1))
+}
+
+fn unwrap_range((start, end): (u32, u32)) -> Vec<(u32, u32)> {
+    if start <= end {
+        vec![(start, end)]
+    } else {
+        vec![(start, MINUTES_PER_DAY), (0, end)]
+    }
+}
+
+/// period names are used in output filenames, so they are restricted to
+/// alphanumeric characters, dashes and underscores.
+fn validate_period_name(name: &str) -> Result<(), OsmError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(OsmError::ConfigurationError(format!(
+            "invalid time period name '{name}', must contain only alphanumeric characters, '-' or '_'"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_time_of_day, TimePeriodProfile, Weekdays};
+    use crate::model::feature::highway::Highway;
+
+    #[test]
+    fn test_parse_time_of_day_bounds() {
+        assert_eq!(parse_time_of_day("07:30").unwrap(), 450);
+        assert_eq!(parse_time_of_day("24:00").unwrap(), 1440);
+        assert!(parse_time_of_day("24:01").is_err());
+        assert!(parse_time_of_day("99999999:00").is_err());
+        assert!(parse_time_of_day("4294967295:00").is_err());
+        assert!(parse_time_of_day("07:60").is_err());
+    }
+
+    #[test]
+    fn test_parse_weekdays() {
+        let weekdays = Weekdays::parse("Mo-Fr").unwrap();
+        let weekend = Weekdays::parse("Sa,Su").unwrap();
+        assert!(!weekdays.intersects(&weekend));
+        assert!(Weekdays::parse("Fr-Mo").unwrap().intersects(&weekend));
+        assert!(Weekdays::parse("Mo-We,Fr").unwrap().intersects(&weekdays));
+        assert!(Weekdays::default().intersects(&weekend));
+        assert_eq!(Weekdays::parse("PH"), None);
+    }
+
+    #[test]
+    fn test_speed_factor_by_osm_highway_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("profile.csv");
+        std::fs::write(
+            &file,
+            "period,start_time,end_time,highway,speed_factor,days\n\
+             am_peak,07:00,09:00,,0.8,Mo-Fr\n\
+             am_peak,07:00,09:00,motorway_link,0.5,Mo-Fr\n",
+        )
+        .unwrap();
+        let profile = TimePeriodProfile::from_csv(file.to_str().unwrap()).unwrap();
+        assert_eq!(profile.speed_factor("am_peak", &Highway::MotorwayLink), 0.5);
+        assert_eq!(profile.speed_factor("am_peak", &Highway::Primary), 0.8);
+        assert_eq!(profile.periods[0].days, Weekdays::parse("Mo-Fr").unwrap());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    config::OsmImportConfiguration,
+    model::{
+        osm::{
+            graph::{CompassWriter, OsmGraphVectorized},
+            pedestrian::pedestrian_ops,
+            speed::speed_ops,
+            OsmError, OsmSource,
+        },
+        OsmCliError,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// outcome of importing a PBF file as a Compass network dataset.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PbfImportSummary {
+    /// vertices of the imported graph
+    pub nodes: usize,
+    /// edges of the imported graph
+    pub ways: usize,
+    pub output_directory: String,
+}
+
+/// imports a .pbf file, filtered by an optional extent file containing WKT, and writes
+/// the Compass dataset along with any optional datasets requested in the configuration.
+/// if no configuration file is provided, the default import configuration is used.
+pub fn run_pbf_import(
+    pbf_file: &str,
+    extent_file: Option<&str>,
+    configuration_file: Option<&str>,
+    output_directory: &str,
+) -> Result<PbfImportSummary, OsmCliError> {
+    let conf = match configuration_file {
+        None => Ok(OsmImportConfiguration::default()),
+        Some(f) => {
+            log::info!("reading bambam configuration from {f}");
+            OsmImportConfiguration::try_from(&f.to_string())
+        }
+    }?;
+    let consolidation_threshold = conf.get_consolidation_threshold();
+    let out_path = Path::new(output_directory);
+    let pbf_config = OsmSource::Pbf {
+        pbf_filepath: pbf_file.to_string(),
+        extent_filter_filepath: extent_file.map(String::from),
+        network_filter: Some(conf.element_filter.clone()),
+        component_filter: Some(conf.component_filter.clone()),
+        truncate_by_edge: conf.truncate_by_edge,
+        ignore_errors: conf.ignore_osm_parsing_errors,
+        simplify: conf.simplify,
+        consolidate: conf.consolidate,
+        consolidation_threshold,
+        parallelize: conf.parallelize,
+        pedestrian_network: conf.pedestrian_network.clone(),
+        multiedge_conflict_policy: conf.multiedge_conflict_policy.clone().unwrap_or_default(),
+    };
+    let graph = pbf_config.import()?;
+    write_outputs(&graph, &conf, out_path)?;
+    Ok(PbfImportSummary {
+        nodes: graph.nodes.len(),
+        ways: graph.ways.len(),
+        output_directory: output_directory.to_string(),
+    })
+}
+
+/// writes the compass dataset along with any optional datasets requested in the configuration.
+fn write_outputs(
+    graph: &OsmGraphVectorized,
+    conf: &OsmImportConfiguration,
+    out_path: &Path,
+) -> Result<(), OsmError> {
+    graph.write_compass(out_path, true)?;
+    if let Some(ped_conf) = &conf.pedestrian_network {
+        pedestrian_ops::write_pedestrian_dataset(graph, ped_conf, out_path, true)?;
+    }
+    if let Some(speed_conf) = &conf.speed_model {
+        speed_ops::write_speed_dataset(graph, speed_conf, out_path, true)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::run_pbf_import;
+    use crate::model::{osm::graph::OsmWayDataSerializable, OsmCliError};
+    use routee_compass_core::util::fs::read_utils;
+
+    #[test]
+    fn test_e2e_pbf_import_summary() {
+        // requires the dataset downloaded by script/setup_test_bambam_osm.sh
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let output_directory = tmp.path().to_string_lossy().to_string();
+        let summary = run_pbf_import(
+            "src/test/liechtenstein-250101.osm.pbf",
+            Some("src/test/schaan_liechtenstein.txt"),
+            Some("src/test/test_osm_import.toml"),
+            &output_directory,
+        )
+        .expect("test invariant failed");
+        assert!(summary.nodes > 0);
+        assert!(summary.ways > 0);
+        assert_eq!(summary.output_directory, output_directory);
+
+        let ways: Box<[OsmWayDataSerializable]> =
+            read_utils::from_csv(&tmp.path().join("edges-complete.csv.gz"), true, None, None)
+                .expect("test invariant failed");
+        assert_eq!(ways.len(), summary.ways);
+        assert!(tmp.path().join("vertices-compass.csv.gz").is_file());
+    }
+
+    #[test]
+    fn test_pbf_import_missing_configuration() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let result = run_pbf_import(
+            "src/test/liechtenstein-250101.osm.pbf",
+            None,
+            Some("src/test/does_not_exist.toml"),
+            &tmp.path().to_string_lossy(),
+        );
+        assert!(matches!(result, Err(OsmCliError::ConfigurationError(_))));
+    }
+
+    #[test]
+    fn test_pbf_import_unsupported_configuration() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let result = run_pbf_import(
+            "src/test/liechtenstein-250101.osm.pbf",
+            None,
+            Some("src/test/schaan_liechtenstein.txt"),
+            &tmp.path().to_string_lossy(),
+        );
+        assert!(matches!(result, Err(OsmCliError::ConfigurationError(_))));
+    }
+
+    #[test]
+    fn test_pbf_import_missing_pbf() {
+        let tmp = tempfile::tempdir().expect("test invariant failed");
+        let result = run_pbf_import(
+            "src/test/does_not_exist.osm.pbf",
+            None,
+            None,
+            &tmp.path().to_string_lossy(),
+        );
+        assert!(matches!(result, Err(OsmCliError::OsmError { .. })));
+        assert!(!tmp.path().join("edges-compass.csv.gz").exists());
+    }
+}
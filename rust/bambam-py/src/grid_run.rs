@@ -0,0 +1,110 @@
+//! runs a grid of queries from Python in batches, consumed by
+//! `BambamRunner.run_grid` so that large runs can be written out incrementally.
+
+use bambam::{
+    app::{grid_run::GridRunBatches, preprocess_grid},
+    model::input_plugin::grid::{extent_format::ExtentFormat, grid_type::GridType},
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    BambamAppWrapper,
+    preprocess::{BambamError, GridError, from_py, parse_value_enum},
+};
+
+/// iterator over the results of a grid run. each step builds and runs the next
+/// batch of queries with the GIL released and returns the results as JSON strings.
+#[pyclass]
+pub struct GridRunIterator {
+    app: Py<BambamAppWrapper>,
+    batches: GridRunBatches,
+    progress: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl GridRunIterator {
+    /// number of grid cells in this run
+    #[getter]
+    fn n_cells(&self) -> usize {
+        self.batches.n_cells()
+    }
+
+    /// total number of queries in this run, one per grid cell and mode
+    #[getter]
+    fn n_queries(&self) -> usize {
+        self.batches.n_queries()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Vec<String>>> {
+        let wrapper = self.app.borrow(py);
+        let app = &wrapper.app;
+        let batches = &mut self.batches;
+        // queries of the batch are built along with the run, with the GIL released
+        let results = py.detach(|| {
+            let batch = match batches.next() {
+                None => return Ok(None),
+                Some(batch) => batch.map_err(GridError::new_err)?,
+            };
+            let results = app
+                .run(batch, None)
+                .map_err(|e| BambamError::new_err(e.to_string()))?;
+            results
+                .iter()
+                .map(|r| serde_json::to_string(r).map_err(|e| BambamError::new_err(e.to_string())))
+                .collect::<PyResult<Vec<_>>>()
+                .map(Some)
+        })?;
+        let Some(results) = results else {
+            return Ok(None);
+        };
+        if let Some(progress) = &self.progress {
+            progress.call1(py, (self.batches.n_taken(), self.batches.n_queries()))?;
+        }
+        Ok(Some(results))
+    }
+}
+
+/// expands an extent into an H3 grid of queries and returns an iterator that runs
+/// them through the app in batches. each grid cell query copies the keys of the
+/// `query` template and is repeated for each of the `modes`, if provided. the
+/// `progress` callback, if provided, is called after each batch with the number of
+/// queries completed and the total number of queries.
+#[pyfunction]
+#[pyo3(signature = (app, extent, grid_resolution, modes=None, query=None, extent_format="wkt", batch_size=1000, progress=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn grid_run<'py>(
+    py: Python<'py>,
+    app: Py<BambamAppWrapper>,
+    extent: &Bound<'py, PyAny>,
+    grid_resolution: u8,
+    modes: Option<Vec<String>>,
+    query: Option<&Bound<'py, PyAny>>,
+    extent_format: &str,
+    batch_size: usize,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<GridRunIterator> {
+    let extent_format: ExtentFormat = parse_value_enum("extent_format", extent_format)?;
+    let resolution = h3o::Resolution::try_from(grid_resolution)
+        .map_err(|e| PyValueError::new_err(format!("invalid grid_resolution: {e}")))?;
+    let grid_type = GridType::H3 { resolution };
+    let extent: serde_json::Value = from_py(py, "extent", extent)?;
+    let template: Option<serde_json::Map<String, serde_json::Value>> =
+        query.map(|q| from_py(py, "query", q)).transpose()?;
+    // only the grid cells are found here, each query is built as its batch is taken
+    let cells = py
+        .detach(|| {
+            preprocess_grid::grid_queries(extent, extent_format, grid_type, None, template.as_ref())
+        })
+        .map_err(GridError::new_err)?;
+    let batches = GridRunBatches::new(cells, modes.unwrap_or_default(), batch_size)
+        .map_err(PyValueError::new_err)?;
+    Ok(GridRunIterator {
+        app,
+        batches,
+        progress,
+    })
+}
@@ -6,6 +6,10 @@ use routee_compass::app::{
 };
 use routee_compass_macros::pybindings;
 
+mod grid_run;
+mod preprocess;
+mod result_table;
+
 // add BAMBAM extensions to RouteE Compass
 inventory::submit! { builders::BUILDER_REGISTRATION }
 
@@ -45,5 +49,9 @@ impl CompassAppBindings for BambamAppWrapper {
 #[pymodule]
 fn bambam_py_api(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<BambamAppWrapper>()?;
+    result_table::register(m)?;
+    m.add_class::<grid_run::GridRunIterator>()?;
+    m.add_function(wrap_pyfunction!(grid_run::grid_run, m)?)?;
+    preprocess::register(m)?;
     Ok(())
 }
@@ -0,0 +1,724 @@
+//! bindings for the pre-processing tools otherwise run through the `bambam_util`,
+//! `bambam_gtfs` and `bambam_osm` command line applications. each returns a dict
+//! summarizing the outcome and raises a subclass of `BambamError` on failure. where
+//! a tool fails with an error enum, each variant maps to the most specific
+//! exception type, such as `ScheduleError` for GTFS data that cannot be scheduled.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use bambam::{
+    app::{
+        gtfs_config::GtfsConfigError as GtfsConfigRunError,
+        oppvec::{self, GeometryFormat, SourceFormat},
+        overlay::{self, GeometryColumnType, OverlayOperation, OverlayOutputFormat, OverlaySource},
+    },
+    model::input_plugin::{
+        grid::{extent_format::ExtentFormat, grid_type::GridType},
+        population::population_source_config::PopulationSourceConfig,
+    },
+};
+use bambam_gtfs::schedule::{
+    DateMappingPolicyType, DistanceCalculationPolicy, MissingStopLocationPolicy,
+    ScheduleError as ScheduleRunError, app::PreprocessBundleArgs,
+};
+use bambam_osm::model::{OsmCliError, osm::OsmError as OsmRunError};
+use clap::ValueEnum;
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+create_exception!(
+    bambam_py_api,
+    BambamError,
+    PyException,
+    "base class of errors raised by bambam pre-processing tools."
+);
+create_exception!(
+    bambam_py_api,
+    GridError,
+    BambamError,
+    "failure building a grid of queries from an extent."
+);
+create_exception!(
+    bambam_py_api,
+    OpportunityError,
+    BambamError,
+    "failure vectorizing an opportunity dataset."
+);
+create_exception!(
+    bambam_py_api,
+    OverlayError,
+    BambamError,
+    "failure partitioning a bambam output by an overlay dataset."
+);
+create_exception!(
+    bambam_py_api,
+    GtfsConfigError,
+    BambamError,
+    "failure adding GTFS edge lists to a configuration file."
+);
+create_exception!(
+    bambam_py_api,
+    GtfsConfigReadError,
+    GtfsConfigError,
+    "failure reading the base configuration or the GTFS edge list directory."
+);
+create_exception!(
+    bambam_py_api,
+    GtfsError,
+    BambamError,
+    "failure processing GTFS archives into edge lists."
+);
+create_exception!(
+    bambam_py_api,
+    GtfsReadError,
+    GtfsError,
+    "failure reading or parsing a GTFS archive."
+);
+create_exception!(
+    bambam_py_api,
+    MapMatchError,
+    GtfsError,
+    "failure matching GTFS stops to the vertices of a Compass network."
+);
+create_exception!(
+    bambam_py_api,
+    ScheduleError,
+    GtfsError,
+    "contents of a GTFS archive cannot be built into schedules."
+);
+create_exception!(
+    bambam_py_api,
+    OsmError,
+    BambamError,
+    "failure importing an OpenStreetMap network."
+);
+create_exception!(
+    bambam_py_api,
+    OsmConfigError,
+    OsmError,
+    "invalid OpenStreetMap import configuration or extent."
+);
+create_exception!(
+    bambam_py_api,
+    PbfReadError,
+    OsmError,
+    "failure reading the elements of a .pbf file."
+);
+create_exception!(
+    bambam_py_api,
+    OsmGraphError,
+    OsmError,
+    "failure building, simplifying or consolidating the OpenStreetMap graph."
+);
+create_exception!(
+    bambam_py_api,
+    OsmWriteError,
+    OsmError,
+    "failure writing the imported network dataset."
+);
+
+/// registers the pre-processing functions and exception types with the module.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("BambamError", py.get_type::<BambamError>())?;
+    m.add("GridError", py.get_type::<GridError>())?;
+    m.add("OpportunityError", py.get_type::<OpportunityError>())?;
+    m.add("OverlayError", py.get_type::<OverlayError>())?;
+    m.add("GtfsConfigError", py.get_type::<GtfsConfigError>())?;
+    m.add("GtfsConfigReadError", py.get_type::<GtfsConfigReadError>())?;
+    m.add("GtfsError", py.get_type::<GtfsError>())?;
+    m.add("GtfsReadError", py.get_type::<GtfsReadError>())?;
+    m.add("MapMatchError", py.get_type::<MapMatchError>())?;
+    m.add("ScheduleError", py.get_type::<ScheduleError>())?;
+    m.add("OsmError", py.get_type::<OsmError>())?;
+    m.add("OsmConfigError", py.get_type::<OsmConfigError>())?;
+    m.add("PbfReadError", py.get_type::<PbfReadError>())?;
+    m.add("OsmGraphError", py.get_type::<OsmGraphError>())?;
+    m.add("OsmWriteError", py.get_type::<OsmWriteError>())?;
+    m.add_function(wrap_pyfunction!(preprocess_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(opportunities_long, m)?)?;
+    m.add_function(wrap_pyfunction!(opportunities_wide, m)?)?;
+    m.add_function(wrap_pyfunction!(overlay_shapefile, m)?)?;
+    m.add_function(wrap_pyfunction!(gtfs_config, m)?)?;
+    m.add_function(wrap_pyfunction!(gtfs_preprocess_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(osm_pbf, m)?)?;
+    Ok(())
+}
+
+/// expands a WKT extent into an H3 grid of queries, optionally attaching population
+/// from a `population_source` dict such as `{"type": "acs", "acs_type": "5", ...}`,
+/// and writes them as newline-delimited JSON to the output file.
+#[pyfunction]
+#[pyo3(signature = (extent, output_file, grid_resolution, extent_format="wkt", population_source=None))]
+pub fn preprocess_grid<'py>(
+    py: Python<'py>,
+    extent: String,
+    output_file: PathBuf,
+    grid_resolution: u8,
+    extent_format: &str,
+    population_source: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let extent_format: ExtentFormat = parse_value_enum("extent_format", extent_format)?;
+    let resolution = h3o::Resolution::try_from(grid_resolution)
+        .map_err(|e| PyValueError::new_err(format!("invalid grid_resolution: {e}")))?;
+    let grid_type = GridType::H3 { resolution };
+    let population_source: Option<PopulationSourceConfig> = population_source
+        .map(|p| from_py(py, "population_source", p))
+        .transpose()?;
+    let output_file = output_file.to_string_lossy().to_string();
+    let summary = py
+        .detach(|| {
+            bambam::app::preprocess_grid::run(
+                &extent,
+                extent_format,
+                grid_type,
+                population_source.as_ref(),
+                &output_file,
+            )
+        })
+        .map_err(GridError::new_err)?;
+    to_py(py, &summary)
+}
+
+/// aggregates a long-format opportunity CSV to the vertices of a Compass network,
+/// where `category_mapping` maps each category to the activity types it counts towards.
+#[pyfunction]
+#[pyo3(signature = (vertices_compass_filename, opportunities_filename, output_filename, category_column, category_mapping, geometry_column=None, x_column=None, y_column=None, count_column=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn opportunities_long<'py>(
+    py: Python<'py>,
+    vertices_compass_filename: PathBuf,
+    opportunities_filename: PathBuf,
+    output_filename: PathBuf,
+    category_column: String,
+    category_mapping: HashMap<String, Vec<String>>,
+    geometry_column: Option<String>,
+    x_column: Option<String>,
+    y_column: Option<String>,
+    count_column: Option<String>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let geometry_format = GeometryFormat::new(
+        geometry_column.as_ref(),
+        x_column.as_ref(),
+        y_column.as_ref(),
+    )
+    .map_err(PyValueError::new_err)?;
+    let source_format = SourceFormat::LongFormat {
+        geometry_format,
+        category_column,
+        count_column,
+        category_mapping,
+    };
+    run_oppvec(
+        py,
+        vertices_compass_filename,
+        opportunities_filename,
+        output_filename,
+        source_format,
+    )
+}
+
+/// aggregates a wide-format opportunity CSV to the vertices of a Compass network,
+/// where `column_mapping` maps each count column to the activity types it counts towards.
+#[pyfunction]
+#[pyo3(signature = (vertices_compass_filename, opportunities_filename, output_filename, column_mapping, geometry_column=None, x_column=None, y_column=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn opportunities_wide<'py>(
+    py: Python<'py>,
+    vertices_compass_filename: PathBuf,
+    opportunities_filename: PathBuf,
+    output_filename: PathBuf,
+    column_mapping: HashMap<String, Vec<String>>,
+    geometry_column: Option<String>,
+    x_column: Option<String>,
+    y_column: Option<String>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let geometry_format = GeometryFormat::new(
+        geometry_column.as_ref(),
+        x_column.as_ref(),
+        y_column.as_ref(),
+    )
+    .map_err(PyValueError::new_err)?;
+    if column_mapping.is_empty() {
+        return Err(PyValueError::new_err(
+            "cannot build wide-format source with empty column mapping",
+        ));
+    }
+    let source_format = SourceFormat::WideFormat {
+        geometry_format,
+        column_mapping,
+    };
+    run_oppvec(
+        py,
+        vertices_compass_filename,
+        opportunities_filename,
+        output_filename,
+        source_format,
+    )
+}
+
+/// partitions a bambam output CSV into one CSV per feature of an overlay dataset
+/// such as a shapefile, named by the `id_field` of the feature. with an
+/// `output_format` of "flatgeobuf" or "geopackage", rows are instead written to a
+/// single spatial file with the overlay id as a column.
+#[pyfunction]
+#[pyo3(signature = (bambam_output_filepath, overlay_filepath, output_directory, xcol=None, ycol=None, geomcol=None, geomfmt=None, how="intersection", id_field="GEOID", output_format="csv", verbose=false))]
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_shapefile<'py>(
+    py: Python<'py>,
+    bambam_output_filepath: PathBuf,
+    overlay_filepath: PathBuf,
+    output_directory: PathBuf,
+    xcol: Option<String>,
+    ycol: Option<String>,
+    geomcol: Option<String>,
+    geomfmt: Option<&str>,
+    how: &str,
+    id_field: &str,
+    output_format: &str,
+    verbose: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let geomfmt: Option<overlay::GeometryFormat> = geomfmt
+        .map(|f| parse_value_enum("geomfmt", f))
+        .transpose()?;
+    let how: OverlayOperation = parse_value_enum("how", how)?;
+    let output_format: OverlayOutputFormat = parse_value_enum("output_format", output_format)?;
+    let col_type = GeometryColumnType::new(
+        xcol.as_ref(),
+        ycol.as_ref(),
+        geomcol.as_ref(),
+        geomfmt.as_ref(),
+    )
+    .map_err(PyValueError::new_err)?;
+    let overlay_source = OverlaySource::Shapefile {
+        file: overlay_filepath.to_string_lossy().to_string(),
+        id_field: id_field.to_string(),
+    };
+    let bambam_output_filepath = bambam_output_filepath.to_string_lossy().to_string();
+    let output_directory = output_directory.to_string_lossy().to_string();
+    let summary = py
+        .detach(|| {
+            overlay::run(
+                &bambam_output_filepath,
+                &output_directory,
+                &overlay_source,
+                &col_type,
+                &how,
+                &output_format,
+                verbose,
+            )
+        })
+        .map_err(OverlayError::new_err)?;
+    to_py(py, &summary)
+}
+
+/// writes a copy of a bambam configuration file with the GTFS edge lists found in a
+/// directory of `bambam_gtfs` outputs, next to the base configuration with a `_gtfs`
+/// suffix.
+#[pyfunction]
+#[pyo3(signature = (directory, base_config, inject_filepath=None))]
+pub fn gtfs_config<'py>(
+    py: Python<'py>,
+    directory: PathBuf,
+    base_config: PathBuf,
+    inject_filepath: Option<String>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let directory = directory.to_string_lossy().to_string();
+    let base_config = base_config.to_string_lossy().to_string();
+    let summary = py
+        .detach(|| {
+            bambam::app::gtfs_config::run(&directory, &base_config, inject_filepath.as_deref())
+        })
+        .map_err(gtfs_config_error)?;
+    to_py(py, &summary)
+}
+
+/// processes a GTFS archive, or a directory of archives, into Compass edge lists.
+/// policy arguments take the same values as the `bambam_gtfs preprocess-bundle`
+/// command line options.
+#[pyfunction]
+#[pyo3(signature = (input, output_directory, vertices_compass_filename, start_date, end_date, date_mapping_policy, starting_edge_list_id, start_time=None, end_time=None, parallelism=1, vertex_match_tolerance=325.0, missing_stop_location_policy="fail", distance_calculation_policy="haversine", date_mapping_date_tolerance=None, date_mapping_match_weekday=None, extent_file=None, overwrite=true, ignore_bad_gtfs=true))]
+#[allow(clippy::too_many_arguments)]
+pub fn gtfs_preprocess_bundle<'py>(
+    py: Python<'py>,
+    input: PathBuf,
+    output_directory: PathBuf,
+    vertices_compass_filename: PathBuf,
+    start_date: String,
+    end_date: String,
+    date_mapping_policy: &str,
+    starting_edge_list_id: usize,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    parallelism: usize,
+    vertex_match_tolerance: f64,
+    missing_stop_location_policy: &str,
+    distance_calculation_policy: &str,
+    date_mapping_date_tolerance: Option<u64>,
+    date_mapping_match_weekday: Option<bool>,
+    extent_file: Option<PathBuf>,
+    overwrite: bool,
+    ignore_bad_gtfs: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let args = PreprocessBundleArgs {
+        input: input.to_string_lossy().to_string(),
+        starting_edge_list_id,
+        parallelism,
+        output_directory: output_directory.to_string_lossy().to_string(),
+        vertices_compass_filename: vertices_compass_filename.to_string_lossy().to_string(),
+        start_date,
+        end_date,
+        start_time,
+        end_time,
+        vertex_match_tolerance,
+        missing_stop_location_policy: parse_value_enum::<MissingStopLocationPolicy>(
+            "missing_stop_location_policy",
+            missing_stop_location_policy,
+        )?,
+        distance_calculation_policy: parse_value_enum::<DistanceCalculationPolicy>(
+            "distance_calculation_policy",
+            distance_calculation_policy,
+        )?,
+        date_mapping_policy: parse_value_enum::<DateMappingPolicyType>(
+            "date_mapping_policy",
+            date_mapping_policy,
+        )?,
+        date_mapping_date_tolerance,
+        date_mapping_match_weekday,
+        extent_file: extent_file.map(|f| f.to_string_lossy().to_string()),
+        overwrite,
+        ignore_bad_gtfs,
+    };
+    let summary = py.detach(|| args.run()).map_err(schedule_error)?;
+    to_py(py, &summary)
+}
+
+/// imports an OpenStreetMap .pbf file as a Compass network dataset, optionally
+/// filtered by a WKT extent file and configured by a bambam-osm configuration file.
+#[pyfunction]
+#[pyo3(signature = (pbf_file, output_directory, extent_file=None, configuration_file=None))]
+pub fn osm_pbf<'py>(
+    py: Python<'py>,
+    pbf_file: PathBuf,
+    output_directory: PathBuf,
+    extent_file: Option<PathBuf>,
+    configuration_file: Option<PathBuf>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let pbf_file = pbf_file.to_string_lossy().to_string();
+    let output_directory = output_directory.to_string_lossy().to_string();
+    let extent_file = extent_file.map(|f| f.to_string_lossy().to_string());
+    let configuration_file = configuration_file.map(|f| f.to_string_lossy().to_string());
+    let summary = py
+        .detach(|| {
+            bambam_osm::model::run_pbf_import(
+                &pbf_file,
+                extent_file.as_deref(),
+                configuration_file.as_deref(),
+                &output_directory,
+            )
+        })
+        .map_err(osm_cli_error)?;
+    to_py(py, &summary)
+}
+
+fn run_oppvec<'py>(
+    py: Python<'py>,
+    vertices_compass_filename: PathBuf,
+    opportunities_filename: PathBuf,
+    output_filename: PathBuf,
+    source_format: SourceFormat,
+) -> PyResult<Bound<'py, PyAny>> {
+    let vertices_compass_filename = vertices_compass_filename.to_string_lossy().to_string();
+    let opportunities_filename = opportunities_filename.to_string_lossy().to_string();
+    let output_filename = output_filename.to_string_lossy().to_string();
+    let summary = py
+        .detach(|| {
+            oppvec::run(
+                &vertices_compass_filename,
+                &opportunities_filename,
+                &output_filename,
+                &source_format,
+            )
+        })
+        .map_err(OpportunityError::new_err)?;
+    to_py(py, &summary)
+}
+
+/// raises a failure adding GTFS edge lists to a configuration by its variant.
+fn gtfs_config_error(error: GtfsConfigRunError) -> PyErr {
+    let message = error.to_string();
+    match error {
+        GtfsConfigRunError::ReadFailure { .. } | GtfsConfigRunError::ConfigReadFailure { .. } => {
+            GtfsConfigReadError::new_err(message)
+        }
+        GtfsConfigRunError::RunFailure(_) | GtfsConfigRunError::InternalError(_) => {
+            GtfsConfigError::new_err(message)
+        }
+    }
+}
+
+/// raises a failure processing GTFS archives by its variant.
+fn schedule_error(error: ScheduleRunError) -> PyErr {
+    let message = error.to_string();
+    match error {
+        ScheduleRunError::BundleRead(_) | ScheduleRunError::MalformedGtfs(_) => {
+            GtfsReadError::new_err(message)
+        }
+        ScheduleRunError::SpatialIndexMap { .. }
+        | ScheduleRunError::SpatialIndexIncorrectMap
+        | ScheduleRunError::MapMatchError { .. }
+        | ScheduleRunError::FailedToCreateVertexIndex(_) => MapMatchError::new_err(message),
+        ScheduleRunError::StopTimeMissingStop
+        | ScheduleRunError::MissingAllStopTimes(_)
+        | ScheduleRunError::MissingShapeDistanceTraveled(_, _)
+        | ScheduleRunError::ShapeCut(_)
+        | ScheduleRunError::InvalidCalendar(_)
+        | ScheduleRunError::InvalidCalendarDates(_)
+        | ScheduleRunError::InvalidResultKeys
+        | ScheduleRunError::InvalidData(_) => ScheduleError::new_err(message),
+        ScheduleRunError::GtfsApp(_)
+        | ScheduleRunError::Internal(_)
+        | ScheduleRunError::BatchProcessing(_) => GtfsError::new_err(message),
+    }
+}
+
+/// raises a failure importing an OpenStreetMap network by its variant.
+fn osm_cli_error(error: OsmCliError) -> PyErr {
+    match error {
+        OsmCliError::OsmError { source } => osm_error(source),
+        OsmCliError::ConfigurationError(_)
+        | OsmCliError::StdIoError { .. }
+        | OsmCliError::SerdeJsonError { .. } => OsmConfigError::new_err(error.to_string()),
+    }
+}
+
+fn osm_error(error: OsmRunError) -> PyErr {
+    let message = error.to_string();
+    match error {
+        OsmRunError::ConfigurationError(_)
+        | OsmRunError::InvalidWKT(_)
+        | OsmRunError::InvalidExtentWKT(_) => OsmConfigError::new_err(message),
+        OsmRunError::PbfLibError { .. }
+        | OsmRunError::InvalidOsmData(_)
+        | OsmRunError::NoNodesFound
+        | OsmRunError::NoWaysFound => PbfReadError::new_err(message),
+        OsmRunError::GraphSimplificationError(_)
+        | OsmRunError::GraphConsolidationError(_)
+        | OsmRunError::AdjacencyMissingSourceNodeId(_, _)
+        | OsmRunError::AdjacencyWithSourceMissingDestinationNodeId(_, _, _)
+        | OsmRunError::AdjacencyRemovalError(_, _)
+        | OsmRunError::GraphMissingNodeId(_)
+        | OsmRunError::GraphMissingWayId(_)
+        | OsmRunError::GraphModificationError(_) => OsmGraphError::new_err(message),
+        OsmRunError::CsvWriteError(_, _) | OsmRunError::GraphSerializationError(_) => {
+            OsmWriteError::new_err(message)
+        }
+        OsmRunError::InternalError(_) => OsmError::new_err(message),
+    }
+}
+
+/// parses an argument with the same names accepted on the command line.
+pub(crate) fn parse_value_enum<T: ValueEnum>(argument: &str, value: &str) -> PyResult<T> {
+    T::from_str(value, true).map_err(|e| {
+        let options = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        PyValueError::new_err(format!(
+            "invalid {argument} '{value}', expected one of [{options}]: {e}"
+        ))
+    })
+}
+
+/// converts a summary into Python objects by way of JSON.
+fn to_py<'py, T: Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| BambamError::new_err(format!("failure serializing summary: {e}")))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// reads a Python argument such as a dict into a Rust type by way of JSON.
+pub(crate) fn from_py<T: DeserializeOwned>(
+    py: Python<'_>,
+    argument: &str,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<T> {
+    let json: String = py
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json)
+        .map_err(|e| PyValueError::new_err(format!("invalid {argument}: {e}")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    /// runs a test with an attached Python interpreter.
+    fn with_python<F, R>(f: F) -> R
+    where
+        F: for<'py> FnOnce(Python<'py>) -> R,
+    {
+        Python::initialize();
+        Python::attach(f)
+    }
+
+    #[test]
+    fn test_parse_value_enum() {
+        let policy: DateMappingPolicyType =
+            parse_value_enum("date_mapping_policy", "nearest-date").expect("test invariant failed");
+        assert_eq!(policy, DateMappingPolicyType::NearestDate);
+        let format: ExtentFormat =
+            parse_value_enum("extent_format", "GeoJSON").expect("test invariant failed");
+        assert_eq!(format, ExtentFormat::GeoJson);
+    }
+
+    #[test]
+    fn test_parse_value_enum_invalid() {
+        with_python(|py| {
+            let error = parse_value_enum::<ExtentFormat>("extent_format", "wkb")
+                .expect_err("test invariant failed");
+            assert!(error.is_instance_of::<PyValueError>(py));
+            assert!(!error.is_instance_of::<BambamError>(py));
+            let message = error.value(py).to_string();
+            assert!(
+                message.starts_with("invalid extent_format 'wkb', expected one of [wkt, geojson]"),
+                "unexpected message: {message}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_from_py() {
+        with_python(|py| {
+            let value = PyDict::new(py);
+            value.set_item("type", "h3").expect("test invariant failed");
+            value
+                .set_item("resolution", 8)
+                .expect("test invariant failed");
+            let grid_type: GridType =
+                from_py(py, "grid", value.as_any()).expect("test invariant failed");
+            assert!(matches!(
+                grid_type,
+                GridType::H3 { resolution } if resolution == h3o::Resolution::Eight
+            ));
+
+            value
+                .set_item("type", "square")
+                .expect("test invariant failed");
+            let error =
+                from_py::<GridType>(py, "grid", value.as_any()).expect_err("test invariant failed");
+            assert!(error.is_instance_of::<PyValueError>(py));
+            assert!(error.value(py).to_string().starts_with("invalid grid: "));
+        });
+    }
+
+    #[test]
+    fn test_failures_raise_typed_errors() {
+        with_python(|py| {
+            let tmp = tempfile::tempdir().expect("test invariant failed");
+
+            let error = preprocess_grid(
+                py,
+                String::from("NOT VALID WKT"),
+                tmp.path().join("grid.json"),
+                8,
+                "wkt",
+                None,
+            )
+            .expect_err("test invariant failed");
+            assert!(error.is_instance_of::<GridError>(py));
+            assert!(error.is_instance_of::<BambamError>(py));
+
+            let error = osm_pbf(
+                py,
+                tmp.path().join("missing.osm.pbf"),
+                tmp.path().join("output"),
+                None,
+                None,
+            )
+            .expect_err("test invariant failed");
+            assert!(error.is_instance_of::<PbfReadError>(py));
+            assert!(error.is_instance_of::<OsmError>(py));
+            assert!(!error.is_instance_of::<GtfsError>(py));
+
+            let population_source = PyDict::new(py);
+            population_source
+                .set_item("type", "unknown")
+                .expect("test invariant failed");
+            let error = preprocess_grid(
+                py,
+                String::from("POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))"),
+                tmp.path().join("grid.json"),
+                8,
+                "wkt",
+                Some(population_source.as_any()),
+            )
+            .expect_err("test invariant failed");
+            assert!(error.is_instance_of::<PyValueError>(py));
+            assert!(
+                error
+                    .value(py)
+                    .to_string()
+                    .starts_with("invalid population_source: ")
+            );
+        });
+    }
+
+    #[test]
+    fn test_error_variants_raise_subclasses() {
+        with_python(|py| {
+            let error = schedule_error(ScheduleRunError::InvalidCalendar(String::from("wk")));
+            assert!(error.is_instance_of::<ScheduleError>(py));
+            assert!(error.is_instance_of::<GtfsError>(py));
+            assert!(!error.is_instance_of::<MapMatchError>(py));
+            assert_eq!(
+                error.value(py).to_string(),
+                "Cannot find service in calendar.txt with service_id: wk"
+            );
+
+            let error = schedule_error(ScheduleRunError::FailedToCreateVertexIndex(String::from(
+                "missing file",
+            )));
+            assert!(error.is_instance_of::<MapMatchError>(py));
+            let error = schedule_error(ScheduleRunError::MalformedGtfs(String::from("no trips")));
+            assert!(error.is_instance_of::<GtfsReadError>(py));
+            let error = schedule_error(ScheduleRunError::Internal(String::from("bug")));
+            assert!(error.is_instance_of::<GtfsError>(py));
+            assert!(!error.is_instance_of::<ScheduleError>(py));
+            assert!(!error.is_instance_of::<GtfsReadError>(py));
+
+            let error = osm_cli_error(OsmCliError::from(OsmRunError::NoNodesFound));
+            assert!(error.is_instance_of::<PbfReadError>(py));
+            assert!(error.is_instance_of::<OsmError>(py));
+            let error = osm_cli_error(OsmCliError::from(OsmRunError::GraphModificationError(
+                String::from("cycle"),
+            )));
+            assert!(error.is_instance_of::<OsmGraphError>(py));
+            let error = osm_cli_error(OsmCliError::ConfigurationError(String::from("bad")));
+            assert!(error.is_instance_of::<OsmConfigError>(py));
+            let error = osm_cli_error(OsmCliError::from(OsmRunError::InternalError(String::from(
+                "bug",
+            ))));
+            assert!(error.is_instance_of::<OsmError>(py));
+            assert!(!error.is_instance_of::<OsmGraphError>(py));
+
+            let error = gtfs_config_error(GtfsConfigRunError::ReadFailure {
+                filepath: String::from("base.toml"),
+                error: String::from("not found"),
+            });
+            assert!(error.is_instance_of::<GtfsConfigReadError>(py));
+            assert!(error.is_instance_of::<GtfsConfigError>(py));
+            let error = gtfs_config_error(GtfsConfigRunError::RunFailure(String::from("empty")));
+            assert!(!error.is_instance_of::<GtfsConfigReadError>(py));
+        });
+    }
+}
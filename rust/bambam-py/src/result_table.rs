@@ -0,0 +1,122 @@
+//! columnar views of BAMBAM output rows, consumed by the `nlr.bambam.results`
+//! module to build DataFrames without walking the nested JSON in Python.
+
+use bambam_core::model::bambam_table::{self, IsochroneTableRow, OpportunityTableRow};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+use serde_json::Value;
+
+/// registers the result table functions and their column names. column names come
+/// from the `bambam_field` constants, so the Python module never hardcodes them.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "OPPORTUNITY_TABLE_COLUMNS",
+        bambam_table::OPPORTUNITY_TABLE_COLUMNS,
+    )?;
+    m.add(
+        "ISOCHRONE_TABLE_COLUMNS",
+        bambam_table::ISOCHRONE_TABLE_COLUMNS,
+    )?;
+    m.add_function(wrap_pyfunction!(opportunity_table, m)?)?;
+    m.add_function(wrap_pyfunction!(isochrone_table, m)?)?;
+    Ok(())
+}
+
+/// flattens the aggregate opportunity counts of each output row (as JSON strings)
+/// into a dict of equal-length columns keyed by column name.
+#[pyfunction]
+pub fn opportunity_table<'py>(
+    py: Python<'py>,
+    results: Vec<String>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rows = py
+        .detach(|| flatten(&results, bambam_table::opportunity_rows))
+        .map_err(PyValueError::new_err)?;
+    let mut grid_id = Vec::with_capacity(rows.len());
+    let mut mode = Vec::with_capacity(rows.len());
+    let mut bin = Vec::with_capacity(rows.len());
+    let mut activity_type = Vec::with_capacity(rows.len());
+    let mut count = Vec::with_capacity(rows.len());
+    for OpportunityTableRow {
+        grid_id: g,
+        mode: m,
+        bin: b,
+        activity_type: a,
+        count: c,
+    } in rows
+    {
+        grid_id.push(g);
+        mode.push(m);
+        bin.push(b);
+        activity_type.push(a);
+        count.push(c);
+    }
+    let [grid_id_col, mode_col, bin_col, activity_type_col, count_col] =
+        bambam_table::OPPORTUNITY_TABLE_COLUMNS;
+    let table = PyDict::new(py);
+    table.set_item(grid_id_col, grid_id)?;
+    table.set_item(mode_col, mode)?;
+    table.set_item(bin_col, bin)?;
+    table.set_item(activity_type_col, activity_type)?;
+    table.set_item(count_col, count)?;
+    Ok(table)
+}
+
+/// flattens the isochrones of each output row (as JSON strings) into a dict of
+/// equal-length columns keyed by column name, with isochrones as WKB bytes.
+#[pyfunction]
+pub fn isochrone_table<'py>(py: Python<'py>, results: Vec<String>) -> PyResult<Bound<'py, PyDict>> {
+    let rows = py
+        .detach(|| flatten(&results, bambam_table::isochrone_rows))
+        .map_err(PyValueError::new_err)?;
+    let mut grid_id = Vec::with_capacity(rows.len());
+    let mut mode = Vec::with_capacity(rows.len());
+    let mut bin = Vec::with_capacity(rows.len());
+    let mut n_destinations = Vec::with_capacity(rows.len());
+    let mut isochrone = Vec::with_capacity(rows.len());
+    for IsochroneTableRow {
+        grid_id: g,
+        mode: m,
+        bin: b,
+        n_destinations: n,
+        isochrone_wkb,
+    } in rows
+    {
+        grid_id.push(g);
+        mode.push(m);
+        bin.push(b);
+        n_destinations.push(n);
+        isochrone.push(PyBytes::new(py, &isochrone_wkb));
+    }
+    let [
+        grid_id_col,
+        mode_col,
+        bin_col,
+        n_destinations_col,
+        isochrone_col,
+    ] = bambam_table::ISOCHRONE_TABLE_COLUMNS;
+    let table = PyDict::new(py);
+    table.set_item(grid_id_col, grid_id)?;
+    table.set_item(mode_col, mode)?;
+    table.set_item(bin_col, bin)?;
+    table.set_item(n_destinations_col, n_destinations)?;
+    table.set_item(isochrone_col, isochrone)?;
+    Ok(table)
+}
+
+fn flatten<T, E: std::fmt::Display>(
+    results: &[String],
+    op: impl Fn(&mut Value) -> Result<Vec<T>, E>,
+) -> Result<Vec<T>, String> {
+    let mut rows = vec![];
+    for (idx, result) in results.iter().enumerate() {
+        let mut value: Value = serde_json::from_str(result)
+            .map_err(|e| format!("result {idx} is not valid JSON: {e}"))?;
+        let mut flattened = op(&mut value).map_err(|e| format!("result {idx}: {e}"))?;
+        rows.append(&mut flattened);
+    }
+    Ok(rows)
+}
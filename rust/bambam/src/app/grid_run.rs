@@ -0,0 +1,146 @@
+use bambam_core::model::bambam_field;
+use serde_json::json;
+
+/// splits the queries of a grid run into batches. the query of each grid cell is
+/// only built when its batch is taken, and is paired with each travel mode at that
+/// time, so neither the grid nor its product with the modes is held in memory.
+/// queries are ordered by grid cell, and by mode within each cell.
+pub struct GridRunBatches {
+    queries: Box<dyn Iterator<Item = Result<serde_json::Value, String>> + Send + Sync>,
+    n_cells: usize,
+    n_modes: usize,
+    batch_size: usize,
+    position: usize,
+}
+
+impl GridRunBatches {
+    /// creates batches over the grid cell queries. if no modes are provided, the
+    /// cell queries are submitted unmodified, otherwise each query has its `mode`
+    /// key set to one of the modes.
+    pub fn new<I>(cells: I, modes: Vec<String>, batch_size: usize) -> Result<GridRunBatches, String>
+    where
+        I: ExactSizeIterator<Item = Result<serde_json::Value, String>> + Send + Sync + 'static,
+    {
+        if batch_size == 0 {
+            return Err(String::from("batch size must be greater than zero"));
+        }
+        let n_cells = cells.len();
+        let n_modes = modes.len();
+        let queries = cells.flat_map(move |cell| with_modes(cell, &modes));
+        Ok(GridRunBatches {
+            queries: Box::new(queries),
+            n_cells,
+            n_modes,
+            batch_size,
+            position: 0,
+        })
+    }
+
+    /// number of grid cells in this run
+    pub fn n_cells(&self) -> usize {
+        self.n_cells
+    }
+
+    /// total number of queries in this run
+    pub fn n_queries(&self) -> usize {
+        self.n_cells * self.n_modes.max(1)
+    }
+
+    /// number of queries already taken in batches
+    pub fn n_taken(&self) -> usize {
+        self.position
+    }
+}
+
+/// repeats a grid cell query once per mode.
+fn with_modes(
+    cell: Result<serde_json::Value, String>,
+    modes: &[String],
+) -> Vec<Result<serde_json::Value, String>> {
+    let cell = match cell {
+        Ok(cell) if !modes.is_empty() => cell,
+        other => return vec![other],
+    };
+    modes
+        .iter()
+        .map(|mode| {
+            let mut query = cell.clone();
+            let map = query.as_object_mut().ok_or_else(|| {
+                format!("internal error, grid cell query is not JSON mappable: {cell}")
+            })?;
+            map.insert(bambam_field::MODE.to_string(), json![mode]);
+            Ok(query)
+        })
+        .collect()
+}
+
+impl Iterator for GridRunBatches {
+    type Item = Result<Vec<serde_json::Value>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self
+            .queries
+            .by_ref()
+            .take(self.batch_size)
+            .collect::<Result<Vec<_>, _>>();
+        match batch {
+            Ok(batch) if batch.is_empty() => None,
+            Ok(batch) => {
+                self.position += batch.len();
+                Some(Ok(batch))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_pair_cells_with_modes() {
+        let cells = vec![json!({ "grid_id": "a" }), json!({ "grid_id": "b" })];
+        let modes = vec![String::from("walk"), String::from("bike")];
+        let batches = GridRunBatches::new(cells.into_iter().map(Ok), modes, 3)
+            .expect("should build batches")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should create batches");
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), 3);
+        assert_eq!(batches[1].len(), 1);
+        assert_eq!(batches[0][1], json!({ "grid_id": "a", "mode": "bike" }));
+        assert_eq!(batches[1][0], json!({ "grid_id": "b", "mode": "bike" }));
+    }
+
+    #[test]
+    fn test_batches_without_modes() {
+        let cells = vec![json!({ "grid_id": "a", "mode": "drive" })];
+        let batches = GridRunBatches::new(cells.clone().into_iter().map(Ok), vec![], 10)
+            .expect("should build batches")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should create batches");
+        assert_eq!(batches, vec![cells]);
+    }
+
+    #[test]
+    fn test_batches_take_queries_lazily() {
+        let cells = (0..5).map(|idx| {
+            if idx < 3 {
+                Ok(json!({ "grid_id": idx }))
+            } else {
+                Err(format!("cell {idx} should not be built"))
+            }
+        });
+        let mut batches = GridRunBatches::new(cells, vec![String::from("walk")], 3)
+            .expect("should build batches");
+        assert_eq!(batches.n_queries(), 5);
+        let first = batches
+            .next()
+            .expect("should have a batch")
+            .expect("first batch only builds the first three cells");
+        assert_eq!(first.len(), 3);
+        assert_eq!(batches.n_taken(), 3);
+        assert!(batches.next().expect("should have a batch").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 mod gtfs_config_error;
 mod run;
 
-pub use run::run;
+pub use gtfs_config_error::GtfsConfigError;
+pub use run::{run, GtfsConfigSummary};
@@ -54,6 +54,17 @@ pub fn metadata_filename(edge_list_id: EdgeListId) -> String {
     format!("edges-gtfs-metadata-{edge_list_id}.json")
 }
 
+/// outcome of adding GTFS edge lists to a configuration file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GtfsConfigSummary {
+    /// GTFS edge lists appended to the configuration
+    pub gtfs_edge_lists: usize,
+    /// edge list id assigned to the first GTFS edge list in the configuration
+    pub first_edge_list_id: usize,
+    /// path of the configuration file written
+    pub output_file: String,
+}
+
 /// executes a run of the GTFS configuration application.
 ///
 /// the algorithm here can be seen as the following steps:
@@ -67,7 +78,7 @@ pub fn run(
     directory: &str,
     base_config_filepath: &str,
     base_config_relative_path: Option<&str>,
-) -> Result<(), GtfsConfigError> {
+) -> Result<GtfsConfigSummary, GtfsConfigError> {
     // we will load and modify the base TOML configuration file. in particular,
     // we are modifying the `[[graph.edge_list]]` and `[[search]]` sections.
     let mut compass_conf: CompassAppConfig =
@@ -215,7 +226,11 @@ pub fn run(
         ))
     })?;
 
-    Ok(())
+    Ok(GtfsConfigSummary {
+        gtfs_edge_lists: n_entries,
+        first_edge_list_id: start_edge_list_id,
+        output_file: out_filepath.to_string_lossy().to_string(),
+    })
 }
 
 /// collect all fully-qualified route ids as a contiguous vector for enumeration and write to disk,
@@ -15,6 +15,7 @@ use crate::{
         traversal::multimodal::MultimodalTraversalConfig,
     },
 };
+use bambam_core::model::bambam_field;
 use bambam_gtfs_flex::{
     model::{
         consts::{self, MODE_NAME},
@@ -439,7 +440,7 @@ fn inject_plugin_start_time(start_time: NaiveDateTime) -> Value {
         "type": "inject",
         "write_mode": "overwrite",
         "format": "key_value",
-        "key": "start_time",
+        "key": bambam_field::START_TIME,
         "value": json!(start_time)
     })
 }
@@ -1,4 +1,6 @@
+pub mod grid_run;
 pub mod gtfs_config;
 pub mod gtfs_flex_config;
 pub mod oppvec;
 pub mod overlay;
+pub mod preprocess_grid;
@@ -18,6 +18,7 @@ use routee_compass_core::{
     },
     util::fs::read_utils,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fs::File,
@@ -25,6 +26,19 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+/// outcome of vectorizing an opportunity dataset.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OpportunityVectorSummary {
+    /// opportunity rows read from the source dataset
+    pub opportunities: usize,
+    /// opportunity rows that were not attached to a vertex of the graph
+    pub dropped_opportunities: usize,
+    /// rows written to the output file, one per vertex
+    pub vertices: usize,
+    /// total count of each activity type attached to the graph
+    pub activity_totals: HashMap<String, u64>,
+}
+
 /// reads in opportunity data from some long-formatted opportunity dataset and aggregates
 /// it to some vertex dataset
 pub fn run(
@@ -33,7 +47,7 @@ pub fn run(
     output_filename: &str,
     source_format: &SourceFormat,
     // activity_categories: &[String],
-) -> Result<(), String> {
+) -> Result<OpportunityVectorSummary, String> {
     // load Compass Vertices, create spatial index
     let bar_builder = Bar::builder().desc("read vertices file");
     let vertices: Box<[Vertex]> = read_utils::from_csv(
@@ -51,6 +65,7 @@ pub fn run(
     // load opportunity data, build activity types lookup
     let opportunities: Vec<OppRow> =
         read_opportunity_rows_v2(opportunities_filename, source_format)?;
+    let n_opportunities = opportunities.len();
     let activity_types_lookup = source_format
         .activity_categories()
         .into_iter()
@@ -95,6 +110,7 @@ pub fn run(
         desc = "group opportunities by vertex id"
     );
     let grouped = group_iter.flatten().into_group_map();
+    let n_attached: usize = grouped.values().map(|opps| opps.len()).sum();
     eprintln!();
 
     // aggregate long-format data to wide-format using the activity type lookup to
@@ -168,6 +184,17 @@ pub fn run(
         .write_record(&header)
         .map_err(|e| format!("failure writing CSV header: {e}"))?;
     let n_output_rows = result.len();
+    let mut activity_totals = header
+        .iter()
+        .map(|name| (name.clone(), 0))
+        .collect::<HashMap<_, _>>();
+    for row in result.iter() {
+        for (name, count) in header.iter().zip(row.iter()) {
+            if let Some(total) = activity_totals.get_mut(name) {
+                *total += count;
+            }
+        }
+    }
     let write_iter = tqdm!(
         result.into_iter().enumerate(),
         desc = "writing opportunities.csv",
@@ -181,7 +208,12 @@ pub fn run(
     }
     eprintln!();
 
-    Ok(())
+    Ok(OpportunityVectorSummary {
+        opportunities: n_opportunities,
+        dropped_opportunities: n_opportunities - n_attached,
+        vertices: n_output_rows,
+        activity_totals,
+    })
 }
 
 pub struct OppRow {
@@ -5,7 +5,7 @@ pub mod oppvec_ops;
 mod source_format;
 mod source_format_config;
 
-pub use app::run;
+pub use app::{run, OpportunityVectorSummary};
 pub use geometry_format::GeometryFormat;
 pub use source_format::SourceFormat;
 pub use source_format_config::SourceFormatConfig;
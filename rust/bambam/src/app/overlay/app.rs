@@ -1,11 +1,16 @@
-use super::OverlayOperation;
+use super::{OverlayOperation, OverlayOutputFormat};
 use crate::app::overlay::{GeometryColumnType, Grouping, OverlaySource};
+use crate::model::output_plugin::spatial_file::{
+    SpatialColumn, SpatialColumnType, SpatialFileFormat, SpatialFileWriter, SpatialGeometryType,
+    SpatialValue,
+};
 use csv::{Reader, StringRecord};
 use geo::Geometry;
 use itertools::Itertools;
 use kdam::{tqdm, BarBuilder, BarExt};
 use rayon::prelude::*;
 use routee_compass_core::util::{fs::read_utils, geo::PolygonalRTree};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs::File,
@@ -13,18 +18,37 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+/// outcome of partitioning a bambam output by an overlay dataset.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OverlaySummary {
+    /// features read from the overlay dataset
+    pub overlay_features: usize,
+    /// rows read from the bambam output
+    pub rows: usize,
+    /// rows that did not match exactly one overlay feature and were not written
+    pub dropped_rows: usize,
+    /// partitions written, one per matched overlay feature. partitions are written
+    /// to separate CSVs or as features of a single spatial file.
+    pub files_written: usize,
+}
+
 /// function to aggregate mep output rows to some overlay geometry dataset.
 /// the number of output rows is not dependent on the size of the source geometry dataset,
 /// instead based on the number of geometry rows with matches in the mep dataset.
 /// only mep score and population data are aggregated at this time, via summation.
+///
+/// with a spatial output format, all matched rows are written to a single
+/// `overlay.fgb` or `overlay.gpkg` file in the output directory, with the row
+/// geometry, the overlay id and the columns of the bambam output as attributes.
 pub fn run(
     bambam_filepath: &str,
     output_directory: &str,
     overlay_source: &OverlaySource,
     col_type: &GeometryColumnType,
     _: &OverlayOperation,
+    output_format: &OverlayOutputFormat,
     verbose: bool,
-) -> Result<(), String> {
+) -> Result<OverlaySummary, String> {
     // fail early if IO error from read/write destinations
     let output_directory = Path::new(output_directory);
     std::fs::create_dir_all(output_directory).map_err(|e| {
@@ -36,7 +60,8 @@ pub fn run(
 
     // read overlay dataset
     let overlay_data = overlay_source.build()?;
-    log::info!("found {} rows in overlay dataset", overlay_data.len());
+    let n_overlay_features = overlay_data.len();
+    log::info!("found {n_overlay_features} rows in overlay dataset");
     let overlay_lookup = overlay_data
         .iter()
         .map(|(geom, geoid)| (geoid.clone(), geom.clone()))
@@ -52,13 +77,14 @@ pub fn run(
 
     let (header_record, headers) = build_header_lookup(&mut reader)?;
 
-    let grouped_rows: Vec<(String, (StringRecord, Geometry))> =
+    let (grouped_rows, n_rows) =
         spatial_lookup(reader, overlay.clone(), &headers, col_type, verbose)?;
+    let n_matched = grouped_rows.len();
 
-    let mut grouped_lookup: HashMap<String, (Geometry, Vec<StringRecord>)> = HashMap::new();
+    let mut grouped_lookup: HashMap<String, Partition> = HashMap::new();
     for (grouping, (row, geom)) in grouped_rows.into_iter() {
         match grouped_lookup.get_mut(&grouping) {
-            Some((_, v)) => v.push(row),
+            Some((_, v)) => v.push((row, geom)),
             None => {
                 let geometry = overlay_lookup.get(&grouping).ok_or_else(|| {
                     format!(
@@ -66,20 +92,47 @@ pub fn run(
                         grouping
                     )
                 })?;
-                let _ = grouped_lookup.insert(grouping.clone(), (geometry.clone(), vec![row]));
+                let _ =
+                    grouped_lookup.insert(grouping.clone(), (geometry.clone(), vec![(row, geom)]));
             }
         }
     }
 
     let len = grouped_lookup.len();
+    let partitions = grouped_lookup
+        .into_iter()
+        .sorted_by_cached_key(|(k, _)| k.clone());
+    match output_format.spatial_file_format() {
+        None => write_csv_partitions(output_directory, &header_record, partitions, len)?,
+        Some(format) => {
+            write_spatial_file(output_directory, format, &header_record, partitions, len)?
+        }
+    }
+
+    Ok(OverlaySummary {
+        overlay_features: n_overlay_features,
+        rows: n_rows,
+        dropped_rows: n_rows - n_matched,
+        files_written: len,
+    })
+}
+
+/// the geometry of an overlay feature along with the matched rows and their geometries.
+type Partition = (Geometry, Vec<(StringRecord, Geometry)>);
+
+/// writes each partition to its own CSV named by the sanitized overlay id.
+fn write_csv_partitions(
+    output_directory: &Path,
+    header_record: &StringRecord,
+    partitions: impl Iterator<Item = (String, Partition)>,
+    len: usize,
+) -> Result<(), String> {
     let write_iter = tqdm!(
-        grouped_lookup
-            .into_iter()
-            .sorted_by_cached_key(|(k, _)| k.clone()),
+        partitions,
         desc = "writing partitioned datasets",
         total = len
     );
-    for (id, (overlay, raw_rows)) in write_iter {
+    for (id, (_, rows)) in write_iter {
         let id_sani = sanitize_filename::sanitize(&id);
         let out_filename = format!("{id_sani}.csv");
         let out_filepath = output_directory.join(out_filename);
@@ -91,20 +144,133 @@ pub fn run(
         })?;
 
         output_writer
-            .write_record(&header_record)
+            .write_record(header_record)
             .map_err(|e| format!("failure writing header to output: {e}"))?;
 
-        for row in raw_rows.into_iter() {
+        for (row, _) in rows.into_iter() {
             output_writer
                 .write_record(&row)
                 .map_err(|e| format!("failure writing row to output: {e}"))?;
         }
     }
+    Ok(())
+}
+
+/// column name of the overlay feature id in spatial file outputs.
+const OVERLAY_ID_COLUMN: &str = "overlay_id";
 
+/// writes all partitions to a single spatial file, with one feature per row. column
+/// types are inferred from the values: integer or real if every non-empty value
+/// parses as one, otherwise text.
+fn write_spatial_file(
+    output_directory: &Path,
+    format: SpatialFileFormat,
+    header_record: &StringRecord,
+    partitions: impl Iterator<Item = (String, Partition)>,
+    len: usize,
+) -> Result<(), String> {
+    let partitions = partitions.collect_vec();
+    let rows = || {
+        partitions
+            .iter()
+            .flat_map(|(id, (_, rows))| rows.iter().map(move |r| (id, r)))
+    };
+    let Some((_, (_, first_geometry))) = rows().next() else {
+        return Ok(());
+    };
+    let geometry_type = match first_geometry {
+        Geometry::Point(_) => SpatialGeometryType::Point,
+        Geometry::Polygon(_) | Geometry::MultiPolygon(_) => SpatialGeometryType::MultiPolygon,
+        _ => {
+            return Err(String::from(
+                "spatial overlay outputs support point or polygonal row geometries",
+            ))
+        }
+    };
+    let column_types = header_record
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| infer_column_type(rows().filter_map(|(_, (row, _))| row.get(idx))))
+        .collect_vec();
+    let columns = std::iter::once(SpatialColumn {
+        name: String::from(OVERLAY_ID_COLUMN),
+        column_type: SpatialColumnType::Text,
+    })
+    .chain(
+        header_record
+            .iter()
+            .zip(column_types.iter())
+            .filter(|(name, _)| *name != OVERLAY_ID_COLUMN)
+            .map(|(name, column_type)| SpatialColumn {
+                name: name.to_string(),
+                column_type: *column_type,
+            }),
+    )
+    .collect_vec();
+
+    let out_filepath = output_directory.join(format!("overlay.{}", format.extension()));
+    let mut writer =
+        SpatialFileWriter::create(&out_filepath, format, "overlay", geometry_type, columns)?;
+    let write_iter = tqdm!(
+        partitions.iter(),
+        desc = "writing overlay features",
+        total = len
+    );
+    for (id, (_, rows)) in write_iter {
+        for (row, geometry) in rows.iter() {
+            let values = std::iter::once(SpatialValue::Text(id.clone()))
+                .chain(
+                    header_record
+                        .iter()
+                        .zip(row.iter())
+                        .zip(column_types.iter())
+                        .filter(|((name, _), _)| *name != OVERLAY_ID_COLUMN)
+                        .map(|((_, value), column_type)| parse_value(value, *column_type)),
+                )
+                .collect_vec();
+            writer.add_feature(geometry, &values)?;
+        }
+    }
+    let n_features = writer.finish()?;
+    log::info!(
+        "wrote {n_features} features to '{}'",
+        out_filepath.to_string_lossy()
+    );
     Ok(())
 }
 
-type SpatialLookupResult = Result<Vec<(String, (csv::StringRecord, Geometry))>, String>;
+fn infer_column_type<'a>(values: impl Iterator<Item = &'a str>) -> SpatialColumnType {
+    let mut column_type = SpatialColumnType::Integer;
+    for value in values.filter(|v| !v.is_empty()) {
+        if column_type == SpatialColumnType::Integer && value.parse::<i64>().is_err() {
+            column_type = SpatialColumnType::Real;
+        }
+        if column_type == SpatialColumnType::Real && value.parse::<f64>().is_err() {
+            return SpatialColumnType::Text;
+        }
+    }
+    column_type
+}
+
+fn parse_value(value: &str, column_type: SpatialColumnType) -> SpatialValue {
+    if value.is_empty() {
+        return SpatialValue::Null;
+    }
+    match column_type {
+        SpatialColumnType::Text => SpatialValue::Text(value.to_string()),
+        SpatialColumnType::Integer => value
+            .parse()
+            .map(SpatialValue::Integer)
+            .unwrap_or(SpatialValue::Null),
+        SpatialColumnType::Real => value
+            .parse()
+            .map(SpatialValue::Real)
+            .unwrap_or(SpatialValue::Null),
+    }
+}
+
+/// rows matched to a grouping identifier along with the number of rows read.
+type SpatialLookupResult = Result<(Vec<(String, (csv::StringRecord, Geometry))>, usize), String>;
 
 /// performs batch geospatial intersection operations to assign each [`MepRow`] its
 /// grouping identifier (GEOID). run in parallel over the rows argument, a chunk of
@@ -119,7 +285,9 @@ fn spatial_lookup(
     let iter = tqdm!(reader.into_records(), desc = "spatial lookup");
 
     let mut result = vec![];
+    let mut n_rows = 0;
     for (idx, row_result) in iter.enumerate() {
+        n_rows += 1;
         let row = row_result.map_err(|e| format!("cannot read row {idx}: {e}"))?;
         let point = col_type
             .get_point(&row, headers)
@@ -144,7 +312,7 @@ fn spatial_lookup(
     }
 
     eprintln!();
-    Ok(result)
+    Ok((result, n_rows))
 }
 
 pub fn build_header_lookup(
@@ -163,3 +331,148 @@ pub fn build_header_lookup(
 
     Ok((headers, lookup))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geozero::FeatureProperties;
+    use rusqlite::Connection;
+    use std::io::BufReader;
+
+    /// writes two side-by-side square overlay features and a bambam output with a row
+    /// in each square and a row outside of both.
+    fn write_inputs(dir: &Path) -> (String, OverlaySource) {
+        let overlay_file = dir.join("overlay.csv");
+        std::fs::write(
+            &overlay_file,
+            "geoid,geometry\n\
+             A,\"POLYGON((0 0,1 0,1 1,0 1,0 0))\"\n\
+             B,\"POLYGON((1 0,2 0,2 1,1 1,1 0))\"\n",
+        )
+        .unwrap();
+        let bambam_file = dir.join("bambam.csv");
+        std::fs::write(
+            &bambam_file,
+            "grid_id,x,y,score,label\n\
+             1,0.5,0.5,1.5,walk\n\
+             2,1.5,0.5,2,\n\
+             3,5.0,5.0,3,bike\n",
+        )
+        .unwrap();
+        let overlay_source = OverlaySource::Csv {
+            file: overlay_file.to_string_lossy().to_string(),
+            geometry_column: String::from("geometry"),
+            id_column: String::from("geoid"),
+        };
+        (bambam_file.to_string_lossy().to_string(), overlay_source)
+    }
+
+    fn run_overlay(dir: &Path, output_format: OverlayOutputFormat) -> OverlaySummary {
+        let (bambam_file, overlay_source) = write_inputs(dir);
+        let col_type = GeometryColumnType::Xy {
+            x: String::from("x"),
+            y: String::from("y"),
+        };
+        let summary = run(
+            &bambam_file,
+            &dir.join("output").to_string_lossy(),
+            &overlay_source,
+            &col_type,
+            &OverlayOperation::Intersection,
+            &output_format,
+            false,
+        )
+        .unwrap();
+        assert_eq!(summary.overlay_features, 2);
+        assert_eq!(summary.rows, 3);
+        assert_eq!(summary.dropped_rows, 1);
+        assert_eq!(summary.files_written, 2);
+        summary
+    }
+
+    #[test]
+    fn test_run_csv_partitions() {
+        let dir = tempfile::tempdir().unwrap();
+        run_overlay(dir.path(), OverlayOutputFormat::Csv);
+        let a = std::fs::read_to_string(dir.path().join("output").join("A.csv")).unwrap();
+        let b = std::fs::read_to_string(dir.path().join("output").join("B.csv")).unwrap();
+        assert_eq!(a, "grid_id,x,y,score,label\n1,0.5,0.5,1.5,walk\n");
+        assert_eq!(b, "grid_id,x,y,score,label\n2,1.5,0.5,2,\n");
+    }
+
+    #[test]
+    fn test_run_geopackage() {
+        let dir = tempfile::tempdir().unwrap();
+        run_overlay(dir.path(), OverlayOutputFormat::GeoPackage);
+        let conn = Connection::open(dir.path().join("output").join("overlay.gpkg")).unwrap();
+        let mut statement = conn
+            .prepare("SELECT overlay_id, grid_id, score, label FROM overlay ORDER BY overlay_id")
+            .unwrap();
+        let rows = statement
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, i64>(1)?,
+                    r.get::<_, f64>(2)?,
+                    r.get::<_, Option<String>>(3)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (String::from("A"), 1, 1.5, Some(String::from("walk"))),
+                (String::from("B"), 2, 2.0, None),
+            ]
+        );
+        let geometry_type: String = conn
+            .query_row(
+                "SELECT geometry_type_name FROM gpkg_geometry_columns WHERE table_name = 'overlay'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(geometry_type, "POINT");
+    }
+
+    #[test]
+    fn test_run_flatgeobuf() {
+        let dir = tempfile::tempdir().unwrap();
+        run_overlay(dir.path(), OverlayOutputFormat::FlatGeobuf);
+        let file = File::open(dir.path().join("output").join("overlay.fgb")).unwrap();
+        let mut reader = BufReader::new(file);
+        let mut features = FgbReader::open(&mut reader)
+            .unwrap()
+            .select_all_seq()
+            .unwrap();
+        let mut rows = vec![];
+        while let Some(feature) = features.next().unwrap() {
+            rows.push((
+                feature.property::<String>(OVERLAY_ID_COLUMN).unwrap(),
+                feature.property::<i64>("grid_id").unwrap(),
+                feature.property::<f64>("score").unwrap(),
+            ));
+        }
+        assert_eq!(
+            rows,
+            vec![(String::from("A"), 1, 1.5), (String::from("B"), 2, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_infer_column_type() {
+        let infer = |values: &[&str]| infer_column_type(values.iter().copied());
+        assert_eq!(infer(&["1", "", "2"]), SpatialColumnType::Integer);
+        assert_eq!(infer(&["1", "2.5"]), SpatialColumnType::Real);
+        assert_eq!(infer(&["1", "walk", "2.5"]), SpatialColumnType::Text);
+        assert_eq!(infer(&[]), SpatialColumnType::Integer);
+        assert_eq!(
+            parse_value("2", SpatialColumnType::Real),
+            SpatialValue::Real(2.0)
+        );
+        assert_eq!(parse_value("", SpatialColumnType::Text), SpatialValue::Null);
+    }
+}
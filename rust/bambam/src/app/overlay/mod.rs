@@ -2,10 +2,16 @@ mod app;
 mod geometry_column_type;
 mod grouping;
 mod overlay_operation;
+mod overlay_output_format;
 mod overlay_source;
+mod tiger_geography;
+mod tiger_lines;
 
-pub use app::run;
+pub use app::{run, OverlaySummary};
 pub use geometry_column_type::{GeometryColumnType, GeometryFormat};
 pub use grouping::Grouping;
 pub use overlay_operation::OverlayOperation;
+pub use overlay_output_format::OverlayOutputFormat;
 pub use overlay_source::OverlaySource;
+pub use tiger_geography::TigerGeography;
+pub use tiger_lines::read_tiger_lines;
@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::model::output_plugin::spatial_file::SpatialFileFormat;
+
+/// how partitioned overlay results are written.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayOutputFormat {
+    /// one CSV per overlay feature, named by the overlay id
+    #[default]
+    Csv,
+    /// a single FlatGeobuf file of row points with the overlay id as a column
+    #[serde(rename = "flatgeobuf")]
+    #[value(name = "flatgeobuf")]
+    FlatGeobuf,
+    /// a single GeoPackage of row points with the overlay id as a column
+    #[serde(rename = "geopackage")]
+    #[value(name = "geopackage")]
+    GeoPackage,
+}
+
+impl OverlayOutputFormat {
+    /// the spatial file format to write, or None when writing CSVs.
+    pub fn spatial_file_format(&self) -> Option<SpatialFileFormat> {
+        match self {
+            OverlayOutputFormat::Csv => None,
+            OverlayOutputFormat::FlatGeobuf => Some(SpatialFileFormat::FlatGeobuf),
+            OverlayOutputFormat::GeoPackage => Some(SpatialFileFormat::GeoPackage),
+        }
+    }
+}
+
+impl Display for OverlayOutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverlayOutputFormat::Csv => write!(f, "csv"),
+            OverlayOutputFormat::FlatGeobuf => write!(f, "flatgeobuf"),
+            OverlayOutputFormat::GeoPackage => write!(f, "geopackage"),
+        }
+    }
+}
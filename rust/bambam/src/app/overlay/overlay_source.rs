@@ -5,6 +5,8 @@ use geozero::{wkt, ToGeo};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs::File, io::BufReader};
 
+use super::{tiger_lines, TigerGeography};
+
 /// source of overlay geometry dataset
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -17,8 +19,16 @@ pub enum OverlaySource {
     },
     /// reads overlay geometries from a shapefile with an id field
     Shapefile { file: String, id_field: String },
-    /// uses bamsoda-tiger to retrieve the geometries associated with the given geoids from the web
-    TigerLines { geoids: Vec<String> },
+    /// reads TIGER/Line shapefiles or GeoPackages for a vintage and geography level from
+    /// a local directory, selecting the features whose GEOID begins with any of the given
+    /// prefixes. all features are selected if no prefixes are given.
+    TigerLines {
+        directory: String,
+        year: u64,
+        geography: TigerGeography,
+        #[serde(default)]
+        geoids: Vec<String>,
+    },
 }
 
 impl OverlaySource {
@@ -30,15 +40,18 @@ impl OverlaySource {
                 id_column,
             } => read_overlay_csv(file, geometry_column, id_column),
             OverlaySource::Shapefile { file, id_field } => read_overlay_shapefile(file, id_field),
-            OverlaySource::TigerLines { .. } => {
-                todo!("not yet implemented, requires improvements to bamcensus")
-            }
+            OverlaySource::TigerLines {
+                directory,
+                year,
+                geography,
+                geoids,
+            } => tiger_lines::read_tiger_lines(directory, *year, geography, geoids),
         }
     }
 }
 
 /// reads geometries and Strings from a shapefile source
-fn read_overlay_shapefile(
+pub(super) fn read_overlay_shapefile(
     overlay_filepath: &str,
     id_field: &str,
 ) -> Result<Vec<(Geometry, String)>, String> {
@@ -0,0 +1,72 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// geography levels published as TIGER/Line files by the US Census Bureau.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TigerGeography {
+    State,
+    County,
+    Tract,
+    BlockGroup,
+    Block,
+    Place,
+}
+
+impl TigerGeography {
+    /// true if TIGER/Line publishes this geography as a single national file
+    /// instead of one file per state.
+    pub fn is_national(&self) -> bool {
+        matches!(self, TigerGeography::State | TigerGeography::County)
+    }
+
+    /// the layer name used in TIGER/Line filenames for this geography.
+    /// blocks are published against the most recent decennial census.
+    pub fn layer(&self, year: u64) -> &'static str {
+        match self {
+            TigerGeography::State => "state",
+            TigerGeography::County => "county",
+            TigerGeography::Tract => "tract",
+            TigerGeography::BlockGroup => "bg",
+            TigerGeography::Block if year >= 2020 => "tabblock20",
+            TigerGeography::Block => "tabblock10",
+            TigerGeography::Place => "place",
+        }
+    }
+
+    /// the name of the GEOID attribute in TIGER/Line files for this geography.
+    pub fn geoid_field(&self, year: u64) -> &'static str {
+        match self {
+            TigerGeography::Block if year >= 2020 => "GEOID20",
+            TigerGeography::Block => "GEOID10",
+            _ => "GEOID",
+        }
+    }
+
+    /// the filename without extension of a TIGER/Line file, such as `tl_2020_08_tract`.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - vintage of the TIGER/Line release
+    /// * `state_fips` - two-digit state FIPS code, ignored for national geographies
+    pub fn file_stem(&self, year: u64, state_fips: &str) -> String {
+        let scope = if self.is_national() { "us" } else { state_fips };
+        format!("tl_{year}_{scope}_{}", self.layer(year))
+    }
+}
+
+impl Display for TigerGeography {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TigerGeography::State => "state",
+            TigerGeography::County => "county",
+            TigerGeography::Tract => "tract",
+            TigerGeography::BlockGroup => "block_group",
+            TigerGeography::Block => "block",
+            TigerGeography::Place => "place",
+        };
+        write!(f, "{s}")
+    }
+}
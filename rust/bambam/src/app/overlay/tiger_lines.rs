@@ -0,0 +1,242 @@
+use super::{overlay_source::read_overlay_shapefile, TigerGeography};
+use geo::Geometry;
+use geozero::{wkb::GpkgWkb, ToGeo};
+use itertools::Itertools;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// reads TIGER/Line boundaries from a directory of downloaded TIGER/Line files, keeping
+/// the features whose GEOID begins with any of the provided prefixes.
+///
+/// files keep their published names, such as `tl_2020_08_tract.shp` or
+/// `tl_2020_us_county.shp`, and may be unzipped either directly into the directory
+/// or into a sub-directory of the same name. a GeoPackage with the same name and a
+/// `.gpkg` extension may be used in place of a shapefile.
+///
+/// # Arguments
+///
+/// * `directory` - directory containing the TIGER/Line files
+/// * `year` - vintage of the TIGER/Line release
+/// * `geography` - geography level to read
+/// * `geoids` - GEOID prefixes used to select features, such as `08` for all features
+///   in Colorado or `08031` for all features in Denver county. for geographies published
+///   by state, only the files of the states in these prefixes are read. if empty, all
+///   features of all files found for this year and geography are read.
+pub fn read_tiger_lines(
+    directory: &str,
+    year: u64,
+    geography: &TigerGeography,
+    geoids: &[String],
+) -> Result<Vec<(Geometry, String)>, String> {
+    let directory = Path::new(directory);
+    let id_field = geography.geoid_field(year);
+    let stems = file_stems(directory, year, geography, geoids)?;
+    if stems.is_empty() {
+        return Err(format!(
+            "no TIGER/Line {geography} files for year {year} found in directory '{}'",
+            directory.to_string_lossy()
+        ));
+    }
+
+    let mut result = vec![];
+    for stem in stems.iter() {
+        let path = find_tiger_file(directory, stem)?;
+        log::debug!("reading TIGER/Line file {}", path.to_string_lossy());
+        let features = match path.extension().and_then(|e| e.to_str()) {
+            Some("gpkg") => read_geopackage(&path, id_field)?,
+            _ => {
+                let path_str = path.to_str().ok_or_else(|| {
+                    format!("path '{}' is not valid unicode", path.to_string_lossy())
+                })?;
+                read_overlay_shapefile(path_str, id_field)?
+            }
+        };
+        result.extend(
+            features.into_iter().filter(|(_, geoid)| {
+                geoids.is_empty() || geoids.iter().any(|g| geoid.starts_with(g))
+            }),
+        );
+    }
+    Ok(result)
+}
+
+/// finds the TIGER/Line file stems to read. national geographies have a single file,
+/// while other geographies have one file per state, taken from the GEOID prefixes or
+/// found in the directory.
+fn file_stems(
+    directory: &Path,
+    year: u64,
+    geography: &TigerGeography,
+    geoids: &[String],
+) -> Result<Vec<String>, String> {
+    if geography.is_national() {
+        return Ok(vec![geography.file_stem(year, "us")]);
+    }
+    let states: BTreeSet<String> = if geoids.is_empty() {
+        states_in_directory(directory, year, geography)?
+    } else {
+        geoids
+            .iter()
+            .map(|geoid| match geoid.get(0..2) {
+                Some(state) if state.chars().all(|c| c.is_ascii_digit()) => Ok(state.to_string()),
+                _ => Err(format!(
+                    "GEOID prefix '{geoid}' must begin with a two-digit state FIPS code when reading {geography} files"
+                )),
+            })
+            .collect::<Result<_, _>>()?
+    };
+    Ok(states
+        .iter()
+        .map(|state| geography.file_stem(year, state))
+        .collect_vec())
+}
+
+/// lists the state FIPS codes of all TIGER/Line files in a directory for this year
+/// and geography.
+fn states_in_directory(
+    directory: &Path,
+    year: u64,
+    geography: &TigerGeography,
+) -> Result<BTreeSet<String>, String> {
+    let prefix = format!("tl_{year}_");
+    let suffix = format!("_{}", geography.layer(year));
+    let entries = std::fs::read_dir(directory).map_err(|e| {
+        format!(
+            "failure reading directory '{}': {e}",
+            directory.to_string_lossy()
+        )
+    })?;
+    let mut states = BTreeSet::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("failure reading directory entry: {e}"))?;
+        let path = entry.path();
+        let is_tiger_file = path.is_dir()
+            || matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("shp") | Some("gpkg")
+            );
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let state = stem
+            .strip_prefix(&prefix)
+            .and_then(|s| s.strip_suffix(&suffix))
+            .filter(|s| s.len() == 2 && s.chars().all(|c| c.is_ascii_digit()));
+        if let (true, Some(state)) = (is_tiger_file, state) {
+            states.insert(state.to_string());
+        }
+    }
+    Ok(states)
+}
+
+/// finds the shapefile or GeoPackage for a TIGER/Line file stem.
+fn find_tiger_file(directory: &Path, stem: &str) -> Result<PathBuf, String> {
+    let candidates = [
+        directory.join(format!("{stem}.shp")),
+        directory.join(stem).join(format!("{stem}.shp")),
+        directory.join(format!("{stem}.gpkg")),
+    ];
+    candidates
+        .iter()
+        .find(|p| p.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            let tried = candidates.iter().map(|p| p.to_string_lossy()).join(", ");
+            format!("TIGER/Line file '{stem}' not found, tried: [{tried}]")
+        })
+}
+
+/// reads geometries and GEOIDs from the first feature table of a GeoPackage.
+fn read_geopackage(path: &Path, id_field: &str) -> Result<Vec<(Geometry, String)>, String> {
+    let path_str = path.to_string_lossy();
+    let conn =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("failed opening '{path_str}': {e}"))?;
+    let (table, geometry_column): (String, String) = conn
+        .query_row(
+            "SELECT table_name, column_name FROM gpkg_geometry_columns LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("failed finding feature table in '{path_str}': {e}"))?;
+    let query = format!("SELECT \"{id_field}\", \"{geometry_column}\" FROM \"{table}\"");
+    let mut statement = conn
+        .prepare(&query)
+        .map_err(|e| format!("failed querying table '{table}' in '{path_str}': {e}"))?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })
+        .map_err(|e| format!("failed querying table '{table}' in '{path_str}': {e}"))?;
+
+    let mut processed = vec![];
+    for (idx, row) in rows.enumerate() {
+        let (geoid, blob) = row.map_err(|e| format!("failed reading row {idx}: {e}"))?;
+        let geometry = GpkgWkb(blob)
+            .to_geo()
+            .map_err(|e| format!("failed decoding geometry for GEOID {geoid}: {e}"))?;
+        match geometry {
+            Geometry::Polygon(_) | Geometry::MultiPolygon(_) => processed.push((geometry, geoid)),
+            _ => {
+                return Err(format!(
+                    "unexpected geometry type found for GEOID {geoid}, must be polygonal"
+                ))
+            }
+        }
+    }
+    Ok(processed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_tiger_lines;
+    use crate::app::overlay::TigerGeography;
+    use itertools::Itertools;
+    use std::path::PathBuf;
+
+    fn fixture_directory() -> String {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test")
+            .join("tiger")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_read_tracts_by_county_prefix() {
+        let directory = fixture_directory();
+        let tracts = read_tiger_lines(
+            &directory,
+            2020,
+            &TigerGeography::Tract,
+            &[String::from("08013")],
+        )
+        .expect("failed reading fixture");
+        let geoids = tracts
+            .iter()
+            .map(|(_, g)| g.as_str())
+            .sorted()
+            .collect_vec();
+        assert_eq!(geoids, vec!["08013012201", "08013012202"]);
+
+        let all = read_tiger_lines(&directory, 2020, &TigerGeography::Tract, &[])
+            .expect("failed reading fixture");
+        assert_eq!(all.len(), 3);
+    }
+
+    #[test]
+    fn test_read_counties_from_geopackage() {
+        let counties = read_tiger_lines(
+            &fixture_directory(),
+            2020,
+            &TigerGeography::County,
+            &[String::from("08")],
+        )
+        .expect("failed reading fixture");
+        let geoids = counties.iter().map(|(_, g)| g.as_str()).collect_vec();
+        assert_eq!(geoids, vec!["08013"]);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::model::input_plugin::grid::{
+    extent_format::ExtentFormat,
+    grid_input_plugin::{get_query_population_proportion, population_lookup},
+    grid_input_plugin_builder,
+    grid_type::GridType,
+    EXTENT, EXTENT_FORMAT, GRID_TYPE, POPULATION, POPULATION_SOURCE,
+};
+use crate::model::input_plugin::population::population_source_config::PopulationSourceConfig;
+use routee_compass_core::util::geo::PolygonalRTree;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// outcome of pre-processing a grid.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreprocessGridSummary {
+    /// number of grid cells written, one query per line
+    pub grid_cells: usize,
+    pub output_file: String,
+}
+
+/// expands an extent into grid cell queries ahead of a bambam run, attaching
+/// population estimates if a population source is provided, and writes them to
+/// the output file as newline-delimited JSON.
+pub fn run(
+    extent: &str,
+    extent_format: ExtentFormat,
+    grid_type: GridType,
+    population_source: Option<&PopulationSourceConfig>,
+    output_file: &str,
+) -> Result<PreprocessGridSummary, String> {
+    let queries = grid_queries(
+        json![extent],
+        extent_format,
+        grid_type,
+        population_source,
+        None,
+    )?;
+    let grid_cells = queries.n_cells();
+
+    let file = File::create(output_file)
+        .map_err(|e| format!("failure creating output file '{output_file}': {e}"))?;
+    let mut writer = BufWriter::new(file);
+    for value in queries {
+        let json_line = serde_json::to_string(&value?).map_err(|e| e.to_string())?;
+        writeln!(writer, "{json_line}").map_err(|e| e.to_string())?;
+    }
+
+    Ok(PreprocessGridSummary {
+        grid_cells,
+        output_file: output_file.to_string(),
+    })
+}
+
+/// expands an extent into one query per grid cell, as the grid input plugin would.
+/// any keys of the template are copied into each query, as they would be for a
+/// query with an extent submitted to a bambam app. the grid cells are found here,
+/// while the query of each cell is only built as the returned iterator reaches it.
+pub fn grid_queries(
+    extent: serde_json::Value,
+    extent_format: ExtentFormat,
+    grid_type: GridType,
+    population_source: Option<&PopulationSourceConfig>,
+    template: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Result<GridQueries, String> {
+    let mut data = json!({
+        EXTENT_FORMAT: extent_format,
+        GRID_TYPE: grid_type,
+    });
+    if let Some(pop_config) = population_source {
+        data[POPULATION_SOURCE] = json![pop_config];
+    }
+    let plugin = grid_input_plugin_builder::plugin_builder(&data)
+        .map_err(|e| format!("failed to read grid model arguments: {e}"))?;
+
+    let mut input = json!({ EXTENT: extent });
+    let extent = plugin.extent_format.get_extent(&mut input)?;
+    let cells = plugin.grid_type.cells(&extent)?;
+    eprintln!(
+        "finished creating {} grid with {} cells",
+        plugin.grid_type,
+        cells.len()
+    );
+    let population = plugin
+        .population_source
+        .as_ref()
+        .map(|source| population_lookup(&extent, source))
+        .transpose()
+        .map_err(|e| format!("failure running grid processing: {e}"))?;
+
+    Ok(GridQueries {
+        cells: cells.into_iter(),
+        grid_type: plugin.grid_type,
+        template: serde_json::Value::Object(template.cloned().unwrap_or_default()),
+        population,
+    })
+}
+
+/// iterator over the queries of a grid, building the query of each cell, along
+/// with its population estimate, as it is taken.
+pub struct GridQueries {
+    cells: std::vec::IntoIter<h3o::CellIndex>,
+    grid_type: GridType,
+    template: serde_json::Value,
+    population: Option<PolygonalRTree<f64, f64>>,
+}
+
+impl GridQueries {
+    /// number of grid cells not yet taken from this iterator
+    pub fn n_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn query(&self, cell: h3o::CellIndex) -> Result<serde_json::Value, String> {
+        let mut query = self.grid_type.cell_row(cell, &self.template)?;
+        if let Some(rtree) = &self.population {
+            let population = get_query_population_proportion(&query, rtree)
+                .map_err(|e| format!("failure matching query with population data: {e}"))?;
+            query[POPULATION] = json![population];
+        }
+        Ok(query)
+    }
+}
+
+impl Iterator for GridQueries {
+    type Item = Result<serde_json::Value, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cell = self.cells.next()?;
+        Some(self.query(cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl ExactSizeIterator for GridQueries {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::input_plugin::grid::{
+        grid_input_plugin::process_grid_input, GRID_ID, ORIGIN_X, ORIGIN_Y,
+    };
+
+    const EXTENT_WKT: &str =
+        "POLYGON((-105.0 39.7, -104.98 39.7, -104.98 39.72, -105.0 39.72, -105.0 39.7))";
+
+    fn h3(resolution: u8) -> GridType {
+        GridType::H3 {
+            resolution: h3o::Resolution::try_from(resolution)
+                .expect("should be a valid resolution"),
+        }
+    }
+
+    #[test]
+    fn test_run_writes_one_query_per_line() {
+        let tmp = tempfile::tempdir().expect("should create temp directory");
+        let output_file = tmp.path().join("grid.json").to_string_lossy().to_string();
+        let summary = run(EXTENT_WKT, ExtentFormat::Wkt, h3(8), None, &output_file)
+            .expect("should write grid");
+        assert!(summary.grid_cells > 0);
+        assert_eq!(summary.output_file, output_file);
+
+        let contents = std::fs::read_to_string(&output_file).expect("should read output");
+        let queries = contents
+            .lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("each line should be a JSON query");
+        assert_eq!(queries.len(), summary.grid_cells);
+        for query in queries.iter() {
+            assert!(query.get(GRID_ID).is_some());
+            assert!(query.get(ORIGIN_X).is_some());
+            assert!(query.get(ORIGIN_Y).is_some());
+            assert!(query.get(EXTENT).is_none());
+        }
+    }
+
+    #[test]
+    fn test_grid_queries_copy_template_and_match_formats() {
+        let template = json!({ "mode": "walk" });
+        let queries = grid_queries(
+            json![EXTENT_WKT],
+            ExtentFormat::Wkt,
+            h3(8),
+            None,
+            template.as_object(),
+        )
+        .expect("should build grid from WKT");
+        let n_cells = queries.n_cells();
+        let from_wkt = queries
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should build queries from WKT");
+        assert!(!from_wkt.is_empty());
+        assert_eq!(from_wkt.len(), n_cells);
+        assert!(from_wkt.iter().all(|q| q["mode"] == json!("walk")));
+
+        let geojson = json!({
+            "type": "Polygon",
+            "coordinates": [[[-105.0, 39.7], [-104.98, 39.7], [-104.98, 39.72], [-105.0, 39.72], [-105.0, 39.7]]]
+        });
+        let from_geojson = grid_queries(geojson, ExtentFormat::GeoJson, h3(8), None, None)
+            .expect("should build grid from GeoJSON")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should build queries from GeoJSON");
+        let ids =
+            |qs: &[serde_json::Value]| qs.iter().map(|q| q[GRID_ID].clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&from_wkt), ids(&from_geojson));
+    }
+
+    #[test]
+    fn test_grid_queries_are_built_as_taken() {
+        let mut queries = grid_queries(json![EXTENT_WKT], ExtentFormat::Wkt, h3(8), None, None)
+            .expect("should build grid from WKT");
+        let n_cells = queries.n_cells();
+        let first = queries
+            .next()
+            .expect("grid should have cells")
+            .expect("should build first query");
+        assert_eq!(queries.n_cells(), n_cells - 1);
+
+        // the same queries as the grid input plugin builds in a single pass
+        let mut input = json!({ EXTENT: EXTENT_WKT });
+        process_grid_input(&mut input, ExtentFormat::Wkt, h3(8), &None)
+            .expect("should process grid input");
+        let all = std::iter::once(Ok(first))
+            .chain(queries)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should build remaining queries");
+        assert_eq!(json![all], input);
+    }
+
+    #[test]
+    fn test_run_invalid_extent() {
+        let tmp = tempfile::tempdir().expect("should create temp directory");
+        let output_path = tmp.path().join("grid.json");
+        let output_file = output_path.to_string_lossy().to_string();
+        let result = run(
+            "NOT VALID WKT",
+            ExtentFormat::Wkt,
+            h3(8),
+            None,
+            &output_file,
+        );
+        assert!(result.is_err(), "expected error for invalid WKT");
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn test_run_unwritable_output() {
+        let tmp = tempfile::tempdir().expect("should create temp directory");
+        let output_file = tmp
+            .path()
+            .join("missing")
+            .join("grid.json")
+            .to_string_lossy()
+            .to_string();
+        let result = run(EXTENT_WKT, ExtentFormat::Wkt, h3(8), None, &output_file);
+        let error = result.expect_err("expected error for missing output directory");
+        assert!(error.contains("failure creating output file"));
+    }
+}
@@ -1,9 +1,13 @@
 use bambam::app::gtfs_flex_config::CliGtfsFlexConfigApp;
 use bambam::app::oppvec::{self, oppvec_ops};
 use bambam::app::overlay::{
-    self, GeometryColumnType, GeometryFormat, OverlayOperation, OverlaySource,
+    self, GeometryColumnType, GeometryFormat, OverlayOperation, OverlayOutputFormat, OverlaySource,
+    TigerGeography,
 };
+use bambam::app::preprocess_grid;
 use bambam_modal_metrics::common::bulk_compute_modal_metric::bulk_compute_modal_metric;
+use bambam_modal_metrics::islands::bulk_compute_lts_islands::bulk_compute_lts_islands;
+use bambam_modal_metrics::islands::components::ComponentsType;
 use bambam_osm::model::osm::graph::{OsmNodeDataSerializable, OsmWayDataSerializable};
 use clap::{Parser, Subcommand};
 #[derive(Parser)]
@@ -15,17 +19,11 @@ pub struct CliArgs {
 }
 
 use bambam::model::input_plugin::grid::extent_format::ExtentFormat;
-use bambam::model::input_plugin::grid::grid_input_plugin;
-use bambam::model::input_plugin::grid::grid_input_plugin_builder;
 use bambam::model::input_plugin::grid::grid_type::GridType;
 use bambam::model::input_plugin::population::population_source_config::PopulationSourceConfig;
 use bamcensus_acs::model::AcsType;
 use bamcensus_core::model::identifier::GeoidType;
 use h3o::Resolution;
-use serde_json::json;
-use std::fs::File;
-use std::io::BufWriter;
-use std::io::Write;
 
 #[derive(Subcommand)]
 pub enum App {
@@ -34,7 +32,7 @@ pub enum App {
         about = "calculate the modal metric of each way in the OSM network, write to file"
     )]
     ModalMetricSet {
-        /// modal metric type to compute, either "WCI" or "LTS"
+        /// modal metric type to compute, one of "WCI", "LTS", "BLOS", "SIDEWALK" or "CROSSING"
         #[arg(long)]
         metric_name: String,
         /// input csv file with edges data
@@ -43,10 +41,35 @@ pub enum App {
         /// input csv file with vertices data
         #[arg(long)]
         vertices_file: String,
-        /// file to write modal metric values to
+        /// file to write modal metric values to, keyed by the edge id of each row
+        /// in the edges file
         #[arg(long)]
         output_file: String,
     },
+    #[command(
+        name = "modal_metric_islands",
+        about = "find the islands of the OSM network connected by low-stress edges and the gap edges between them, write to files"
+    )]
+    ModalMetricIslands {
+        /// input csv file with edges data
+        #[arg(long)]
+        edges_file: String,
+        /// input csv file with vertices data
+        #[arg(long)]
+        vertices_file: String,
+        /// directory to write the island id of each edge and a GeoJSON of islands and gaps
+        #[arg(long)]
+        output_directory: String,
+        /// highest Level of Traffic Stress of an edge in the low-stress network
+        #[arg(long, default_value_t = 2)]
+        max_lts: u8,
+        /// connected components algorithm used to find islands
+        #[arg(long, value_enum, default_value_t = ComponentsType::Wcc)]
+        components: ComponentsType,
+        /// number of gap edges to report, beginning with those that merge the largest islands
+        #[arg(long, default_value_t = 100)]
+        max_gaps: usize,
+    },
     #[command(
         name = "preprocess_grid",
         about = "processs the grid before running bambam to avoid time-out errors"
@@ -180,6 +203,10 @@ pub enum App {
         /// overlay method to apply
         #[arg(long, default_value_t = OverlayOperation::Intersection)]
         how: OverlayOperation,
+        /// format of the result: one CSV per overlay feature, or a single FlatGeobuf or
+        /// GeoPackage file of row geometries with the overlay id as a column
+        #[arg(long, value_enum, default_value_t = OverlayOutputFormat::Csv)]
+        output_format: OverlayOutputFormat,
         /// name of the id field in the shapefile, used to create the output filepath
         /// for each partitioned dataset. values will be re-encoded for the filesystem,
         /// removing values such as forward slashes that could have unintended effects.
@@ -189,6 +216,55 @@ pub enum App {
         #[arg(long)]
         verbose: bool,
     },
+    #[command(
+        name = "overlay-tiger",
+        about = "partition the BAMBAM output into TIGER/Line boundaries read from a local directory"
+    )]
+    OverlayTiger {
+        /// a CSV file containing a bambam output
+        bambam_output_filepath: String,
+        /// directory containing downloaded TIGER/Line shapefiles or GeoPackages
+        tiger_directory: String,
+        /// file path to write the result dataset
+        output_directory: String,
+        /// vintage of the TIGER/Line files
+        #[arg(long)]
+        year: u64,
+        /// geography level of the boundaries
+        #[arg(long, value_enum)]
+        geography: TigerGeography,
+        /// comma-separated GEOID prefixes used to select boundaries, such as "08" for
+        /// Colorado or "08031" for Denver county. if omitted, all boundaries found in
+        /// the directory are used.
+        #[arg(long, value_delimiter = ',')]
+        geoids: Vec<String>,
+        /// used for specifying column name for the geometry x value. do not combine with
+        /// geomcol argument.
+        #[arg(long)]
+        xcol: Option<String>,
+        /// used for specifying column name for the geometry y value. do not combine with
+        /// geomcol argument.
+        #[arg(long)]
+        ycol: Option<String>,
+        /// used for specifying column name for the geometry. do not combine with xcol or
+        /// ycol arguments.
+        #[arg(long)]
+        geomcol: Option<String>,
+        /// used for specifying the geometry type of the value stored at geomcol do not combine
+        /// with xcol or ycol arguments.
+        #[arg(long)]
+        geomfmt: Option<GeometryFormat>,
+        /// overlay method to apply
+        #[arg(long, default_value_t = OverlayOperation::Intersection)]
+        how: OverlayOperation,
+        /// format of the result: one CSV per overlay feature, or a single FlatGeobuf or
+        /// GeoPackage file of row geometries with the overlay id as a column
+        #[arg(long, value_enum, default_value_t = OverlayOutputFormat::Csv)]
+        output_format: OverlayOutputFormat,
+        /// if true, log if any rows fail to match the provided overlay dataset
+        #[arg(long)]
+        verbose: bool,
+    },
     #[command(
         name = "gtfs-config",
         about = "modifies a BAMBAM configuration file to incorporate a directory of GTFS data assets generated by bambam-gtfs"
@@ -218,12 +294,8 @@ impl App {
                 edges_file,
                 vertices_file,
             } =>
-            // TODO: eventually support other types of graph data beyond OSM for modal metric computation.
-            // Generic traits are available (see bambam-modal-metrics crate).
-            //
-            // Currently, only OSM graph data is supported for modal metric computation.
-            // Once OMF graph data support is implemented, we can add an option to the CLI
-            // to specify the type of graph data to use.
+            // this reads the CSV outputs of an OSM import. networks imported from OvertureMaps
+            // compute modal metrics from their own edge lists with `bambam_omf modal-metric`.
             {
                 bulk_compute_modal_metric::<OsmWayDataSerializable, OsmNodeDataSerializable>(
                     metric_name,
@@ -233,6 +305,22 @@ impl App {
                 )
                 .map_err(|e| format!("failed to run bulk compute modal metric: {e:?}"))
             }
+            Self::ModalMetricIslands {
+                edges_file,
+                vertices_file,
+                output_directory,
+                max_lts,
+                components,
+                max_gaps,
+            } => bulk_compute_lts_islands::<OsmWayDataSerializable, OsmNodeDataSerializable>(
+                edges_file,
+                vertices_file,
+                output_directory,
+                *max_lts,
+                components,
+                *max_gaps,
+            )
+            .map_err(|e| format!("failed to run bulk compute lts islands: {e:?}")),
             Self::PreProcessGrid {
                 acs_type,
                 acs_year,
@@ -259,59 +347,24 @@ impl App {
                 };
 
                 // Using grid_resolution, build grid_type:Gridtype
-                let grid_res_add = *grid_resolution;
                 let grid_type = GridType::H3 {
-                    resolution: grid_res_add,
+                    resolution: *grid_resolution,
                 };
 
                 let extent = std::fs::read_to_string(extent_file)
                     .map_err(|e| format!("failed to read extent file: {e}"))?;
 
-                // unpack the command line arguments into serde_json::Values
-                let mut data: serde_json::Value = json!({
-                    "extent": extent,
-                    "population_source": pop_config,
-                    "extent_format": extent_format,
-                    "grid": grid_type,
-                    "output_file": output_file
-                });
-
-                // BUILD THE PLUGIN
-                let plugin = grid_input_plugin_builder::plugin_builder(&data)
-                    .map_err(|e| format!("failed to read grid model arguments: {e}"))?;
-
-                // RUN GRID BUILDING PROCESS
-                grid_input_plugin::process_grid_input(
-                    &mut data,
-                    plugin.extent_format,
-                    plugin.grid_type,
-                    &plugin.population_source,
-                )
-                .map_err(|e| format!("failure running grid processing: {e}"))?;
-
-                // mutable data as input to process_grid_input becomes a json array
-                // these 3 lines make sure the resulting data array is json, if it is, we have an array to loop through
-                let array = match data.as_array() {
-                    Some(a) => a,
-                    None => {
-                        eprintln!(
-                            "{}",
-                            serde_json::to_string_pretty(&data).unwrap_or_default()
-                        );
-                        return Err(
-                            "resulting data is not an array of JSON as expected".to_string()
-                        );
-                    }
-                };
-
-                // write the resulting array to the output file location as newline-delimited JSON
-                let file = File::create(output_file).map_err(|e| e.to_string())?;
-                let mut writeto = BufWriter::new(file);
-                for value in array {
-                    let json_line = serde_json::to_string(value).map_err(|e| e.to_string())?;
-                    writeln!(writeto, "{json_line}").map_err(|e| e.to_string())?;
-                }
-                println!("Wrote newline-delimited JSON to {output_file}");
+                let summary = preprocess_grid::run(
+                    &extent,
+                    *extent_format,
+                    grid_type,
+                    Some(&pop_config),
+                    output_file,
+                )?;
+                println!(
+                    "Wrote {} grid cells as newline-delimited JSON to {}",
+                    summary.grid_cells, summary.output_file
+                );
                 Ok(())
             }
             Self::OpportunitiesLongFormat {
@@ -348,6 +401,7 @@ impl App {
                     &source_format,
                     // &cats,
                 )
+                .map(log_opportunity_summary)
             }
             Self::OpportunitiesWideFormat {
                 vertices_compass_filename,
@@ -384,6 +438,7 @@ impl App {
                     output_filename,
                     &source_format,
                 )
+                .map(log_opportunity_summary)
             }
             Self::GtfsConfigApp {
                 directory,
@@ -394,6 +449,13 @@ impl App {
                 base_config_filepath,
                 base_config_relative_path.as_deref(),
             )
+            .map(|summary| {
+                log::info!(
+                    "added {} GTFS edge lists to {}",
+                    summary.gtfs_edge_lists,
+                    summary.output_file
+                )
+            })
             .map_err(|e| e.to_string()),
 
             Self::OverlayShapefile {
@@ -405,6 +467,7 @@ impl App {
                 geomcol,
                 geomfmt: geomformat,
                 how,
+                output_format,
                 id_field,
                 verbose,
             } => {
@@ -424,8 +487,48 @@ impl App {
                     &overlay_source,
                     &col_type,
                     how,
+                    output_format,
+                    *verbose,
+                )
+                .map(log_overlay_summary)
+            }
+            Self::OverlayTiger {
+                bambam_output_filepath,
+                tiger_directory,
+                output_directory,
+                year,
+                geography,
+                geoids,
+                xcol: x_column,
+                ycol: y_column,
+                geomcol,
+                geomfmt: geomformat,
+                how,
+                output_format,
+                verbose,
+            } => {
+                let col_type = GeometryColumnType::new(
+                    x_column.as_ref(),
+                    y_column.as_ref(),
+                    geomcol.as_ref(),
+                    geomformat.as_ref(),
+                )?;
+                let overlay_source = OverlaySource::TigerLines {
+                    directory: tiger_directory.clone(),
+                    year: *year,
+                    geography: *geography,
+                    geoids: geoids.clone(),
+                };
+                overlay::run(
+                    bambam_output_filepath,
+                    output_directory,
+                    &overlay_source,
+                    &col_type,
+                    how,
+                    output_format,
                     *verbose,
                 )
+                .map(log_overlay_summary)
             }
             App::GtfsFlexConfigApp(app) => app
                 .clone() // shouldn't happen, App::run should pass owned self.
@@ -435,6 +538,25 @@ impl App {
     }
 }
 
+fn log_opportunity_summary(summary: oppvec::OpportunityVectorSummary) {
+    log::info!(
+        "attached {} of {} opportunities to {} vertices ({} dropped)",
+        summary.opportunities - summary.dropped_opportunities,
+        summary.opportunities,
+        summary.vertices,
+        summary.dropped_opportunities
+    );
+}
+
+fn log_overlay_summary(summary: overlay::OverlaySummary) {
+    log::info!(
+        "wrote {} partitions from {} rows ({} without a single overlay match)",
+        summary.files_written,
+        summary.rows,
+        summary.dropped_rows
+    );
+}
+
 fn main() {
     let args = CliArgs::parse();
     match args.app.run() {
@@ -1,14 +1,20 @@
 use super::input_plugin::grid::grid_input_plugin_builder::GridInputPluginBuilder;
+use super::traversal::comfort::ComfortBuilder;
 use super::traversal::fixed_speed::FixedSpeedBuilder;
+use super::traversal::incoming_edge::IncomingEdgeBuilder;
 use super::traversal::time_delay::TripArrivalDelayBuilder;
 use super::traversal::time_delay::TripDepartureDelayBuilder;
+use crate::model::constraint::max_lts::MaxLtsConstraintBuilder;
 use crate::model::constraint::multimodal::MultimodalConstraintBuilder;
 use crate::model::constraint::time_limit::TimeLimitConstraintBuilder;
+use crate::model::constraint::time_restriction::TimeRestrictionConstraintBuilder;
+use crate::model::constraint::turn_restriction::TurnRestrictionConstraintBuilder;
 use crate::model::label::multimodal::MultimodalLabelBuilder;
 use crate::model::output_plugin::bambam::BambamOutputPluginBuilder;
 use crate::model::output_plugin::h3_util::H3UtilOutputPluginBuilder;
 use crate::model::output_plugin::isochrone::isochrone_output_plugin_builder::IsochroneOutputPluginBuilder;
 use crate::model::output_plugin::opportunity::OpportunityOutputPluginBuilder;
+use crate::model::output_plugin::spatial_file::SpatialFileOutputPluginBuilder;
 use crate::model::traversal::multimodal::MultimodalTraversalBuilder;
 use crate::model::traversal::switch::switch_traversal_builder::SwitchTraversalBuilder;
 use bambam_gbfs::model::constraint::boarding::BoardingConstraintBuilder;
@@ -35,6 +41,11 @@ pub const BUILDER_REGISTRATION: BuilderRegistration = BuilderRegistration(|build
         Rc::new(TripDepartureDelayBuilder {}),
     );
     builders.add_traversal_model(String::from("arrival"), Rc::new(TripArrivalDelayBuilder {}));
+    builders.add_traversal_model(String::from("comfort"), Rc::new(ComfortBuilder {}));
+    builders.add_traversal_model(
+        String::from("incoming_edge"),
+        Rc::new(IncomingEdgeBuilder {}),
+    );
     builders.add_traversal_model(
         String::from("multimodal"),
         Rc::new(MultimodalTraversalBuilder {}),
@@ -64,6 +75,15 @@ pub const BUILDER_REGISTRATION: BuilderRegistration = BuilderRegistration(|build
         String::from("time_limit"),
         Rc::new(TimeLimitConstraintBuilder {}),
     );
+    builders.add_constraint_model(String::from("max_lts"), Rc::new(MaxLtsConstraintBuilder {}));
+    builders.add_constraint_model(
+        String::from("time_restriction"),
+        Rc::new(TimeRestrictionConstraintBuilder {}),
+    );
+    builders.add_constraint_model(
+        String::from("turn_restriction_sequence"),
+        Rc::new(TurnRestrictionConstraintBuilder {}),
+    );
     builders.add_constraint_model(
         String::from("gtfs-flex"),
         Rc::new(GtfsFlexDepartureFrontierBuilder {}),
@@ -81,6 +101,10 @@ pub const BUILDER_REGISTRATION: BuilderRegistration = BuilderRegistration(|build
         String::from("opportunity"),
         Rc::new(OpportunityOutputPluginBuilder {}),
     );
+    builders.add_output_plugin(
+        String::from("spatial_file"),
+        Rc::new(SpatialFileOutputPluginBuilder {}),
+    );
 
     Ok(())
 });
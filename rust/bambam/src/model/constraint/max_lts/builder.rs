@@ -0,0 +1,22 @@
+use super::{MaxLtsConstraintConfig, MaxLtsConstraintService};
+use routee_compass_core::model::constraint::{
+    ConstraintModelBuilder, ConstraintModelError, ConstraintModelService,
+};
+use std::sync::Arc;
+
+pub struct MaxLtsConstraintBuilder {}
+
+impl ConstraintModelBuilder for MaxLtsConstraintBuilder {
+    fn build(
+        &self,
+        config: &serde_json::Value,
+    ) -> Result<Arc<dyn ConstraintModelService>, ConstraintModelError> {
+        let conf: MaxLtsConstraintConfig = serde_json::from_value(config.clone()).map_err(|e| {
+            ConstraintModelError::BuildError(format!(
+                "failure reading max lts constraint model configuration: {e}"
+            ))
+        })?;
+        let service = MaxLtsConstraintService::try_from(&conf)?;
+        Ok(Arc::new(service))
+    }
+}
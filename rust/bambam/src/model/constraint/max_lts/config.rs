@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MaxLtsConstraintConfig {
+    /// file of Level of Traffic Stress values keyed by edge id, written by the
+    /// `modal_metric` command of `bambam_util` with `--metric-name LTS`.
+    pub lts_input_file: String,
+    /// highest LTS allowed when the query does not provide a `max_lts`. if neither
+    /// provide a value, no edges are restricted.
+    #[serde(default)]
+    pub max_lts: Option<u8>,
+    /// LTS assumed for edges missing from the input file. if not provided, edges
+    /// without an LTS value are never restricted.
+    #[serde(default)]
+    pub missing_lts: Option<u8>,
+}
@@ -0,0 +1,12 @@
+mod builder;
+mod config;
+mod model;
+mod service;
+
+pub use builder::MaxLtsConstraintBuilder;
+pub use config::MaxLtsConstraintConfig;
+pub use model::MaxLtsConstraintModel;
+pub use service::MaxLtsConstraintService;
+
+/// query field with the highest Level of Traffic Stress allowed on any edge of the search.
+pub const MAX_LTS_FIELD: &str = "max_lts";
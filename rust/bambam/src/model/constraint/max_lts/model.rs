@@ -0,0 +1,83 @@
+use routee_compass_core::model::{
+    constraint::{ConstraintModel, ConstraintModelError},
+    network::{Edge, EdgeId},
+    state::{StateModel, StateVariable},
+    traversal::EdgeFrontierContext,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// prohibits traversing edges with a Level of Traffic Stress above some maximum, such
+/// as `2` for "low-stress" bike networks. when no maximum is set, all edges are valid.
+pub struct MaxLtsConstraintModel {
+    pub lts: Arc<HashMap<EdgeId, u8>>,
+    pub max_lts: Option<u8>,
+    pub missing_lts: Option<u8>,
+}
+
+impl MaxLtsConstraintModel {
+    /// tests whether the LTS of an edge is within the maximum.
+    pub fn is_valid(&self, edge_id: &EdgeId) -> bool {
+        let max_lts = match self.max_lts {
+            Some(m) => m,
+            None => return true,
+        };
+        match self.lts.get(edge_id).or(self.missing_lts.as_ref()) {
+            Some(lts) => *lts <= max_lts,
+            None => true,
+        }
+    }
+}
+
+impl ConstraintModel for MaxLtsConstraintModel {
+    fn valid_frontier(
+        &self,
+        ctx: &EdgeFrontierContext,
+        _state: &[StateVariable],
+        _state_model: &StateModel,
+    ) -> Result<bool, ConstraintModelError> {
+        Ok(self.is_valid(&ctx.edge.edge_id))
+    }
+
+    fn valid_edge(&self, edge: &Edge) -> Result<bool, ConstraintModelError> {
+        Ok(self.is_valid(&edge.edge_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MaxLtsConstraintModel;
+    use routee_compass_core::model::network::EdgeId;
+    use std::{collections::HashMap, sync::Arc};
+
+    #[test]
+    fn test_max_lts() {
+        let lts = Arc::new(HashMap::from([
+            (EdgeId(0), 1),
+            (EdgeId(1), 2),
+            (EdgeId(2), 4),
+        ]));
+        let model = MaxLtsConstraintModel {
+            lts: lts.clone(),
+            max_lts: Some(2),
+            missing_lts: None,
+        };
+        assert!(model.is_valid(&EdgeId(0)));
+        assert!(model.is_valid(&EdgeId(1)));
+        assert!(!model.is_valid(&EdgeId(2)));
+        assert!(model.is_valid(&EdgeId(3)));
+
+        let strict = MaxLtsConstraintModel {
+            lts: lts.clone(),
+            max_lts: Some(2),
+            missing_lts: Some(4),
+        };
+        assert!(!strict.is_valid(&EdgeId(3)));
+
+        let unrestricted = MaxLtsConstraintModel {
+            lts,
+            max_lts: None,
+            missing_lts: Some(4),
+        };
+        assert!(unrestricted.is_valid(&EdgeId(2)));
+    }
+}
@@ -0,0 +1,99 @@
+use super::{MaxLtsConstraintConfig, MaxLtsConstraintModel};
+use bambam_modal_metrics::{
+    common::edge_metric_record::EdgeLtsRecord,
+    lts::lts::{MAX_LTS, MIN_LTS},
+};
+use kdam::Bar;
+use routee_compass_core::{
+    model::{
+        constraint::{ConstraintModel, ConstraintModelError, ConstraintModelService},
+        network::EdgeId,
+        state::StateModel,
+    },
+    util::fs::read_utils,
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct MaxLtsConstraintService {
+    pub lts: Arc<HashMap<EdgeId, u8>>,
+    pub max_lts: Option<u8>,
+    pub missing_lts: Option<u8>,
+}
+
+impl TryFrom<&MaxLtsConstraintConfig> for MaxLtsConstraintService {
+    type Error = ConstraintModelError;
+
+    fn try_from(conf: &MaxLtsConstraintConfig) -> Result<Self, Self::Error> {
+        let max_lts = conf.max_lts.map(validate_lts).transpose()?;
+        let missing_lts = conf.missing_lts.map(validate_lts).transpose()?;
+        let rows: Box<[EdgeLtsRecord]> = read_utils::from_csv(
+            &conf.lts_input_file,
+            true,
+            Some(Bar::builder().desc("edge lts")),
+            None,
+        )
+        .map_err(|e| {
+            ConstraintModelError::BuildError(format!(
+                "failure reading edge lts values from {}: {e}",
+                conf.lts_input_file
+            ))
+        })?;
+
+        let mut lts = HashMap::with_capacity(rows.len());
+        for row in rows.iter() {
+            let value = validate_lts(row.lts).map_err(|e| {
+                ConstraintModelError::BuildError(format!("edge {}: {e}", row.edge_id))
+            })?;
+            lts.insert(row.edge_id, value);
+        }
+        log::info!(
+            "loaded lts values for {} edges from {}",
+            lts.len(),
+            conf.lts_input_file
+        );
+        Ok(MaxLtsConstraintService {
+            lts: Arc::new(lts),
+            max_lts,
+            missing_lts,
+        })
+    }
+}
+
+impl ConstraintModelService for MaxLtsConstraintService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn ConstraintModel>, ConstraintModelError> {
+        let max_lts = match query.get(super::MAX_LTS_FIELD) {
+            None => self.max_lts,
+            Some(value) => {
+                let max_lts = value
+                    .as_u64()
+                    .and_then(|v| u8::try_from(v).ok())
+                    .ok_or_else(|| {
+                        ConstraintModelError::ConstraintModelError(format!(
+                            "query max_lts must be an integer, found {value}"
+                        ))
+                    })?;
+                Some(validate_lts(max_lts)?)
+            }
+        };
+        let model = MaxLtsConstraintModel {
+            lts: self.lts.clone(),
+            max_lts,
+            missing_lts: self.missing_lts,
+        };
+        Ok(Arc::new(model))
+    }
+}
+
+fn validate_lts(lts: u8) -> Result<u8, ConstraintModelError> {
+    if (MIN_LTS..=MAX_LTS).contains(&lts) {
+        Ok(lts)
+    } else {
+        Err(ConstraintModelError::BuildError(format!(
+            "lts value {lts} must be in the range [{MIN_LTS}..{MAX_LTS}]"
+        )))
+    }
+}
@@ -1,2 +1,5 @@
+pub mod max_lts;
 pub mod multimodal;
 pub mod time_limit;
+pub mod time_restriction;
+pub mod turn_restriction;
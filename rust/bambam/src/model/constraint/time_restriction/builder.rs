@@ -0,0 +1,23 @@
+use super::{TimeRestrictionConstraintConfig, TimeRestrictionConstraintService};
+use routee_compass_core::model::constraint::{
+    ConstraintModelBuilder, ConstraintModelError, ConstraintModelService,
+};
+use std::sync::Arc;
+
+pub struct TimeRestrictionConstraintBuilder {}
+
+impl ConstraintModelBuilder for TimeRestrictionConstraintBuilder {
+    fn build(
+        &self,
+        config: &serde_json::Value,
+    ) -> Result<Arc<dyn ConstraintModelService>, ConstraintModelError> {
+        let conf: TimeRestrictionConstraintConfig = serde_json::from_value(config.clone())
+            .map_err(|e| {
+                ConstraintModelError::BuildError(format!(
+                    "failure reading time restriction constraint model configuration: {e}"
+                ))
+            })?;
+        let service = TimeRestrictionConstraintService::try_from(&conf)?;
+        Ok(Arc::new(service))
+    }
+}
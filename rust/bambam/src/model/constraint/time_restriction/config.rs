@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimeRestrictionConstraintConfig {
+    /// file of time-dependent access restrictions written by an OvertureMaps network import,
+    /// such as `drive/edges-time-restrictions.csv.gz`.
+    pub time_restriction_input_file: String,
+    /// edge list of the graph that the restricted edge ids belong to. restrictions
+    /// only apply to edges of this edge list.
+    pub edge_list_id: usize,
+}
@@ -0,0 +1,9 @@
+mod builder;
+mod config;
+mod model;
+mod service;
+
+pub use builder::TimeRestrictionConstraintBuilder;
+pub use config::TimeRestrictionConstraintConfig;
+pub use model::{EdgeTimeRestrictions, TimeRestrictionConstraintModel};
+pub use service::TimeRestrictionConstraintService;
@@ -0,0 +1,162 @@
+use bambam_core::model::bambam_state;
+use chrono::{NaiveDateTime, TimeDelta};
+use opening_hours::OpeningHours;
+use routee_compass_core::model::{
+    constraint::{ConstraintModel, ConstraintModelError},
+    network::{Edge, EdgeId, EdgeListId},
+    state::{StateModel, StateVariable},
+    traversal::EdgeFrontierContext,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// time-dependent access restrictions on a single edge.
+#[derive(Default)]
+pub struct EdgeTimeRestrictions {
+    /// the edge is closed while any of these are open
+    pub denied: Vec<OpeningHours>,
+    /// if not empty, the edge is only open while one of these is open
+    pub allowed: Vec<OpeningHours>,
+}
+
+impl EdgeTimeRestrictions {
+    /// tests whether the edge may be traversed at the given time.
+    pub fn is_open(&self, datetime: NaiveDateTime) -> bool {
+        let denied = self.denied.iter().any(|oh| oh.is_open(datetime));
+        let allowed = self.allowed.is_empty() || self.allowed.iter().any(|oh| oh.is_open(datetime));
+        allowed && !denied
+    }
+}
+
+/// prohibits traversing edges whose time-dependent access restrictions close them
+/// at the current time of the search, found by adding the trip time to the start datetime.
+/// when the query has no start datetime, no restrictions are applied. restrictions
+/// only apply to edges of their edge list, as edge ids repeat across edge lists.
+pub struct TimeRestrictionConstraintModel {
+    pub edge_list_id: EdgeListId,
+    pub restrictions: Arc<HashMap<EdgeId, EdgeTimeRestrictions>>,
+    pub start_datetime: Option<NaiveDateTime>,
+}
+
+impl ConstraintModel for TimeRestrictionConstraintModel {
+    fn valid_frontier(
+        &self,
+        ctx: &EdgeFrontierContext,
+        state: &[StateVariable],
+        state_model: &StateModel,
+    ) -> Result<bool, ConstraintModelError> {
+        if ctx.edge.edge_list_id != self.edge_list_id {
+            return Ok(true);
+        }
+        let (restrictions, start_datetime) = match (
+            self.restrictions.get(&ctx.edge.edge_id),
+            self.start_datetime.as_ref(),
+        ) {
+            (Some(r), Some(s)) => (r, s),
+            _ => return Ok(true),
+        };
+        let trip_time = state_model
+            .get_time(state, bambam_state::TRIP_TIME)
+            .map_err(|e| ConstraintModelError::ConstraintModelError(e.to_string()))?;
+        let seconds = trip_time.get::<uom::si::time::second>() as i64;
+        let current_datetime = start_datetime
+            .checked_add_signed(TimeDelta::seconds(seconds))
+            .ok_or_else(|| {
+                ConstraintModelError::ConstraintModelError(format!(
+                    "overflow when adding {seconds} seconds to {start_datetime}"
+                ))
+            })?;
+        Ok(restrictions.is_open(current_datetime))
+    }
+
+    fn valid_edge(&self, _edge: &Edge) -> Result<bool, ConstraintModelError> {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EdgeTimeRestrictions, TimeRestrictionConstraintModel};
+    use bambam_core::model::bambam_state;
+    use chrono::NaiveDateTime;
+    use opening_hours::OpeningHours;
+    use routee_compass_core::{
+        algorithm::search::SearchTree,
+        model::{
+            constraint::ConstraintModel,
+            label::Label,
+            network::{Edge, EdgeId, EdgeListId, Vertex, VertexId},
+            state::{StateModel, StateVariableConfig},
+            traversal::EdgeFrontierContext,
+            unit::TimeUnit,
+        },
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use uom::{
+        si::f64::{Length, Time},
+        ConstZero,
+    };
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_school_hours_closure() {
+        let restrictions = EdgeTimeRestrictions {
+            denied: vec![OpeningHours::parse("Mo-Fr 07:30-09:00").unwrap()],
+            allowed: vec![],
+        };
+        // 2025-01-06 is a Monday
+        assert!(!restrictions.is_open(datetime("2025-01-06 08:00:00")));
+        assert!(restrictions.is_open(datetime("2025-01-06 10:00:00")));
+        assert!(restrictions.is_open(datetime("2025-01-11 08:00:00")));
+    }
+
+    #[test]
+    fn test_off_peak_bus_lane() {
+        let restrictions = EdgeTimeRestrictions {
+            denied: vec![],
+            allowed: vec![OpeningHours::parse("Mo-Fr 09:00-16:00").unwrap()],
+        };
+        assert!(!restrictions.is_open(datetime("2025-01-06 08:00:00")));
+        assert!(restrictions.is_open(datetime("2025-01-06 12:00:00")));
+    }
+
+    #[test]
+    fn test_restrictions_apply_within_their_edge_list() {
+        // edge 0 of edge list 1 is closed during school hours. edge 0 of edge list 0 is not
+        let restrictions = EdgeTimeRestrictions {
+            denied: vec![OpeningHours::parse("Mo-Fr 07:30-09:00").unwrap()],
+            allowed: vec![],
+        };
+        let model = TimeRestrictionConstraintModel {
+            edge_list_id: EdgeListId(1),
+            restrictions: Arc::new(HashMap::from([(EdgeId(0), restrictions)])),
+            start_datetime: Some(datetime("2025-01-06 08:00:00")),
+        };
+        let state_model = StateModel::new(vec![(
+            bambam_state::TRIP_TIME.to_string(),
+            StateVariableConfig::Time {
+                initial: Time::ZERO,
+                output_unit: Some(TimeUnit::Minutes),
+                accumulator: true,
+            },
+        )]);
+        let state = state_model
+            .initial_state(None)
+            .expect("test invariant failed");
+        let tree = SearchTree::default();
+        let label = Label::Vertex(VertexId(0));
+        let (src, dst) = (Vertex::new(0, 0.0, 0.0), Vertex::new(1, 0.01, 0.0));
+        let valid_frontier = |edge_list_id: usize| {
+            let length = Length::new::<uom::si::length::meter>(100.0);
+            let edge = Edge::new(edge_list_id, 0, 0, 1, length);
+            let ctx = EdgeFrontierContext::new(&label, &src, &edge, &dst, &tree);
+            model
+                .valid_frontier(&ctx, &state, &state_model)
+                .expect("test invariant failed")
+        };
+        assert!(!valid_frontier(1));
+        assert!(valid_frontier(0));
+    }
+}
@@ -0,0 +1,104 @@
+use super::{
+    EdgeTimeRestrictions, TimeRestrictionConstraintConfig, TimeRestrictionConstraintModel,
+};
+use bambam_core::{
+    model::bambam_field,
+    util::date_deserialization_ops::{parse_naive_datetime, APP_DATETIME_FORMAT},
+};
+use bambam_omf::{collection::record::SegmentAccessType, graph::TimeRestriction};
+use kdam::Bar;
+use opening_hours::OpeningHours;
+use routee_compass_core::{
+    model::{
+        constraint::{ConstraintModel, ConstraintModelError, ConstraintModelService},
+        network::{EdgeId, EdgeListId},
+        state::StateModel,
+    },
+    util::fs::read_utils,
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct TimeRestrictionConstraintService {
+    /// edge list that the restricted edges belong to
+    pub edge_list_id: EdgeListId,
+    pub restrictions: Arc<HashMap<EdgeId, EdgeTimeRestrictions>>,
+}
+
+impl TryFrom<&TimeRestrictionConstraintConfig> for TimeRestrictionConstraintService {
+    type Error = ConstraintModelError;
+
+    fn try_from(conf: &TimeRestrictionConstraintConfig) -> Result<Self, Self::Error> {
+        let rows: Box<[TimeRestriction]> = read_utils::from_csv(
+            &conf.time_restriction_input_file,
+            true,
+            Some(Bar::builder().desc("time restrictions")),
+            None,
+        )
+        .map_err(|e| {
+            ConstraintModelError::BuildError(format!(
+                "failure reading time restrictions from {}: {e}",
+                conf.time_restriction_input_file
+            ))
+        })?;
+
+        let mut restrictions: HashMap<EdgeId, EdgeTimeRestrictions> = HashMap::new();
+        for row in rows.iter() {
+            let opening_hours = OpeningHours::parse(&row.during).map_err(|e| {
+                ConstraintModelError::BuildError(format!(
+                    "invalid opening hours '{}' for edge {}: {e}",
+                    row.during, row.edge_id
+                ))
+            })?;
+            let entry = restrictions.entry(row.edge_id).or_default();
+            match row.access_type {
+                SegmentAccessType::Denied => entry.denied.push(opening_hours),
+                SegmentAccessType::Allowed | SegmentAccessType::Designated => {
+                    entry.allowed.push(opening_hours)
+                }
+            }
+        }
+        log::info!(
+            "loaded {} time restrictions on {} edges of edge list {} from {}",
+            rows.len(),
+            restrictions.len(),
+            conf.edge_list_id,
+            conf.time_restriction_input_file
+        );
+        Ok(TimeRestrictionConstraintService {
+            edge_list_id: EdgeListId(conf.edge_list_id),
+            restrictions: Arc::new(restrictions),
+        })
+    }
+}
+
+impl ConstraintModelService for TimeRestrictionConstraintService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn ConstraintModel>, ConstraintModelError> {
+        let start_datetime = match query.get(bambam_field::START_TIME) {
+            None => {
+                log::debug!("query has no start_time, time restrictions will not be applied");
+                None
+            }
+            Some(value) => {
+                let datetime = value
+                    .as_str()
+                    .and_then(|s| parse_naive_datetime(s).ok())
+                    .ok_or_else(|| {
+                        ConstraintModelError::ConstraintModelError(format!(
+                            "query start_time must be a string in the format '{APP_DATETIME_FORMAT}', found {value}"
+                        ))
+                    })?;
+                Some(datetime)
+            }
+        };
+        let model = TimeRestrictionConstraintModel {
+            edge_list_id: self.edge_list_id,
+            restrictions: self.restrictions.clone(),
+            start_datetime,
+        };
+        Ok(Arc::new(model))
+    }
+}
@@ -0,0 +1,23 @@
+use super::{TurnRestrictionConstraintConfig, TurnRestrictionConstraintService};
+use routee_compass_core::model::constraint::{
+    ConstraintModelBuilder, ConstraintModelError, ConstraintModelService,
+};
+use std::sync::Arc;
+
+pub struct TurnRestrictionConstraintBuilder {}
+
+impl ConstraintModelBuilder for TurnRestrictionConstraintBuilder {
+    fn build(
+        &self,
+        config: &serde_json::Value,
+    ) -> Result<Arc<dyn ConstraintModelService>, ConstraintModelError> {
+        let conf: TurnRestrictionConstraintConfig = serde_json::from_value(config.clone())
+            .map_err(|e| {
+                ConstraintModelError::BuildError(format!(
+                    "failure reading turn restriction constraint model configuration: {e}"
+                ))
+            })?;
+        let service = TurnRestrictionConstraintService::try_from(&conf)?;
+        Ok(Arc::new(service))
+    }
+}
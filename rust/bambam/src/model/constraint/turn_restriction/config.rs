@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// configures the turn restriction constraint. searches using it must build
+/// turn-aware labels: set `turn_aware = true` on the multimodal label model and
+/// include the `incoming_edge` traversal model in every search.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TurnRestrictionConstraintConfig {
+    /// file of prohibited edge sequences written by an OvertureMaps network import,
+    /// such as `drive/edges-turn-restrictions.csv.gz`.
+    pub turn_restriction_input_file: String,
+    /// edge list of the graph that the restricted edge ids belong to. restrictions
+    /// only apply to edges of this edge list.
+    pub edge_list_id: usize,
+}
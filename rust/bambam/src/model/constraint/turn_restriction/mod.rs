@@ -0,0 +1,9 @@
+mod builder;
+mod config;
+mod model;
+mod service;
+
+pub use builder::TurnRestrictionConstraintBuilder;
+pub use config::TurnRestrictionConstraintConfig;
+pub use model::TurnRestrictionConstraintModel;
+pub use service::TurnRestrictionConstraintService;
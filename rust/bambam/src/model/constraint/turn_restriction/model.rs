@@ -0,0 +1,338 @@
+use crate::model::label::multimodal::multimodal_label_ops;
+use routee_compass_core::{
+    algorithm::search::SearchTree,
+    model::{
+        constraint::{ConstraintModel, ConstraintModelError},
+        label::Label,
+        network::{Edge, EdgeId, EdgeListId},
+        state::{StateModel, StateVariable},
+        traversal::EdgeFrontierContext,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// prohibits traversing the final edge of a turn restriction when the search
+/// arrived along the preceding edges of that restriction. restrictions are keyed by
+/// edge list, and the traversed edge and every preceding edge must belong to the
+/// edge list of the restriction, so edges of other edge lists sharing an edge id
+/// are never restricted.
+///
+/// requires turn-aware search labels, built by the multimodal label model with
+/// `turn_aware = true` and the `incoming_edge` traversal model. with vertex-only
+/// labels, every edge arriving at a vertex shares one search tree node, so a legal
+/// alternate approach to a restricted turn would never be explored. this model
+/// fails the search instead of silently returning such routes.
+pub struct TurnRestrictionConstraintModel {
+    pub restrictions: Arc<HashMap<(EdgeListId, EdgeId), Vec<Vec<EdgeId>>>>,
+}
+
+impl ConstraintModel for TurnRestrictionConstraintModel {
+    fn valid_frontier(
+        &self,
+        ctx: &EdgeFrontierContext,
+        _state: &[StateVariable],
+        _state_model: &StateModel,
+    ) -> Result<bool, ConstraintModelError> {
+        let restrictions = match self
+            .restrictions
+            .get(&(ctx.edge.edge_list_id, ctx.edge.edge_id))
+        {
+            Some(r) => r,
+            None => return Ok(true),
+        };
+        let incoming_edge_id = match incoming_edge_id(ctx.tree, ctx.parent_label)? {
+            Some(edge_id) => edge_id,
+            None => return Ok(true),
+        };
+        let is_restricted = restrictions.iter().any(|preceding| {
+            preceding.first() == Some(&incoming_edge_id)
+                && arrived_along(
+                    ctx.tree,
+                    ctx.parent_label,
+                    &ctx.edge.edge_list_id,
+                    preceding,
+                )
+        });
+        Ok(!is_restricted)
+    }
+
+    fn valid_edge(&self, _edge: &Edge) -> Result<bool, ConstraintModelError> {
+        Ok(true)
+    }
+}
+
+/// the edge the search used to arrive at the label, read from the turn-aware label.
+/// returns None at the search origin and fails for labels without turn awareness.
+fn incoming_edge_id(
+    tree: &SearchTree,
+    label: &Label,
+) -> Result<Option<EdgeId>, ConstraintModelError> {
+    if let Some(edge_id) = multimodal_label_ops::get_incoming_edge_id(label) {
+        return Ok(Some(edge_id));
+    }
+    let is_origin = tree
+        .get(label)
+        .map(|node| node.incoming_edge().is_none())
+        .unwrap_or(true);
+    if is_origin {
+        Ok(None)
+    } else {
+        Err(ConstraintModelError::ConstraintModelError(format!(
+            "turn restrictions require turn-aware labels (multimodal label model with \
+            turn_aware = true and the incoming_edge traversal model), found label {label}"
+        )))
+    }
+}
+
+/// walks back the search tree from a label, testing whether the most recent edges
+/// of the branch match the preceding edges (most recent first) on the same edge list.
+fn arrived_along(
+    tree: &SearchTree,
+    label: &Label,
+    edge_list_id: &EdgeListId,
+    preceding: &[EdgeId],
+) -> bool {
+    let mut label = label;
+    for expected in preceding.iter() {
+        let node = match tree.get(label) {
+            Some(node) => node,
+            None => return false,
+        };
+        match (node.incoming_edge(), node.parent_label()) {
+            (Some(et), Some(parent))
+                if et.edge_id == *expected && et.edge_list_id == *edge_list_id =>
+            {
+                label = parent;
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::TurnRestrictionConstraintModel;
+    use crate::model::traversal::incoming_edge::IncomingEdgeModel;
+    use routee_compass_core::{
+        algorithm::search::{EdgeTraversal, SearchTree},
+        model::{
+            constraint::ConstraintModel,
+            cost::{CostConstraint, CostModel, VehicleCostRate},
+            label::Label,
+            network::{Edge, EdgeId, EdgeListId, Vertex, VertexId},
+            state::{StateModel, StateVariable},
+            traversal::{EdgeFrontierContext, TraversalModel},
+        },
+        testing::mock::traversal_model::TestTraversalModel,
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use uom::si::f64::Length;
+
+    /// search tree over the network
+    ///
+    /// ```text
+    /// (0) -[0]-> (1) -[1]-> (3)
+    ///  |          ^
+    /// [2]        [3]
+    ///  v          |
+    /// (2) --------+
+    /// ```
+    ///
+    /// where vertex 1 is reached both directly along edge 0 and along edges 2 and 3.
+    struct TestTree {
+        tree: SearchTree,
+        state: Vec<StateVariable>,
+        state_model: Arc<StateModel>,
+    }
+
+    /// (src, dst) vertex of each edge by edge id
+    const EDGES: [(usize, usize); 4] = [(0, 1), (1, 3), (0, 2), (2, 1)];
+
+    fn vertex(vertex_id: usize) -> Vertex {
+        Vertex::new(vertex_id, vertex_id as f32 * 0.01, 0.0)
+    }
+
+    fn trajectory(edge_list_id: usize, edge_id: usize) -> (Vertex, Edge, Vertex) {
+        let (src, dst) = EDGES[edge_id];
+        let length = Length::new::<uom::si::length::meter>(100.0);
+        (
+            vertex(src),
+            Edge::new(edge_list_id, edge_id, src, dst, length),
+            vertex(dst),
+        )
+    }
+
+    /// a turn-aware label, or a label at the search origin.
+    fn label(vertex_id: usize, incoming_edge_id: Option<usize>) -> Label {
+        match incoming_edge_id {
+            Some(edge_id) => Label::VertexWithIntStateVec {
+                vertex_id: VertexId(vertex_id),
+                state: vec![edge_id],
+            },
+            None => Label::Vertex(VertexId(vertex_id)),
+        }
+    }
+
+    fn build_tree(branches: &[(Label, usize, Label)]) -> TestTree {
+        build_tree_on(0, branches)
+    }
+
+    /// builds a search tree whose edges all belong to the given edge list.
+    fn build_tree_on(edge_list_id: usize, branches: &[(Label, usize, Label)]) -> TestTree {
+        let tm =
+            TestTraversalModel::new(Arc::new(IncomingEdgeModel {})).expect("test invariant failed");
+        let state_model = Arc::new(StateModel::new(tm.output_features()));
+        let state = state_model
+            .initial_state(None)
+            .expect("test invariant failed");
+        let cost_model = mock_cost_model(state_model.clone());
+        let mut tree = SearchTree::default();
+        for (parent, edge_id, child) in branches.iter() {
+            let (src, e, dst) = trajectory(edge_list_id, *edge_id);
+            let ctx = EdgeFrontierContext::new(parent, &src, &e, &dst, &tree);
+            let et = EdgeTraversal::new_local(&ctx, &state, &state_model, tm.as_ref(), &cost_model)
+                .expect("test invariant failed");
+            tree.insert_trajectory(parent.clone(), et, child.clone());
+        }
+        TestTree {
+            tree,
+            state,
+            state_model,
+        }
+    }
+
+    fn turn_aware_tree() -> TestTree {
+        turn_aware_tree_on(0)
+    }
+
+    fn turn_aware_tree_on(edge_list_id: usize) -> TestTree {
+        build_tree_on(
+            edge_list_id,
+            &[
+                (label(0, None), 0, label(1, Some(0))),
+                (label(0, None), 2, label(2, Some(2))),
+                (label(2, Some(2)), 3, label(1, Some(3))),
+            ],
+        )
+    }
+
+    fn valid_frontier(
+        model: &TurnRestrictionConstraintModel,
+        test_tree: &TestTree,
+        parent: &Label,
+        edge_id: usize,
+    ) -> Result<bool, String> {
+        valid_frontier_on(model, test_tree, parent, 0, edge_id)
+    }
+
+    fn valid_frontier_on(
+        model: &TurnRestrictionConstraintModel,
+        test_tree: &TestTree,
+        parent: &Label,
+        edge_list_id: usize,
+        edge_id: usize,
+    ) -> Result<bool, String> {
+        let (src, e, dst) = trajectory(edge_list_id, edge_id);
+        let ctx = EdgeFrontierContext::new(parent, &src, &e, &dst, &test_tree.tree);
+        model
+            .valid_frontier(&ctx, &test_tree.state, &test_tree.state_model)
+            .map_err(|err| err.to_string())
+    }
+
+    fn model(restrictions: &[&[usize]]) -> TurnRestrictionConstraintModel {
+        model_on(0, restrictions)
+    }
+
+    fn model_on(edge_list_id: usize, restrictions: &[&[usize]]) -> TurnRestrictionConstraintModel {
+        let mut lookup: HashMap<(EdgeListId, EdgeId), Vec<Vec<EdgeId>>> = HashMap::new();
+        for restriction in restrictions.iter() {
+            let (last, preceding) = restriction.split_last().expect("test invariant failed");
+            let preceding = preceding.iter().rev().map(|e| EdgeId(*e)).collect();
+            lookup
+                .entry((EdgeListId(edge_list_id), EdgeId(*last)))
+                .or_default()
+                .push(preceding);
+        }
+        TurnRestrictionConstraintModel {
+            restrictions: Arc::new(lookup),
+        }
+    }
+
+    #[test]
+    fn test_prohibited_turn_with_legal_alternate() {
+        // the 0 -> 1 turn is prohibited, but vertex 1 may be approached along edge 3
+        let model = model(&[&[0, 1]]);
+        let tree = turn_aware_tree();
+        let direct = valid_frontier(&model, &tree, &label(1, Some(0)), 1);
+        let alternate = valid_frontier(&model, &tree, &label(1, Some(3)), 1);
+        assert_eq!(direct, Ok(false));
+        assert_eq!(alternate, Ok(true));
+    }
+
+    #[test]
+    fn test_prohibited_via_sequence() {
+        // the 2 -> 3 -> 1 sequence is prohibited, the 0 -> 1 turn is not
+        let model = model(&[&[2, 3, 1]]);
+        let tree = turn_aware_tree();
+        let direct = valid_frontier(&model, &tree, &label(1, Some(0)), 1);
+        let alternate = valid_frontier(&model, &tree, &label(1, Some(3)), 1);
+        assert_eq!(direct, Ok(true));
+        assert_eq!(alternate, Ok(false));
+    }
+
+    #[test]
+    fn test_unrestricted_edge_and_origin() {
+        let model = model(&[&[0, 1]]);
+        let tree = turn_aware_tree();
+        assert_eq!(valid_frontier(&model, &tree, &label(0, None), 0), Ok(true));
+        assert_eq!(valid_frontier(&model, &tree, &label(0, None), 2), Ok(true));
+        // a search starting at vertex 1 has no incoming edge to restrict
+        let empty = build_tree(&[]);
+        assert_eq!(valid_frontier(&model, &empty, &label(1, None), 1), Ok(true));
+    }
+
+    #[test]
+    fn test_restrictions_apply_within_their_edge_list() {
+        // edge lists 0 and 1 share edge ids. the 0 -> 1 turn is prohibited on list 1 only
+        let model = model_on(1, &[&[0, 1]]);
+        let tree_0 = turn_aware_tree_on(0);
+        let tree_1 = turn_aware_tree_on(1);
+        let parent = label(1, Some(0));
+        // current edge on an unrestricted list
+        assert_eq!(valid_frontier_on(&model, &tree_0, &parent, 0, 1), Ok(true));
+        // previous edge on an unrestricted list, current edge on the restricted list
+        assert_eq!(valid_frontier_on(&model, &tree_0, &parent, 1, 1), Ok(true));
+        // both edges on the restricted list
+        assert_eq!(valid_frontier_on(&model, &tree_1, &parent, 1, 1), Ok(false));
+    }
+
+    #[test]
+    fn test_vertex_labels_are_rejected() {
+        let model = model(&[&[0, 1]]);
+        let tree = build_tree(&[(label(0, None), 0, label(1, None))]);
+        let result = valid_frontier(&model, &tree, &label(1, None), 1);
+        assert!(result.is_err_and(|e| e.contains("turn-aware")));
+    }
+
+    fn mock_cost_model(state_model: Arc<StateModel>) -> Arc<CostModel> {
+        let weights_mapping = state_model
+            .iter()
+            .map(|(n, _)| (n.to_string(), 1.0))
+            .collect::<HashMap<_, _>>();
+        let vehicle_rate_mapping = state_model
+            .iter()
+            .map(|(n, _)| (n.to_string(), VehicleCostRate::Raw))
+            .collect::<HashMap<_, _>>();
+        let result = CostModel::new(
+            Arc::new(weights_mapping),
+            Arc::new(vehicle_rate_mapping),
+            Arc::new(HashMap::new()),
+            routee_compass_core::model::cost::CostAggregation::Sum,
+            state_model,
+            CostConstraint::StrictlyPositive,
+        )
+        .expect("test invariant failed: unable to build cost model");
+        Arc::new(result)
+    }
+}
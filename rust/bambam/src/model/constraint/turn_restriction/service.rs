@@ -0,0 +1,84 @@
+use super::{TurnRestrictionConstraintConfig, TurnRestrictionConstraintModel};
+use bambam_omf::graph::TurnRestrictionRow;
+use kdam::Bar;
+use routee_compass_core::{
+    model::{
+        constraint::{ConstraintModel, ConstraintModelError, ConstraintModelService},
+        network::{EdgeId, EdgeListId},
+        state::StateModel,
+    },
+    util::fs::read_utils,
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct TurnRestrictionConstraintService {
+    /// for each edge that completes a restriction, the edges that must precede it,
+    /// ordered from the most recent edge backward. all edges of a restriction belong
+    /// to the same edge list.
+    pub restrictions: Arc<HashMap<(EdgeListId, EdgeId), Vec<Vec<EdgeId>>>>,
+}
+
+impl TryFrom<&TurnRestrictionConstraintConfig> for TurnRestrictionConstraintService {
+    type Error = ConstraintModelError;
+
+    fn try_from(conf: &TurnRestrictionConstraintConfig) -> Result<Self, Self::Error> {
+        let rows: Box<[TurnRestrictionRow]> = read_utils::from_csv(
+            &conf.turn_restriction_input_file,
+            true,
+            Some(Bar::builder().desc("turn restrictions")),
+            None,
+        )
+        .map_err(|e| {
+            ConstraintModelError::BuildError(format!(
+                "failure reading turn restrictions from {}: {e}",
+                conf.turn_restriction_input_file
+            ))
+        })?;
+
+        let edge_list_id = EdgeListId(conf.edge_list_id);
+        let mut restrictions: HashMap<(EdgeListId, EdgeId), Vec<Vec<EdgeId>>> = HashMap::new();
+        for row in rows.iter() {
+            let mut edge_ids = row
+                .edge_ids()
+                .map_err(|e| ConstraintModelError::BuildError(e.to_string()))?;
+            let last = edge_ids.pop();
+            match last {
+                Some(last) if !edge_ids.is_empty() => {
+                    edge_ids.reverse();
+                    restrictions
+                        .entry((edge_list_id, last))
+                        .or_default()
+                        .push(edge_ids);
+                }
+                _ => {
+                    return Err(ConstraintModelError::BuildError(format!(
+                        "turn restriction '{}' must have at least two edges",
+                        row.edge_ids
+                    )))
+                }
+            }
+        }
+        log::info!(
+            "loaded {} turn restrictions for edge list {} from {}",
+            rows.len(),
+            conf.edge_list_id,
+            conf.turn_restriction_input_file
+        );
+        Ok(TurnRestrictionConstraintService {
+            restrictions: Arc::new(restrictions),
+        })
+    }
+}
+
+impl ConstraintModelService for TurnRestrictionConstraintService {
+    fn build(
+        &self,
+        _query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn ConstraintModel>, ConstraintModelError> {
+        let model = TurnRestrictionConstraintModel {
+            restrictions: self.restrictions.clone(),
+        };
+        Ok(Arc::new(model))
+    }
+}
@@ -1,6 +1,6 @@
 use clap::ValueEnum;
 use geo::Geometry;
-use geozero::{wkt::Wkt as WktReader, ToGeo};
+use geozero::{geojson::GeoJsonString, wkt::Wkt as WktReader, ToGeo};
 use routee_compass_core::config::ConfigJsonExtensions;
 use serde::{Deserialize, Serialize};
 
@@ -10,14 +10,18 @@ pub enum ExtentFormat {
     /// user extent field to be treated as a WKT
     #[default]
     Wkt,
+    /// user extent field to be treated as a GeoJSON geometry, either as an
+    /// object or as a string
+    #[serde(rename = "geojson")]
+    #[value(name = "geojson")]
+    GeoJson,
     // future extention points:
     // Wkb,
-    // GeoJson,
 }
 
 impl ExtentFormat {
     /// Reads `extent` key in the root of the input and parses it into [`Geometry`].
-    /// Currently only implements WKT format
+    /// Currently implements WKT and GeoJSON formats
     pub fn get_extent(&self, input: &mut serde_json::Value) -> Result<Geometry, String> {
         match self {
             ExtentFormat::Wkt => {
@@ -32,6 +36,19 @@ impl ExtentFormat {
                     .to_geo()
                     .map_err(|e| format!("failure converting wkt to geo: {e}"))
             }
+            ExtentFormat::GeoJson => {
+                let extent = input.get(super::EXTENT).ok_or_else(|| {
+                    format!("failure reading extent, missing key '{}'", super::EXTENT)
+                })?;
+                let geojson_str = match extent {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => serde_json::to_string(other)
+                        .map_err(|e| format!("failure serializing geojson extent: {e}"))?,
+                };
+                GeoJsonString(geojson_str)
+                    .to_geo()
+                    .map_err(|e| format!("failure converting geojson to geo: {e}"))
+            }
         }
     }
 }
@@ -61,6 +78,19 @@ mod tests {
         assert!(matches!(result, Geometry::MultiPolygon(_)));
     }
 
+    #[test]
+    fn test_extent_format_geojson_object() {
+        let fmt = ExtentFormat::GeoJson;
+        let mut input = json!({ "extent": {
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]]
+        }});
+        let result = fmt
+            .get_extent(&mut input)
+            .expect("should parse GeoJSON polygon");
+        assert!(matches!(result, Geometry::Polygon(_)));
+    }
+
     #[test]
     fn test_extent_format_wkt_invalid() {
         let fmt = ExtentFormat::Wkt;
@@ -182,12 +182,7 @@ fn add_population_source(
     extent: &Geometry,
     population_source: &PopulationSource,
 ) -> Result<(), InputPluginError> {
-    let pop_data = population_source.create_dataset(extent).map_err(|e| {
-        InputPluginError::InputPluginFailed(format!("failure creating population dataset: {e}"))
-    })?;
-    let rtree = Arc::new(PolygonalRTree::new(pop_data).map_err(|e| {
-        InputPluginError::InputPluginFailed(format!("failure building spatial lookup: {e}"))
-    })?);
+    let rtree = Arc::new(population_lookup(extent, population_source)?);
     let mut bar = Arc::new(Mutex::new(
         Bar::builder()
             .desc("map match population")
@@ -244,11 +239,25 @@ fn add_population_source(
     Ok(())
 }
 
+/// loads the population dataset over the extent into a spatial lookup for
+/// [`get_query_population_proportion`].
+pub(crate) fn population_lookup(
+    extent: &Geometry,
+    population_source: &PopulationSource,
+) -> Result<PolygonalRTree<f64, f64>, InputPluginError> {
+    let pop_data = population_source.create_dataset(extent).map_err(|e| {
+        InputPluginError::InputPluginFailed(format!("failure creating population dataset: {e}"))
+    })?;
+    PolygonalRTree::new(pop_data).map_err(|e| {
+        InputPluginError::InputPluginFailed(format!("failure building spatial lookup: {e}"))
+    })
+}
+
 /// to determine the area for this grid cell, we want to know what
 /// percent of each intersecting geometry overlaps geographically
 /// with the grid geometry, and then we use that percentage to
 /// perform a uniform (dis) aggregation from the source data.
-fn get_query_population_proportion(
+pub(crate) fn get_query_population_proportion(
     row: &serde_json::Value,
     population_rtree: &PolygonalRTree<f64, f64>,
 ) -> Result<f64, String> {
@@ -26,11 +26,18 @@ impl GridType {
         extent: &geo::Geometry,
         template: &serde_json::Value,
     ) -> Result<Vec<serde_json::Value>, String> {
+        self.cells(extent)?
+            .into_iter()
+            .map(|cell| self.cell_row(cell, template))
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    /// finds the cells of the grid over the extent without building their rows, so
+    /// that large grids can create each row as it is needed.
+    pub fn cells(&self, extent: &geo::Geometry) -> Result<Vec<h3o::CellIndex>, String> {
         match self {
             GridType::H3 { resolution } => match extent {
-                geo::Geometry::Polygon(polygon) => {
-                    h3_grid::from_polygon_extent(polygon, template, resolution)
-                }
+                geo::Geometry::Polygon(polygon) => h3_grid::polygon_cells(polygon, resolution),
                 geo::Geometry::MultiPolygon(mp) => {
                     log::info!(
                         "input MULTIPOLYGON has {} polygons to generate grid",
@@ -38,7 +45,7 @@ impl GridType {
                     );
                     let nested = mp
                         .into_iter()
-                        .map(|p| self.create_grid(&geo::Geometry::Polygon(p.clone()), template))
+                        .map(|p| self.cells(&geo::Geometry::Polygon(p.clone())))
                         .collect::<Result<Vec<_>, _>>()?;
                     let result = nested.into_iter().flatten().collect::<Vec<_>>();
                     Ok(result)
@@ -50,7 +57,7 @@ impl GridType {
                     );
                     let nested = gc
                         .into_iter()
-                        .map(|g| self.create_grid(g, template))
+                        .map(|g| self.cells(g))
                         .collect::<Result<Vec<_>, _>>()?;
                     let result = nested.into_iter().flatten().collect::<Vec<_>>();
                     Ok(result)
@@ -61,4 +68,15 @@ impl GridType {
             },
         }
     }
+
+    /// creates the grid row of a cell found by [`GridType::cells`].
+    pub fn cell_row(
+        &self,
+        cell: h3o::CellIndex,
+        template: &serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        match self {
+            GridType::H3 { .. } => h3_grid::cell_row(cell, template),
+        }
+    }
 }
@@ -9,6 +9,17 @@ pub fn from_polygon_extent(
     template: &serde_json::Value,
     resolution: &h3o::Resolution,
 ) -> Result<Vec<serde_json::Value>, String> {
+    polygon_cells(extent, resolution)?
+        .into_iter()
+        .map(|cell| cell_row(cell, template))
+        .collect::<Result<Vec<_>, _>>()
+}
+
+/// finds the cells of the given resolution that intersect the extent.
+pub fn polygon_cells(
+    extent: &geo::Polygon,
+    resolution: &h3o::Resolution,
+) -> Result<Vec<h3o::CellIndex>, String> {
     let mut tiler = h3o::geom::TilerBuilder::new(*resolution)
         .containment_mode(ContainmentMode::IntersectsBoundary)
         // .with_polygon(extent.clone())
@@ -17,29 +28,29 @@ pub fn from_polygon_extent(
         .add(extent.clone())
         .map_err(|e| format!("failure adding extent to h3 tiler: {e}"))?;
 
-    let cells = tiler.into_coverage().collect_vec();
+    Ok(tiler.into_coverage().collect_vec())
+}
 
-    cells
-        .into_iter()
-        .map(|cell| {
-            let line: geo::LineString = cell.boundary().into();
-            let polygon = geo::Polygon::new(line, vec![]);
-            let centroid = polygon.centroid().ok_or_else(|| {
-                format!(
-                    "unable to retrieve centroid of polygon: {}",
-                    geo::Geometry::from(polygon.clone())
-                        .to_wkt()
-                        .unwrap_or_default()
-                )
-            })?;
-            let row = grid_ops::create_grid_row(
-                cell.to_string(),
-                centroid.x(),
-                centroid.y(),
-                &geo::Geometry::Polygon(polygon),
-                template,
-            )?;
-            Ok(row)
-        })
-        .collect::<Result<Vec<_>, _>>()
+/// creates the grid row of a cell, located at the centroid of the cell.
+pub fn cell_row(
+    cell: h3o::CellIndex,
+    template: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let line: geo::LineString = cell.boundary().into();
+    let polygon = geo::Polygon::new(line, vec![]);
+    let centroid = polygon.centroid().ok_or_else(|| {
+        format!(
+            "unable to retrieve centroid of polygon: {}",
+            geo::Geometry::from(polygon.clone())
+                .to_wkt()
+                .unwrap_or_default()
+        )
+    })?;
+    grid_ops::create_grid_row(
+        cell.to_string(),
+        centroid.x(),
+        centroid.y(),
+        &geo::Geometry::Polygon(polygon),
+        template,
+    )
 }
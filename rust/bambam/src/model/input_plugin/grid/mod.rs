@@ -10,7 +10,7 @@ pub use grid_ops::create_grid_row;
 pub const EXTENT: &str = "extent";
 pub const EXTENT_FORMAT: &str = "extent_format";
 pub const GRID_TYPE: &str = "grid";
-pub const GRID_ID: &str = "grid_id";
+pub use bambam_core::model::bambam_field::GRID_ID;
 pub const ORIGIN_X: &str = "origin_x";
 pub const ORIGIN_Y: &str = "origin_y";
 pub const GEOMETRY: &str = "geometry";
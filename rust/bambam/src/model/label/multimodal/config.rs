@@ -14,4 +14,8 @@ use bambam_core::model::state::LegIdx;
 pub struct MultimodalLabelConfig {
     pub max_trip_legs: Option<NonZeroU64>,
     pub modes: Option<Vec<String>>,
+    /// when true, labels also record the edge used to arrive at a vertex, which
+    /// turn restrictions require. the `incoming_edge` traversal model must be
+    /// included in every search. defaults to false.
+    pub turn_aware: Option<bool>,
 }
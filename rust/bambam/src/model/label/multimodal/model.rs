@@ -9,15 +9,21 @@ use routee_compass_core::model::{
     state::{StateModel, StateVariable},
 };
 
-use bambam_core::model::state::{
-    multimodal_state_ops as ops, CategoricalMapping, CategoricalStateMapping, LegIdx,
+use bambam_core::model::{
+    bambam_state,
+    state::{multimodal_state_ops as ops, CategoricalMapping, CategoricalStateMapping, LegIdx},
 };
 
 /// builds trip leg mode sequences into the tree labels.
 /// does not impose a pareto domination scheme over the space of valid trips.
+///
+/// when turn-aware, labels after the first edge also record the incoming edge id
+/// ahead of the mode sequence, so that arriving at a vertex along different edges
+/// produces distinct search tree branches.
 pub struct MultimodalLabelModel {
     mode_to_state: CategoricalStateMapping,
     max_trip_legs: NonZeroU64,
+    turn_aware: bool,
 }
 
 impl MultimodalLabelModel {
@@ -28,9 +34,17 @@ impl MultimodalLabelModel {
         MultimodalLabelModel {
             mode_to_state,
             max_trip_legs,
+            turn_aware: false,
         }
     }
 
+    /// sets whether labels record the incoming edge read from the
+    /// [`bambam_state::INCOMING_EDGE_ID`] state feature.
+    pub fn with_turn_aware(mut self, turn_aware: bool) -> MultimodalLabelModel {
+        self.turn_aware = turn_aware;
+        self
+    }
+
     pub const ERR_EMPTY: &str = "cannot build a multimodal search Label for a trip with no legs";
 }
 
@@ -53,7 +67,17 @@ impl LabelModel for MultimodalLabelModel {
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
-        let label = Label::new_u8_state(vertex_id, &mode_labels)?;
+        let label = match self.incoming_edge_id(state, state_model)? {
+            Some(edge_id) => {
+                let mut label_state = vec![edge_id];
+                label_state.extend(mode_labels.iter().map(|l| *l as usize));
+                Label::VertexWithIntStateVec {
+                    vertex_id,
+                    state: label_state,
+                }
+            }
+            None => Label::new_u8_state(vertex_id, &mode_labels)?,
+        };
 
         log::debug!(
             "multimodal label model at vertex {} produced label [{}] for state at time: {:.2} minutes",
@@ -69,18 +93,46 @@ impl LabelModel for MultimodalLabelModel {
     }
 }
 
+impl MultimodalLabelModel {
+    /// the incoming edge to record in a turn-aware label, or None if this model is not
+    /// turn-aware or no edge has been traversed yet.
+    fn incoming_edge_id(
+        &self,
+        state: &[StateVariable],
+        state_model: &StateModel,
+    ) -> Result<Option<usize>, LabelModelError> {
+        if !self.turn_aware {
+            return Ok(None);
+        }
+        let edge_id = state_model
+            .get_custom_i64(state, bambam_state::INCOMING_EDGE_ID)
+            .map_err(|e| {
+                LabelModelError::LabelModelError(format!(
+                    "turn-aware multimodal labels require the 'incoming_edge' traversal model: {e}"
+                ))
+            })?;
+        Ok(usize::try_from(edge_id).ok())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::num::NonZeroU64;
 
     use routee_compass_core::model::state::StateVariable;
     use routee_compass_core::model::traversal::TraversalModel;
-    use routee_compass_core::model::{label::LabelModel, network::VertexId, state::StateModel};
+    use routee_compass_core::model::{
+        label::LabelModel,
+        network::{EdgeId, VertexId},
+        state::StateModel,
+    };
 
     use crate::model::label::multimodal::{
         multimodal_label_ops as label_ops, MultimodalLabelModel,
     };
+    use crate::model::traversal::incoming_edge::IncomingEdgeModel;
     use crate::model::traversal::multimodal::MultimodalTraversalModel;
+    use bambam_core::model::bambam_state;
     use bambam_core::model::state::CategoricalMapping;
     use bambam_core::model::state::{multimodal_state_ops as state_ops, CategoricalStateMapping};
     #[test]
@@ -135,6 +187,61 @@ mod test {
         assert_eq!(result, &["drive", "transit", "walk"]);
     }
 
+    #[test]
+    fn test_turn_aware_label_records_incoming_edge() {
+        let max_trip_legs = NonZeroU64::new(2).unwrap();
+        let am = MultimodalTraversalModel::new_local("drive", max_trip_legs, &["walk", "drive"])
+            .expect("test invariant failed");
+        let features = [am.output_features(), IncomingEdgeModel {}.output_features()].concat();
+        let sm = StateModel::new(features);
+        let mut state = sm.initial_state(None).expect("test invariant failed");
+        inject_trip_legs(
+            &["walk", "drive"],
+            &mut state,
+            &sm,
+            &am.mode_enumeration,
+            max_trip_legs,
+        );
+        let model = MultimodalLabelModel::new(CategoricalMapping::empty(), max_trip_legs)
+            .with_turn_aware(true);
+
+        // no edge has been traversed at the search origin
+        let origin = model
+            .label_from_state(VertexId(0), &state, &sm)
+            .expect("test failed");
+        assert_eq!(label_ops::get_incoming_edge_id(&origin), None);
+
+        sm.set_custom_i64(&mut state, bambam_state::INCOMING_EDGE_ID, &7)
+            .expect("test invariant failed");
+        let label = model
+            .label_from_state(VertexId(1), &state, &sm)
+            .expect("test failed");
+        assert_eq!(label_ops::get_incoming_edge_id(&label), Some(EdgeId(7)));
+        let result =
+            label_ops::get_mode_sequence(&label, &am.mode_enumeration).expect("test failed");
+        assert_eq!(result, &["walk", "drive"]);
+
+        // arriving at the same vertex along another edge produces another label
+        sm.set_custom_i64(&mut state, bambam_state::INCOMING_EDGE_ID, &8)
+            .expect("test invariant failed");
+        let other = model
+            .label_from_state(VertexId(1), &state, &sm)
+            .expect("test failed");
+        assert_ne!(label, other);
+    }
+
+    #[test]
+    fn test_turn_aware_label_requires_incoming_edge_feature() {
+        let max_trip_legs = NonZeroU64::new(1).unwrap();
+        let am = MultimodalTraversalModel::new_local("walk", max_trip_legs, &["walk"])
+            .expect("test invariant failed");
+        let sm = StateModel::new(am.output_features());
+        let state = sm.initial_state(None).expect("test invariant failed");
+        let model = MultimodalLabelModel::new(CategoricalMapping::empty(), max_trip_legs)
+            .with_turn_aware(true);
+        assert!(model.label_from_state(VertexId(0), &state, &sm).is_err());
+    }
+
     fn inject_trip_legs(
         legs: &[&str],
         state: &mut [StateVariable],
@@ -1,4 +1,7 @@
-use routee_compass_core::model::label::{label_model_error::LabelModelError, Label};
+use routee_compass_core::model::{
+    label::{label_model_error::LabelModelError, Label},
+    network::EdgeId,
+};
 
 use bambam_core::model::state::{CategoricalMapping, CategoricalStateMapping};
 
@@ -31,8 +34,33 @@ pub fn get_mode_sequence<'a>(
             }
             Ok(modes)
         }
+        Label::VertexWithIntStateVec { state, .. } => {
+            // turn-aware labels store the incoming edge id ahead of the mode sequence
+            state
+                .iter()
+                .skip(1)
+                .map(|mode| {
+                    let mode_i64 = *mode as i64;
+                    let mode = mode_to_state.get_categorical(mode_i64)?;
+                    mode.map(String::as_str).ok_or_else(|| {
+                        LabelModelError::LabelModelError(format!(
+                            "mode label {mode_i64} not present in multimodal label mapping"
+                        ))
+                    })
+                })
+                .collect()
+        }
         _ => Err(LabelModelError::LabelModelError(format!(
             "invalid label type, cannot get mode sequence: {label}"
         ))),
     }
 }
+
+/// the edge used to arrive at the vertex of a turn-aware label. labels without turn
+/// awareness, and labels at the search origin, have no incoming edge.
+pub fn get_incoming_edge_id(label: &Label) -> Option<EdgeId> {
+    match label {
+        Label::VertexWithIntStateVec { state, .. } => state.first().map(|e| EdgeId(*e)),
+        _ => None,
+    }
+}
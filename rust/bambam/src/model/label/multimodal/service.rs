@@ -30,7 +30,8 @@ impl LabelModelService for MultimodalLabelService {
         let max_trip_legs = self.get_max_trip_legs(&optional_conf)?;
         let modes = self.get_modes(&optional_conf)?;
         let mapping = CategoricalMapping::new(modes)?;
-        let model = MultimodalLabelModel::new(mapping, max_trip_legs);
+        let turn_aware = self.get_turn_aware(&optional_conf);
+        let model = MultimodalLabelModel::new(mapping, max_trip_legs).with_turn_aware(turn_aware);
         Ok(Arc::new(model))
     }
 }
@@ -65,6 +66,14 @@ impl MultimodalLabelService {
         }
     }
 
+    /// get the turn-aware flag from the query or fallback to the service config, or false
+    pub fn get_turn_aware(&self, query_conf: &MultimodalLabelConfig) -> bool {
+        query_conf
+            .turn_aware
+            .or(self.config.turn_aware)
+            .unwrap_or_default()
+    }
+
     /// helper to attempt to get a value from either the query configuration or the service configuration
     /// as all fields are optional
     pub fn get_conf<T>(
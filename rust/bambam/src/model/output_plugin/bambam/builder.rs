@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use bambam_core::model::output_plugin::BambamOutputConfig;
+use bambam_core::model::{
+    bambam_field::ISOCHRONE_POST_PROCESSING, output_plugin::BambamOutputConfig,
+};
 use routee_compass::plugin::{
     output::{OutputPluginBuilder, OutputPluginError},
     PluginError,
@@ -24,7 +26,46 @@ impl OutputPluginBuilder for BambamOutputPluginBuilder {
                     source: OutputPluginError::JsonError { source },
                 }
             })?;
+        validate_bin_isochrone_algorithms(&conf).map_err(|e| PluginError::OutputPluginFailed {
+            source: OutputPluginError::BuildFailed(e),
+        })?;
+        if parameters.get(ISOCHRONE_POST_PROCESSING).is_some() {
+            return Err(PluginError::OutputPluginFailed {
+                source: OutputPluginError::BuildFailed(format!(
+                    "{ISOCHRONE_POST_PROCESSING} is configured on the isochrone output plugin"
+                )),
+            }
+            .into());
+        }
         let plugin = BambamOutputPlugin(conf);
         Ok(Arc::new(plugin))
     }
 }
+
+/// confirms that each per-bin isochrone algorithm is keyed by one of the bins, since
+/// a mistyped key would otherwise silently fall back to the default algorithm.
+fn validate_bin_isochrone_algorithms(conf: &BambamOutputConfig) -> Result<(), String> {
+    let (binning, algorithms) = match conf {
+        BambamOutputConfig::Aggregate {
+            binning,
+            bin_isochrone_algorithms: Some(algorithms),
+            ..
+        } => (binning, algorithms),
+        _ => return Ok(()),
+    };
+    let bin_keys = binning
+        .build_bins(false)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|b| b.bin_key())
+        .collect::<Vec<_>>();
+    for key in algorithms.keys() {
+        if !bin_keys.contains(key) {
+            return Err(format!(
+                "bin_isochrone_algorithms has key '{key}' which is not one of the bins [{}]",
+                bin_keys.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
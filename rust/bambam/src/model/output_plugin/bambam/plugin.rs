@@ -27,6 +27,7 @@ impl OutputPlugin for BambamOutputPlugin {
                 destination_filter,
                 geometry_model,
                 isochrone_algorithm,
+                bin_isochrone_algorithms,
                 isochrone_format,
                 opportunity_orientation,
             } => {
@@ -38,6 +39,9 @@ impl OutputPlugin for BambamOutputPlugin {
                 }
                 info.set_geometry_model(geometry_model)?;
                 info.set_isochrone_algorithm(isochrone_algorithm)?;
+                if let Some(algorithms) = bin_isochrone_algorithms {
+                    info.set_bin_isochrone_algorithms(algorithms)?;
+                }
                 info.set_isochrone_format(isochrone_format)?;
             }
             BambamOutputConfig::Disaggregate {
@@ -1,12 +1,15 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use bambam_core::model::bambam_typed::BambamOutputRow;
 use bambam_core::model::destination::{self, BinInterval, DestinationFilter, DestinationPredicate};
 use bambam_core::model::output_plugin::isochrone::{
-    GeometryModel, IsochroneAlgorithm, IsochroneOutputFormat,
+    GeometryModel, IsochroneAlgorithm, IsochroneOutputFormat, IsochronePostProcessing,
+    IsochronePostProcessingConfig,
 };
 use bambam_core::model::output_plugin::opportunity::OpportunityFormat;
 use bambam_core::model::{bambam_field as field, bambam_ops, bambam_typed, TimeBin};
+use geo::MultiPolygon;
 use routee_compass::app::{compass::CompassAppError, search::SearchAppResult};
 use routee_compass::plugin::output::OutputPlugin;
 use routee_compass::plugin::output::OutputPluginError;
@@ -14,7 +17,33 @@ use routee_compass_core::algorithm::search::{SearchInstance, SearchResult};
 use serde_json::json;
 use serde_json::Value;
 
-pub struct IsochroneOutputPlugin {}
+use super::IsochroneOutputPluginConfig;
+
+#[derive(Default)]
+pub struct IsochroneOutputPlugin {
+    /// post-processing steps applied to each isochrone, built once with the plugin
+    /// since they may load clip geometries from file.
+    post_processing: Option<Arc<IsochronePostProcessing>>,
+    /// configuration of the post-processing steps, recorded on each row.
+    post_processing_config: Option<IsochronePostProcessingConfig>,
+}
+
+impl TryFrom<&IsochroneOutputPluginConfig> for IsochroneOutputPlugin {
+    type Error = OutputPluginError;
+
+    fn try_from(value: &IsochroneOutputPluginConfig) -> Result<Self, Self::Error> {
+        let post_processing = value
+            .isochrone_post_processing
+            .as_ref()
+            .map(IsochronePostProcessing::try_from)
+            .transpose()?
+            .map(Arc::new);
+        Ok(Self {
+            post_processing,
+            post_processing_config: value.isochrone_post_processing.clone(),
+        })
+    }
+}
 
 impl OutputPlugin for IsochroneOutputPlugin {
     fn process(
@@ -27,8 +56,12 @@ impl OutputPlugin for IsochroneOutputPlugin {
             return Ok(());
         }
 
+        if let Some(config) = &self.post_processing_config {
+            row.info_mut()?.set_isochrone_post_processing(config)?;
+        }
+
         match result {
-            Ok((sr, si)) => run_isochrone(row, sr, si),
+            Ok((sr, si)) => run_isochrone(row, sr, si, self.post_processing.as_deref()),
             Err(_) => empty_isochrones(row),
         }
     }
@@ -70,11 +103,12 @@ pub fn empty_isochrones(mut row: BambamOutputRow<'_>) -> Result<(), OutputPlugin
     Ok(())
 }
 
-/// generate isochrones for this row of data.
+/// generate isochrones for this row of data, post-processing each if configured.
 pub fn run_isochrone(
     mut row: BambamOutputRow<'_>,
     sr: &SearchAppResult,
     si: &SearchInstance,
+    post_processing: Option<&IsochronePostProcessing>,
 ) -> Result<(), OutputPluginError> {
     let get_isochrone_request = GetIsochroneRequest::try_from(&row)?;
 
@@ -91,9 +125,11 @@ pub fn run_isochrone(
     let bins = bin_config
         .build_bins(false)
         .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?;
+    // bins are in ascending order, so the union of the smaller bins is carried forward
+    let mut smaller_bins: Option<MultiPolygon<f64>> = None;
     for bin in bins.into_iter() {
         let bin_key = bin.bin_key();
-        let result = get_isochrone_request.run(&bin, sr, si)?;
+        let result = get_isochrone_request.run(&bin, sr, si, post_processing, &mut smaller_bins)?;
         agg.set_isochrone(&bin_key, result.isochrone_value);
         agg.set_n_destinations(&bin_key, result.tree_size);
     }
@@ -105,6 +141,7 @@ struct GetIsochroneRequest {
     filter: Option<DestinationFilter>,
     geometry_model: GeometryModel,
     isochrone_algorithm: IsochroneAlgorithm,
+    bin_isochrone_algorithms: HashMap<String, IsochroneAlgorithm>,
     isochrone_format: IsochroneOutputFormat,
 }
 
@@ -122,6 +159,7 @@ impl<'a> TryFrom<&'a BambamOutputRow<'a>> for GetIsochroneRequest {
         let isochrone_algorithm = info
             .get_isochrone_algorithm()?
             .ok_or_else(|| missing_expected("info.isochrone_algorithm"))?;
+        let bin_isochrone_algorithms = info.get_bin_isochrone_algorithms()?.unwrap_or_default();
         let isochrone_format = info
             .get_isochrone_format()?
             .ok_or_else(|| missing_expected("info.isochrone_format"))?;
@@ -129,6 +167,7 @@ impl<'a> TryFrom<&'a BambamOutputRow<'a>> for GetIsochroneRequest {
             filter,
             geometry_model,
             isochrone_algorithm,
+            bin_isochrone_algorithms,
             isochrone_format,
         })
     }
@@ -149,6 +188,8 @@ impl GetIsochroneRequest {
         bin: &BinInterval,
         search_result: &SearchAppResult,
         si: &SearchInstance,
+        post_processing: Option<&IsochronePostProcessing>,
+        smaller_bins: &mut Option<MultiPolygon<f64>>,
     ) -> Result<GetIsochroneResult, OutputPluginError> {
         let tree_destinations: Vec<_> = destination::iter::new_destinations_iterator(
             search_result,
@@ -163,10 +204,19 @@ impl GetIsochroneRequest {
         let tree_size = tree_destinations.len();
 
         // draw isochrone and serialize result
-        let tree_mp = self
-            .geometry_model
-            .generate_destination_points(&tree_destinations, si.map_model.clone())?;
-        let geometry = self.isochrone_algorithm.run(tree_mp)?;
+        let isochrone_algorithm = self
+            .bin_isochrone_algorithms
+            .get(&bin.bin_key())
+            .unwrap_or(&self.isochrone_algorithm);
+        let geometry = self.geometry_model.generate_isochrone(
+            &tree_destinations,
+            si.map_model.clone(),
+            isochrone_algorithm,
+        )?;
+        let geometry = match post_processing {
+            Some(pp) => pp.apply(&geometry, smaller_bins)?,
+            None => geometry,
+        };
         let isochrone = self.isochrone_format.serialize_geometry(&geometry)?;
         let result = GetIsochroneResult {
             isochrone_value: json![isochrone],
@@ -186,3 +236,47 @@ fn missing_expected(field: &str) -> OutputPluginError {
     let msg = format!("output row missing expected field '{field}'");
     OutputPluginError::OutputPluginFailed(msg)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn plugin(parameters: Value) -> Result<IsochroneOutputPlugin, OutputPluginError> {
+        let config: IsochroneOutputPluginConfig =
+            serde_json::from_value(parameters).expect("test invariant failed");
+        IsochroneOutputPlugin::try_from(&config)
+    }
+
+    #[test]
+    fn test_post_processing_built_with_plugin() {
+        let built = plugin(json!({
+            "type": "isochrone",
+            "isochrone_post_processing": { "nested": true, "smoothing_iterations": 2 }
+        }))
+        .expect("should build plugin");
+        let post_processing = built
+            .post_processing
+            .as_ref()
+            .expect("post-processing should be built with the plugin");
+        assert!(post_processing.nested);
+        assert_eq!(post_processing.smoothing_iterations, Some(2));
+
+        let built = plugin(json!({ "type": "isochrone" })).expect("should build plugin");
+        assert!(built.post_processing.is_none());
+        assert!(built.post_processing_config.is_none());
+    }
+
+    #[test]
+    fn test_invalid_post_processing_fails_build() {
+        let result = plugin(json!({
+            "type": "isochrone",
+            "isochrone_post_processing": { "min_hole_area": -1.0 }
+        }));
+        assert!(matches!(result, Err(OutputPluginError::BuildFailed(_))));
+        let result = plugin(json!({
+            "type": "isochrone",
+            "isochrone_post_processing": { "water_mask_file": "missing/water.geojson" }
+        }));
+        assert!(matches!(result, Err(OutputPluginError::BuildFailed(_))));
+    }
+}
@@ -1,9 +1,7 @@
-use super::isochrone_output_plugin::IsochroneOutputPlugin;
-use bambam_core::model::output_plugin::isochrone::GeometryModel;
+use super::{IsochroneOutputPlugin, IsochroneOutputPluginConfig};
 use routee_compass::app::compass::CompassComponentError;
 use routee_compass::plugin::output::{OutputPlugin, OutputPluginBuilder, OutputPluginError};
 use routee_compass::plugin::PluginError;
-use routee_compass_core::config::{CompassConfigurationField, ConfigJsonExtensions};
 use std::sync::Arc;
 
 pub struct IsochroneOutputPluginBuilder {}
@@ -13,7 +11,14 @@ impl OutputPluginBuilder for IsochroneOutputPluginBuilder {
         &self,
         parameters: &serde_json::Value,
     ) -> Result<Arc<dyn OutputPlugin>, CompassComponentError> {
-        let model = IsochroneOutputPlugin {};
-        Ok(Arc::new(model))
+        let config: IsochroneOutputPluginConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| PluginError::OutputPluginFailed {
+                source: OutputPluginError::BuildFailed(format!(
+                    "failed reading isochrone plugin configuration: {e}"
+                )),
+            })?;
+        let plugin = IsochroneOutputPlugin::try_from(&config)
+            .map_err(|source| PluginError::OutputPluginFailed { source })?;
+        Ok(Arc::new(plugin))
     }
 }
@@ -0,0 +1,11 @@
+use bambam_core::model::output_plugin::isochrone::IsochronePostProcessingConfig;
+use serde::{Deserialize, Serialize};
+
+/// configuration of the isochrone output plugin.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct IsochroneOutputPluginConfig {
+    /// optional steps applied to each isochrone after it is drawn, such as
+    /// simplification or nesting each bin within the next.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isochrone_post_processing: Option<IsochronePostProcessingConfig>,
+}
@@ -1,5 +1,7 @@
 pub mod isochrone_output_plugin;
 pub mod isochrone_output_plugin_builder;
+mod isochrone_output_plugin_config;
 
 pub use isochrone_output_plugin::IsochroneOutputPlugin;
 pub use isochrone_output_plugin_builder::IsochroneOutputPluginBuilder;
+pub use isochrone_output_plugin_config::IsochroneOutputPluginConfig;
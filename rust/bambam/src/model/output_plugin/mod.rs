@@ -3,3 +3,4 @@ pub mod error_handler;
 pub mod h3_util;
 pub mod isochrone;
 pub mod opportunity;
+pub mod spatial_file;
@@ -3,6 +3,7 @@
 //! The modules below provide modeling for activities and processing
 //! search destinations into opportunities.
 // pub mod opportunity_iterator;
+mod opportunity_hours;
 mod opportunity_model;
 mod opportunity_model_config;
 mod opportunity_output_plugin;
@@ -14,6 +15,7 @@ mod opportunity_spatial_row;
 mod study_region;
 
 pub mod source;
+pub use opportunity_hours::{MissingHoursPolicy, OpportunityHours};
 pub use opportunity_model::OpportunityModel;
 pub use opportunity_model_config::OpportunityModelConfig;
 pub use opportunity_output_plugin::OpportunityOutputPlugin;
@@ -24,10 +26,14 @@ pub use opportunity_source::OpportunitySource;
 pub use opportunity_spatial_row::OpportunitySpatialRow;
 pub use study_region::StudyRegion;
 
+use bambam_omf::collection::record::DuringExpression;
 use geo::Geometry;
 
 /// for each geometry, a list of activity counts.
 pub type OpportunityDataset = Vec<(Geometry<f32>, Vec<f64>)>;
 
+/// for each row of an [`OpportunityDataset`], the opening hours of the location, if known.
+pub type OpportunityLocationHours = Vec<Option<DuringExpression>>;
+
 /// for each Place, a mask of activity types at that location
 pub type GeoActivityMask = Vec<(Geometry<f32>, Vec<bool>)>;
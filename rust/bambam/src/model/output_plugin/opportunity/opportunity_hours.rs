@@ -0,0 +1,115 @@
+use bambam_omf::collection::record::DuringExpression;
+use chrono::NaiveDateTime;
+use opening_hours::OpeningHours;
+use serde::{Deserialize, Serialize};
+
+/// how to treat opportunities at locations without (valid) opening hours when
+/// filtering opportunities by arrival time.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingHoursPolicy {
+    /// locations without opening hours are always counted
+    #[default]
+    Open,
+    /// locations without opening hours are never counted when an arrival time is known
+    Closed,
+}
+
+/// opening hours for the rows of a tabular opportunity model. many rows may share the
+/// opening hours of the same source location, so the hours are stored once per source
+/// location and each row refers to its source location by index.
+pub struct OpportunityHours {
+    source_hours: Vec<Option<OpeningHours>>,
+    row_sources: Vec<Option<usize>>,
+    missing_hours_policy: MissingHoursPolicy,
+}
+
+impl OpportunityHours {
+    /// builds the opening hours for a tabular opportunity model.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_hours` - opening hours of each source location. expressions that are
+    ///   not valid opening hours are treated as missing.
+    /// * `row_sources` - for each row of the opportunity table, the source location
+    ///   its counts came from, if any.
+    /// * `missing_hours_policy` - treatment of locations without opening hours
+    pub fn new(
+        source_hours: &[Option<DuringExpression>],
+        row_sources: Vec<Option<usize>>,
+        missing_hours_policy: MissingHoursPolicy,
+    ) -> Self {
+        let mut n_invalid = 0;
+        let source_hours = source_hours
+            .iter()
+            .map(|during| {
+                let expression = during.as_ref()?;
+                let parsed = expression
+                    .as_opening_hours()
+                    .and_then(|oh| OpeningHours::parse(&oh.to_string()).ok());
+                if parsed.is_none() {
+                    n_invalid += 1;
+                }
+                parsed
+            })
+            .collect::<Vec<_>>();
+        if n_invalid > 0 {
+            log::warn!(
+                "{n_invalid} opportunity locations have invalid opening hours, these are treated as missing"
+            );
+        }
+        Self {
+            source_hours,
+            row_sources,
+            missing_hours_policy,
+        }
+    }
+
+    /// tests whether the opportunities in a row of the opportunity table are
+    /// available at the given arrival time.
+    pub fn is_open(&self, row: usize, arrival: &NaiveDateTime) -> bool {
+        let hours = self
+            .row_sources
+            .get(row)
+            .copied()
+            .flatten()
+            .and_then(|src| self.source_hours.get(src))
+            .and_then(Option::as_ref);
+        match hours {
+            Some(oh) => oh.is_open(*arrival),
+            None => self.missing_hours_policy == MissingHoursPolicy::Open,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MissingHoursPolicy, OpportunityHours};
+    use bambam_omf::collection::record::DuringExpression;
+    use chrono::NaiveDateTime;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_grocery_store_closed_at_night() {
+        let source_hours = vec![
+            Some(DuringExpression::parse("Mo-Su 07:00-21:00")),
+            None,
+            Some(DuringExpression::parse("whenever")),
+        ];
+        let row_sources = vec![Some(0), Some(0), Some(1), Some(2), None];
+        let hours = OpportunityHours::new(&source_hours, row_sources, MissingHoursPolicy::Closed);
+
+        let evening = datetime("2025-01-06 20:00:00");
+        let night = datetime("2025-01-06 23:00:00");
+        assert!(hours.is_open(0, &evening));
+        assert!(hours.is_open(1, &evening));
+        assert!(!hours.is_open(1, &night));
+        // missing, invalid and unmatched hours follow the policy
+        assert!(!hours.is_open(2, &evening));
+        assert!(!hours.is_open(3, &evening));
+        assert!(!hours.is_open(4, &evening));
+    }
+}
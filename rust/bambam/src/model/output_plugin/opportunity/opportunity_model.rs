@@ -1,16 +1,23 @@
+use super::opportunity_hours::OpportunityHours;
 use super::opportunity_spatial_row::OpportunitySpatialRow;
 use bambam_core::model::{
+    bambam_ops,
     destination::iter::DestinationsIter,
     output_plugin::opportunity::{
         DestinationOpportunity, OpportunityOrientation, OpportunityRowId,
     },
 };
+use chrono::{NaiveDateTime, TimeDelta};
 use geo::Convert;
 use itertools::Itertools;
 use routee_compass::plugin::output::OutputPluginError;
 use routee_compass_core::{
-    algorithm::search::{SearchInstance, SearchTreeNode},
-    model::{label::Label, network::VertexId},
+    algorithm::search::SearchTreeNode,
+    model::{
+        label::Label,
+        network::VertexId,
+        state::{StateModel, StateVariable},
+    },
 };
 use rstar::{RTree, RTreeObject};
 use std::collections::{HashMap, HashSet};
@@ -25,6 +32,9 @@ pub enum OpportunityModel {
         activity_types: Vec<String>,
         activity_counts: Vec<Vec<f64>>,
         opportunity_orientation: OpportunityOrientation,
+        /// opening hours of each row. when present along with an arrival time,
+        /// only rows that are open at the arrival time are counted.
+        opening_hours: Option<OpportunityHours>,
     },
     // TODO: rewrite or remove spatial variant
     //   - one of the challenges posed by this variant is ensuring no double-counting.
@@ -105,7 +115,10 @@ impl OpportunityModel {
     /// # Arguments
     ///
     /// * `destinations` - an iterator over the destinations found during the search
-    /// * `si` - the RouteE Compass [`SearchInstance`] for the associated search query
+    /// * `state_model` - the state model of the associated search query
+    /// * `start_datetime` - start time of the search, if known. the arrival time at each destination
+    ///   is found by adding the reachability time, and opportunities that are closed at that
+    ///   time are not collected.
     ///
     /// # Returns
     ///
@@ -115,7 +128,8 @@ impl OpportunityModel {
     pub fn collect_trip_opportunities(
         &self,
         destinations: DestinationsIter<'_>,
-        si: &SearchInstance,
+        state_model: &StateModel,
+        start_datetime: Option<&NaiveDateTime>,
     ) -> Result<Vec<(OpportunityRowId, DestinationOpportunity)>, OutputPluginError> {
         let mut found = HashMap::new();
         for dest_result in destinations {
@@ -125,7 +139,14 @@ impl OpportunityModel {
                     return Err(OutputPluginError::OutputPluginFailed(msg));
                 }
                 Ok((src, branch)) => {
-                    let row = self.collect_destination_opportunities(&src, branch, si)?;
+                    let arrival = match (start_datetime, branch.incoming_edge()) {
+                        (Some(start), Some(et)) => {
+                            Some(arrival_datetime(start, &et.result_state, state_model)?)
+                        }
+                        _ => None,
+                    };
+                    let row =
+                        self.collect_destination_opportunities(&src, branch, arrival.as_ref())?;
                     for (id, opps) in row.into_iter() {
                         if let Some(et) = branch.incoming_edge() {
                             let state = et.result_state.clone();
@@ -150,22 +171,24 @@ impl OpportunityModel {
     /// # Arguments
     /// * `destination_vertex_id` - the destination that was reached
     /// * `search_tree_branch` - the branch in the search tree that reached this destination.
-    /// * `si` - the RouteE Compass [`SearchInstance`] for the associated search query
+    /// * `arrival` - the time the destination was reached, if known
     ///
     /// # Returns
     ///
-    /// an opportunity vector id along with a vector of opportunity counts.
+    /// an opportunity vector id along with a vector of opportunity counts, or nothing
+    /// if the opportunities are closed at the arrival time.
     fn collect_destination_opportunities(
         &self,
         origin_label: &Label,
         search_tree_branch: &SearchTreeNode,
-        _si: &SearchInstance,
+        arrival: Option<&NaiveDateTime>,
     ) -> Result<Vec<(OpportunityRowId, Vec<f64>)>, OutputPluginError> {
         match self {
             OpportunityModel::Tabular {
                 activity_types: _,
                 activity_counts,
                 opportunity_orientation,
+                opening_hours,
             } => {
                 let opp_row = OpportunityRowId::new(
                     origin_label,
@@ -184,6 +207,12 @@ impl OpportunityModel {
                     }
                 };
 
+                if let (Some(hours), Some(arrival)) = (opening_hours, arrival) {
+                    if !hours.is_open(index, arrival) {
+                        return Ok(vec![]);
+                    }
+                }
+
                 let result = activity_counts
                     .get(index)
                     .map(|opps| (opp_row, opps.clone()))
@@ -237,7 +266,11 @@ impl OpportunityModel {
                 for model in models.iter() {
                     let vector_length = model.vector_length();
                     let matches = model
-                        .collect_destination_opportunities(origin_label, search_tree_branch, _si)?
+                        .collect_destination_opportunities(
+                            origin_label,
+                            search_tree_branch,
+                            arrival,
+                        )?
                         .into_iter()
                         .collect::<HashMap<_, _>>();
 
@@ -279,6 +312,25 @@ impl OpportunityModel {
     }
 }
 
+/// the time a destination is reached, found by adding the reachability time of the
+/// destination state to the search start time.
+fn arrival_datetime(
+    start_datetime: &NaiveDateTime,
+    state: &[StateVariable],
+    state_model: &StateModel,
+) -> Result<NaiveDateTime, OutputPluginError> {
+    let time = bambam_ops::get_reachability_time(state, state_model)
+        .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?;
+    let seconds = time.get::<uom::si::time::second>() as i64;
+    start_datetime
+        .checked_add_signed(TimeDelta::seconds(seconds))
+        .ok_or_else(|| {
+            OutputPluginError::OutputPluginFailed(format!(
+                "overflow when adding {seconds} seconds to {start_datetime}"
+            ))
+        })
+}
+
 /// sums all counts into a global total for each category
 fn activity_totals(
     activity_types: &[String],
@@ -304,3 +356,199 @@ fn activity_totals(
         .collect::<HashMap<_, _>>();
     Ok(result)
 }
+
+#[cfg(test)]
+mod test {
+    use super::OpportunityModel;
+    use crate::model::output_plugin::opportunity::{MissingHoursPolicy, OpportunityHours};
+    use bambam_core::model::{
+        bambam_state,
+        destination::iter::DestinationsIter,
+        output_plugin::opportunity::{opportunity_ops, OpportunityOrientation},
+    };
+    use bambam_omf::collection::record::DuringExpression;
+    use chrono::NaiveDateTime;
+    use routee_compass_core::{
+        algorithm::search::{EdgeTraversal, SearchTree},
+        model::{
+            cost::{CostAggregation, CostConstraint, CostModel, VehicleCostRate},
+            label::Label,
+            network::{Edge, Vertex, VertexId},
+            state::{InputFeature, StateModel, StateVariable, StateVariableConfig},
+            traversal::{EdgeFrontierContext, TraversalModel, TraversalModelError},
+            unit::TimeUnit,
+        },
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use uom::{
+        si::f64::{Length, Time},
+        ConstZero,
+    };
+
+    const ACTIVITY_TYPES: [&str; 2] = ["food", "retail"];
+
+    /// adds ten minutes of trip time on each edge
+    struct TenMinuteEdges;
+
+    impl TraversalModel for TenMinuteEdges {
+        fn name(&self) -> String {
+            String::from("Ten Minute Edges")
+        }
+
+        fn input_features(&self) -> Vec<InputFeature> {
+            vec![]
+        }
+
+        fn output_features(&self) -> Vec<(String, StateVariableConfig)> {
+            vec![(
+                bambam_state::TRIP_TIME.to_string(),
+                StateVariableConfig::Time {
+                    initial: Time::ZERO,
+                    output_unit: Some(TimeUnit::Minutes),
+                    accumulator: true,
+                },
+            )]
+        }
+
+        fn traverse_edge(
+            &self,
+            _ctx: &EdgeFrontierContext,
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            let trip_time = state_model.get_time(state, bambam_state::TRIP_TIME)?
+                + Time::new::<uom::si::time::minute>(10.0);
+            state_model.set_time(state, bambam_state::TRIP_TIME, &trip_time)?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            _od: (&Vertex, &Vertex),
+            _state: &mut Vec<StateVariable>,
+            _tree: &SearchTree,
+            _state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            Ok(())
+        }
+    }
+
+    /// a search along the path (0) -> (1) -> (2) -> (3), reaching each vertex
+    /// ten minutes after the previous one.
+    fn search_tree() -> (SearchTree, Arc<StateModel>) {
+        let tm = TenMinuteEdges;
+        let state_model = Arc::new(StateModel::new(tm.output_features()));
+        let cost_model = CostModel::new(
+            Arc::new(HashMap::from([(bambam_state::TRIP_TIME.to_string(), 1.0)])),
+            Arc::new(HashMap::from([(
+                bambam_state::TRIP_TIME.to_string(),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            state_model.clone(),
+            CostConstraint::StrictlyPositive,
+        )
+        .expect("test invariant failed");
+        let mut tree = SearchTree::default();
+        let mut state = state_model
+            .initial_state(None)
+            .expect("test invariant failed");
+        for src in 0..3 {
+            let dst = src + 1;
+            let (src_label, dst_label) =
+                (Label::Vertex(VertexId(src)), Label::Vertex(VertexId(dst)));
+            let src_vertex = Vertex::new(src, src as f32 * 0.01, 0.0);
+            let dst_vertex = Vertex::new(dst, dst as f32 * 0.01, 0.0);
+            let edge = Edge::new(
+                0,
+                src,
+                src,
+                dst,
+                Length::new::<uom::si::length::meter>(500.0),
+            );
+            let ctx = EdgeFrontierContext::new(&src_label, &src_vertex, &edge, &dst_vertex, &tree);
+            let et = EdgeTraversal::new_local(&ctx, &state, &state_model, &tm, &cost_model)
+                .expect("test invariant failed");
+            state = et.result_state.clone();
+            tree.insert_trajectory(src_label, et, dst_label);
+        }
+        (tree, state_model)
+    }
+
+    fn destinations(tree: &SearchTree) -> DestinationsIter<'_> {
+        Box::new(tree.iter().filter_map(|(label, branch)| {
+            branch.incoming_edge().map(|_| Ok((label.clone(), branch)))
+        }))
+    }
+
+    /// vertex-oriented opportunities where vertices 1 and 2 share the opening hours of
+    /// one source location and vertex 3 has no opening hours.
+    fn tabular_model(missing_hours: MissingHoursPolicy) -> OpportunityModel {
+        let source_hours = vec![Some(DuringExpression::parse("Mo-Su 07:00-09:15"))];
+        let row_sources = vec![None, Some(0), Some(0), None];
+        OpportunityModel::Tabular {
+            activity_types: ACTIVITY_TYPES.iter().map(|a| a.to_string()).collect(),
+            activity_counts: vec![
+                vec![100.0, 100.0],
+                vec![1.0, 10.0],
+                vec![2.0, 20.0],
+                vec![4.0, 40.0],
+            ],
+            opportunity_orientation: OpportunityOrientation::DestinationVertexOriented,
+            opening_hours: Some(OpportunityHours::new(
+                &source_hours,
+                row_sources,
+                missing_hours,
+            )),
+        }
+    }
+
+    fn aggregate(
+        model: &OpportunityModel,
+        start_datetime: Option<&NaiveDateTime>,
+    ) -> HashMap<String, f64> {
+        let (tree, state_model) = search_tree();
+        let opportunities = model
+            .collect_trip_opportunities(destinations(&tree), &state_model, start_datetime)
+            .expect("test failed");
+        opportunity_ops::collect_aggregate(&opportunities, &model.activity_types())
+            .expect("test failed")
+    }
+
+    fn monday_at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2025-01-06 {time}"), "%Y-%m-%d %H:%M:%S")
+            .expect("test invariant failed")
+    }
+
+    #[test]
+    fn test_closed_rows_drop_out_of_aggregate_counts() {
+        // arrivals at 09:10, 09:20 and 09:30, after the 09:15 closing time for vertex 2
+        let model = tabular_model(MissingHoursPolicy::default());
+        let start = monday_at("09:00:00");
+        let counts = aggregate(&model, Some(&start));
+        assert_eq!(counts.get("food"), Some(&5.0));
+        assert_eq!(counts.get("retail"), Some(&50.0));
+    }
+
+    #[test]
+    fn test_all_rows_counted_without_start_time_or_when_open() {
+        let model = tabular_model(MissingHoursPolicy::default());
+        let counts = aggregate(&model, None);
+        assert_eq!(counts.get("food"), Some(&7.0));
+        assert_eq!(counts.get("retail"), Some(&70.0));
+
+        let early = monday_at("08:00:00");
+        let counts = aggregate(&model, Some(&early));
+        assert_eq!(counts.get("food"), Some(&7.0));
+    }
+
+    #[test]
+    fn test_missing_hours_policy() {
+        assert_eq!(MissingHoursPolicy::default(), MissingHoursPolicy::Open);
+        let start = monday_at("09:00:00");
+        let counts = aggregate(&tabular_model(MissingHoursPolicy::Closed), Some(&start));
+        assert_eq!(counts.get("food"), Some(&1.0));
+        assert_eq!(counts.get("retail"), Some(&10.0));
+    }
+}
@@ -1,3 +1,4 @@
+use super::opportunity_hours::{MissingHoursPolicy, OpportunityHours};
 use super::opportunity_model::OpportunityModel;
 use super::opportunity_source::OpportunitySource;
 use bambam_core::model::output_plugin::opportunity::OpportunityOrientation;
@@ -37,12 +38,16 @@ pub enum OpportunityModelConfig {
     /// - `opportunity_source`: Variant of [`OpportunitySource`] describing the API to be used for opportunity collection
     /// - `activity_column_names`: Vector of String identifiers for the types of activities. E.g., ["food", "healthcare"]
     /// - `table_orientation`: Variant of [`OpportunityTableOrientation`] describing how to attach opportunities to graph elements
+    /// - `missing_hours`: when the query has a `start_time`, opportunities are only counted if open at the
+    ///   arrival time. this [`MissingHoursPolicy`] decides how to treat locations with no opening hours.
     #[serde(rename = "api")]
     ApiSource {
         vertex_input_file: String,
         opportunity_source: OpportunitySource,
         activity_column_names: Vec<String>,
         table_orientation: OpportunityOrientation,
+        #[serde(default)]
+        missing_hours: MissingHoursPolicy,
     },
     #[serde(rename = "combined")]
     Combined {
@@ -118,6 +123,7 @@ impl OpportunityModelConfig {
                     activity_types: activity_column_names.to_owned(),
                     activity_counts,
                     opportunity_orientation: table_orientation.to_owned(),
+                    opening_hours: None,
                 };
                 Ok(result)
             }
@@ -126,9 +132,10 @@ impl OpportunityModelConfig {
                 opportunity_source,
                 activity_column_names,
                 table_orientation,
+                missing_hours,
             } => {
-                let raw_dataset = opportunity_source
-                    .generate_dataset(activity_column_names)
+                let (raw_dataset, raw_hours) = opportunity_source
+                    .generate_dataset_with_hours(activity_column_names)
                     .map_err(OutputPluginError::OutputPluginFailed)?;
                 let rtree_dataset = raw_dataset
                     .iter()
@@ -241,10 +248,22 @@ impl OpportunityModelConfig {
                     })
                     .collect::<Result<Vec<_>, OutputPluginError>>()?;
 
+                // only attach opening hours when the source provides some
+                let opening_hours = if raw_hours.iter().any(Option::is_some) {
+                    Some(OpportunityHours::new(
+                        &raw_hours,
+                        match_by_vertex,
+                        *missing_hours,
+                    ))
+                } else {
+                    None
+                };
+
                 let result = OpportunityModel::Tabular {
                     activity_types: activity_column_names.clone(),
                     activity_counts,
                     opportunity_orientation: *table_orientation,
+                    opening_hours,
                 };
                 Ok(result)
             }
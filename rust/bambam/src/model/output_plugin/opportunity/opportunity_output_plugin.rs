@@ -5,6 +5,7 @@ use super::OpportunityPluginConfig;
 use bambam_core::model::bambam_typed::{self, BambamOutputRow};
 use bambam_core::model::destination::{self, DestinationFilter};
 use bambam_core::model::output_plugin::opportunity::{opportunity_ops, OpportunityFormat};
+use chrono::NaiveDateTime;
 use routee_compass::app::{compass::CompassAppError, search::SearchAppResult};
 use routee_compass::plugin::output::OutputPlugin;
 use routee_compass::plugin::output::OutputPluginError;
@@ -69,9 +70,19 @@ impl OutputPlugin for OpportunityOutputPlugin {
         // read destination filter from the row info
         let filter = row.get_destination_filter()?;
 
+        // when the query has a start time, opportunities are only counted if open on arrival
+        let start_datetime = row.request()?.get_start_time()?;
+
         match format {
             OpportunityFormat::Aggregate => {
-                process_aggregate_opportunities(&mut row, app_result, si, self, filter.as_ref())?;
+                process_aggregate_opportunities(
+                    &mut row,
+                    app_result,
+                    si,
+                    self,
+                    filter.as_ref(),
+                    start_datetime.as_ref(),
+                )?;
             }
             OpportunityFormat::Disaggregate => {
                 process_disaggregate_opportunities(
@@ -80,6 +91,7 @@ impl OutputPlugin for OpportunityOutputPlugin {
                     si,
                     self,
                     filter.as_ref(),
+                    start_datetime.as_ref(),
                 )?;
             }
         }
@@ -124,12 +136,15 @@ fn process_disaggregate_opportunities(
     instance: &SearchInstance,
     plugin: &OpportunityOutputPlugin,
     filter: Option<&DestinationFilter>,
+    start_datetime: Option<&NaiveDateTime>,
 ) -> Result<(), OutputPluginError> {
     let destinations_iter =
         destination::iter::new_destinations_iterator(result, None, filter, &instance.state_model);
-    let opportunities = plugin
-        .model
-        .collect_trip_opportunities(destinations_iter, instance)?;
+    let opportunities = plugin.model.collect_trip_opportunities(
+        destinations_iter,
+        &instance.state_model,
+        start_datetime,
+    )?;
     let opps =
         opportunity_ops::collect_disaggregate(&opportunities, &plugin.model.activity_types())?;
     let mut dis = row.disaggregate()?;
@@ -174,6 +189,7 @@ fn process_aggregate_opportunities(
     instance: &SearchInstance,
     plugin: &OpportunityOutputPlugin,
     filter: Option<&DestinationFilter>,
+    start_datetime: Option<&NaiveDateTime>,
 ) -> Result<(), OutputPluginError> {
     // expect bin configuration for aggregate format
     let bin_config = row.info_ref()?.get_bin_range()?.ok_or_else(|| {
@@ -199,9 +215,11 @@ fn process_aggregate_opportunities(
         );
 
         // collect aggregated opportunities and write to output
-        let destination_opportunities = plugin
-            .model
-            .collect_trip_opportunities(destinations_iter, instance)?;
+        let destination_opportunities = plugin.model.collect_trip_opportunities(
+            destinations_iter,
+            &instance.state_model,
+            start_datetime,
+        )?;
         let opps = opportunity_ops::collect_aggregate(
             &destination_opportunities,
             &plugin.model.activity_types(),
@@ -1,8 +1,9 @@
 use crate::model::output_plugin::opportunity::{
-    source::OverturePlacesMappingConfig, OpportunityDataset,
+    source::OverturePlacesMappingConfig, OpportunityDataset, OpportunityLocationHours,
 };
 
 use super::{
+    source::building_allocation::{self, BuildingAllocationConfig},
     source::lodes::lodes_ops,
     source::overture_opportunity_collection_model::OvertureOpportunityCollectionModel,
     study_region::StudyRegion,
@@ -46,6 +47,19 @@ pub enum OpportunitySource {
         #[serde(default)]
         release_version: ReleaseVersion,
     },
+    /// distributes the totals of a zone-level opportunity source, such as LODES jobs
+    /// by block, onto the OvertureMaps buildings within each zone proportional to
+    /// estimated floor area. the zone source must produce polygonal geometries.
+    #[serde(rename = "building_weighted")]
+    BuildingWeighted {
+        zone_source: Box<OpportunitySource>,
+        collector_config: OvertureMapsCollectorConfig,
+        bbox_boundary: Bbox,
+        #[serde(default)]
+        release_version: ReleaseVersion,
+        #[serde(default)]
+        allocation: BuildingAllocationConfig,
+    },
 }
 
 impl OpportunitySource {
@@ -64,6 +78,25 @@ impl OpportunitySource {
         &self,
         activity_types: &[String],
     ) -> Result<OpportunityDataset, String> {
+        let (dataset, _) = self.generate_dataset_with_hours(activity_types)?;
+        Ok(dataset)
+    }
+
+    /// generates a collection of Geometries paired with activity counts, along with
+    /// the opening hours of each Geometry. only OvertureMaps places carry opening hours,
+    /// all other rows have none.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_types` - the types of activities expected
+    ///
+    /// # Returns
+    ///
+    /// A collection of Geometries tagged with activity rows, and the opening hours of each row.
+    pub fn generate_dataset_with_hours(
+        &self,
+        activity_types: &[String],
+    ) -> Result<(OpportunityDataset, OpportunityLocationHours), String> {
         match self {
             OpportunitySource::OvertureMapsPlaces {
                 collector_config,
@@ -79,7 +112,7 @@ impl OpportunitySource {
                 // to reduce repetition in the configuration file by making some assumptions
                 // about the filters being used.
                 let colletor_model = OvertureOpportunityCollectionModel::new(
-                    *collector_config,
+                    collector_config.clone(),
                     release_version.clone(),
                     *bbox_boundary,
                     places_mapping,
@@ -117,7 +150,37 @@ impl OpportunitySource {
                     activity_types,
                     activity_mapping,
                 )
+                .map(without_hours)
+            }
+            OpportunitySource::BuildingWeighted {
+                zone_source,
+                collector_config,
+                bbox_boundary,
+                release_version,
+                allocation,
+            } => {
+                let zones = zone_source.generate_dataset(activity_types)?;
+                let buildings = building_allocation::collect_buildings(
+                    collector_config,
+                    release_version,
+                    bbox_boundary,
+                    allocation,
+                )
+                .map_err(|e| format!("Error during overturemaps buildings collection: {e}"))?;
+                building_allocation::allocate_to_buildings(
+                    zones,
+                    &buildings,
+                    activity_types,
+                    allocation,
+                )
+                .map(without_hours)
             }
         }
     }
 }
+
+/// pairs a dataset with empty opening hours for sources that do not provide them.
+fn without_hours(dataset: OpportunityDataset) -> (OpportunityDataset, OpportunityLocationHours) {
+    let hours = vec![None; dataset.len()];
+    (dataset, hours)
+}
@@ -0,0 +1,256 @@
+//! dasymetric allocation of zone-level opportunity totals onto the buildings within
+//! each zone, weighted by an estimate of building floor area.
+use crate::model::output_plugin::opportunity::OpportunityDataset;
+use bambam_omf::collection::{
+    Bbox, BuildingsRecord, OvertureMapsCollectionError, OvertureMapsCollector,
+    OvertureMapsCollectorConfig, OvertureRecordType, ReleaseVersion, RowFilterConfig,
+};
+use geo::{Centroid, Convert, GeodesicArea, Geometry, Point};
+use routee_compass_core::util::geo::PolygonalRTree;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// configures how zone totals are distributed onto buildings.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BuildingAllocationConfig {
+    /// for each activity type, the OvertureMaps building classes that may hold it, such
+    /// as residential classes for population and commercial classes for jobs. activity
+    /// types not listed here are allocated to all buildings.
+    #[serde(default)]
+    pub activity_building_classes: HashMap<String, Vec<String>>,
+    /// whether buildings without a class may hold activity types that are filtered
+    /// by class. many OvertureMaps buildings have no class.
+    #[serde(default = "default_allow_unclassified")]
+    pub allow_unclassified: bool,
+    /// height of one floor, used to estimate floors for buildings with a height
+    /// but no floor count.
+    #[serde(default = "default_floor_height_meters")]
+    pub floor_height_meters: f64,
+}
+
+fn default_allow_unclassified() -> bool {
+    true
+}
+
+fn default_floor_height_meters() -> f64 {
+    3.0
+}
+
+impl Default for BuildingAllocationConfig {
+    fn default() -> Self {
+        Self {
+            activity_building_classes: HashMap::new(),
+            allow_unclassified: default_allow_unclassified(),
+            floor_height_meters: default_floor_height_meters(),
+        }
+    }
+}
+
+impl BuildingAllocationConfig {
+    /// whether a building with this class may hold the activity type.
+    fn is_eligible(&self, activity_type: &str, class: Option<&str>) -> bool {
+        match (self.activity_building_classes.get(activity_type), class) {
+            (None, _) => true,
+            (Some(_), None) => self.allow_unclassified,
+            (Some(classes), Some(class)) => classes.iter().any(|c| c == class),
+        }
+    }
+}
+
+/// a building reduced to the attributes used for allocation.
+#[derive(Clone, Debug)]
+pub struct BuildingFloorArea {
+    pub centroid: Point<f32>,
+    pub class: Option<String>,
+    /// estimated floor area in square meters
+    pub floor_area: f64,
+}
+
+impl BuildingFloorArea {
+    /// estimates the floor area of a building as its footprint area times its number of
+    /// floors. floors are taken from `num_floors`, or estimated from `height`, or assumed
+    /// to be one. returns None for underground buildings or buildings without a polygonal
+    /// footprint.
+    pub fn from_record(
+        record: &BuildingsRecord,
+        config: &BuildingAllocationConfig,
+    ) -> Option<Self> {
+        if record.get_is_underground() {
+            return None;
+        }
+        let geometry = record.get_geometry()?;
+        let geometry_f64: Geometry<f64> = geometry.convert();
+        let footprint = match &geometry_f64 {
+            Geometry::Polygon(p) => p.geodesic_area_unsigned(),
+            Geometry::MultiPolygon(mp) => mp.geodesic_area_unsigned(),
+            _ => return None,
+        };
+        let floors = match (record.get_num_floors(), record.get_height()) {
+            (Some(n), _) if n > 0 => n as f64,
+            (_, Some(h)) if h > 0.0 => (h / config.floor_height_meters).round().max(1.0),
+            _ => 1.0,
+        };
+        let centroid = geometry.centroid()?;
+        Some(Self {
+            centroid,
+            class: record.get_class(),
+            floor_area: footprint * floors,
+        })
+    }
+}
+
+/// collects the buildings within the bounding box from an OvertureMaps release.
+pub fn collect_buildings(
+    collector_config: &OvertureMapsCollectorConfig,
+    release_version: &ReleaseVersion,
+    bbox_boundary: &Bbox,
+    config: &BuildingAllocationConfig,
+) -> Result<Vec<BuildingFloorArea>, OvertureMapsCollectionError> {
+    let collector = OvertureMapsCollector::try_from(collector_config.clone())?;
+    let uri = match release_version {
+        ReleaseVersion::Latest => collector.get_latest_release()?,
+        ReleaseVersion::Monthly { .. } => release_version.to_string(),
+    };
+    let records = collector
+        .collect_from_release(
+            &uri,
+            &OvertureRecordType::Buildings,
+            Some(RowFilterConfig::from(*bbox_boundary)),
+        )?
+        .into_iter()
+        .map(BuildingsRecord::try_from)
+        .collect::<Result<Vec<BuildingsRecord>, OvertureMapsCollectionError>>()?;
+    log::info!("Total buildings records {}", records.len());
+
+    let buildings = records
+        .iter()
+        .filter_map(|r| BuildingFloorArea::from_record(r, config))
+        .collect::<Vec<_>>();
+    log::info!("Buildings with floor area estimates {}", buildings.len());
+    Ok(buildings)
+}
+
+/// distributes the activity counts of each zone onto the eligible buildings whose centroid
+/// falls within the zone, proportional to floor area. zone counts for an activity type with
+/// no eligible buildings stay on the zone geometry.
+///
+/// # Returns
+///
+/// the buildings (as centroid points) with their allocated counts, followed by any
+/// zones that kept some of their counts.
+pub fn allocate_to_buildings(
+    zones: OpportunityDataset,
+    buildings: &[BuildingFloorArea],
+    activity_types: &[String],
+    config: &BuildingAllocationConfig,
+) -> Result<OpportunityDataset, String> {
+    let rtree = PolygonalRTree::new(
+        zones
+            .iter()
+            .enumerate()
+            .map(|(i, (geom, _))| (geom.clone(), i))
+            .collect::<Vec<(Geometry<f32>, usize)>>(),
+    )?;
+
+    // assign each building to the first zone containing its centroid
+    let mut zone_buildings: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (building_idx, building) in buildings.iter().enumerate() {
+        let point = Geometry::Point(building.centroid);
+        if let Some(node) = rtree.intersection(&point)?.next() {
+            zone_buildings
+                .entry(node.data)
+                .or_default()
+                .push(building_idx);
+        }
+    }
+
+    let mut building_counts = vec![vec![0.0; activity_types.len()]; buildings.len()];
+    let mut unallocated = vec![];
+    for (zone_idx, (zone_geometry, zone_counts)) in zones.into_iter().enumerate() {
+        let members = zone_buildings.get(&zone_idx).cloned().unwrap_or_default();
+        let mut remainder = vec![0.0; activity_types.len()];
+        for (act_idx, (activity_type, total)) in activity_types.iter().zip(zone_counts).enumerate()
+        {
+            if total == 0.0 {
+                continue;
+            }
+            let eligible = members
+                .iter()
+                .filter(|b| config.is_eligible(activity_type, buildings[**b].class.as_deref()))
+                .collect::<Vec<_>>();
+            let total_area: f64 = eligible.iter().map(|b| buildings[**b].floor_area).sum();
+            if total_area <= 0.0 {
+                remainder[act_idx] = total;
+                continue;
+            }
+            for b in eligible {
+                building_counts[*b][act_idx] += total * buildings[*b].floor_area / total_area;
+            }
+        }
+        if remainder.iter().any(|v| *v != 0.0) {
+            unallocated.push((zone_geometry, remainder));
+        }
+    }
+    if !unallocated.is_empty() {
+        log::warn!(
+            "{} zones had activity counts with no eligible buildings, these remain on the zone geometry",
+            unallocated.len()
+        );
+    }
+
+    let result = buildings
+        .iter()
+        .zip(building_counts)
+        .filter(|(_, counts)| counts.iter().any(|v| *v != 0.0))
+        .map(|(b, counts)| (Geometry::Point(b.centroid), counts))
+        .chain(unallocated)
+        .collect();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{allocate_to_buildings, BuildingAllocationConfig, BuildingFloorArea};
+    use geo::{polygon, Geometry, Point};
+    use std::collections::HashMap;
+
+    fn building(x: f32, y: f32, class: &str, floor_area: f64) -> BuildingFloorArea {
+        BuildingFloorArea {
+            centroid: Point::new(x, y),
+            class: Some(class.to_string()),
+            floor_area,
+        }
+    }
+
+    #[test]
+    fn test_allocate_by_floor_area_and_class() {
+        let zone: Geometry<f32> = polygon![
+            (x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 1.0), (x: 0.0, y: 1.0), (x: 0.0, y: 0.0)
+        ]
+        .into();
+        let empty_zone: Geometry<f32> = polygon![
+            (x: 2.0, y: 0.0), (x: 3.0, y: 0.0), (x: 3.0, y: 1.0), (x: 2.0, y: 1.0), (x: 2.0, y: 0.0)
+        ]
+        .into();
+        let zones = vec![(zone, vec![100.0, 40.0]), (empty_zone, vec![10.0, 0.0])];
+        let buildings = vec![
+            building(0.25, 0.25, "office", 300.0),
+            building(0.75, 0.75, "house", 100.0),
+        ];
+        let activity_types = vec![String::from("jobs"), String::from("population")];
+        let config = BuildingAllocationConfig {
+            activity_building_classes: HashMap::from([(
+                String::from("population"),
+                vec![String::from("house")],
+            )]),
+            ..Default::default()
+        };
+
+        let result = allocate_to_buildings(zones, &buildings, &activity_types, &config).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].1, vec![75.0, 0.0]);
+        assert_eq!(result[1].1, vec![25.0, 40.0]);
+        // the zone without buildings keeps its jobs
+        assert_eq!(result[2].1, vec![10.0, 0.0]);
+    }
+}
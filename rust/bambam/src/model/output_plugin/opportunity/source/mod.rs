@@ -1,3 +1,4 @@
+pub mod building_allocation;
 pub mod lodes;
 pub mod overture_opportunity_collection_model;
 mod overture_places_mapping;
@@ -1,4 +1,6 @@
-use crate::model::output_plugin::opportunity::{GeoActivityMask, OpportunityDataset};
+use crate::model::output_plugin::opportunity::{
+    GeoActivityMask, OpportunityDataset, OpportunityLocationHours,
+};
 use bambam_omf::collection::{
     Bbox, BuildingsRecord, OvertureMapsCollectionError, OvertureMapsCollector, OvertureRecordType,
     PlacesRecord,
@@ -84,13 +86,16 @@ impl OvertureOpportunityCollectionModel {
     }
 
     /// Collect opportunities from Places and Buildings datasets and
-    /// process them into Vec<[`Geometry`], f64> according to the configuration of the model
+    /// process them into Vec<[`Geometry`], f64> according to the configuration of the model,
+    /// along with the opening hours of each opportunity location when known. Buildings
+    /// have no opening hours.
     pub fn collect(
         &self,
         activity_types: &[String],
-    ) -> Result<OpportunityDataset, OvertureMapsCollectionError> {
+    ) -> Result<(OpportunityDataset, OpportunityLocationHours), OvertureMapsCollectionError> {
         // Collect raw opportunities
-        let mut places_opportunities = self.collect_places_opportunities(activity_types)?;
+        let (mut places_opportunities, mut opening_hours) =
+            self.collect_places_opportunities(activity_types)?;
 
         if let Some(building_mappings) = &self.buildings_activity_mappings {
             let buildings_opportunities =
@@ -156,20 +161,22 @@ impl OvertureOpportunityCollectionModel {
 
             // Merge places_opportunities + buildings.centroid
             places_opportunities.extend(filtered_buildings);
+            opening_hours.resize(places_opportunities.len(), None);
         }
 
-        Ok(places_opportunities
+        let dataset = places_opportunities
             .into_iter()
             .map(|(g, vec)| (g, vec.into_iter().map(|v| v as i16 as f64).collect()))
-            .collect())
+            .collect();
+        Ok((dataset, opening_hours))
     }
 
     /// collect the places where opportunities can be found along with a mask
-    /// for matching activity types per location.
+    /// for matching activity types per location and the opening hours of each place.
     fn collect_places_opportunities(
         &self,
         activity_types: &[String],
-    ) -> Result<GeoActivityMask, OvertureMapsCollectionError> {
+    ) -> Result<(GeoActivityMask, OpportunityLocationHours), OvertureMapsCollectionError> {
         let uri = match self.release_version {
             ReleaseVersion::Latest => self.collector.get_latest_release()?,
             ReleaseVersion::Monthly { .. } => self.release_version.to_string(),
@@ -203,11 +210,16 @@ impl OvertureOpportunityCollectionModel {
                 .collect::<Vec<f64>>()
         );
 
-        // Collect POI geometries
-        let mep_geometries: Vec<Option<Geometry<f32>>> = places_records
-            .into_iter()
-            .map(|record| record.get_geometry())
-            .collect();
+        // Collect POI geometries and opening hours
+        let (mep_geometries, mep_hours): (Vec<Option<Geometry<f32>>>, OpportunityLocationHours) =
+            places_records
+                .into_iter()
+                .map(|record| (record.get_geometry(), record.get_opening_hours().cloned()))
+                .unzip();
+        log::info!(
+            "Places with opening hours: {}",
+            mep_hours.iter().filter(|h| h.is_some()).count()
+        );
 
         log::debug!(
             "Non-empty geometries: {:?}",
@@ -218,14 +230,15 @@ impl OvertureOpportunityCollectionModel {
                 .len()
         );
 
-        // Zip geometries and vectors (Filtering Empty geometries in the process)
+        // Zip geometries, vectors and hours (Filtering Empty geometries in the process)
         Ok(mep_geometries
             .into_iter()
             .zip(mep_vectors)
-            .filter_map(|(maybe_geometry, vector)| {
-                maybe_geometry.map(|geometry| (geometry, vector))
+            .zip(mep_hours)
+            .filter_map(|((maybe_geometry, vector), hours)| {
+                maybe_geometry.map(|geometry| ((geometry, vector), hours))
             })
-            .collect::<Vec<(Geometry<f32>, Vec<bool>)>>())
+            .unzip())
     }
 
     fn collect_building_opportunities(
@@ -0,0 +1,11 @@
+mod spatial_file_format;
+mod spatial_file_output_plugin;
+mod spatial_file_output_plugin_builder;
+mod spatial_file_writer;
+
+pub use spatial_file_format::SpatialFileFormat;
+pub use spatial_file_output_plugin::{SpatialFileOutputPlugin, SpatialFileOutputPluginConfig};
+pub use spatial_file_output_plugin_builder::SpatialFileOutputPluginBuilder;
+pub use spatial_file_writer::{
+    SpatialColumn, SpatialColumnType, SpatialFileWriter, SpatialGeometryType, SpatialValue,
+};
@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// file formats for writing features with geometries and attributes, readable by
+/// GIS tools such as QGIS and GDAL.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpatialFileFormat {
+    /// FlatGeobuf (.fgb), a single-layer binary format with a spatial index
+    #[serde(rename = "flatgeobuf")]
+    #[value(name = "flatgeobuf")]
+    FlatGeobuf,
+    /// OGC GeoPackage (.gpkg), a SQLite database with a feature table
+    #[serde(rename = "geopackage")]
+    #[value(name = "geopackage")]
+    GeoPackage,
+}
+
+impl SpatialFileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SpatialFileFormat::FlatGeobuf => "fgb",
+            SpatialFileFormat::GeoPackage => "gpkg",
+        }
+    }
+}
+
+impl Display for SpatialFileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpatialFileFormat::FlatGeobuf => write!(f, "flatgeobuf"),
+            SpatialFileFormat::GeoPackage => write!(f, "geopackage"),
+        }
+    }
+}
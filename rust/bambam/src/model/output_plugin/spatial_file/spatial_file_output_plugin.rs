@@ -0,0 +1,463 @@
+use super::{
+    SpatialColumn, SpatialColumnType, SpatialFileFormat, SpatialFileWriter, SpatialGeometryType,
+    SpatialValue,
+};
+use bambam_core::model::{bambam_field, bambam_table};
+use geozero::{wkb::Wkb, ToGeo};
+use itertools::Itertools;
+use routee_compass::app::{compass::CompassAppError, search::SearchAppResult};
+use routee_compass::plugin::output::{OutputPlugin, OutputPluginError};
+use routee_compass_core::algorithm::search::SearchInstance;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpatialFileOutputPluginConfig {
+    pub format: SpatialFileFormat,
+    /// file to write, replaced if it exists
+    pub output_file: String,
+    /// name of the layer in the file, "isochrones" by default
+    #[serde(default)]
+    pub layer: Option<String>,
+}
+
+/// writes the isochrones of aggregate output rows to a FlatGeobuf or GeoPackage file
+/// with one feature per (origin, mode, bin). attribute columns are the grid id, mode,
+/// bin and destination count, followed by the scalar fields of the `info` section and
+/// the opportunity count of each activity type within the bin.
+///
+/// columns are fixed by the first row with isochrones, so this plugin is expected
+/// after the opportunity and isochrone plugins. activity types are read from the
+/// `activity_types` of the `info` section when present, otherwise from the counts of
+/// the first row. info fields and activity types without a column, and info values
+/// that do not match their column type, are skipped with a warning. the file is
+/// finished when the plugin is dropped at the end of the run.
+pub struct SpatialFileOutputPlugin {
+    format: SpatialFileFormat,
+    output_file: PathBuf,
+    layer: String,
+    state: Mutex<Option<IsochroneLayer>>,
+}
+
+/// an open spatial file along with the source of each attribute column.
+struct IsochroneLayer {
+    writer: SpatialFileWriter,
+    info_columns: Vec<(String, SpatialColumnType)>,
+    activity_types: Vec<String>,
+    /// fields already reported as skipped, so that each is only logged once
+    skipped: HashSet<String>,
+}
+
+const FIXED_COLUMNS: [(&str, SpatialColumnType); 4] = [
+    (bambam_field::GRID_ID, SpatialColumnType::Text),
+    (bambam_field::MODE, SpatialColumnType::Text),
+    (bambam_field::TIME_BINS, SpatialColumnType::Integer),
+    (bambam_field::N_DESTINATIONS, SpatialColumnType::Integer),
+];
+
+impl From<&SpatialFileOutputPluginConfig> for SpatialFileOutputPlugin {
+    fn from(value: &SpatialFileOutputPluginConfig) -> Self {
+        Self {
+            format: value.format,
+            output_file: PathBuf::from(&value.output_file),
+            layer: value
+                .layer
+                .clone()
+                .unwrap_or_else(|| String::from("isochrones")),
+            state: Mutex::new(None),
+        }
+    }
+}
+
+impl OutputPlugin for SpatialFileOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        _result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let isochrones = bambam_table::isochrone_rows(output)?;
+        if isochrones.is_empty() {
+            return Ok(());
+        }
+        let opportunities = bambam_table::opportunity_rows(output)?;
+        let counts = opportunities
+            .iter()
+            .map(|r| ((r.bin, r.activity_type.as_str()), r.count))
+            .collect::<HashMap<_, _>>();
+        let info = output.get(bambam_field::INFO);
+
+        let mut state = self.state.lock().map_err(|e| {
+            OutputPluginError::OutputPluginFailed(format!(
+                "failure acquiring lock on spatial file writer: {e}"
+            ))
+        })?;
+        if state.is_none() {
+            *state = Some(self.create_layer(info, &opportunities)?);
+        }
+        let Some(layer) = state.as_mut() else {
+            return Ok(());
+        };
+        layer.warn_unknown(info, &opportunities);
+
+        for row in isochrones.iter() {
+            let geometry = Wkb(row.isochrone_wkb.clone()).to_geo().map_err(|e| {
+                OutputPluginError::OutputPluginFailed(format!(
+                    "failure decoding isochrone of bin {}: {e}",
+                    row.bin
+                ))
+            })?;
+            let fixed = [
+                row.grid_id
+                    .clone()
+                    .map(SpatialValue::Text)
+                    .unwrap_or(SpatialValue::Null),
+                SpatialValue::Text(row.mode.clone()),
+                SpatialValue::Integer(row.bin as i64),
+                row.n_destinations
+                    .map(|n| SpatialValue::Integer(n as i64))
+                    .unwrap_or(SpatialValue::Null),
+            ];
+            let info_values = layer.info_values(info);
+            let opportunity_values = layer.activity_types.iter().map(|a| {
+                counts
+                    .get(&(row.bin, a.as_str()))
+                    .map(|c| SpatialValue::Real(*c))
+                    .unwrap_or(SpatialValue::Null)
+            });
+            let values = fixed
+                .into_iter()
+                .chain(info_values)
+                .chain(opportunity_values)
+                .collect_vec();
+            layer
+                .writer
+                .add_feature(&geometry, &values)
+                .map_err(OutputPluginError::OutputPluginFailed)?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "spatial_file"
+    }
+}
+
+impl SpatialFileOutputPlugin {
+    /// creates the file with columns for the scalar info fields and activity types
+    /// found on the first row.
+    fn create_layer(
+        &self,
+        info: Option<&Value>,
+        opportunities: &[bambam_table::OpportunityTableRow],
+    ) -> Result<IsochroneLayer, OutputPluginError> {
+        let info_columns = info
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|i| i.iter())
+            .filter_map(|(key, value)| column_type(value).map(|t| (key.clone(), t)))
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .collect_vec();
+        let activity_types = info
+            .and_then(|i| i.get(bambam_field::ACTIVITY_TYPES))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .chain(opportunities.iter().map(|r| r.activity_type.clone()))
+            .unique()
+            .sorted()
+            .collect_vec();
+
+        let columns = FIXED_COLUMNS
+            .iter()
+            .map(|(name, column_type)| (name.to_string(), *column_type))
+            .chain(info_columns.iter().cloned())
+            .chain(
+                activity_types
+                    .iter()
+                    .map(|a| (a.clone(), SpatialColumnType::Real)),
+            )
+            .map(|(name, column_type)| SpatialColumn { name, column_type })
+            .collect_vec();
+        let mut names = HashSet::new();
+        if let Some(duplicate) = columns.iter().find(|c| !names.insert(c.name.as_str())) {
+            return Err(OutputPluginError::OutputPluginFailed(format!(
+                "cannot write spatial file with more than one column named '{}'",
+                duplicate.name
+            )));
+        }
+        let writer = SpatialFileWriter::create(
+            &self.output_file,
+            self.format,
+            &self.layer,
+            SpatialGeometryType::MultiPolygon,
+            columns,
+        )
+        .map_err(OutputPluginError::OutputPluginFailed)?;
+        log::info!(
+            "writing isochrones to {} file '{}'",
+            self.format,
+            self.output_file.to_string_lossy()
+        );
+        Ok(IsochroneLayer {
+            writer,
+            info_columns,
+            activity_types,
+            skipped: HashSet::new(),
+        })
+    }
+}
+
+impl Drop for SpatialFileOutputPlugin {
+    fn drop(&mut self) {
+        let state = match self.state.get_mut() {
+            Ok(state) => state.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        let Some(layer) = state else {
+            return;
+        };
+        let filename = self.output_file.to_string_lossy();
+        match layer.writer.finish() {
+            Ok(n) => log::info!("wrote {n} isochrones to '{filename}'"),
+            Err(e) => log::error!("failure finishing spatial file '{filename}': {e}"),
+        }
+    }
+}
+
+impl IsochroneLayer {
+    /// logs the scalar info fields and activity types of a row without a column,
+    /// once per field, as they are left out of the file.
+    fn warn_unknown(
+        &mut self,
+        info: Option<&Value>,
+        opportunities: &[bambam_table::OpportunityTableRow],
+    ) {
+        let unknown_info = info
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|i| i.iter())
+            .filter(|(key, value)| {
+                column_type(value).is_some() && !self.info_columns.iter().any(|(k, _)| k == *key)
+            })
+            .map(|(key, _)| key.as_str());
+        let unknown_activity_types = opportunities
+            .iter()
+            .map(|r| r.activity_type.as_str())
+            .filter(|a| !self.activity_types.iter().any(|t| t == a));
+        for key in unknown_info.chain(unknown_activity_types) {
+            if self.skipped.insert(key.to_string()) {
+                log::warn!(
+                    "spatial file has no column for '{key}', which was not found on the first row written; skipping it"
+                );
+            }
+        }
+    }
+
+    /// the value of each info column for a row, or null where the row has no value
+    /// or a value that does not match the column type.
+    fn info_values(&mut self, info: Option<&Value>) -> Vec<SpatialValue> {
+        let mut values = Vec::with_capacity(self.info_columns.len());
+        for (key, column_type) in self.info_columns.iter() {
+            let Some(value) = info.and_then(|i| i.get(key)) else {
+                values.push(SpatialValue::Null);
+                continue;
+            };
+            match to_spatial_value(value, *column_type) {
+                Some(v) => values.push(v),
+                None => {
+                    if self.skipped.insert(key.clone()) {
+                        log::warn!(
+                            "info field '{key}' has value {value} which does not match its {column_type:?} column; writing null"
+                        );
+                    }
+                    values.push(SpatialValue::Null);
+                }
+            }
+        }
+        values
+    }
+}
+
+/// the column type for a scalar JSON value, or None for arrays, objects and nulls.
+fn column_type(value: &Value) -> Option<SpatialColumnType> {
+    match value {
+        Value::String(_) => Some(SpatialColumnType::Text),
+        Value::Bool(_) => Some(SpatialColumnType::Integer),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(SpatialColumnType::Integer),
+        Value::Number(_) => Some(SpatialColumnType::Real),
+        _ => None,
+    }
+}
+
+/// converts a JSON value to the type of its column, or None if it does not match.
+fn to_spatial_value(value: &Value, column_type: SpatialColumnType) -> Option<SpatialValue> {
+    match (column_type, value) {
+        (_, Value::Null) => Some(SpatialValue::Null),
+        (SpatialColumnType::Text, Value::String(s)) => Some(SpatialValue::Text(s.clone())),
+        (SpatialColumnType::Text, other) => Some(SpatialValue::Text(other.to_string())),
+        (SpatialColumnType::Integer, Value::Bool(b)) => Some(SpatialValue::Integer(*b as i64)),
+        (SpatialColumnType::Integer, v) => v.as_i64().map(SpatialValue::Integer),
+        (SpatialColumnType::Real, v) => v.as_f64().map(SpatialValue::Real),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geozero::FeatureProperties;
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    fn plugin(format: SpatialFileFormat, output_file: &std::path::Path) -> SpatialFileOutputPlugin {
+        SpatialFileOutputPlugin::from(&SpatialFileOutputPluginConfig {
+            format,
+            output_file: output_file.to_string_lossy().to_string(),
+            layer: None,
+        })
+    }
+
+    fn output_row(grid_id: &str, info: Value) -> Value {
+        json!({
+            "request": { "mode": "walk", "grid_id": grid_id },
+            "info": info,
+            "aggregate_opportunities": {
+                "20": {
+                    "isochrone": "POLYGON((0 0,2 0,2 2,0 2,0 0))",
+                    "n_destinations": 12,
+                    "opportunities": { "retail": 4.0, "jobs": 10.0 }
+                },
+                "10": {
+                    "isochrone": "POLYGON((0 0,1 0,1 1,0 1,0 0))",
+                    "n_destinations": 3,
+                    "opportunities": { "jobs": 2.0 }
+                }
+            }
+        })
+    }
+
+    fn process(plugin: &SpatialFileOutputPlugin, output: Value) -> Result<(), OutputPluginError> {
+        let result = Err(CompassAppError::BuildFailure(String::from(
+            "search result is unused by the plugin",
+        )));
+        plugin.process(&mut output.clone(), &result)
+    }
+
+    #[test]
+    fn test_process_writes_feature_per_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("isochrones.gpkg");
+        let plugin = plugin(SpatialFileFormat::GeoPackage, &file);
+        let info = json!({
+            "isochrone_format": "wkt",
+            "activity_types": ["retail", "jobs", "food"],
+            "tree_size": 42,
+            "bin_range": { "min": 0 }
+        });
+        process(&plugin, output_row("a", info.clone())).unwrap();
+        process(
+            &plugin,
+            json!({ "request": { "mode": "walk" }, "error": "no path" }),
+        )
+        .unwrap();
+        process(&plugin, output_row("b", info)).unwrap();
+
+        // the file is finished when the plugin is dropped
+        drop(plugin);
+        let conn = Connection::open(&file).unwrap();
+        let mut statement = conn
+            .prepare("SELECT grid_id, bin, n_destinations, tree_size, food, jobs, retail FROM isochrones ORDER BY fid")
+            .unwrap();
+        let rows = statement
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, i64>(1)?,
+                    r.get::<_, i64>(2)?,
+                    r.get::<_, i64>(3)?,
+                    r.get::<_, Option<f64>>(4)?,
+                    r.get::<_, Option<f64>>(5)?,
+                    r.get::<_, Option<f64>>(6)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (String::from("a"), 10, 3, 42, None, Some(2.0), None),
+                (String::from("a"), 20, 12, 42, None, Some(10.0), Some(4.0)),
+                (String::from("b"), 10, 3, 42, None, Some(2.0), None),
+                (String::from("b"), 20, 12, 42, None, Some(10.0), Some(4.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_skips_unknown_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("isochrones.fgb");
+        let plugin = plugin(SpatialFileFormat::FlatGeobuf, &file);
+        let info = json!({ "isochrone_format": "wkt", "tree_size": 42 });
+        process(&plugin, output_row("a", info)).unwrap();
+
+        let new_info_field =
+            json!({ "isochrone_format": "wkt", "tree_size": 42, "runtime": "00:01" });
+        process(&plugin, output_row("b", new_info_field)).unwrap();
+
+        let mut new_activity_type = output_row("c", json!({ "isochrone_format": "wkt" }));
+        new_activity_type["aggregate_opportunities"]["10"]["opportunities"]["parks"] = json!(1.0);
+        process(&plugin, new_activity_type).unwrap();
+
+        let mismatched_type = json!({ "isochrone_format": "wkt", "tree_size": "many" });
+        process(&plugin, output_row("d", mismatched_type)).unwrap();
+        drop(plugin);
+
+        let mut reader = std::io::BufReader::new(std::fs::File::open(&file).unwrap());
+        let fgb = FgbReader::open(&mut reader).unwrap();
+        let columns = fgb
+            .header()
+            .columns()
+            .unwrap()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect_vec();
+        assert!(!columns.iter().any(|c| c == "runtime" || c == "parks"));
+        let mut features = fgb.select_all_seq().unwrap();
+        let mut rows = vec![];
+        while let Some(feature) = features.next().unwrap() {
+            rows.push((
+                feature.property::<String>(bambam_field::GRID_ID).unwrap(),
+                feature.property::<i64>("tree_size").ok(),
+            ));
+        }
+        let tree_sizes = rows
+            .iter()
+            .map(|(grid_id, tree_size)| (grid_id.as_str(), *tree_size))
+            .unique()
+            .collect_vec();
+        assert_eq!(
+            tree_sizes,
+            vec![("a", Some(42)), ("b", Some(42)), ("c", None), ("d", None)]
+        );
+    }
+
+    #[test]
+    fn test_process_fails_on_duplicate_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("isochrones.gpkg");
+        let plugin = plugin(SpatialFileFormat::GeoPackage, &file);
+        let info = json!({ "isochrone_format": "wkt", "activity_types": ["mode"] });
+        let error = process(&plugin, output_row("a", info)).unwrap_err();
+        assert!(error.to_string().contains("'mode'"), "{error}");
+        assert!(!file.exists());
+    }
+}
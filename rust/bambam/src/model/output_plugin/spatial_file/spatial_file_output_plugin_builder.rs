@@ -0,0 +1,28 @@
+use super::{SpatialFileOutputPlugin, SpatialFileOutputPluginConfig};
+use routee_compass::{
+    app::compass::CompassComponentError,
+    plugin::{
+        output::{OutputPlugin, OutputPluginBuilder},
+        PluginError,
+    },
+};
+use std::sync::Arc;
+
+/// RouteE Compass OutputPluginBuilder for writing bambam isochrones to a FlatGeobuf
+/// or GeoPackage file.
+pub struct SpatialFileOutputPluginBuilder {}
+
+impl OutputPluginBuilder for SpatialFileOutputPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassComponentError> {
+        let config: SpatialFileOutputPluginConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| {
+                PluginError::BuildFailed(format!(
+                    "failed to read spatial file plugin configuration: {e}"
+                ))
+            })?;
+        Ok(Arc::new(SpatialFileOutputPlugin::from(&config)))
+    }
+}
@@ -0,0 +1,656 @@
+use super::SpatialFileFormat;
+use flatgeobuf::{ColumnType, FgbCrs, FgbWriter, FgbWriterOptions, GeometryType};
+use geo::{BoundingRect, Geometry, MultiPolygon};
+use geozero::{ColumnValue, CoordDimensions, PropertyProcessor, ToWkb};
+use rusqlite::{params_from_iter, types::Value as SqlValue, Connection};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// all features are written in WGS84 lat/lon degrees.
+const WGS84_SRS_ID: i32 = 4326;
+const WGS84_WKT: &str = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#;
+/// "GPKG" as a big-endian integer, identifying the SQLite file as a GeoPackage.
+const GPKG_APPLICATION_ID: i32 = 0x47504B47;
+/// GeoPackage version 1.3.0.
+const GPKG_USER_VERSION: i32 = 10300;
+/// number of GeoPackage features inserted per transaction.
+const GPKG_COMMIT_INTERVAL: usize = 10_000;
+
+/// geometry type of every feature in a spatial file. polygons are promoted to
+/// multipolygons so that a layer holds a single geometry type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpatialGeometryType {
+    Point,
+    MultiPolygon,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpatialColumnType {
+    Text,
+    Integer,
+    Real,
+}
+
+/// an attribute column of a spatial file.
+#[derive(Clone, Debug)]
+pub struct SpatialColumn {
+    pub name: String,
+    pub column_type: SpatialColumnType,
+}
+
+/// an attribute value of a feature. all columns are nullable.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpatialValue {
+    Text(String),
+    Integer(i64),
+    Real(f64),
+    Null,
+}
+
+/// writes features to a FlatGeobuf or GeoPackage file with a fixed set of attribute
+/// columns. the file is only complete once [`SpatialFileWriter::finish`] is called.
+///
+/// FlatGeobuf features are passed as geozero geometries to a single FlatGeobuf
+/// writer, which holds them until the file is written with its spatial index on
+/// finish. GeoPackage features are inserted within transactions committed every
+/// [`GPKG_COMMIT_INTERVAL`] features, on [`SpatialFileWriter::flush`] and on finish.
+pub struct SpatialFileWriter {
+    layer: String,
+    geometry_type: SpatialGeometryType,
+    columns: Vec<SpatialColumn>,
+    sink: Sink,
+    extent: Option<[f64; 4]>,
+    n_features: usize,
+}
+
+enum Sink {
+    FlatGeobuf {
+        fgb: Box<FgbWriter<'static>>,
+        out: BufWriter<File>,
+        filename: String,
+    },
+    GeoPackage {
+        conn: Connection,
+        insert: String,
+    },
+}
+
+impl SpatialFileWriter {
+    /// creates a writer for a single layer of features, replacing any existing file.
+    pub fn create(
+        file: &Path,
+        format: SpatialFileFormat,
+        layer: &str,
+        geometry_type: SpatialGeometryType,
+        columns: Vec<SpatialColumn>,
+    ) -> Result<Self, String> {
+        let filename = file.to_string_lossy();
+        if let Some(parent) = file.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failure creating directory for '{filename}': {e}"))?;
+        }
+        let sink = match format {
+            SpatialFileFormat::FlatGeobuf => {
+                // created up front so that an unwritable path fails before any search
+                let out = File::create(file)
+                    .map_err(|e| format!("failure creating FlatGeobuf '{filename}': {e}"))?;
+                let fgb = create_flatgeobuf(layer, geometry_type, &columns)
+                    .map_err(|e| format!("failure creating FlatGeobuf '{filename}': {e}"))?;
+                Sink::FlatGeobuf {
+                    fgb: Box::new(fgb),
+                    out: BufWriter::new(out),
+                    filename: filename.to_string(),
+                }
+            }
+            SpatialFileFormat::GeoPackage => {
+                if file.exists() {
+                    std::fs::remove_file(file)
+                        .map_err(|e| format!("failure replacing '{filename}': {e}"))?;
+                }
+                let (conn, insert) = create_geopackage(file, layer, geometry_type, &columns)
+                    .map_err(|e| format!("failure creating GeoPackage '{filename}': {e}"))?;
+                Sink::GeoPackage { conn, insert }
+            }
+        };
+        Ok(Self {
+            layer: layer.to_string(),
+            geometry_type,
+            columns,
+            sink,
+            extent: None,
+            n_features: 0,
+        })
+    }
+
+    pub fn columns(&self) -> &[SpatialColumn] {
+        &self.columns
+    }
+
+    pub fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    /// adds a feature with one value per column, in column order.
+    pub fn add_feature(
+        &mut self,
+        geometry: &Geometry<f64>,
+        values: &[SpatialValue],
+    ) -> Result<(), String> {
+        if values.len() != self.columns.len() {
+            return Err(format!(
+                "feature has {} values but the file has {} columns",
+                values.len(),
+                self.columns.len()
+            ));
+        }
+        let geometry = promote(geometry, self.geometry_type)?;
+        if let Some(rect) = geometry.bounding_rect() {
+            let [min_x, min_y, max_x, max_y] = self.extent.get_or_insert([
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ]);
+            *min_x = min_x.min(rect.min().x);
+            *min_y = min_y.min(rect.min().y);
+            *max_x = max_x.max(rect.max().x);
+            *max_y = max_y.max(rect.max().y);
+        }
+
+        match &mut self.sink {
+            Sink::FlatGeobuf { fgb, filename, .. } => {
+                add_flatgeobuf_feature(fgb, &self.columns, geometry, values)
+                    .map_err(|e| format!("failure writing FlatGeobuf '{filename}': {e}"))?;
+            }
+            Sink::GeoPackage { conn, insert } => {
+                let blob = geopackage_blob(&geometry)?;
+                let params = std::iter::once(SqlValue::Blob(blob))
+                    .chain(values.iter().map(SpatialValue::to_sql))
+                    .collect::<Vec<_>>();
+                let mut statement = conn
+                    .prepare_cached(insert)
+                    .map_err(|e| format!("failure preparing GeoPackage insert: {e}"))?;
+                statement
+                    .execute(params_from_iter(params))
+                    .map_err(|e| format!("failure writing GeoPackage feature: {e}"))?;
+            }
+        }
+        self.n_features += 1;
+        if self.n_features % GPKG_COMMIT_INTERVAL == 0 {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// commits the GeoPackage features added so far, leaving a complete file that
+    /// further features may be added to. FlatGeobuf features are only written on
+    /// [`SpatialFileWriter::finish`], since the spatial index precedes them in the file.
+    pub fn flush(&mut self) -> Result<(), String> {
+        if let Sink::GeoPackage { conn, .. } = &self.sink {
+            commit_geopackage(conn, &self.layer, self.extent)?;
+            conn.execute_batch("BEGIN")
+                .map_err(|e| format!("failure starting GeoPackage transaction: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// completes the file, returning the number of features written.
+    pub fn finish(self) -> Result<usize, String> {
+        match self.sink {
+            Sink::FlatGeobuf {
+                fgb,
+                mut out,
+                filename,
+            } => {
+                fgb.write(&mut out)
+                    .map_err(|e| format!("failure writing FlatGeobuf '{filename}': {e}"))?;
+                out.flush()
+                    .map_err(|e| format!("failure writing FlatGeobuf '{filename}': {e}"))?;
+            }
+            Sink::GeoPackage { conn, .. } => commit_geopackage(&conn, &self.layer, self.extent)?,
+        }
+        Ok(self.n_features)
+    }
+}
+
+/// writes the extent of the features so far and commits the open transaction.
+fn commit_geopackage(
+    conn: &Connection,
+    layer: &str,
+    extent: Option<[f64; 4]>,
+) -> Result<(), String> {
+    if let Some([min_x, min_y, max_x, max_y]) = extent {
+        conn.execute(
+            "UPDATE gpkg_contents SET min_x = ?1, min_y = ?2, max_x = ?3, max_y = ?4 WHERE table_name = ?5",
+            rusqlite::params![min_x, min_y, max_x, max_y, layer],
+        )
+        .map_err(|e| format!("failure writing GeoPackage extent: {e}"))?;
+    }
+    conn.execute_batch("COMMIT")
+        .map_err(|e| format!("failure committing GeoPackage features: {e}"))
+}
+
+impl SpatialValue {
+    fn to_sql(&self) -> SqlValue {
+        match self {
+            SpatialValue::Text(s) => SqlValue::Text(s.clone()),
+            SpatialValue::Integer(i) => SqlValue::Integer(*i),
+            SpatialValue::Real(r) => SqlValue::Real(*r),
+            SpatialValue::Null => SqlValue::Null,
+        }
+    }
+}
+
+impl SpatialColumnType {
+    fn sql_type(&self) -> &'static str {
+        match self {
+            SpatialColumnType::Text => "TEXT",
+            SpatialColumnType::Integer => "INTEGER",
+            SpatialColumnType::Real => "DOUBLE",
+        }
+    }
+
+    fn flatgeobuf_type(&self) -> ColumnType {
+        match self {
+            SpatialColumnType::Text => ColumnType::String,
+            SpatialColumnType::Integer => ColumnType::Long,
+            SpatialColumnType::Real => ColumnType::Double,
+        }
+    }
+}
+
+impl SpatialGeometryType {
+    fn sql_type(&self) -> &'static str {
+        match self {
+            SpatialGeometryType::Point => "POINT",
+            SpatialGeometryType::MultiPolygon => "MULTIPOLYGON",
+        }
+    }
+
+    fn flatgeobuf_type(&self) -> GeometryType {
+        match self {
+            SpatialGeometryType::Point => GeometryType::Point,
+            SpatialGeometryType::MultiPolygon => GeometryType::MultiPolygon,
+        }
+    }
+}
+
+fn create_flatgeobuf(
+    layer: &str,
+    geometry_type: SpatialGeometryType,
+    columns: &[SpatialColumn],
+) -> Result<FgbWriter<'static>, flatgeobuf::Error> {
+    let options = FgbWriterOptions {
+        write_index: true,
+        crs: FgbCrs {
+            code: WGS84_SRS_ID,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut writer =
+        FgbWriter::create_with_options(layer, geometry_type.flatgeobuf_type(), options)?;
+    for column in columns.iter() {
+        writer.add_column(
+            &column.name,
+            column.column_type.flatgeobuf_type(),
+            |_, c| {
+                c.nullable = true;
+            },
+        );
+    }
+    Ok(writer)
+}
+
+/// adds a feature to the FlatGeobuf writer, passing the geometry through geozero and
+/// skipping null values.
+fn add_flatgeobuf_feature(
+    fgb: &mut FgbWriter<'static>,
+    columns: &[SpatialColumn],
+    geometry: Geometry<f64>,
+    values: &[SpatialValue],
+) -> Result<(), String> {
+    let mut failure = None;
+    fgb.add_feature_geom(geometry, |feature| {
+        for (idx, (column, value)) in columns.iter().zip(values).enumerate() {
+            let result = match value {
+                SpatialValue::Text(s) => {
+                    feature.property(idx, &column.name, &ColumnValue::String(s))
+                }
+                SpatialValue::Integer(i) => {
+                    feature.property(idx, &column.name, &ColumnValue::Long(*i))
+                }
+                SpatialValue::Real(r) => {
+                    feature.property(idx, &column.name, &ColumnValue::Double(*r))
+                }
+                SpatialValue::Null => continue,
+            };
+            if let Err(e) = result {
+                failure = Some(format!("failure writing column '{}': {e}", column.name));
+                break;
+            }
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// creates a new GeoPackage with the minimal set of metadata tables and an empty
+/// feature table, returning the open connection inside a transaction along with
+/// the statement for inserting a feature.
+fn create_geopackage(
+    file: &Path,
+    layer: &str,
+    geometry_type: SpatialGeometryType,
+    columns: &[SpatialColumn],
+) -> Result<(Connection, String), rusqlite::Error> {
+    let conn = Connection::open(file)?;
+    conn.pragma_update(None, "application_id", GPKG_APPLICATION_ID)?;
+    conn.pragma_update(None, "user_version", GPKG_USER_VERSION)?;
+    conn.execute_batch(
+        "CREATE TABLE gpkg_spatial_ref_sys (
+            srs_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL PRIMARY KEY,
+            organization TEXT NOT NULL,
+            organization_coordsys_id INTEGER NOT NULL,
+            definition TEXT NOT NULL,
+            description TEXT
+        );
+        CREATE TABLE gpkg_contents (
+            table_name TEXT NOT NULL PRIMARY KEY,
+            data_type TEXT NOT NULL,
+            identifier TEXT UNIQUE,
+            description TEXT DEFAULT '',
+            last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+            min_x DOUBLE,
+            min_y DOUBLE,
+            max_x DOUBLE,
+            max_y DOUBLE,
+            srs_id INTEGER,
+            CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+        );
+        CREATE TABLE gpkg_geometry_columns (
+            table_name TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            geometry_type_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL,
+            z TINYINT NOT NULL,
+            m TINYINT NOT NULL,
+            CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+            CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+            CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+        );
+        INSERT INTO gpkg_spatial_ref_sys VALUES
+            ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', 'undefined cartesian coordinate reference system'),
+            ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', 'undefined geographic coordinate reference system');",
+    )?;
+    conn.execute(
+        "INSERT INTO gpkg_spatial_ref_sys VALUES ('WGS 84 geodetic', ?1, 'EPSG', ?1, ?2, 'longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid')",
+        rusqlite::params![WGS84_SRS_ID, WGS84_WKT],
+    )?;
+    conn.execute(
+        "INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id) VALUES (?1, 'features', ?1, ?2)",
+        rusqlite::params![layer, WGS84_SRS_ID],
+    )?;
+    conn.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES (?1, 'geom', ?2, ?3, 0, 0)",
+        rusqlite::params![layer, geometry_type.sql_type(), WGS84_SRS_ID],
+    )?;
+
+    let column_definitions = columns
+        .iter()
+        .map(|c| format!("{} {}", quote(&c.name), c.column_type.sql_type()))
+        .collect::<Vec<_>>();
+    let create = format!(
+        "CREATE TABLE {} (fid INTEGER PRIMARY KEY AUTOINCREMENT, geom {}{})",
+        quote(layer),
+        geometry_type.sql_type(),
+        column_definitions
+            .iter()
+            .map(|c| format!(", {c}"))
+            .collect::<String>()
+    );
+    conn.execute(&create, [])?;
+
+    let column_names = std::iter::once(String::from("geom"))
+        .chain(columns.iter().map(|c| quote(&c.name)))
+        .collect::<Vec<_>>();
+    let placeholders = (1..=column_names.len())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>();
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(layer),
+        column_names.join(", "),
+        placeholders.join(", ")
+    );
+    conn.execute_batch("BEGIN")?;
+    Ok((conn, insert))
+}
+
+/// a GeoPackage geometry blob: the "GP" header with an xy envelope followed by the
+/// geometry as little-endian WKB.
+fn geopackage_blob(geometry: &Geometry<f64>) -> Result<Vec<u8>, String> {
+    let wkb = geometry
+        .to_wkb(CoordDimensions::xy())
+        .map_err(|e| format!("failure writing geometry as WKB: {e}"))?;
+    let mut blob = vec![b'G', b'P', 0];
+    match geometry.bounding_rect() {
+        Some(rect) => {
+            // little endian, xy envelope
+            blob.push(0b0000_0011);
+            blob.extend_from_slice(&WGS84_SRS_ID.to_le_bytes());
+            for value in [rect.min().x, rect.max().x, rect.min().y, rect.max().y] {
+                blob.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        None => {
+            // little endian, no envelope, empty geometry
+            blob.push(0b0001_0001);
+            blob.extend_from_slice(&WGS84_SRS_ID.to_le_bytes());
+        }
+    }
+    blob.extend(wkb);
+    Ok(blob)
+}
+
+/// converts a geometry to the geometry type of the file.
+fn promote(
+    geometry: &Geometry<f64>,
+    geometry_type: SpatialGeometryType,
+) -> Result<Geometry<f64>, String> {
+    match (geometry_type, geometry) {
+        (SpatialGeometryType::Point, Geometry::Point(_)) => Ok(geometry.clone()),
+        (SpatialGeometryType::MultiPolygon, Geometry::MultiPolygon(_)) => Ok(geometry.clone()),
+        (SpatialGeometryType::MultiPolygon, Geometry::Polygon(p)) if p.exterior().0.is_empty() => {
+            Ok(Geometry::MultiPolygon(MultiPolygon::new(vec![])))
+        }
+        (SpatialGeometryType::MultiPolygon, Geometry::Polygon(p)) => {
+            Ok(Geometry::MultiPolygon(MultiPolygon::new(vec![p.clone()])))
+        }
+        (expected, _) => Err(format!(
+            "cannot write geometry of this type to a {expected:?} layer"
+        )),
+    }
+}
+
+/// quotes a SQLite identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geo::{polygon, Point};
+    use geozero::{wkb::GpkgWkb, FeatureProperties, ToGeo};
+    use std::io::BufReader;
+
+    fn columns() -> Vec<SpatialColumn> {
+        vec![
+            SpatialColumn {
+                name: String::from("mode"),
+                column_type: SpatialColumnType::Text,
+            },
+            SpatialColumn {
+                name: String::from("bin"),
+                column_type: SpatialColumnType::Integer,
+            },
+        ]
+    }
+
+    fn square() -> Geometry<f64> {
+        Geometry::Polygon(polygon![
+            (x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 1.0), (x: 0.0, y: 1.0), (x: 0.0, y: 0.0),
+        ])
+    }
+
+    /// reads the mode, bin and geometry of each feature of a FlatGeobuf file.
+    fn read_flatgeobuf(file: &Path) -> Vec<(String, Option<i64>, Option<Geometry<f64>>)> {
+        let mut reader = BufReader::new(File::open(file).unwrap());
+        let mut features = FgbReader::open(&mut reader)
+            .unwrap()
+            .select_all_seq()
+            .unwrap();
+        let mut result = vec![];
+        while let Some(feature) = features.next().unwrap() {
+            result.push((
+                feature.property::<String>("mode").unwrap(),
+                feature.property::<i64>("bin").ok(),
+                feature.to_geo().ok(),
+            ));
+        }
+        result
+    }
+
+    #[test]
+    fn test_geopackage_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("isochrones.gpkg");
+        let mut writer = SpatialFileWriter::create(
+            &file,
+            SpatialFileFormat::GeoPackage,
+            "isochrones",
+            SpatialGeometryType::MultiPolygon,
+            columns(),
+        )
+        .unwrap();
+        writer
+            .add_feature(
+                &square(),
+                &[
+                    SpatialValue::Text(String::from("walk")),
+                    SpatialValue::Integer(10),
+                ],
+            )
+            .unwrap();
+        writer.flush().unwrap();
+
+        // features are committed on flush while the writer is still open
+        let conn = Connection::open(&file).unwrap();
+        let n: i64 = conn
+            .query_row("SELECT COUNT(*) FROM isochrones", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(n, 1);
+
+        writer
+            .add_feature(
+                &Geometry::Polygon(polygon!()),
+                &[SpatialValue::Text(String::from("walk")), SpatialValue::Null],
+            )
+            .unwrap();
+        assert!(writer
+            .add_feature(&Geometry::Point(Point::new(0.0, 0.0)), &[])
+            .is_err());
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let n: i64 = conn
+            .query_row("SELECT COUNT(*) FROM isochrones", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(n, 2);
+        let (blob, mode, bin): (Vec<u8>, String, i64) = conn
+            .query_row(
+                "SELECT geom, mode, bin FROM isochrones WHERE bin IS NOT NULL",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((mode.as_str(), bin), ("walk", 10));
+        let geometry = GpkgWkb(blob).to_geo().unwrap();
+        assert!(matches!(geometry, Geometry::MultiPolygon(mp) if mp.0.len() == 1));
+        let max_x: f64 = conn
+            .query_row("SELECT max_x FROM gpkg_contents", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(max_x, 1.0);
+    }
+
+    #[test]
+    fn test_flatgeobuf_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("isochrones.fgb");
+        let mut writer = SpatialFileWriter::create(
+            &file,
+            SpatialFileFormat::FlatGeobuf,
+            "isochrones",
+            SpatialGeometryType::MultiPolygon,
+            columns(),
+        )
+        .unwrap();
+        writer
+            .add_feature(
+                &square(),
+                &[
+                    SpatialValue::Text(String::from("walk")),
+                    SpatialValue::Integer(10),
+                ],
+            )
+            .unwrap();
+        // features are held by the FlatGeobuf writer until finish
+        writer.flush().unwrap();
+        assert_eq!(std::fs::metadata(&file).unwrap().len(), 0);
+
+        writer
+            .add_feature(
+                &square(),
+                &[SpatialValue::Text(String::from("bike")), SpatialValue::Null],
+            )
+            .unwrap();
+        assert!(writer
+            .add_feature(&Geometry::Point(Point::new(0.0, 0.0)), &[])
+            .is_err());
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let features = read_flatgeobuf(&file);
+        let attributes = features
+            .iter()
+            .map(|(mode, bin, _)| (mode.as_str(), *bin))
+            .collect::<Vec<_>>();
+        assert_eq!(attributes, vec![("walk", Some(10)), ("bike", None)]);
+        assert!(features.iter().all(
+            |(_, _, geometry)| matches!(geometry, Some(Geometry::MultiPolygon(mp)) if mp.0.len() == 1)
+        ));
+    }
+
+    #[test]
+    fn test_promote() {
+        let promoted = promote(&square(), SpatialGeometryType::MultiPolygon).unwrap();
+        assert!(matches!(promoted, Geometry::MultiPolygon(mp) if mp.0.len() == 1));
+        let empty = promote(
+            &Geometry::Polygon(polygon!()),
+            SpatialGeometryType::MultiPolygon,
+        )
+        .unwrap();
+        assert!(matches!(empty, Geometry::MultiPolygon(mp) if mp.0.is_empty()));
+        let point = Geometry::Point(Point::new(1.0, 2.0));
+        assert_eq!(promote(&point, SpatialGeometryType::Point).unwrap(), point);
+        assert!(promote(&point, SpatialGeometryType::MultiPolygon).is_err());
+        assert!(promote(&square(), SpatialGeometryType::Point).is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use super::{ComfortConfig, ComfortService};
+use routee_compass_core::model::traversal::{
+    TraversalModelBuilder, TraversalModelError, TraversalModelService,
+};
+use std::sync::Arc;
+
+pub struct ComfortBuilder {}
+
+impl TraversalModelBuilder for ComfortBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        let config: ComfortConfig = serde_json::from_value(parameters.clone()).map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "failure reading comfort traversal model configuration: {e}",
+            ))
+        })?;
+        let service = ComfortService::try_from(&config)?;
+        Ok(Arc::new(service))
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ComfortConfig {
+    /// file of Walking Comfort Index values keyed by edge id, written by the
+    /// `modal_metric` command of `bambam_util` with `--metric-name WCI`.
+    pub wci_input_file: String,
+    /// edges with a total WCI below this value are penalized. may be overridden
+    /// by the `min_wci` query field.
+    pub wci_threshold: i32,
+    /// penalty factor assigned to edges below the threshold, such as `1.5` to
+    /// treat uncomfortable edges as 50% more costly. edges at or above the threshold,
+    /// or without a WCI value, are assigned `1.0`.
+    pub penalty_factor: f64,
+}
@@ -0,0 +1,264 @@
+use super::ComfortConfig;
+use bambam_core::model::bambam_state;
+use bambam_modal_metrics::common::edge_metric_record::EdgeWciRecord;
+use kdam::Bar;
+use ordered_float::OrderedFloat;
+use routee_compass_core::{
+    algorithm::search::SearchTree,
+    model::{
+        network::{EdgeId, Vertex},
+        state::{
+            CustomVariableConfig, InputFeature, StateModel, StateVariable, StateVariableConfig,
+        },
+        traversal::{
+            EdgeFrontierContext, TraversalModel, TraversalModelError, TraversalModelService,
+        },
+    },
+    util::fs::read_utils,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// penalty factor for edges that are not penalized.
+const NO_PENALTY: f64 = 1.0;
+
+/// loads Walking Comfort Index values by edge id and builds a [`ComfortModel`] for
+/// each query.
+pub struct ComfortService {
+    pub config: Arc<ComfortConfig>,
+    pub wci: Arc<HashMap<EdgeId, i32>>,
+}
+
+/// writes a cost penalty factor for each traversed edge based on its Walking Comfort
+/// Index (WCI). edges with a WCI below the threshold receive the configured penalty.
+///
+/// the penalty is written to the [`bambam_state::COST_PENALTY_FACTOR`] state feature,
+/// which is intended to be used as a "raw" vehicle rate combined with trip time via
+/// "mul" cost aggregation.
+pub struct ComfortModel {
+    pub wci: Arc<HashMap<EdgeId, i32>>,
+    pub wci_threshold: i32,
+    pub penalty_factor: f64,
+}
+
+impl TryFrom<&ComfortConfig> for ComfortService {
+    type Error = TraversalModelError;
+
+    fn try_from(config: &ComfortConfig) -> Result<Self, Self::Error> {
+        if config.penalty_factor <= 0.0 {
+            return Err(TraversalModelError::BuildError(format!(
+                "comfort penalty_factor must be strictly positive, found {}",
+                config.penalty_factor
+            )));
+        }
+        let rows: Box<[EdgeWciRecord]> = read_utils::from_csv(
+            &config.wci_input_file,
+            true,
+            Some(Bar::builder().desc("edge wci")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "failure reading edge wci values from {}: {e}",
+                config.wci_input_file
+            ))
+        })?;
+        let wci = rows
+            .iter()
+            .map(|row| (row.edge_id, row.wci_total))
+            .collect::<HashMap<_, _>>();
+        log::info!(
+            "loaded wci values for {} edges from {}",
+            wci.len(),
+            config.wci_input_file
+        );
+        Ok(ComfortService {
+            config: Arc::new(config.clone()),
+            wci: Arc::new(wci),
+        })
+    }
+}
+
+impl TraversalModelService for ComfortService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let wci_threshold = match query.get(super::MIN_WCI_FIELD) {
+            None => self.config.wci_threshold,
+            Some(value) => value
+                .as_i64()
+                .and_then(|v| i32::try_from(v).ok())
+                .ok_or_else(|| {
+                    TraversalModelError::BuildError(format!(
+                        "query min_wci must be an integer, found {value}"
+                    ))
+                })?,
+        };
+        let model = ComfortModel {
+            wci: self.wci.clone(),
+            wci_threshold,
+            penalty_factor: self.config.penalty_factor,
+        };
+        Ok(Arc::new(model))
+    }
+}
+
+impl ComfortModel {
+    /// the penalty factor for an edge.
+    pub fn penalty(&self, edge_id: &EdgeId) -> f64 {
+        match self.wci.get(edge_id) {
+            Some(wci) if *wci < self.wci_threshold => self.penalty_factor,
+            _ => NO_PENALTY,
+        }
+    }
+}
+
+impl TraversalModel for ComfortModel {
+    fn name(&self) -> String {
+        format!("Comfort Traversal Model (min wci {})", self.wci_threshold)
+    }
+
+    fn input_features(&self) -> Vec<InputFeature> {
+        vec![]
+    }
+
+    fn output_features(&self) -> Vec<(String, StateVariableConfig)> {
+        vec![(
+            bambam_state::COST_PENALTY_FACTOR.to_string(),
+            StateVariableConfig::Custom {
+                custom_type: String::from("PenaltyFactor"),
+                value: CustomVariableConfig::FloatingPoint {
+                    initial: OrderedFloat(NO_PENALTY),
+                },
+                accumulator: false,
+            },
+        )]
+    }
+
+    fn traverse_edge(
+        &self,
+        ctx: &EdgeFrontierContext,
+        state: &mut Vec<StateVariable>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let penalty = self.penalty(&ctx.edge.edge_id);
+        state_model.set_custom_f64(state, bambam_state::COST_PENALTY_FACTOR, &penalty)?;
+        Ok(())
+    }
+
+    fn estimate_traversal(
+        &self,
+        _od: (&Vertex, &Vertex),
+        state: &mut Vec<StateVariable>,
+        _tree: &SearchTree,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        state_model.set_custom_f64(state, bambam_state::COST_PENALTY_FACTOR, &NO_PENALTY)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ComfortModel, ComfortService, NO_PENALTY};
+    use crate::model::traversal::comfort::ComfortConfig;
+    use routee_compass_core::model::{network::EdgeId, traversal::TraversalModelService};
+    use std::{collections::HashMap, sync::Arc};
+
+    fn config(penalty_factor: f64) -> ComfortConfig {
+        ComfortConfig {
+            wci_input_file: String::from("edges-wci.csv"),
+            wci_threshold: 0,
+            penalty_factor,
+        }
+    }
+
+    #[test]
+    fn test_penalty() {
+        let wci = Arc::new(HashMap::from([
+            (EdgeId(0), -3),
+            (EdgeId(1), 1),
+            (EdgeId(2), 2),
+        ]));
+        let model = ComfortModel {
+            wci,
+            wci_threshold: 2,
+            penalty_factor: 1.5,
+        };
+        assert_eq!(model.penalty(&EdgeId(0)), 1.5);
+        assert_eq!(model.penalty(&EdgeId(1)), 1.5);
+        // edges at the threshold are not penalized
+        assert_eq!(model.penalty(&EdgeId(2)), NO_PENALTY);
+        // edges without a WCI value are not penalized
+        assert_eq!(model.penalty(&EdgeId(3)), NO_PENALTY);
+    }
+
+    #[test]
+    fn test_query_threshold() {
+        let service = ComfortService {
+            config: Arc::new(config(1.5)),
+            wci: Arc::new(HashMap::new()),
+        };
+        let default = service
+            .build(&serde_json::json!({}))
+            .expect("test invariant failed");
+        assert_eq!(default.name(), "Comfort Traversal Model (min wci 0)");
+        let query = service
+            .build(&serde_json::json!({ "min_wci": 2 }))
+            .expect("test invariant failed");
+        assert_eq!(query.name(), "Comfort Traversal Model (min wci 2)");
+        assert!(service
+            .build(&serde_json::json!({ "min_wci": 2.5 }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_wci_file() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        let wci_input_file = dir.path().join("edges-wci.csv");
+        std::fs::write(
+            &wci_input_file,
+            "edge_id,wci_total,wci_walk,wci_speed,wci_cycle,wci_signal\n\
+             0,-2,-1,-1,0,0\n\
+             1,3,1,1,1,0\n\
+             2,0,,,,\n",
+        )
+        .expect("test invariant failed");
+        let config = ComfortConfig {
+            wci_input_file: wci_input_file.to_string_lossy().to_string(),
+            wci_threshold: 0,
+            penalty_factor: 1.5,
+        };
+        let service = ComfortService::try_from(&config).expect("test invariant failed");
+        assert_eq!(
+            *service.wci,
+            HashMap::from([(EdgeId(0), -2), (EdgeId(1), 3), (EdgeId(2), 0)])
+        );
+        let model = ComfortModel {
+            wci: service.wci.clone(),
+            wci_threshold: config.wci_threshold,
+            penalty_factor: config.penalty_factor,
+        };
+        assert_eq!(model.penalty(&EdgeId(0)), 1.5);
+        assert_eq!(model.penalty(&EdgeId(1)), NO_PENALTY);
+        assert_eq!(model.penalty(&EdgeId(2)), NO_PENALTY);
+    }
+
+    #[test]
+    fn test_missing_wci_file() {
+        let dir = tempfile::tempdir().expect("test invariant failed");
+        let mut config = config(1.5);
+        config.wci_input_file = dir
+            .path()
+            .join("edges-wci.csv")
+            .to_string_lossy()
+            .to_string();
+        assert!(ComfortService::try_from(&config).is_err());
+    }
+
+    #[test]
+    fn test_invalid_penalty_factor() {
+        assert!(ComfortService::try_from(&config(0.0)).is_err());
+        assert!(ComfortService::try_from(&config(-1.0)).is_err());
+    }
+}
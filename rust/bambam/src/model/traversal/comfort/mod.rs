@@ -0,0 +1,10 @@
+mod comfort_builder;
+mod comfort_config;
+mod comfort_model;
+
+pub use comfort_builder::ComfortBuilder;
+pub use comfort_config::ComfortConfig;
+pub use comfort_model::{ComfortModel, ComfortService};
+
+/// query field overriding the WCI threshold below which edges are penalized.
+pub const MIN_WCI_FIELD: &str = "min_wci";
@@ -0,0 +1,16 @@
+use super::IncomingEdgeModel;
+use routee_compass_core::model::traversal::{
+    TraversalModelBuilder, TraversalModelError, TraversalModelService,
+};
+use std::sync::Arc;
+
+pub struct IncomingEdgeBuilder {}
+
+impl TraversalModelBuilder for IncomingEdgeBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
+        Ok(Arc::new(IncomingEdgeModel {}))
+    }
+}
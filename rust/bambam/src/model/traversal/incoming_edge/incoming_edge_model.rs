@@ -0,0 +1,84 @@
+use bambam_core::model::bambam_state;
+use routee_compass_core::{
+    algorithm::search::SearchTree,
+    model::{
+        network::Vertex,
+        state::{
+            CustomVariableConfig, InputFeature, StateModel, StateVariable, StateVariableConfig,
+        },
+        traversal::{
+            EdgeFrontierContext, TraversalModel, TraversalModelError, TraversalModelService,
+        },
+    },
+};
+use std::sync::Arc;
+
+/// records the id of each traversed edge in the [`bambam_state::INCOMING_EDGE_ID`]
+/// state feature so that a turn-aware label model can key search labels on the
+/// edge used to arrive at a vertex.
+#[derive(Clone, Debug)]
+pub struct IncomingEdgeModel {}
+
+impl IncomingEdgeModel {
+    /// value of the incoming edge feature before any edge has been traversed.
+    pub const NO_INCOMING_EDGE: i64 = -1;
+}
+
+impl TraversalModelService for IncomingEdgeModel {
+    fn build(
+        &self,
+        _query: &serde_json::Value,
+    ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
+        let model: Arc<dyn TraversalModel> = Arc::new(self.clone());
+        Ok(model)
+    }
+}
+
+impl TraversalModel for IncomingEdgeModel {
+    fn name(&self) -> String {
+        String::from("Incoming Edge Traversal Model")
+    }
+
+    fn input_features(&self) -> Vec<InputFeature> {
+        vec![]
+    }
+
+    fn output_features(&self) -> Vec<(String, StateVariableConfig)> {
+        vec![(
+            bambam_state::INCOMING_EDGE_ID.to_string(),
+            StateVariableConfig::Custom {
+                custom_type: String::from("EdgeId"),
+                value: CustomVariableConfig::SignedInteger {
+                    initial: Self::NO_INCOMING_EDGE,
+                },
+                accumulator: false,
+            },
+        )]
+    }
+
+    fn traverse_edge(
+        &self,
+        ctx: &EdgeFrontierContext,
+        state: &mut Vec<StateVariable>,
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let edge_id = i64::try_from(ctx.edge.edge_id.0).map_err(|e| {
+            TraversalModelError::TraversalModelFailure(format!(
+                "edge id {} cannot be stored as a state variable: {e}",
+                ctx.edge.edge_id
+            ))
+        })?;
+        state_model.set_custom_i64(state, bambam_state::INCOMING_EDGE_ID, &edge_id)?;
+        Ok(())
+    }
+
+    fn estimate_traversal(
+        &self,
+        _od: (&Vertex, &Vertex),
+        _state: &mut Vec<StateVariable>,
+        _tree: &SearchTree,
+        _state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        Ok(())
+    }
+}
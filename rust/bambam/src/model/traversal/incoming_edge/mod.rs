@@ -0,0 +1,5 @@
+mod incoming_edge_builder;
+mod incoming_edge_model;
+
+pub use incoming_edge_builder::IncomingEdgeBuilder;
+pub use incoming_edge_model::IncomingEdgeModel;
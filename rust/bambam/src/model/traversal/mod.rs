@@ -1,4 +1,6 @@
+pub mod comfort;
 pub mod fixed_speed;
+pub mod incoming_edge;
 pub mod multimodal;
 pub mod schedule;
 pub mod switch;
@@ -1,3 +1,4 @@
+use bambam_core::model::bambam_field;
 use chrono::{DateTime, Utc};
 use routee_compass_core::config::ConfigJsonExtensions;
 use routee_compass_core::model::traversal::TraversalModel;
@@ -30,7 +31,7 @@ impl TraversalModelService for ScheduleTraversalService {
         query: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
         let start_time: DateTime<Utc> = query
-            .get_config_serde(&"start_time", &"schedule traversal model")
+            .get_config_serde(&bambam_field::START_TIME, &"schedule traversal model")
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
         let engine = self.engine.clone();
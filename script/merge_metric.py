@@ -10,7 +10,9 @@ Merges modal metric scores (e.g., WCI, LTS) with an edges-complete.csv file.
 #           --metric metric-output-file.csv \
 #           --output edges-complete-with-metric.csv
 #
-# The input files must have matching row order and row counts:
+# Metric rows are joined to edges by their edge_id column, the row index of the
+# edge in the edges file. Edges without a metric value are written with empty
+# metric columns:
 #   - --edges: edge network CSV containing geometry and OSM edge attributes
 #   - --metric: modal metric score CSV produced by bulk_compute_modal_metric
 #   - --output: combined CSV output
@@ -25,17 +27,19 @@ from pathlib import Path
 csv.field_size_limit(sys.maxsize)
 
 
-def read_metric_rows(path: Path) -> tuple[list[str], list[list[str]]]:
-    """Read a CSV containing modal metric outputs (e.g., WCI, LTS)."""
+def read_metric_rows(path: Path) -> tuple[list[str], dict[int, list[str]]]:
+    """Read a CSV containing modal metric outputs (e.g., WCI, LTS) keyed by edge_id."""
     with path.open("r", newline="", encoding="utf-8") as f:
         reader = csv.reader(f)
         try:
             header = next(reader)
         except StopIteration:
             raise ValueError(f"{path} is empty")
-        rows = [row for row in reader]
+        if not header or header[0] != "edge_id":
+            raise ValueError(f"{path} must begin with an edge_id column")
+        rows = {int(row[0]): row[1:] for row in reader}
 
-    return header, rows
+    return header[1:], rows
 
 
 def merge_modal_metric(
@@ -55,19 +59,20 @@ def merge_modal_metric(
 
     metric_header, metric_rows = read_metric_rows(metric_csv)
 
-    if len(edge_data) != len(metric_rows):
+    unknown = [edge_id for edge_id in metric_rows if edge_id >= len(edge_data)]
+    if unknown:
         raise ValueError(
-            f"Row count mismatch:\n"
-            f"edges:  {len(edge_data)}\n"
-            f"metric: {len(metric_rows)}"
+            f"{len(unknown)} metric rows have an edge_id beyond the "
+            f"{len(edge_data)} edges in {edges_csv}, first: {unknown[0]}"
         )
 
+    empty = [""] * len(metric_header)
     with output_csv.open("w", newline="", encoding="utf-8") as f:
         writer = csv.writer(f)
         writer.writerow(edge_header + metric_header)
 
-        for edge, metric in zip(edge_data, metric_rows):
-            writer.writerow(edge + metric)
+        for edge_id, edge in enumerate(edge_data):
+            writer.writerow(edge + metric_rows.get(edge_id, empty))
 
 
 def parse_args() -> argparse.Namespace: